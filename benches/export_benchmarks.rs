@@ -3,7 +3,7 @@
 //! 使用 criterion 框架测试 CSV/XLSX 生成在不同数据规模下的性能
 
 use belobog_stellar_grid::bench_exports::{
-    MergeRange, TableData, generate_csv_bytes, generate_xlsx_bytes,
+    CellValue, CsvDialect, MergeRange, TableData, generate_csv_bytes, generate_xlsx_bytes,
 };
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 
@@ -18,6 +18,13 @@ fn generate_rows(row_count: usize, col_count: usize) -> Vec<Vec<String>> {
         .collect()
 }
 
+/// 将字符串行转为单元格行（XLSX 写入路径使用）
+fn to_cells(rows: Vec<Vec<String>>) -> Vec<Vec<CellValue>> {
+    rows.into_iter()
+        .map(|row| row.into_iter().map(CellValue::Text).collect())
+        .collect()
+}
+
 /// 生成包含合并区域的 TableData（模拟嵌套表头场景）
 fn generate_table_data_with_merges(row_count: usize, col_count: usize) -> TableData {
     // 1 行表头 + row_count 行数据
@@ -33,16 +40,16 @@ fn generate_table_data_with_merges(row_count: usize, col_count: usize) -> TableD
     // 模拟合并区域：每 10 行第一列 rowSpan=2
     let mut merge_ranges = Vec::new();
     for r in (1..=row_count).step_by(10) {
-        if r + 1 <= row_count {
+        if r < row_count {
             merge_ranges.push(MergeRange::new(r as u32, 0, (r + 1) as u32, 0));
         }
     }
 
     TableData {
-        rows,
+        rows: to_cells(rows),
         merge_ranges,
         header_row_count: 1,
-        style_sheet: None,
+        ..Default::default()
     }
 }
 
@@ -66,7 +73,8 @@ fn bench_csv_generation(c: &mut Criterion) {
 
         group.bench_with_input(BenchmarkId::new("无BOM", label), &data, |b, data| {
             b.iter(|| {
-                generate_csv_bytes(data.clone(), None, false, false).expect("CSV 生成不应失败")
+                generate_csv_bytes(data.clone(), None, false, false, &CsvDialect::default())
+                    .expect("CSV 生成不应失败")
             })
         });
     }
@@ -78,7 +86,8 @@ fn bench_csv_generation(c: &mut Criterion) {
         &data_bom,
         |b, data| {
             b.iter(|| {
-                generate_csv_bytes(data.clone(), None, false, true).expect("CSV 生成不应失败")
+                generate_csv_bytes(data.clone(), None, false, true, &CsvDialect::default())
+                    .expect("CSV 生成不应失败")
             })
         },
     );
@@ -105,21 +114,44 @@ fn bench_xlsx_generation(c: &mut Criterion) {
     for (rows, cols, label) in configs {
         // 无合并区域的简单 TableData
         let table_data = TableData {
-            rows: generate_rows(rows, cols),
-            merge_ranges: Vec::new(),
-            header_row_count: 0,
-            style_sheet: None,
+            rows: to_cells(generate_rows(rows, cols)),
+            ..Default::default()
         };
 
         group.bench_with_input(
             BenchmarkId::new("无合并", label),
             &table_data,
             |b, data| {
-                b.iter(|| generate_xlsx_bytes(data, None, false, None).expect("XLSX 生成不应失败"))
+                b.iter(|| {
+                    generate_xlsx_bytes(data, None, false, None, &Default::default())
+                        .expect("XLSX 生成不应失败")
+                })
             },
         );
     }
 
+    // 数字单元格测试（write_number 路径）
+    let numeric_data = TableData {
+        rows: (0..1_000)
+            .map(|r| {
+                (0..10)
+                    .map(|c| CellValue::Number((r * 10 + c) as f64))
+                    .collect()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    group.bench_with_input(
+        BenchmarkId::new("数字单元格", "1000行x10列"),
+        &numeric_data,
+        |b, data| {
+            b.iter(|| {
+                generate_xlsx_bytes(data, None, false, None, &Default::default())
+                    .expect("XLSX 生成不应失败")
+            })
+        },
+    );
+
     // 含合并区域测试
     let merge_data = generate_table_data_with_merges(1_000, 10);
     group.bench_with_input(
@@ -127,7 +159,8 @@ fn bench_xlsx_generation(c: &mut Criterion) {
         &merge_data,
         |b, data| {
             b.iter(|| {
-                generate_xlsx_bytes(data, None, false, Some((1, 0))).expect("XLSX 生成不应失败")
+                generate_xlsx_bytes(data, None, false, Some((1, 0)), &Default::default())
+                    .expect("XLSX 生成不应失败")
             })
         },
     );
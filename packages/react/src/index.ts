@@ -43,7 +43,15 @@ export type {
   ProgressCallback,
   ExportTableOptions,
   ExportTablesXlsxOptions,
+  DataSheetConfig,
+  WorkbookSheetConfig,
+  ExportWorkbookOptions,
+  ExportWorkbookWrapperOptions,
+  TemplateBindings,
+  ExportFromTemplateOptions,
+  ExportFromTemplateWrapperOptions,
   ExportCsvBatchOptions,
+  CsvDialectOptions,
   ExportXlsxBatchOptions,
   ExportTablesBatchOptions,
   PreviewOptions,
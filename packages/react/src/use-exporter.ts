@@ -21,6 +21,8 @@ import type {
   ExportDataOptions,
   ExportTableOptions,
   ExportTablesXlsxOptions,
+  ExportWorkbookWrapperOptions,
+  ExportFromTemplateWrapperOptions,
   ExportCsvBatchOptions,
   ExportXlsxBatchOptions,
   ExportTablesBatchOptions,
@@ -46,6 +48,10 @@ export interface UseExporterReturn {
   exportData: (data: DataRow[], options?: ExportDataOptions) => boolean;
   /** 多工作表同步导出 */
   exportTablesXlsx: (options: ExportTablesXlsxOptions) => boolean;
+  /** 混合工作簿导出（DOM 表格与 JS 数据工作表） */
+  exportWorkbook: (options: ExportWorkbookWrapperOptions) => boolean;
+  /** 按 XLSX 模板导出 */
+  exportFromTemplate: (options: ExportFromTemplateWrapperOptions) => boolean;
   /** 分批异步导出 CSV */
   exportCsvBatch: (options: ExportCsvBatchOptions) => Promise<boolean>;
   /** 分批异步导出 XLSX */
@@ -171,6 +177,7 @@ export function useExporter(): UseExporterReturn {
           options.strictProgressCallback,
           options.headerStyle,
           options.cellStyle,
+          options,
         );
       });
     },
@@ -199,12 +206,34 @@ export function useExporter(): UseExporterReturn {
           options.strictProgressCallback,
           options.headerStyle,
           options.cellStyle,
+          options,
         );
       });
     },
     [wrapSync, createProgressCallback],
   );
 
+  const exportWorkbook = useCallback(
+    (options: ExportWorkbookWrapperOptions) => {
+      return wrapSync(() => {
+        const { sheets, ...rest } = options;
+        wasmModule!.export_workbook(sheets, { ...rest, progressCallback: createProgressCallback() });
+      });
+    },
+    [wrapSync, createProgressCallback],
+  );
+
+  const exportFromTemplate = useCallback(
+    (options: ExportFromTemplateWrapperOptions) => {
+      return wrapSync(() => {
+        wasmModule!.export_from_template(options.template, options.bindings, {
+          filename: options.filename,
+        });
+      });
+    },
+    [wrapSync],
+  );
+
   const exportCsvBatch = useCallback(
     async (options: ExportCsvBatchOptions) => {
       return await wrapAsync(async () => {
@@ -217,6 +246,7 @@ export function useExporter(): UseExporterReturn {
           createProgressCallback(),
           options.withBom,
           options.strictProgressCallback,
+          { format: options.format, csv: options.csv },
         );
       });
     },
@@ -236,6 +266,7 @@ export function useExporter(): UseExporterReturn {
           options.strictProgressCallback,
           options.headerStyle,
           options.cellStyle,
+          options,
         );
       });
     },
@@ -253,6 +284,7 @@ export function useExporter(): UseExporterReturn {
           options.strictProgressCallback,
           options.headerStyle,
           options.cellStyle,
+          options,
         );
       });
     },
@@ -267,6 +299,8 @@ export function useExporter(): UseExporterReturn {
     exportTable,
     exportData,
     exportTablesXlsx,
+    exportWorkbook,
+    exportFromTemplate,
     exportCsvBatch,
     exportXlsxBatch,
     exportTablesBatch,
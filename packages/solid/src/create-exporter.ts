@@ -25,6 +25,8 @@ import type {
   ExportDataOptions,
   ExportTableOptions,
   ExportTablesXlsxOptions,
+  ExportWorkbookWrapperOptions,
+  ExportFromTemplateWrapperOptions,
   ExportCsvBatchOptions,
   ExportXlsxBatchOptions,
   ExportTablesBatchOptions,
@@ -48,6 +50,10 @@ export interface CreateExporterReturn {
   exportData: (data: DataRow[], options?: ExportDataOptions) => boolean;
   /** 多工作表同步导出 */
   exportTablesXlsx: (options: ExportTablesXlsxOptions) => boolean;
+  /** 混合工作簿导出（DOM 表格与 JS 数据工作表） */
+  exportWorkbook: (options: ExportWorkbookWrapperOptions) => boolean;
+  /** 按 XLSX 模板导出 */
+  exportFromTemplate: (options: ExportFromTemplateWrapperOptions) => boolean;
   /** 分批异步导出 CSV */
   exportCsvBatch: (options: ExportCsvBatchOptions) => Promise<boolean>;
   /** 分批异步导出 XLSX */
@@ -166,6 +172,7 @@ export function createExporter(): CreateExporterReturn {
         options.strictProgressCallback,
         options.headerStyle,
         options.cellStyle,
+        options,
       );
     });
   };
@@ -190,10 +197,28 @@ export function createExporter(): CreateExporterReturn {
         options.strictProgressCallback,
         options.headerStyle,
         options.cellStyle,
+        options,
       );
     });
   };
 
+  /** 混合工作簿导出（DOM 表格与 JS 数据工作表） */
+  const exportWorkbook = (options: ExportWorkbookWrapperOptions) => {
+    return wrapSync(() => {
+      const { sheets, ...rest } = options;
+      wasmModule!.export_workbook(sheets, { ...rest, progressCallback: createProgressCallback() });
+    });
+  };
+
+  /** 按 XLSX 模板导出 */
+  const exportFromTemplate = (options: ExportFromTemplateWrapperOptions) => {
+    return wrapSync(() => {
+      wasmModule!.export_from_template(options.template, options.bindings, {
+        filename: options.filename,
+      });
+    });
+  };
+
   /** 分批异步导出 CSV */
   const exportCsvBatch = async (options: ExportCsvBatchOptions) => {
     return await wrapAsync(async () => {
@@ -206,6 +231,7 @@ export function createExporter(): CreateExporterReturn {
         createProgressCallback(),
         options.withBom,
         options.strictProgressCallback,
+        { format: options.format, csv: options.csv },
       );
     });
   };
@@ -223,6 +249,7 @@ export function createExporter(): CreateExporterReturn {
         options.strictProgressCallback,
         options.headerStyle,
         options.cellStyle,
+        options,
       );
     });
   };
@@ -238,6 +265,7 @@ export function createExporter(): CreateExporterReturn {
         options.strictProgressCallback,
         options.headerStyle,
         options.cellStyle,
+        options,
       );
     });
   };
@@ -250,6 +278,8 @@ export function createExporter(): CreateExporterReturn {
     exportTable,
     exportData,
     exportTablesXlsx,
+    exportWorkbook,
+    exportFromTemplate,
     exportCsvBatch,
     exportXlsxBatch,
     exportTablesBatch,
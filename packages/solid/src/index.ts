@@ -37,7 +37,15 @@ export type {
   ProgressCallback,
   ExportTableOptions,
   ExportTablesXlsxOptions,
+  DataSheetConfig,
+  WorkbookSheetConfig,
+  ExportWorkbookOptions,
+  ExportWorkbookWrapperOptions,
+  TemplateBindings,
+  ExportFromTemplateOptions,
+  ExportFromTemplateWrapperOptions,
   ExportCsvBatchOptions,
+  CsvDialectOptions,
   ExportXlsxBatchOptions,
   ExportTablesBatchOptions,
   // 预览相关类型
@@ -35,7 +35,15 @@ export type {
   ProgressCallback,
   ExportTableOptions,
   ExportTablesXlsxOptions,
+  DataSheetConfig,
+  WorkbookSheetConfig,
+  ExportWorkbookOptions,
+  ExportWorkbookWrapperOptions,
+  TemplateBindings,
+  ExportFromTemplateOptions,
+  ExportFromTemplateWrapperOptions,
   ExportCsvBatchOptions,
+  CsvDialectOptions,
   ExportXlsxBatchOptions,
   ExportTablesBatchOptions,
   // 预览相关类型
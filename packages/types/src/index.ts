@@ -17,11 +17,33 @@ export enum ExportFormat {
   Csv = 0,
   /** Excel XLSX 格式 */
   Xlsx = 1,
+  /** TSV 格式（制表符分隔） */
+  Tsv = 2,
 }
 
 /** 进度回调函数，接收 0-100 的进度值 */
 export type ProgressCallback = (progress: number) => void;
 
+/** CSV 方言（仅 CSV / TSV 有效），未设置的字段取默认值：CSV 以 `,` 分隔，TSV 以制表符分隔 */
+export interface CsvDialectOptions {
+  /** 字段分隔符，单个 ASCII 字符（如 ';'、'|'、'\t'） */
+  delimiter?: string;
+  /** 引号字符，单个 ASCII 字符（默认 '"'） */
+  quote?: string;
+  /**
+   * 引用策略（默认 'necessary'）：
+   * - `necessary`: 字段包含分隔符、引号或换行时加引号
+   * - `always`: 所有字段加引号
+   * - `nonNumeric`: 非数字字段加引号
+   * - `never`: 从不加引号
+   */
+  quoteStyle?: 'necessary' | 'always' | 'nonNumeric' | 'never';
+  /** 行结束符（默认 '\n'） */
+  terminator?: '\n' | '\r\n' | '\r';
+  /** 字段内引号的转义字符（默认双写引号），如 '\\' */
+  escape?: string;
+}
+
 // =============================================================================
 // 列配置
 // =============================================================================
@@ -43,10 +65,159 @@ export interface Column {
   style?: CellStyle;
   /** 该列表头单元格样式（仅 XLSX 有效） */
   headerStyle?: CellStyle;
+  /**
+   * 列数据类型：按类型转换该列的值，并自动设置数字格式与对齐
+   * （列级 `style` 中显式设置的 `numberFormat` / `align` 优先）。
+   * `'date'` / `'datetime'` 列中的 ISO-8601 字符串
+   * （如 '2024-03-05'、'2024-03-05T08:30:00Z'）解析为 Excel 原生日期，
+   * `'date'` 会截断时间部分。无法解析的值原样输出。
+   */
+  type?: ColumnType;
+  /** 小数位数（0–15，作用于 number / currency / percent 列） */
+  precision?: number;
+  /** 货币符号（currency 列有效，默认 '¥'，空字符串表示不显示） */
+  currencySymbol?: string;
+  /**
+   * 表尾聚合方式：任一叶子列声明后在数据末尾追加合计行。
+   * XLSX 中为引用数据区的公式，CSV 中为计算值；树形模式仅聚合叶子行。
+   */
+  aggregate?: AggregateKind;
+  /** 表尾标签文本（如 '合计'），显示在该列的合计行单元格中 */
+  footerLabel?: string;
+  /** 数据验证（仅 XLSX 有效）：下拉列表、数值范围、日期范围或最大文本长度 */
+  validation?: ColumnValidation;
+  /** 列级条件格式（仅 XLSX 有效），作用于该列的数据行 */
+  conditionalFormats?: ConditionalFormat[];
+  /** 表头单元格批注（XLSX 写为 Excel 批注，CSV 忽略） */
+  headerNote?: string;
+  /** 按内容自动计算该列列宽（仅 XLSX 有效，显式 `width` 优先） */
+  autoWidth?: boolean;
+  /**
+   * 是否锁定该列数据单元格（默认 true）。`false` 时工作表开启 `protection` 后该列整列可编辑，
+   * 包括数据行以下的空白行（仅 XLSX 有效）
+   */
+  locked?: boolean;
 }
 
+/** 列数据验证规则（只能指定 list、min/max、dateRange、maxLength 中的一种） */
+export type ColumnValidation = (
+  | {
+      /** 下拉列表选项（不能含逗号，拼接后不超过 255 字符） */
+      list: Array<string | number | boolean>;
+    }
+  | {
+      /** 最小值（闭区间） */
+      min?: number;
+      /** 最大值（闭区间） */
+      max?: number;
+      /** 是否仅允许整数（默认 false） */
+      integer?: boolean;
+    }
+  | {
+      /** 日期范围 [start, end]（闭区间），端点为 Date 或 ISO-8601 字符串，一端可为 null */
+      dateRange: [Date | string | null, Date | string | null];
+    }
+  | {
+      /** 最大文本长度 */
+      maxLength: number;
+    }
+) &
+  ValidationMessages;
+
+/** 条件格式规则（仅 XLSX 有效，写为 Excel 原生条件格式） */
+export type ConditionalFormat =
+  | {
+      type: 'cellIs';
+      operator:
+        | 'equal'
+        | 'notEqual'
+        | 'greaterThan'
+        | 'greaterThanOrEqual'
+        | 'lessThan'
+        | 'lessThanOrEqual'
+        | 'between'
+        | 'notBetween';
+      /** 比较值；between / notBetween 时为 [min, max]；以 '=' 开头的字符串按公式处理 */
+      value: number | string | [number | string, number | string];
+      style: CellStyle;
+    }
+  | {
+      type: 'text';
+      /** 默认 'contains' */
+      operator?: 'contains' | 'notContains' | 'beginsWith' | 'endsWith';
+      text: string;
+      style: CellStyle;
+    }
+  | {
+      type: 'top';
+      /** 默认 10 */
+      rank?: number;
+      /** 后 N 项（默认 false） */
+      bottom?: boolean;
+      /** 按百分比（默认 false） */
+      percent?: boolean;
+      style: CellStyle;
+    }
+  | {
+      type: 'duplicate';
+      /** 为 true 时标记唯一值（默认 false） */
+      unique?: boolean;
+      style: CellStyle;
+    }
+  | {
+      type: 'colorScale';
+      minColor?: string;
+      /** 设置时为三色刻度 */
+      midColor?: string;
+      maxColor?: string;
+    }
+  | {
+      type: 'dataBar';
+      color?: string;
+      /** 实心填充（默认 false，渐变） */
+      solid?: boolean;
+    };
+
+/** 工作表级条件格式：可指定作用范围（默认全部数据区） */
+export type SheetConditionalFormat = ConditionalFormat & {
+  /** A1 格式区域（如 'B2:D100'） */
+  range?: string;
+  /** 0 起始列号，作用于该列的数据行 */
+  column?: number;
+};
+
+/** 数据验证的提示配置 */
+export interface ValidationMessages {
+  /** 是否允许空值（默认 true） */
+  allowBlank?: boolean;
+  /** 选中单元格时的提示标题（最长 32 字符） */
+  inputTitle?: string;
+  /** 选中单元格时的提示内容（最长 255 字符） */
+  inputMessage?: string;
+  /** 输入非法值时的错误标题（最长 32 字符） */
+  errorTitle?: string;
+  /** 输入非法值时的错误内容（最长 255 字符） */
+  errorMessage?: string;
+  /** 错误提示级别（默认 'stop' 禁止输入） */
+  errorStyle?: 'stop' | 'warning' | 'information';
+}
+
+/** 表尾聚合方式 */
+export type AggregateKind = 'sum' | 'avg' | 'count' | 'min' | 'max';
+
+/** 列数据类型 */
+export type ColumnType =
+  | 'text'
+  | 'number'
+  | 'integer'
+  | 'currency'
+  | 'percent'
+  | 'date'
+  | 'datetime'
+  | 'boolean';
+
 // =============================================================================
-// 样式类型（三级样式体系：全局 → 列级 → 单元格）
+// 样式类型（样式体系：全局 → 列级 → 行级 → 单元格）
 // =============================================================================
 
 /** 边框线条类型 */
@@ -63,7 +234,7 @@ export interface BorderSides {
 /**
  * 单元格样式配置（仅 XLSX 格式有效，CSV 会忽略）
  *
- * 三级样式体系的优先级：单元格 > 列级 > 全局。
+ * 样式体系的优先级：单元格 > 行级 > 列级 > 全局。
  */
 export interface CellStyle {
   /** 加粗 */
@@ -88,6 +259,10 @@ export interface CellStyle {
   numberFormat?: string;
   /** 自动换行 */
   textWrap?: boolean;
+  /** 缩进级别（0–15 的整数） */
+  indent?: number;
+  /** 是否锁定（默认 true）；工作表开启 `protection` 时 `false` 的单元格仍可编辑 */
+  locked?: boolean;
 }
 
 // =============================================================================
@@ -96,18 +271,75 @@ export interface CellStyle {
 
 /** 合并单元格配置 */
 export interface MergeCellValue {
-  /** 单元格显示值 */
-  value: string | number | boolean | null;
+  /** 单元格显示值（公式单元格中为缓存结果） */
+  value?: string | number | boolean | Date | null;
   /** 列合并数（默认 1） */
   colSpan?: number;
   /** 行合并数（默认 1） */
   rowSpan?: number;
   /** 单元格级样式（优先级最高，仅 XLSX 有效） */
   style?: CellStyle;
+  /**
+   * 公式（XLSX 写为公式单元格，CSV 输出 `value` 缓存结果）。
+   * 支持相对数据区的占位符：`{row}` / `{row-1}` / `{row+1}`、
+   * `{firstRow}` / `{lastRow}`、`{col}` / `{col:key}`。
+   * @example '=SUM({col:amount}{firstRow}:{col:amount}{lastRow})'
+   */
+  formula?: string;
+  /**
+   * 超链接地址（仅 XLSX 有效，CSV 输出 `value`）。
+   * 支持 http(s)/ftp(s)/mailto 以及 `#Sheet2!A1` 形式的工作簿内部链接，
+   * `javascript:` 等不安全协议会被拒绝并降级为普通文本。
+   */
+  link?: string;
+  /** 单元格批注（XLSX 写为 Excel 批注；CSV 默认忽略，见 `csvNoteColumn`） */
+  note?: string;
+  /**
+   * 嵌入图片（仅 XLSX 有效，CSV 输出 `value`）：PNG / JPEG / GIF 字节或
+   * `data:image/...;base64,` 字符串，单张不超过 5 MiB，单个工作表合计不超过 50 MiB。
+   */
+  image?: Uint8Array | string;
+  /** 图片替代文本 */
+  alt?: string;
+  /**
+   * 富文本片段（仅 XLSX 有效，CSV 输出拼接后的文本）；同时设置 `formula` 或 `link` 时忽略。
+   * @example [{ text: '逾期', style: { bold: true, fontColor: '#FF0000' } }, ' 3 天']
+   */
+  runs?: Array<string | RichTextRun>;
+}
+
+/** 富文本片段的字体格式（未设置的属性继承单元格样式） */
+export interface RichTextStyle {
+  bold?: boolean;
+  italic?: boolean;
+  underline?: boolean;
+  /** 删除线 */
+  strikethrough?: boolean;
+  /** 字体颜色，如 `#FF0000` */
+  fontColor?: string;
+  /** 字号（磅） */
+  fontSize?: number;
+  fontName?: string;
+  /** 上标 */
+  superscript?: boolean;
+  /** 下标 */
+  subscript?: boolean;
 }
 
-/** 单元格值类型 */
-export type CellValue = string | number | boolean | null | undefined;
+/** 富文本片段 */
+export interface RichTextRun {
+  text: string;
+  style?: RichTextStyle;
+}
+
+/**
+ * 单元格值类型
+ *
+ * XLSX 中 `number` 写为数字单元格、`boolean` 写为布尔单元格，
+ * `Date` 按本地时间写为原生日期单元格（零点视为纯日期），
+ * `null` / `undefined`（以及 `NaN` / `Infinity` / Invalid Date）为空单元格。
+ */
+export type CellValue = string | number | boolean | Date | null | undefined;
 
 /** 支持合并的单元格值 */
 export type MergeableCellValue = CellValue | MergeCellValue;
@@ -152,8 +384,15 @@ export interface ExportDataOptions {
    * 指定子节点字段名（如 'children'、'subCategories'）。
    */
   childrenKey?: string;
-  /** 是否添加 UTF-8 BOM 头（仅 CSV 有效，解决 Excel 中文乱码） */
+  /**
+   * 树形模式：将层级写为 Excel 行分组（大纲），仅 XLSX 有效。
+   * 启用后 `indentColumn` 改用单元格缩进格式，文本不再带空格前缀；CSV 仍以空格缩进。
+   */
+  outline?: boolean | OutlineOptions;
+  /** 是否添加 UTF-8 BOM 头（仅 CSV / TSV 有效，解决 Excel 中文乱码） */
   withBom?: boolean;
+  /** CSV 方言：分隔符、引号、换行符等（仅 CSV / TSV 有效） */
+  csv?: CsvDialectOptions;
   /** 回调失败是否中断导出（默认 false） */
   strictProgressCallback?: boolean;
   /** 冻结前 N 行（仅 XLSX 有效，默认自动根据表头行数冻结） */
@@ -164,14 +403,317 @@ export interface ExportDataOptions {
   headerStyle?: CellStyle;
   /** 全局数据行样式（仅 XLSX 有效，会被列级 / 单元格样式覆盖） */
   cellStyle?: CellStyle;
+  /**
+   * 日期单元格默认数字格式（仅 XLSX 有效，如 'yyyy/mm/dd'）。
+   * 默认纯日期为 'yyyy-mm-dd'，含时间为 'yyyy-mm-dd hh:mm:ss'；
+   * 列级 / 单元格级 `style.numberFormat` 优先。
+   */
+  dateFormat?: string;
+  /**
+   * 日期单元格的文本渲染模式（仅 CSV 有效，dayjs 风格占位符：
+   * YYYY YY MM M DD D HH H mm m ss s SSS，`[...]` 内为字面量）。
+   * 默认 'YYYY-MM-DD' / 'YYYY-MM-DD HH:mm:ss'。
+   */
+  csvDateFormat?: string;
+  /**
+   * 将表头 + 数据区转为原生 Excel 表格（仅 XLSX 有效），
+   * 提供排序、筛选与结构化引用。要求单行表头且不含合并单元格。
+   */
+  table?: boolean | ExcelTableOptions;
+  /**
+   * 是否在最后一行表头上启用自动筛选（仅 XLSX 有效，默认存在表头时启用）。
+   * 开启 `table` 时由 Excel 表格自带筛选。
+   */
+  autoFilter?: boolean;
+  /**
+   * 导入模板的可填写行数（仅 XLSX 有效）：列 `validation` 至少覆盖表头下的这么多行，
+   * 数据为空时即生成 N 行空白模板。
+   */
+  templateRows?: number;
+  /** 工作表级条件格式（仅 XLSX 有效），列级规则写在 `columns[].conditionalFormats` */
+  conditionalFormats?: SheetConditionalFormat[];
+  /** 批注外观（仅 XLSX 有效），批注来自单元格 `note` 与列 `headerNote` */
+  noteOptions?: NoteOptions;
+  /**
+   * 将批注追加为 CSV 末尾的备注列（仅 CSV 有效）：`true` 使用标题「备注」，字符串为自定义标题。
+   * 数据行的多个批注以 '; ' 拼接并前缀列名，表头批注不输出。
+   */
+  csvNoteColumn?: boolean | string;
+  /** 图片缩放方式（仅 XLSX 有效），图片来自单元格 `image` */
+  imageOptions?: ImageOptions;
+  /** 按内容自动计算所有列的列宽（仅 XLSX 有效，显式 `width` 优先） */
+  autoWidth?: boolean | AutoWidthOptions;
+  /** 行级样式：斑马纹、按字段取值匹配的规则与行高（仅 XLSX 有效） */
+  rowStyle?: RowStyleOptions;
+  /** 工作簿文档属性：标题、作者、公司等与自定义属性（仅 XLSX 有效） */
+  properties?: DocumentProperties;
+  /** 工作表保护（仅 XLSX 有效），可编辑区域由列 / 样式的 `locked: false` 指定 */
+  protection?: boolean | SheetProtectionOptions;
+  /** 打印设置：纸张、方向、页边距、缩放与页眉页脚（仅 XLSX 有效） */
+  print?: PrintOptions;
+  /** 工作表标签颜色，如 `#FF0000`（仅 XLSX 有效） */
+  tabColor?: string;
+  /** 显示缩放比例 10–400（仅 XLSX 有效） */
+  zoom?: number;
+  /** 工作簿结构保护：禁止增删、重命名、移动工作表（仅 XLSX 有效） */
+  workbookProtection?: boolean | WorkbookProtectionOptions;
+}
+
+/** 树形大纲配置 */
+export interface OutlineOptions {
+  /** 是否折叠全部分组，仅显示根节点行（默认 false） */
+  collapsed?: boolean;
+  /** 父节点行是否位于子节点行下方（默认 false，即位于上方） */
+  summaryBelow?: boolean;
+}
+
+/** 自定义文档属性值：字符串、数字（整数写为整型，其余为实数）、布尔值或日期 */
+export type CustomPropertyValue = string | number | boolean | Date;
+
+/** 工作簿文档属性（写入「文件 > 信息 > 属性」） */
+export interface DocumentProperties {
+  /** 标题 */
+  title?: string;
+  /** 主题 */
+  subject?: string;
+  /** 作者 */
+  author?: string;
+  /** 经理 */
+  manager?: string;
+  /** 公司 */
+  company?: string;
+  /** 类别 */
+  category?: string;
+  /** 关键词 */
+  keywords?: string;
+  /** 备注 */
+  comment?: string;
+  /** 状态 */
+  status?: string;
+  /** 超链接基础地址 */
+  hyperlinkBase?: string;
+  /** 创建时间（Date 或 ISO-8601 字符串，默认导出时刻） */
+  created?: Date | string;
+  /** 自定义属性，如 `{ 部门: '财务部', 密级: '内部' }` */
+  custom?: Record<string, CustomPropertyValue>;
+}
+
+/** 工作簿级选项（多工作表导出与分批导出） */
+export interface WorkbookOptions {
+  /** 工作簿文档属性 */
+  properties?: DocumentProperties;
+  /** 工作簿结构保护 */
+  workbookProtection?: boolean | WorkbookProtectionOptions;
+}
+
+/** 工作表保护配置（未列出的操作默认禁止） */
+export interface SheetProtectionOptions {
+  /** 取消保护所需的密码（最多 255 个字符，默认无密码） */
+  password?: string;
+  /** 允许选择锁定的单元格（默认 true） */
+  selectLockedCells?: boolean;
+  /** 允许选择未锁定的单元格（默认 true） */
+  selectUnlockedCells?: boolean;
+  /** 允许设置单元格格式 */
+  allowFormatCells?: boolean;
+  /** 允许设置列格式（含调整列宽） */
+  allowFormatColumns?: boolean;
+  /** 允许设置行格式（含调整行高） */
+  allowFormatRows?: boolean;
+  /** 允许插入列 */
+  allowInsertColumns?: boolean;
+  /** 允许插入行 */
+  allowInsertRows?: boolean;
+  /** 允许插入超链接 */
+  allowInsertLinks?: boolean;
+  /** 允许删除列 */
+  allowDeleteColumns?: boolean;
+  /** 允许删除行 */
+  allowDeleteRows?: boolean;
+  /** 允许排序（仅作用于未锁定的单元格） */
+  allowSort?: boolean;
+  /** 允许使用自动筛选 */
+  allowFilter?: boolean;
+  /** 允许使用数据透视表 */
+  allowPivotTables?: boolean;
+  /** 允许编辑方案 */
+  allowEditScenarios?: boolean;
+  /** 允许编辑图片、批注等对象 */
+  allowEditObjects?: boolean;
+}
+
+/** 工作簿结构保护配置 */
+export interface WorkbookProtectionOptions {
+  /** 取消保护所需的密码（最多 255 个字符，默认无密码） */
+  password?: string;
+}
+
+/** 纸张大小：常用纸张名称（不区分大小写）或 Excel 纸张代码（1–118） */
+export type PaperSize = 'A3' | 'A4' | 'A5' | 'B4' | 'B5' | 'Letter' | 'Legal' | 'Tabloid' | number;
+
+/** 打印设置 */
+export interface PrintOptions {
+  /** 是否横向打印（默认纵向） */
+  landscape?: boolean;
+  /** 纸张大小（默认使用打印机默认纸张） */
+  paper?: PaperSize;
+  /** 页边距（英寸，未设置的边使用 Excel 默认值） */
+  margins?: {
+    left?: number;
+    right?: number;
+    top?: number;
+    bottom?: number;
+    /** 页眉距顶端的距离 */
+    header?: number;
+    /** 页脚距底端的距离 */
+    footer?: number;
+  };
+  /** 缩放到指定页数：数字 N 表示宽度缩放到 N 页；对象中 `width` 默认 1，`height` 默认 0（不限） */
+  fitToPages?: number | { width?: number; height?: number };
+  /** 缩放百分比（10–400，不能与 `fitToPages` 同时设置） */
+  scale?: number;
+  /** 是否水平居中 */
+  centerHorizontally?: boolean;
+  /** 是否垂直居中 */
+  centerVertically?: boolean;
+  /** 是否打印网格线 */
+  gridlines?: boolean;
+  /** 是否在每页顶端重复表头行（默认 true） */
+  repeatHeaderRows?: boolean;
+  /** 页眉（最多 255 个字符），支持 `&L` / `&C` / `&R` 分区、`&P` 页码、`&N` 总页数、`&D` 日期、`&T` 时间、`&A` 工作表名 */
+  header?: string;
+  /** 页脚（语法同 `header`） */
+  footer?: string;
+}
+
+/** 行级样式配置 */
+export interface RowStyleOptions {
+  /** 斑马纹：按数据行从 1 开始计数，`odd` 作用于第 1、3、5… 行，`even` 作用于第 2、4、6… 行 */
+  zebra?: { odd?: CellStyle; even?: CellStyle };
+  /** 按顺序求值的行样式规则，后命中的规则覆盖先命中的规则，均叠加在斑马纹之上 */
+  rules?: RowStyleRule[];
+  /** 数据行默认行高（磅，0–409） */
+  height?: number;
+}
+
+/** 行样式规则 */
+export interface RowStyleRule {
+  /**
+   * 匹配条件：行数据中 `key` 字段的值与 `equals` 严格相等（`===`）时命中。
+   * 字段为 `{ value, ... }` 单元格对象时比较其 `value`。
+   */
+  when: { key: string; equals: unknown };
+  /** 命中时的行样式 */
+  style?: CellStyle;
+  /** 命中时的行高（磅，0–409），优先于默认行高 */
+  height?: number;
+}
+
+/** 自动列宽配置 */
+export interface AutoWidthOptions {
+  /** 最小列宽（字符，默认 6） */
+  min?: number;
+  /** 最大列宽（字符，默认 60，不超过 255） */
+  max?: number;
+  /** 参与计算的数据行数（默认 1000，表头与表尾行总是参与） */
+  sampleRows?: number;
+}
+
+/** 图片缩放配置 */
+export interface ImageOptions {
+  /**
+   * 缩放方式（默认 'cell'）：
+   * - 'cell'：按比例缩放到单元格大小
+   * - 'row'：按图片高度设置行高（超过 `maxHeight` 时按比例缩小）
+   */
+  fit?: 'cell' | 'row';
+  /** `fit: 'row'` 时的最大图片高度（像素，1–545，默认 120） */
+  maxHeight?: number;
+}
+
+/** Excel 批注外观配置 */
+export interface NoteOptions {
+  /** 批注作者（最多 52 个字符，显示在批注文本前） */
+  author?: string;
+  /** 批注框宽度（像素，1–2000，默认 128） */
+  width?: number;
+  /** 批注框高度（像素，1–2000，默认 74） */
+  height?: number;
+  /** 是否始终显示批注（默认 false，仅悬停时显示） */
+  visible?: boolean;
+}
+
+/** 原生 Excel 表格（ListObject）配置 */
+export interface ExcelTableOptions {
+  /**
+   * 表格样式（默认 'TableStyleMedium9'）。
+   * 支持 TableStyleLight1–21 / TableStyleMedium1–28 / TableStyleDark1–11 / TableStyleNone，
+   * 也可省略 'TableStyle' 前缀（如 'Medium2'）。
+   */
+  style?: string;
+  /** 是否显示汇总行（默认 false）；存在 aggregate 合计行时作为汇总行 */
+  totalRow?: boolean;
+  /** 是否显示镶边行（默认 true） */
+  bandedRows?: boolean;
+  /** 表格名称（用于结构化引用，默认 Table1、Table2…） */
+  name?: string;
 }
 
 // =============================================================================
 // Hook / Composable 配置接口 (React/Vue 公用)
 // =============================================================================
 
+/**
+ * export_table 的扩展选项：同一对象中混合 DOM 采集（`capture*`、`richText`、`preserveStyles`）、
+ * 工作表与工作簿（`properties`、`workbookProtection`）三类字段
+ */
+export interface DomExportOptions {
+  /** 是否将单元格内首个 `<a href>` 导出为 Excel 超链接（默认 false，仅 XLSX 有效） */
+  captureLinks?: boolean;
+  /** 转为原生 Excel 表格（仅 XLSX 有效，要求单行表头且不含合并单元格） */
+  table?: boolean | ExcelTableOptions;
+  /** 是否在表头行上启用自动筛选（仅 XLSX 有效，默认存在表头时启用） */
+  autoFilter?: boolean;
+  /** 条件格式（仅 XLSX 有效） */
+  conditionalFormats?: SheetConditionalFormat[];
+  /** 是否将单元格的 `data-note`（优先）或 `title` 属性导出为批注（默认 false） */
+  captureNotes?: boolean;
+  /** 批注外观（仅 XLSX 有效） */
+  noteOptions?: NoteOptions;
+  /** 将批注追加为 CSV 末尾的备注列（仅 CSV 有效，需开启 `captureNotes`） */
+  csvNoteColumn?: boolean | string;
+  /** CSV 方言：分隔符、引号、换行符等（仅 CSV / TSV 有效） */
+  csv?: CsvDialectOptions;
+  /** 是否将单元格内首个 `<img>` 的 data URL（PNG / JPEG / GIF）嵌入为图片（默认 false，仅 XLSX 有效） */
+  captureImages?: boolean;
+  /** 图片缩放方式（仅 XLSX 有效） */
+  imageOptions?: ImageOptions;
+  /** 是否将单元格内的粗体、颜色、删除线等内联格式导出为富文本（默认 false，仅 XLSX 有效） */
+  richText?: boolean;
+  /** 是否读取单元格的页面计算样式（字体、颜色、背景、对齐、边框）并导出（默认 false，仅 XLSX 有效） */
+  preserveStyles?: boolean;
+  /** 按内容自动计算列宽（仅 XLSX 有效） */
+  autoWidth?: boolean | AutoWidthOptions;
+  /** 工作簿文档属性（仅 XLSX 有效） */
+  properties?: DocumentProperties;
+  /** 工作表保护（仅 XLSX 有效） */
+  protection?: boolean | SheetProtectionOptions;
+  /** 打印设置（仅 XLSX 有效） */
+  print?: PrintOptions;
+  /** 冻结前 N 行（仅 XLSX 有效，默认按 `<thead>` 行数冻结） */
+  freezeRows?: number;
+  /** 冻结前 N 列（仅 XLSX 有效，默认 0） */
+  freezeCols?: number;
+  /** 工作表标签颜色，如 `#FF0000`（仅 XLSX 有效） */
+  tabColor?: string;
+  /** 显示缩放比例 10–400（仅 XLSX 有效） */
+  zoom?: number;
+  /** 工作簿结构保护（仅 XLSX 有效） */
+  workbookProtection?: boolean | WorkbookProtectionOptions;
+}
+
 /** export_table 的参数配置 */
-export interface ExportTableOptions {
+export interface ExportTableOptions extends DomExportOptions {
   /** 要导出的 HTML 表格元素的 ID */
   tableId: string;
   /** 导出文件名 */
@@ -191,7 +733,7 @@ export interface ExportTableOptions {
 }
 
 /** 多工作表导出的参数配置 */
-export interface ExportTablesXlsxOptions {
+export interface ExportTablesXlsxOptions extends WorkbookOptions {
   /** Sheet 配置数组 */
   sheets: SheetConfig[];
   /** 导出文件名 */
@@ -204,6 +746,79 @@ export interface ExportTablesXlsxOptions {
   cellStyle?: CellStyle;
 }
 
+/**
+ * 混合工作簿中的 JS 数据工作表配置
+ *
+ * 其余字段同 `export_data` 的 options（`filename`、`format`、`progressCallback` 等文件级字段忽略）
+ */
+export interface DataSheetConfig
+  extends Omit<ExportDataOptions, 'filename' | 'format' | 'progressCallback' | 'strictProgressCallback' | 'withBom' | 'csv'> {
+  /** 二维数组或对象数组 */
+  data: DataRow[];
+  /** 工作表名称（默认 `Sheet{序号}`） */
+  sheetName?: string;
+}
+
+/** 混合工作簿的工作表配置：DOM 表格（提供 `tableId`）或 JS 数据（提供 `data`），二者只能选其一 */
+export type WorkbookSheetConfig = SheetConfig | DataSheetConfig;
+
+/** `export_workbook()` / `generate_workbook_bytes()` 的工作簿级配置 */
+export interface ExportWorkbookOptions extends WorkbookOptions {
+  /** 导出文件名（默认 'export.xlsx'） */
+  filename?: string;
+  /** 进度回调函数 */
+  progressCallback?: ProgressCallback;
+  /** 回调失败是否中断导出（默认 false） */
+  strictProgressCallback?: boolean;
+  /** 全局表头样式，与各工作表的 headerStyle 合并（工作表设置优先） */
+  headerStyle?: CellStyle;
+  /** 全局数据行样式，与各工作表的 cellStyle 合并（工作表设置优先） */
+  cellStyle?: CellStyle;
+}
+
+/** 框架封装中混合工作簿导出的参数配置 */
+export interface ExportWorkbookWrapperOptions extends Omit<ExportWorkbookOptions, 'progressCallback'> {
+  /** 工作表配置数组 */
+  sheets: WorkbookSheetConfig[];
+}
+
+/**
+ * 模板绑定数据
+ *
+ * 标量字段替换 `{{field}}` 占位符（对象字段可用 `{{a.b}}` 访问），
+ * 数组字段展开 `{{#name}}` … `{{/name}}` 包围的行区域
+ */
+export type TemplateBindings = { [key: string]: TemplateBindingValue };
+
+/** 模板绑定值 */
+export type TemplateBindingValue = CellValue | TemplateBindingValue[] | TemplateBindings;
+
+/** `export_from_template()` 的配置选项 */
+export interface ExportFromTemplateOptions {
+  /** 导出文件名（默认 'table_export.xlsx'） */
+  filename?: string;
+}
+
+/** 框架封装中模板导出的参数配置 */
+export interface ExportFromTemplateWrapperOptions extends ExportFromTemplateOptions {
+  /** XLSX 模板文件字节 */
+  template: Uint8Array;
+  /** 绑定数据 */
+  bindings: TemplateBindings;
+}
+
+/** `append_sheets_to_workbook()` 的工作表配置 */
+export type AppendSheetConfig = WorkbookSheetConfig & {
+  /** 替换同名的已有工作表（保持原位置，默认 false，同名时报错） */
+  replace?: boolean;
+};
+
+/** `append_sheets_to_workbook()` 的配置选项 */
+export type AppendSheetsOptions = Pick<
+  ExportWorkbookOptions,
+  'progressCallback' | 'strictProgressCallback' | 'headerStyle' | 'cellStyle'
+>;
+
 /** 分批导出 CSV 的参数配置 */
 export interface ExportCsvBatchOptions {
   /** 要导出的 HTML 表格元素的 ID */
@@ -220,10 +835,28 @@ export interface ExportCsvBatchOptions {
   withBom?: boolean;
   /** 回调失败是否中断导出 */
   strictProgressCallback?: boolean;
+  /** 文件类型（默认 ExportFormat.Csv），决定默认分隔符、扩展名与 MIME 类型 */
+  format?: ExportFormat.Csv | ExportFormat.Tsv;
+  /** CSV 方言 */
+  csv?: CsvDialectOptions;
 }
 
+/** `export_table_to_csv_batch()` 的扩展选项 */
+export interface CsvBatchExtraOptions {
+  /** 文件类型（默认 ExportFormat.Csv） */
+  format?: ExportFormat.Csv | ExportFormat.Tsv;
+  /** CSV 方言 */
+  csv?: CsvDialectOptions;
+}
+
+/** `export_table_to_xlsx_batch()` 的扩展选项：`export_table` 扩展选项中分批导出支持的子集 */
+export type XlsxBatchExtraOptions = Pick<
+  DomExportOptions,
+  'preserveStyles' | 'autoFilter' | 'properties' | 'workbookProtection'
+>;
+
 /** 分批导出 XLSX 的参数配置 */
-export interface ExportXlsxBatchOptions {
+export interface ExportXlsxBatchOptions extends XlsxBatchExtraOptions {
   /** 要导出的 HTML 表格元素的 ID */
   tableId: string;
   /** 可选的独立 tbody ID */
@@ -243,7 +876,7 @@ export interface ExportXlsxBatchOptions {
 }
 
 /** 多工作表分批导出的参数配置 */
-export interface ExportTablesBatchOptions {
+export interface ExportTablesBatchOptions extends WorkbookOptions {
   /** Sheet 配置数组 */
   sheets: BatchSheetConfig[];
   /** 导出文件名 */
@@ -270,6 +903,48 @@ export interface SheetConfig {
   sheetName?: string;
   /** 是否排除隐藏行/列（默认 false） */
   excludeHidden?: boolean;
+  /** 是否将单元格内首个 `<a href>` 导出为超链接（默认 false，仅同步导出有效） */
+  captureLinks?: boolean;
+  /** 转为原生 Excel 表格（仅同步导出有效，要求单行表头且不含合并单元格） */
+  table?: boolean | ExcelTableOptions;
+  /** 是否在表头行上启用自动筛选（默认存在表头时启用） */
+  autoFilter?: boolean;
+  /** 条件格式（仅同步导出有效） */
+  conditionalFormats?: SheetConditionalFormat[];
+  /** 是否将单元格的 `data-note`（优先）或 `title` 属性导出为批注（默认 false，仅同步导出有效） */
+  captureNotes?: boolean;
+  /** 批注外观（仅同步导出有效） */
+  noteOptions?: NoteOptions;
+  /** 是否将单元格内首个 `<img>` 的 data URL 嵌入为图片（默认 false，仅同步导出有效） */
+  captureImages?: boolean;
+  /** 图片缩放方式（仅同步导出有效） */
+  imageOptions?: ImageOptions;
+  /** 是否将单元格内的内联格式导出为富文本（默认 false，仅同步导出有效） */
+  richText?: boolean;
+  /** 是否读取单元格的页面计算样式并导出（默认 false，同步与分批导出均有效） */
+  preserveStyles?: boolean;
+  /** 按内容自动计算列宽（仅同步导出有效） */
+  autoWidth?: boolean | AutoWidthOptions;
+  /** 工作表保护（仅同步导出有效） */
+  protection?: boolean | SheetProtectionOptions;
+  /** 打印设置（仅同步导出有效） */
+  print?: PrintOptions;
+  /** 本表表头样式，与全局 headerStyle 合并且本表设置优先（仅同步导出有效） */
+  headerStyle?: CellStyle;
+  /** 本表数据行样式，与全局 cellStyle 合并且本表设置优先（仅同步导出有效） */
+  cellStyle?: CellStyle;
+  /** 列宽（字符数 0–255，按列顺序，`null` 表示该列不设置；仅同步导出有效） */
+  columnWidths?: (number | null)[];
+  /** 冻结前 N 行（默认按 `<thead>` 行数冻结，仅同步导出有效） */
+  freezeRows?: number;
+  /** 冻结前 N 列（默认 0，仅同步导出有效） */
+  freezeCols?: number;
+  /** 工作表标签颜色，如 `#FF0000`（仅同步导出有效） */
+  tabColor?: string;
+  /** 是否隐藏工作表，至少需保留一个可见工作表（仅同步导出有效） */
+  hidden?: boolean;
+  /** 显示缩放比例 10–400（仅同步导出有效） */
+  zoom?: number;
 }
 
 /** 多工作表分批异步导出的 Sheet 配置 */
@@ -317,10 +992,11 @@ export declare function export_data(
  * @param format - 导出格式（默认 Csv）
  * @param excludeHidden - 是否排除隐藏行/列（默认 false）
  * @param progressCallback - 进度回调函数
- * @param withBom - 是否添加 UTF-8 BOM（仅 CSV 有效）
+ * @param withBom - 是否添加 UTF-8 BOM（仅 CSV / TSV 有效）
  * @param strictProgressCallback - 回调失败是否中断导出（默认 false）
  * @param headerStyle - 全局表头样式（仅 XLSX 有效）
  * @param cellStyle - 全局数据行样式（仅 XLSX 有效）
+ * @param options - 扩展选项（`captureLinks`、`captureNotes`、`table`、`csv` 等）
  * @throws 导出失败时抛出错误
  */
 export declare function export_table(
@@ -333,6 +1009,7 @@ export declare function export_table(
   strictProgressCallback?: boolean | null,
   headerStyle?: CellStyle | null,
   cellStyle?: CellStyle | null,
+  options?: DomExportOptions | null,
 ): void;
 
 /**
@@ -344,6 +1021,7 @@ export declare function export_table(
  * @param strictProgressCallback - 回调失败是否中断导出（默认 false）
  * @param headerStyle - 全局表头样式
  * @param cellStyle - 全局数据行样式
+ * @param options - 工作簿级选项（`properties`）
  * @throws 导出失败时抛出错误
  */
 export declare function export_tables_xlsx(
@@ -353,6 +1031,7 @@ export declare function export_tables_xlsx(
   strictProgressCallback?: boolean | null,
   headerStyle?: CellStyle | null,
   cellStyle?: CellStyle | null,
+  options?: WorkbookOptions | null,
 ): void;
 
 /**
@@ -365,6 +1044,8 @@ export declare function export_tables_xlsx(
  * @param excludeHidden - 是否排除隐藏行/列
  * @param progressCallback - 进度回调函数
  * @param withBom - 是否添加 UTF-8 BOM
+ * @param strictProgressCallback - 回调失败是否中断导出（默认 false）
+ * @param options - 扩展选项（`format`、`csv` 方言）
  */
 export declare function export_table_to_csv_batch(
   tableId: string,
@@ -375,6 +1056,7 @@ export declare function export_table_to_csv_batch(
   progressCallback?: ProgressCallback | null,
   withBom?: boolean | null,
   strictProgressCallback?: boolean | null,
+  options?: CsvBatchExtraOptions | null,
 ): Promise<void>;
 
 /**
@@ -389,6 +1071,7 @@ export declare function export_table_to_csv_batch(
  * @param strictProgressCallback - 回调失败是否中断导出（默认 false）
  * @param headerStyle - 全局表头样式
  * @param cellStyle - 全局数据行样式
+ * @param options - 扩展选项（`autoFilter`、`preserveStyles`、`properties`、`workbookProtection`）
  */
 export declare function export_table_to_xlsx_batch(
   tableId: string,
@@ -400,6 +1083,7 @@ export declare function export_table_to_xlsx_batch(
   strictProgressCallback?: boolean | null,
   headerStyle?: CellStyle | null,
   cellStyle?: CellStyle | null,
+  options?: XlsxBatchExtraOptions | null,
 ): Promise<void>;
 
 /**
@@ -412,6 +1096,7 @@ export declare function export_table_to_xlsx_batch(
  * @param strictProgressCallback - 回调失败是否中断导出（默认 false）
  * @param headerStyle - 全局表头样式
  * @param cellStyle - 全局数据行样式
+ * @param options - 工作簿级选项（`properties`）
  */
 export declare function export_tables_to_xlsx_batch(
   sheets: BatchSheetConfig[],
@@ -421,6 +1106,7 @@ export declare function export_tables_to_xlsx_batch(
   strictProgressCallback?: boolean | null,
   headerStyle?: CellStyle | null,
   cellStyle?: CellStyle | null,
+  options?: WorkbookOptions | null,
 ): Promise<void>;
 
 /**
@@ -450,6 +1136,110 @@ export declare function generate_data_bytes(
   options?: ExportDataOptions,
 ): Uint8Array;
 
+/**
+ * 导出混合工作簿：每个工作表来自 DOM 表格或 JS 数据
+ *
+ * 工作表名称按 Excel 规则自动修正：非法字符替换为 `_`，超过 31 个字符截断，重名时追加 `(2)` 等序号
+ *
+ * @param sheets - 工作表配置数组
+ * @param options - 工作簿级配置
+ * @throws 导出失败时抛出错误
+ *
+ * @example
+ * ```typescript
+ * export_workbook(
+ *   [
+ *     { tableId: 'summary-table', sheetName: '汇总' },
+ *     { data: orders, columns, sheetName: '订单明细', freezeCols: 1 },
+ *   ],
+ *   { filename: '月报.xlsx', headerStyle: { bold: true } }
+ * );
+ * ```
+ */
+export declare function export_workbook(
+  sheets: WorkbookSheetConfig[],
+  options?: ExportWorkbookOptions,
+): void;
+
+/**
+ * 生成混合工作簿的 XLSX 字节（不触发下载，`filename` 忽略）
+ *
+ * Web Worker 中没有 DOM，只能使用 `data` 工作表
+ *
+ * @param sheets - 工作表配置数组
+ * @param options - 工作簿级配置
+ * @returns XLSX 文件字节
+ * @throws 生成失败时抛出错误
+ */
+export declare function generate_workbook_bytes(
+  sheets: WorkbookSheetConfig[],
+  options?: ExportWorkbookOptions,
+): Uint8Array;
+
+/**
+ * 按 XLSX 模板导出：替换 `{{field}}` 占位符并展开 `{{#rows}}` 行区域，
+ * 模板的样式、图片、公式等保持不变
+ *
+ * @param templateBytes - XLSX 模板文件字节
+ * @param bindings - 绑定数据
+ * @param options - 配置选项
+ * @throws 模板无效或占位符区域不完整时抛出错误
+ *
+ * @example
+ * ```typescript
+ * const template = new Uint8Array(await (await fetch('/templates/月报.xlsx')).arrayBuffer());
+ * export_from_template(template, {
+ *   title: '2024 年 6 月销售月报',
+ *   rows: [{ name: '华东', amount: 1200 }, { name: '华南', amount: 980 }],
+ * }, { filename: '月报.xlsx' });
+ * ```
+ */
+export declare function export_from_template(
+  templateBytes: Uint8Array,
+  bindings: TemplateBindings,
+  options?: ExportFromTemplateOptions,
+): void;
+
+/**
+ * 按 XLSX 模板生成文件字节（不触发下载，可在 Web Worker 中使用）
+ *
+ * @param templateBytes - XLSX 模板文件字节
+ * @param bindings - 绑定数据
+ * @returns XLSX 文件字节
+ * @throws 模板无效或占位符区域不完整时抛出错误
+ */
+export declare function generate_template_bytes(
+  templateBytes: Uint8Array,
+  bindings: TemplateBindings,
+): Uint8Array;
+
+/**
+ * 向已有的 XLSX 文件追加工作表（不触发下载）
+ *
+ * 已有工作表的内容、样式与公式逐字节保留，新工作表追加在末尾；
+ * `replace: true` 的工作表替换同名的已有工作表
+ *
+ * @param existingBytes - 已有 XLSX 文件字节
+ * @param sheets - 工作表配置数组（DOM 表格或 JS 数据）
+ * @param options - 配置选项
+ * @returns 合并后的 XLSX 文件字节
+ * @throws 文件无效、工作表重名或生成失败时抛出错误
+ *
+ * @example
+ * ```typescript
+ * const existing = new Uint8Array(await file.arrayBuffer());
+ * const bytes = append_sheets_to_workbook(existing, [
+ *   { data: juneOrders, columns, sheetName: '6月' },
+ *   { tableId: 'summary-table', sheetName: '汇总', replace: true },
+ * ]);
+ * ```
+ */
+export declare function append_sheets_to_workbook(
+  existingBytes: Uint8Array,
+  sheets: AppendSheetConfig[],
+  options?: AppendSheetsOptions,
+): Uint8Array;
+
 // =============================================================================
 // 流式导出配置
 // =============================================================================
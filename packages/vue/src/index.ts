@@ -32,7 +32,15 @@ export type {
   ProgressCallback,
   ExportTableOptions,
   ExportTablesXlsxOptions,
+  DataSheetConfig,
+  WorkbookSheetConfig,
+  ExportWorkbookOptions,
+  ExportWorkbookWrapperOptions,
+  TemplateBindings,
+  ExportFromTemplateOptions,
+  ExportFromTemplateWrapperOptions,
   ExportCsvBatchOptions,
+  CsvDialectOptions,
   ExportXlsxBatchOptions,
   ExportTablesBatchOptions,
   PreviewOptions,
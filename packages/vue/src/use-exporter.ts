@@ -25,6 +25,8 @@ import type {
   ExportDataOptions,
   ExportTableOptions,
   ExportTablesXlsxOptions,
+  ExportWorkbookWrapperOptions,
+  ExportFromTemplateWrapperOptions,
   ExportCsvBatchOptions,
   ExportXlsxBatchOptions,
   ExportTablesBatchOptions,
@@ -145,6 +147,7 @@ export function useExporter() {
         options.strictProgressCallback,
         options.headerStyle,
         options.cellStyle,
+        options,
       );
     });
   };
@@ -169,10 +172,28 @@ export function useExporter() {
         options.strictProgressCallback,
         options.headerStyle,
         options.cellStyle,
+        options,
       );
     });
   };
 
+  /** 混合工作簿导出（DOM 表格与 JS 数据工作表） */
+  const exportWorkbook = (options: ExportWorkbookWrapperOptions) => {
+    return wrapSync(() => {
+      const { sheets, ...rest } = options;
+      wasmModule!.export_workbook(sheets, { ...rest, progressCallback: createProgressCallback() });
+    });
+  };
+
+  /** 按 XLSX 模板导出 */
+  const exportFromTemplate = (options: ExportFromTemplateWrapperOptions) => {
+    return wrapSync(() => {
+      wasmModule!.export_from_template(options.template, options.bindings, {
+        filename: options.filename,
+      });
+    });
+  };
+
   /** 分批异步导出 CSV */
   const exportCsvBatch = async (options: ExportCsvBatchOptions) => {
     return await wrapAsync(async () => {
@@ -185,6 +206,7 @@ export function useExporter() {
         createProgressCallback(),
         options.withBom,
         options.strictProgressCallback,
+        { format: options.format, csv: options.csv },
       );
     });
   };
@@ -202,6 +224,7 @@ export function useExporter() {
         options.strictProgressCallback,
         options.headerStyle,
         options.cellStyle,
+        options,
       );
     });
   };
@@ -217,6 +240,7 @@ export function useExporter() {
         options.strictProgressCallback,
         options.headerStyle,
         options.cellStyle,
+        options,
       );
     });
   };
@@ -233,6 +257,8 @@ export function useExporter() {
     exportTable,
     exportData,
     exportTablesXlsx,
+    exportWorkbook,
+    exportFromTemplate,
     exportCsvBatch,
     exportXlsxBatch,
     exportTablesBatch,
@@ -4,9 +4,12 @@
 /// 采用分块 Blob 片段策略：每个批次生成独立的 CSV 字节片段，
 /// 最后拼接成单个 Blob 下载，降低内存峰值。
 /// 支持合并单元格（colspan/rowspan）
-use crate::core::{RowSpanTracker, TableRowSources, process_row_cells};
+use crate::core::csv_dialect::{CsvDialect, parse_csv_dialect};
+use crate::core::{
+    DomCaptureOptions, ExportFormat, RowSpanTracker, TableRowSources, parse_export_format,
+    process_row_cells,
+};
 use crate::utils::{is_element_hidden, report_progress, yield_to_browser};
-use csv::Writer;
 use std::io::Cursor;
 use wasm_bindgen::prelude::*;
 
@@ -28,6 +31,9 @@ use wasm_bindgen::prelude::*;
 /// * `progress_callback` - 进度回调函数，接收进度百分比 (0-100)
 /// * `with_bom` - 可选，是否添加 UTF-8 BOM（默认为 false）
 /// * `strict_progress_callback` - 可选，是否严格报告进度（默认为 false）。如果为 true，则每次进度更新都会触发回调；如果为 false，则可能跳过一些更新以提高性能。
+/// * `options` - 可选的扩展配置对象：
+///   - `format`: `ExportFormat.Csv`（默认）或 `ExportFormat.Tsv`，决定默认分隔符、扩展名与 MIME 类型
+///   - `csv`: CSV 方言 `{ delimiter?, quote?, quoteStyle?, terminator?, escape? }`
 ///
 /// # 返回值
 /// * `Promise<void>` - 异步操作的 Promise
@@ -47,6 +53,11 @@ use wasm_bindgen::prelude::*;
 ///     },
 ///     true // 添加 BOM
 /// );
+///
+/// // 分号分隔、CRLF 换行、所有字段加引号
+/// await export_table_to_csv_batch('my-table', null, 'data.csv', 1000, false, null, true, false, {
+///     csv: { delimiter: ';', terminator: '\r\n', quoteStyle: 'always' },
+/// });
 /// ```
 #[wasm_bindgen]
 #[allow(clippy::too_many_arguments)]
@@ -59,6 +70,7 @@ pub async fn export_table_to_csv_batch(
     progress_callback: Option<js_sys::Function>,
     with_bom: Option<bool>,
     strict_progress_callback: Option<bool>,
+    options: Option<JsValue>,
 ) -> Result<JsValue, JsValue> {
     // 输入验证
     if table_id.is_empty() {
@@ -73,6 +85,20 @@ pub async fn export_table_to_csv_batch(
         return Err(JsValue::from_str("批次大小必须大于 0"));
     }
 
+    // 解析文件类型与 CSV 方言
+    let (format, dialect) = match options {
+        Some(ref obj) if !obj.is_null() && !obj.is_undefined() => {
+            let format = parse_export_format(obj)?;
+            if format == ExportFormat::Xlsx {
+                return Err(JsValue::from_str(
+                    "分批 CSV 导出仅支持 ExportFormat.Csv 或 ExportFormat.Tsv",
+                ));
+            }
+            (format, parse_csv_dialect(obj, format)?)
+        }
+        _ => (ExportFormat::Csv, CsvDialect::default()),
+    };
+
     let sources = TableRowSources::open(&table_id, tbody_id.as_deref())?;
     let total_rows = sources.total_rows();
 
@@ -103,7 +129,7 @@ pub async fn export_table_to_csv_batch(
         let batch_end = std::cmp::min(current_row + batch_size, total_rows);
 
         // 创建当前批次的 CSV Writer
-        let mut wtr = Writer::from_writer(Cursor::new(Vec::new()));
+        let mut wtr = dialect.writer(Cursor::new(Vec::new()));
 
         // 处理当前批次
         for i in current_row..batch_end {
@@ -114,7 +140,13 @@ pub async fn export_table_to_csv_batch(
                 continue;
             }
 
-            let proc_result = process_row_cells(&row, i as u32, &mut tracker, exclude_hidden)?;
+            let proc_result = process_row_cells(
+                &row,
+                i as u32,
+                &mut tracker,
+                exclude_hidden,
+                &DomCaptureOptions::default(),
+            )?;
 
             // CSV 需要转义注入字符
             let safe_row: Vec<_> = proc_result
@@ -162,7 +194,8 @@ pub async fn export_table_to_csv_batch(
     crate::core::export_csv::create_and_download_csv_parts(
         &blob_parts,
         filename,
-        "table_export.csv",
+        "table_export",
+        format,
     )?;
 
     Ok(JsValue::UNDEFINED)
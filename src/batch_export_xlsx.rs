@@ -1,15 +1,16 @@
 use crate::core::export_xlsx::write_sheet_with_progress;
-use crate::core::style::{StyleSheet, parse_cell_style};
+use crate::core::style::parse_cell_style;
+use crate::core::workbook_options::{WorkbookOptions, parse_workbook_options};
 /// XLSX 分批异步导出功能模块
 ///
 /// 提供大数据量表格的分批处理功能，避免阻塞主线程
 /// 采用两阶段策略：分批读取 DOM 数据 + 同步生成 XLSX
 use crate::core::{
-    MergeRange, RowSpanTracker, TableData, TableRowSources, create_and_download_xlsx,
-    process_row_cells,
+    CellValue, DomCaptureOptions, MergeRange, RowSpanTracker, TableData, TableRowSources,
+    create_and_download_xlsx, process_row_cells,
 };
 use crate::utils::{is_element_hidden, report_progress, yield_to_browser};
-use rust_xlsxwriter::Workbook;
+use crate::validation::sanitize_sheet_names;
 use wasm_bindgen::prelude::*;
 
 /// 分批异步导出 HTML 表格到 XLSX 文件
@@ -25,6 +26,12 @@ use wasm_bindgen::prelude::*;
 /// * `batch_size` - 每批处理的行数（默认 1000）
 /// * `exclude_hidden` - 可选，是否排除隐藏的行和列（默认为 false）
 /// * `progress_callback` - 进度回调函数，接收进度百分比 (0-100)
+/// * `strict_progress_callback` - 可选，回调失败是否中断导出（默认 false）
+/// * `header_style` / `cell_style` - 可选，全局表头 / 数据行样式
+/// * `options` - 可选的扩展配置对象，分类与 `export_table` 的 `options` 一致（同名字段含义相同）：
+///   - DOM 采集：`preserveStyles` 是否读取单元格的页面计算样式并导出（默认 false）
+///   - 工作表：`autoFilter` 是否在最后一行表头上启用自动筛选（默认存在表头时启用）
+///   - 工作簿：`properties` / `workbookProtection`（同 `export_data`）
 ///
 /// # 返回值
 /// * `Promise<void>` - 异步操作的 Promise
@@ -56,6 +63,7 @@ pub async fn export_table_to_xlsx_batch(
     strict_progress_callback: Option<bool>,
     header_style: Option<JsValue>,
     cell_style: Option<JsValue>,
+    options: Option<JsValue>,
 ) -> Result<JsValue, JsValue> {
     // 输入验证
     if table_id.is_empty() {
@@ -69,8 +77,24 @@ pub async fn export_table_to_xlsx_batch(
         return Err(JsValue::from_str("批次大小必须大于 0"));
     }
 
-    // 解析样式配置
-    let style_sheet = build_global_style_sheet(header_style.as_ref(), cell_style.as_ref());
+    // 解析样式与工作簿级配置
+    let hs = header_style.as_ref().and_then(parse_cell_style);
+    let cs = cell_style.as_ref().and_then(parse_cell_style);
+    let workbook_options = match options {
+        Some(ref obj) => parse_workbook_options(obj)?,
+        None => WorkbookOptions::default(),
+    };
+    let capture = DomCaptureOptions {
+        styles: options
+            .as_ref()
+            .is_some_and(|obj| bool_field(obj, "preserveStyles")),
+        ..Default::default()
+    };
+    let auto_filter = options.as_ref().and_then(|obj| {
+        js_sys::Reflect::get(obj, &JsValue::from_str("autoFilter"))
+            .ok()
+            .and_then(|v| v.as_bool())
+    });
 
     // 报告初始进度
     if let Some(ref callback) = progress_callback {
@@ -84,16 +108,24 @@ pub async fn export_table_to_xlsx_batch(
         tbody_id.as_deref(),
         batch_size,
         exclude_hidden,
+        &capture,
         &progress_info,
         strict,
     )
     .await?;
 
-    // 注入样式表
-    table_data.style_sheet = style_sheet;
+    // 注入全局样式（保留采集到的页面样式）
+    table_data.apply_global_styles(hs.as_ref(), cs.as_ref());
+    table_data.sheet_options.auto_filter = auto_filter;
 
     // 阶段二：同步生成 XLSX 文件（80% - 100% 进度）
-    generate_and_download_xlsx(table_data, filename, &progress_callback, strict)?;
+    generate_and_download_xlsx(
+        table_data,
+        filename,
+        &progress_callback,
+        strict,
+        &workbook_options,
+    )?;
 
     Ok(JsValue::UNDEFINED)
 }
@@ -106,8 +138,9 @@ fn generate_and_download_xlsx(
     filename: Option<String>,
     progress_callback: &Option<js_sys::Function>,
     strict: bool,
+    workbook_options: &WorkbookOptions,
 ) -> Result<(), JsValue> {
-    let mut workbook = Workbook::new();
+    let mut workbook = workbook_options.new_workbook();
     let worksheet = workbook.add_worksheet();
 
     write_sheet_with_progress(
@@ -121,9 +154,7 @@ fn generate_and_download_xlsx(
         100,
     )?;
 
-    let xlsx_bytes = workbook
-        .save_to_buffer()
-        .map_err(|e| JsValue::from_str(&format!("生成 Excel 文件失败: {}", e)))?;
+    let xlsx_bytes = workbook_options.save(&mut workbook)?;
 
     if xlsx_bytes.is_empty() {
         return Err(JsValue::from_str("没有可导出的数据"));
@@ -146,6 +177,10 @@ struct BatchSheetConfig {
     sheet_name: Option<String>,
     /// 是否排除隐藏行列
     exclude_hidden: bool,
+    /// 是否启用自动筛选（为 None 时存在表头即启用）
+    auto_filter: Option<bool>,
+    /// DOM 额外采集项（页面样式）
+    capture: DomCaptureOptions,
 }
 
 /// 从 JsValue 数组解析分批导出的工作表配置列表
@@ -196,11 +231,21 @@ fn parse_batch_sheet_configs(sheets: &JsValue) -> Result<Vec<BatchSheetConfig>,
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        // 提取 autoFilter（可选）
+        let auto_filter = js_sys::Reflect::get(&item, &JsValue::from_str("autoFilter"))
+            .ok()
+            .and_then(|v| v.as_bool());
+
         configs.push(BatchSheetConfig {
             table_id,
             tbody_id,
             sheet_name,
             exclude_hidden,
+            auto_filter,
+            capture: DomCaptureOptions {
+                styles: bool_field(&item, "preserveStyles"),
+                ..Default::default()
+            },
         });
     }
 
@@ -212,10 +257,14 @@ fn parse_batch_sheet_configs(sheets: &JsValue) -> Result<Vec<BatchSheetConfig>,
 /// 将页面上多个 HTML 表格分批异步提取后导出到同一 Excel 文件的不同工作表中
 ///
 /// # 参数
-/// * `sheets` - JS 数组，每个元素为 `{ tableId: string, tbodyId?: string, sheetName?: string, excludeHidden?: boolean }`。**注意**：如果有 `tbodyId`，此 ID 应指向**不在** `tableId` 所指表格内部的独立 `<tbody>` 元素。如果传入的 `tbody` 在 `table` 内部，会导致该使用部分数据被重复导出（一次作为 table 的一部分，一次作为独立 tbody）。
+/// * `sheets` - JS 数组，每个元素为 `{ tableId: string, tbodyId?: string, sheetName?: string, excludeHidden?: boolean, autoFilter?: boolean, preserveStyles?: boolean }`。**注意**：如果有 `tbodyId`，此 ID 应指向**不在** `tableId` 所指表格内部的独立 `<tbody>` 元素。如果传入的 `tbody` 在 `table` 内部，会导致该使用部分数据被重复导出（一次作为 table 的一部分，一次作为独立 tbody）。
 /// * `filename` - 可选的导出文件名（默认为 "table_export.xlsx"）
 /// * `batch_size` - 每批处理的行数（默认 1000）
 /// * `progress_callback` - 进度回调函数，接收进度百分比 (0-100)
+/// * `strict_progress_callback` - 可选，回调失败是否中断导出（默认 false）
+/// * `header_style` / `cell_style` - 可选，全局表头 / 数据行样式
+/// * `options` - 可选的工作簿级配置对象：`{ properties?, workbookProtection? }`（同 `export_data`）；
+///   DOM 采集（`preserveStyles`）与工作表（`autoFilter`）字段写在每个工作表配置中
 ///
 /// # 返回值
 /// * `Promise<void>` - 异步操作的 Promise
@@ -235,6 +284,7 @@ fn parse_batch_sheet_configs(sheets: &JsValue) -> Result<Vec<BatchSheetConfig>,
 /// );
 /// ```
 #[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
 pub async fn export_tables_to_xlsx_batch(
     sheets: JsValue,
     filename: Option<String>,
@@ -243,9 +293,14 @@ pub async fn export_tables_to_xlsx_batch(
     strict_progress_callback: Option<bool>,
     header_style: Option<JsValue>,
     cell_style: Option<JsValue>,
+    options: Option<JsValue>,
 ) -> Result<JsValue, JsValue> {
     // 解析工作表配置
     let configs = parse_batch_sheet_configs(&sheets)?;
+    let workbook_options = match options {
+        Some(ref obj) => parse_workbook_options(obj)?,
+        None => WorkbookOptions::default(),
+    };
 
     let batch_size = batch_size.unwrap_or(1000) as usize;
     let strict = strict_progress_callback.unwrap_or(false);
@@ -254,7 +309,8 @@ pub async fn export_tables_to_xlsx_batch(
     }
 
     // 解析样式配置
-    let style_sheet = build_global_style_sheet(header_style.as_ref(), cell_style.as_ref());
+    let hs = header_style.as_ref().and_then(parse_cell_style);
+    let cs = cell_style.as_ref().and_then(parse_cell_style);
 
     let total_sheets = configs.len();
 
@@ -281,6 +337,7 @@ pub async fn export_tables_to_xlsx_batch(
             config.tbody_id.as_deref(),
             batch_size,
             config.exclude_hidden,
+            &config.capture,
             &sheet_callback,
             strict,
         )
@@ -291,14 +348,21 @@ pub async fn export_tables_to_xlsx_batch(
             .clone()
             .unwrap_or_else(|| format!("Sheet{}", sheet_idx + 1));
 
-        // 注入样式表
-        table_data.style_sheet = style_sheet.clone();
+        // 注入全局样式（保留采集到的页面样式）
+        table_data.apply_global_styles(hs.as_ref(), cs.as_ref());
+        table_data.sheet_options.auto_filter = config.auto_filter;
 
         all_sheets_data.push((sheet_name, table_data));
     }
 
     // 阶段二：同步生成多工作表 XLSX 文件（80% - 100% 进度）
-    generate_and_download_xlsx_multi(all_sheets_data, filename, &progress_callback, strict)?;
+    generate_and_download_xlsx_multi(
+        all_sheets_data,
+        filename,
+        &progress_callback,
+        strict,
+        &workbook_options,
+    )?;
 
     Ok(JsValue::UNDEFINED)
 }
@@ -311,6 +375,7 @@ async fn extract_table_data_batch_with_offset(
     tbody_id: Option<&str>,
     batch_size: usize,
     exclude_hidden: bool,
+    capture: &DomCaptureOptions,
     progress_info: &Option<(js_sys::Function, f64, f64)>,
     strict: bool,
 ) -> Result<TableData, JsValue> {
@@ -336,7 +401,8 @@ async fn extract_table_data_batch_with_offset(
                 continue;
             }
 
-            let proc_result = process_row_cells(&row, i as u32, &mut tracker, exclude_hidden)?;
+            let proc_result =
+                process_row_cells(&row, i as u32, &mut tracker, exclude_hidden, capture)?;
 
             for (col_idx, span) in &proc_result.cell_spans {
                 let visible_rows_covered = count_visible_rows_cross_source(
@@ -365,9 +431,19 @@ async fn extract_table_data_batch_with_offset(
                         last_col,
                     ));
                 }
+
+                if let Some(ref style) = span.style {
+                    table_data.insert_cell_style(output_row_idx, *col_idx as u16, style.clone());
+                }
             }
 
-            table_data.rows.push(proc_result.row_data);
+            table_data.rows.push(
+                proc_result
+                    .row_data
+                    .into_iter()
+                    .map(CellValue::Text)
+                    .collect(),
+            );
             output_row_idx += 1;
         }
 
@@ -429,15 +505,19 @@ fn generate_and_download_xlsx_multi(
     filename: Option<String>,
     progress_callback: &Option<js_sys::Function>,
     strict: bool,
+    workbook_options: &WorkbookOptions,
 ) -> Result<(), JsValue> {
     if all_sheets_data.is_empty() {
         return Err(JsValue::from_str("没有可导出的工作表数据"));
     }
 
     let total_sheets = all_sheets_data.len();
-    let mut workbook = Workbook::new();
+    let mut workbook = workbook_options.new_workbook();
+    let sheet_names = sanitize_sheet_names(all_sheets_data.iter().map(|(name, _)| name.as_str()));
 
-    for (sheet_idx, (sheet_name, table_data)) in all_sheets_data.iter().enumerate() {
+    for (sheet_idx, ((_, table_data), sheet_name)) in
+        all_sheets_data.iter().zip(&sheet_names).enumerate()
+    {
         let worksheet = workbook.add_worksheet();
         worksheet
             .set_name(sheet_name)
@@ -457,9 +537,7 @@ fn generate_and_download_xlsx_multi(
         )?;
     }
 
-    let xlsx_bytes = workbook
-        .save_to_buffer()
-        .map_err(|e| JsValue::from_str(&format!("生成 Excel 文件失败: {}", e)))?;
+    let xlsx_bytes = workbook_options.save(&mut workbook)?;
 
     if xlsx_bytes.is_empty() {
         return Err(JsValue::from_str("没有可导出的数据"));
@@ -472,21 +550,10 @@ fn generate_and_download_xlsx_multi(
     create_and_download_xlsx(&xlsx_bytes, filename)
 }
 
-/// 从全局 headerStyle 和 cellStyle JsValue 构建 StyleSheet
-fn build_global_style_sheet(
-    header_style: Option<&JsValue>,
-    cell_style: Option<&JsValue>,
-) -> Option<StyleSheet> {
-    let hs = header_style.and_then(parse_cell_style);
-    let cs = cell_style.and_then(parse_cell_style);
-
-    if hs.is_none() && cs.is_none() {
-        return None;
-    }
-
-    Some(StyleSheet {
-        header_style: hs,
-        data_style: cs,
-        ..Default::default()
-    })
+/// 读取 JS 对象上的布尔字段（缺省或非布尔值视为 false）
+fn bool_field(obj: &JsValue, key: &str) -> bool {
+    js_sys::Reflect::get(obj, &JsValue::from_str(key))
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
 }
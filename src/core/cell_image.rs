@@ -0,0 +1,469 @@
+/// 单元格图片模块
+///
+/// DOM 单元格内 `<img>` 的 data URL（需开启 `captureImages`）与数据单元格的 `image` 字段
+/// 在 XLSX 中嵌入为图片，支持 PNG / JPEG / GIF；CSV 导出时忽略
+use super::data_export::get_object_property;
+use super::table_extractor::{CellValue, TableData};
+use rust_xlsxwriter::{Image, Worksheet};
+use std::collections::HashMap;
+use std::fmt;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+
+/// 单张图片的最大字节数（5 MiB）
+pub(crate) const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// 单个工作表内图片的累计最大字节数（50 MiB）
+pub(crate) const MAX_TOTAL_IMAGE_BYTES: usize = 50 * 1024 * 1024;
+
+/// `fit: 'row'` 时的默认最大图片高度（像素）
+const DEFAULT_MAX_HEIGHT: u32 = 120;
+
+/// 图片最大高度上限（像素，Excel 行高上限 409 磅约 545 像素）
+const MAX_HEIGHT_LIMIT: f64 = 545.0;
+
+/// 单元格图片
+#[derive(Clone)]
+pub struct CellImage {
+    /// 图片原始字节（PNG / JPEG / GIF）
+    pub data: Vec<u8>,
+    /// 替代文本（DOM 中为 `alt` 属性）
+    pub alt: Option<String>,
+}
+
+impl fmt::Debug for CellImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 不输出图片字节，只输出大小
+        f.debug_struct("CellImage")
+            .field("bytes", &self.data.len())
+            .field("alt", &self.alt)
+            .finish()
+    }
+}
+
+/// 图片缩放方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageFit {
+    /// 按比例缩放到单元格大小（默认）
+    #[default]
+    Cell,
+    /// 按图片高度（不超过 `max_height`）设置行高
+    Row,
+}
+
+/// 图片配置（`imageOptions`，仅 XLSX 导出时使用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    /// 缩放方式
+    pub fit: ImageFit,
+    /// `fit: 'row'` 时的最大图片高度（像素）
+    pub max_height: u32,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            fit: ImageFit::Cell,
+            max_height: DEFAULT_MAX_HEIGHT,
+        }
+    }
+}
+
+/// 解析 `imageOptions` 配置：`{ fit?: 'cell' | 'row', maxHeight? }`
+pub(crate) fn parse_image_options(val: &JsValue) -> Result<ImageOptions, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(ImageOptions::default());
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("imageOptions 配置必须是对象"));
+    }
+
+    let mut options = ImageOptions::default();
+
+    if let Some(fit) = get_object_property(val, "fit")?.as_string() {
+        options.fit = match fit.as_str() {
+            "cell" => ImageFit::Cell,
+            "row" => ImageFit::Row,
+            other => {
+                return Err(JsValue::from_str(&format!(
+                    "imageOptions.fit 无效: '{}'，仅支持 cell / row",
+                    other
+                )));
+            }
+        };
+    }
+
+    match get_object_property(val, "maxHeight")?.as_f64() {
+        Some(n) if n.fract() == 0.0 && (1.0..=MAX_HEIGHT_LIMIT).contains(&n) => {
+            options.max_height = n as u32;
+        }
+        Some(n) => {
+            return Err(JsValue::from_str(&format!(
+                "imageOptions.maxHeight 无效: {}，应为 1–{} 的整数",
+                n, MAX_HEIGHT_LIMIT
+            )));
+        }
+        None => {}
+    }
+
+    Ok(options)
+}
+
+/// 图片累计大小计数器，防止单个工作表嵌入过多图片导致内存暴涨
+#[derive(Debug, Default)]
+pub(crate) struct ImageBudget {
+    used: usize,
+}
+
+impl ImageBudget {
+    /// 登记一张图片，超过累计上限时返回错误（被拒绝的图片不计入已用额度）
+    pub(crate) fn take(&mut self, len: usize) -> Result<(), String> {
+        let used = self.used.saturating_add(len);
+        if used > MAX_TOTAL_IMAGE_BYTES {
+            return Err(format!(
+                "图片总大小超过上限（{} MiB）",
+                MAX_TOTAL_IMAGE_BYTES / 1024 / 1024
+            ));
+        }
+        self.used = used;
+        Ok(())
+    }
+}
+
+/// 解码 DOM 单元格内的图片
+///
+/// base64 损坏、文件头不符、图片无法解析或超出大小上限时返回 None，
+/// 由调用方跳过该图片并保留单元格文本（见 [`fill_image_alt`]）
+pub(crate) fn decode_dom_image(src: &str, budget: &mut ImageBudget) -> Option<Vec<u8>> {
+    checked_image(decode_data_url(src).ok()?, budget)
+}
+
+/// 解码数据单元格的 `image` 字段，无效图片的处理方式与 [`decode_dom_image`] 相同
+pub(crate) fn decode_data_image(val: &JsValue, budget: &mut ImageBudget) -> Option<Vec<u8>> {
+    checked_image(read_image_value(val).ok()?, budget)
+}
+
+/// 预先解析图片并登记累计大小，失败时返回 None
+fn checked_image(data: Vec<u8>, budget: &mut ImageBudget) -> Option<Vec<u8>> {
+    // 文件头正确但内容截断的图片在写入时才会失败，这里提前解析一次
+    Image::new_from_buffer(&data).ok()?;
+    budget.take(data.len()).ok()?;
+    Some(data)
+}
+
+/// 图片被跳过时，单元格没有文本则以 `alt` 代替
+pub(crate) fn fill_image_alt(cell: &mut CellValue, alt: Option<&String>) {
+    let blank = match cell {
+        CellValue::Empty => true,
+        CellValue::Text(t) => t.trim().is_empty(),
+        _ => false,
+    };
+    if let (true, Some(alt)) = (blank, alt) {
+        *cell = CellValue::Text(alt.clone());
+    }
+}
+
+/// 从 JS 值读取图片字节：`Uint8Array` 或 `data:image/...;base64,` 字符串
+fn read_image_value(val: &JsValue) -> Result<Vec<u8>, String> {
+    if let Some(array) = val.dyn_ref::<js_sys::Uint8Array>() {
+        let len = array.length() as usize;
+        check_image_size(len)?;
+        let data = array.to_vec();
+        check_image_format(&data)?;
+        return Ok(data);
+    }
+    match val.as_string() {
+        Some(src) => decode_data_url(&src),
+        None => Err("image 必须是 Uint8Array 或 data URL 字符串".to_string()),
+    }
+}
+
+/// 解码 `data:image/png|jpeg|gif;base64,...` 形式的 data URL
+pub(crate) fn decode_data_url(src: &str) -> Result<Vec<u8>, String> {
+    let payload = data_url_payload(src)?;
+
+    // 解码前按 base64 长度估算大小，避免为超大图片分配内存
+    check_image_size(payload.len() / 4 * 3)?;
+    let data = decode_base64(payload).ok_or_else(|| "base64 数据无效".to_string())?;
+    check_image_size(data.len())?;
+    check_image_format(&data)?;
+    Ok(data)
+}
+
+/// 校验 data URL 的图片类型与编码，返回 base64 数据部分
+pub(crate) fn data_url_payload(src: &str) -> Result<&str, String> {
+    let rest = src
+        .trim()
+        .strip_prefix("data:")
+        .ok_or_else(|| "仅支持 data URL 形式的图片".to_string())?;
+    let (meta, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| "data URL 格式错误".to_string())?;
+    let mime = meta
+        .strip_suffix(";base64")
+        .ok_or_else(|| "data URL 必须为 base64 编码".to_string())?;
+    if !matches!(
+        mime.to_ascii_lowercase().as_str(),
+        "image/png" | "image/jpeg" | "image/jpg" | "image/gif"
+    ) {
+        return Err(format!(
+            "不支持的图片类型: '{}'，仅支持 PNG / JPEG / GIF",
+            mime
+        ));
+    }
+    Ok(payload)
+}
+
+/// 校验单张图片大小
+fn check_image_size(len: usize) -> Result<(), String> {
+    if len > MAX_IMAGE_BYTES {
+        return Err(format!(
+            "图片过大（{} 字节），单张图片不能超过 {} MiB",
+            len,
+            MAX_IMAGE_BYTES / 1024 / 1024
+        ));
+    }
+    Ok(())
+}
+
+/// 按文件头校验图片格式（PNG / JPEG / GIF）
+fn check_image_format(data: &[u8]) -> Result<(), String> {
+    let supported = data.starts_with(b"\x89PNG\r\n\x1a\n")
+        || data.starts_with(b"\xFF\xD8\xFF")
+        || data.starts_with(b"GIF87a")
+        || data.starts_with(b"GIF89a");
+    if !supported {
+        return Err("无法识别的图片格式，仅支持 PNG / JPEG / GIF".to_string());
+    }
+    Ok(())
+}
+
+/// 解码标准 base64（忽略空白字符，允许省略末尾 `=`）
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+    let mut buf = 0u32;
+    let mut bits = 0u32;
+    let mut padding = false;
+
+    for &c in input.as_bytes() {
+        if c.is_ascii_whitespace() {
+            continue;
+        }
+        if c == b'=' {
+            padding = true;
+            continue;
+        }
+        // 填充符之后不能再出现数据
+        if padding {
+            return None;
+        }
+        buf = (buf << 6) | value(c)?;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buf >> bits) as u8);
+            buf &= (1 << bits) - 1;
+        }
+    }
+
+    // 剩余不足 8 位的只能是编码填充的 0 位（最多 4 位）
+    if bits >= 6 || buf != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+/// 将图片写入工作表
+pub(crate) fn apply_images(
+    worksheet: &mut Worksheet,
+    table_data: &TableData,
+) -> Result<(), JsValue> {
+    if table_data.images.is_empty() {
+        return Ok(());
+    }
+
+    let options = &table_data.sheet_options.image_options;
+    let mut row_heights: HashMap<u32, u32> = HashMap::new();
+
+    for (&(row, col), cell_image) in &table_data.images {
+        let mut image = Image::new_from_buffer(&cell_image.data)
+            .map_err(|e| JsValue::from_str(&format!("写入图片失败: {}", e)))?;
+        if let Some(ref alt) = cell_image.alt {
+            image = image.set_alt_text(alt);
+        }
+
+        match options.fit {
+            ImageFit::Cell => {
+                worksheet
+                    .insert_image_fit_to_cell(row, col, &image, true)
+                    .map_err(|e| JsValue::from_str(&format!("写入图片失败: {}", e)))?;
+            }
+            ImageFit::Row => {
+                let height = image.height().min(options.max_height as f64);
+                if image.height() > height {
+                    let scale = height / image.height();
+                    image = image.set_scale_height(scale).set_scale_width(scale);
+                }
+                worksheet
+                    .insert_image(row, col, &image)
+                    .map_err(|e| JsValue::from_str(&format!("写入图片失败: {}", e)))?;
+                let entry = row_heights.entry(row).or_default();
+                *entry = (*entry).max(height.ceil() as u32);
+            }
+        }
+    }
+
+    for (row, height) in row_heights {
+        worksheet
+            .set_row_height_pixels(row, height)
+            .map_err(|e| JsValue::from_str(&format!("设置行高失败: {}", e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::{generate, part_xml};
+
+    /// 1x1 透明 PNG
+    const PNG_1X1: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("aGVsbG8").unwrap(), b"hello");
+        assert_eq!(decode_base64("aGVs\nbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert!(decode_base64("aGVsbG8=x").is_none());
+        assert!(decode_base64("a$==").is_none());
+        assert!(decode_base64("a").is_none());
+    }
+
+    #[test]
+    fn test_decode_data_url() {
+        let png = decode_data_url(&format!("data:image/png;base64,{}", PNG_1X1)).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        assert!(decode_data_url("https://example.com/a.png").is_err());
+        assert!(decode_data_url("data:image/svg+xml;base64,PHN2Zz4=").is_err());
+        assert!(decode_data_url("data:image/png,raw").is_err());
+        // 声明为 PNG 但内容不是图片
+        assert!(decode_data_url("data:image/png;base64,aGVsbG8=").is_err());
+    }
+
+    #[test]
+    fn test_image_budget() {
+        let mut budget = ImageBudget::default();
+        assert!(budget.take(MAX_TOTAL_IMAGE_BYTES - 10).is_ok());
+        assert!(budget.take(20).is_err());
+        // 被拒绝的图片不占用额度
+        assert!(budget.take(10).is_ok());
+        assert!(budget.take(1).is_err());
+    }
+
+    #[test]
+    fn test_decode_dom_image_skips_invalid_images() {
+        let mut budget = ImageBudget::default();
+        let src = format!("data:image/png;base64,{}", PNG_1X1);
+        assert!(
+            decode_dom_image(&src, &mut budget)
+                .unwrap()
+                .starts_with(b"\x89PNG")
+        );
+
+        // base64 损坏
+        assert!(decode_dom_image("data:image/png;base64,@@@@", &mut budget).is_none());
+        // 文件头不符
+        assert!(decode_dom_image("data:image/png;base64,aGVsbG8=", &mut budget).is_none());
+        // 文件头正确但内容截断
+        assert!(decode_dom_image("data:image/png;base64,iVBORw0KGgo=", &mut budget).is_none());
+
+        // 超出累计上限的图片被跳过，额度未耗尽前后续图片不受影响
+        let mut budget = ImageBudget::default();
+        budget.take(MAX_TOTAL_IMAGE_BYTES - 10).unwrap();
+        assert!(decode_dom_image(&src, &mut budget).is_none());
+        budget = ImageBudget::default();
+        assert!(decode_dom_image(&src, &mut budget).is_some());
+    }
+
+    #[test]
+    fn test_fill_image_alt() {
+        let alt = "缩略图".to_string();
+        let mut cell = CellValue::Empty;
+        fill_image_alt(&mut cell, Some(&alt));
+        assert_eq!(cell, CellValue::from("缩略图"));
+
+        let mut cell = CellValue::from("  ");
+        fill_image_alt(&mut cell, Some(&alt));
+        assert_eq!(cell, CellValue::from("缩略图"));
+
+        // 已有文本或没有 alt 时保持原样
+        let mut cell = CellValue::from("商品");
+        fill_image_alt(&mut cell, Some(&alt));
+        assert_eq!(cell, CellValue::from("商品"));
+        let mut cell = CellValue::Empty;
+        fill_image_alt(&mut cell, None);
+        assert_eq!(cell, CellValue::Empty);
+    }
+
+    #[test]
+    fn test_write_sheet_images() {
+        let png = decode_base64(PNG_1X1).unwrap();
+        let mut table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("图片")],
+                vec![CellValue::Empty],
+                vec![CellValue::Empty],
+            ],
+            header_row_count: 1,
+            ..Default::default()
+        };
+        for row in [1, 2] {
+            table_data.images.insert(
+                (row, 0),
+                CellImage {
+                    data: png.clone(),
+                    alt: Some("缩略图".to_string()),
+                },
+            );
+        }
+        table_data.sheet_options.image_options.fit = ImageFit::Row;
+
+        let bytes = generate(&table_data);
+        let drawing = part_xml(&bytes, "xl/drawings/drawing1.xml");
+        let sheet = part_xml(&bytes, "xl/worksheets/sheet1.xml");
+
+        let pics = drawing.find_all("pic");
+        assert_eq!(pics.len(), 2);
+        for pic in pics {
+            assert_eq!(pic.find("cNvPr").unwrap().attr("descr"), Some("缩略图"));
+        }
+        // 图片锚定在各自单元格（0 起始行号）
+        let anchor_rows: Vec<_> = drawing
+            .find_all("from")
+            .iter()
+            .map(|from| from.child("row").unwrap().text.as_str())
+            .collect();
+        assert_eq!(anchor_rows, vec!["1", "2"]);
+        // 行高按图片高度（1 像素）设置
+        for r in ["2", "3"] {
+            let row = sheet.find_by("row", "r", r).unwrap();
+            assert_eq!(row.attr("customHeight"), Some("1"));
+            assert_eq!(row.attr("ht"), Some("0.75"));
+        }
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        assert!(archive.by_name("xl/media/image1.png").is_ok());
+    }
+}
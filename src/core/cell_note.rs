@@ -0,0 +1,254 @@
+/// 单元格批注模块
+///
+/// DOM 单元格的 `data-note` / `title` 属性、数据单元格的 `note` 字段与列配置的 `headerNote`
+/// 在 XLSX 中写为 Excel 批注（Note）；CSV 默认忽略，可通过 `csvNoteColumn` 追加为备注列
+use super::data_export::get_object_property;
+use super::table_extractor::TableData;
+use rust_xlsxwriter::{Note, Worksheet};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// 批注作者名最大长度（Excel 限制）
+const MAX_AUTHOR_LEN: usize = 52;
+
+/// 批注框宽高上限（像素）
+const MAX_NOTE_SIZE: f64 = 2000.0;
+
+/// `csvNoteColumn: true` 时备注列的默认标题
+const DEFAULT_NOTE_COLUMN_TITLE: &str = "备注";
+
+/// 批注外观配置（`noteOptions`，仅 XLSX 导出时使用）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NoteOptions {
+    /// 批注作者（显示在批注文本前）
+    pub author: Option<String>,
+    /// 批注框宽度（像素，默认 128）
+    pub width: Option<u32>,
+    /// 批注框高度（像素，默认 74）
+    pub height: Option<u32>,
+    /// 是否始终显示批注（默认仅悬停时显示）
+    pub visible: bool,
+}
+
+/// 解析 `noteOptions` 配置：`{ author?, width?, height?, visible? }`
+pub(crate) fn parse_note_options(val: &JsValue) -> Result<NoteOptions, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(NoteOptions::default());
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("noteOptions 配置必须是对象"));
+    }
+
+    let author = get_object_property(val, "author")?.as_string();
+    if let Some(ref name) = author {
+        check_author(name).map_err(|e| JsValue::from_str(&e))?;
+    }
+
+    let size = |key: &str| -> Result<Option<u32>, JsValue> {
+        match get_object_property(val, key)?.as_f64() {
+            Some(n) if n.fract() == 0.0 && (1.0..=MAX_NOTE_SIZE).contains(&n) => Ok(Some(n as u32)),
+            Some(n) => Err(JsValue::from_str(&format!(
+                "noteOptions.{} 无效: {}，应为 1–{} 的整数",
+                key, n, MAX_NOTE_SIZE
+            ))),
+            None => Ok(None),
+        }
+    };
+
+    Ok(NoteOptions {
+        author,
+        width: size("width")?,
+        height: size("height")?,
+        visible: get_object_property(val, "visible")?
+            .as_bool()
+            .unwrap_or(false),
+    })
+}
+
+/// 校验批注作者名长度
+fn check_author(name: &str) -> Result<(), String> {
+    if name.chars().count() > MAX_AUTHOR_LEN {
+        return Err(format!(
+            "noteOptions.author 过长，不能超过 {} 个字符",
+            MAX_AUTHOR_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// 解析 `csvNoteColumn` 配置，返回备注列标题
+///
+/// - `true`：追加标题为「备注」的列
+/// - 非空字符串：使用该字符串作为列标题
+/// - 其他值：不追加
+pub(crate) fn parse_csv_note_column(obj: &JsValue) -> Option<String> {
+    let val = js_sys::Reflect::get(obj, &JsValue::from_str("csvNoteColumn")).ok()?;
+    match val.as_bool() {
+        Some(true) => Some(DEFAULT_NOTE_COLUMN_TITLE.to_string()),
+        Some(false) => None,
+        None => val.as_string().filter(|s| !s.is_empty()),
+    }
+}
+
+/// 将批注写入工作表
+pub(crate) fn apply_notes(
+    worksheet: &mut Worksheet,
+    table_data: &TableData,
+) -> Result<(), JsValue> {
+    if table_data.notes.is_empty() {
+        return Ok(());
+    }
+
+    let options = &table_data.sheet_options.note_options;
+    let mut template = Note::new("").set_visible(options.visible);
+    if let Some(ref author) = options.author {
+        template = template.set_author(author);
+    }
+    if let Some(width) = options.width {
+        template = template.set_width(width);
+    }
+    if let Some(height) = options.height {
+        template = template.set_height(height);
+    }
+
+    for (&(row, col), text) in &table_data.notes {
+        let mut note = template.clone();
+        note.reset_text(text);
+        worksheet
+            .insert_note(row, col, &note)
+            .map_err(|e| JsValue::from_str(&format!("写入批注失败: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// 将批注追加为 CSV 末尾的备注列
+///
+/// 最后一行表头写入列标题；数据行的多个批注以 `; ` 拼接，有表头时前缀所在列的列名。
+/// 表头单元格自身的批注不输出
+pub(crate) fn append_note_column(
+    rows: &mut [Vec<String>],
+    notes: &HashMap<(u32, u16), String>,
+    header_row_count: usize,
+    title: &str,
+) {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+    // 按行分组，行内按列排序
+    let mut by_row: HashMap<usize, Vec<(u16, &str)>> = HashMap::new();
+    for (&(row, col), text) in notes {
+        by_row
+            .entry(row as usize)
+            .or_default()
+            .push((col, text.as_str()));
+    }
+
+    let labels: Vec<String> = header_row_count
+        .checked_sub(1)
+        .and_then(|last| rows.get(last))
+        .cloned()
+        .unwrap_or_default();
+
+    for (row_idx, row) in rows.iter_mut().enumerate() {
+        let cell = if header_row_count > 0 && row_idx + 1 == header_row_count {
+            title.to_string()
+        } else if row_idx < header_row_count {
+            String::new()
+        } else {
+            let mut row_notes = by_row.remove(&row_idx).unwrap_or_default();
+            row_notes.sort_by_key(|(col, _)| *col);
+            row_notes
+                .into_iter()
+                .map(|(col, text)| match labels.get(col as usize) {
+                    Some(label) if !label.is_empty() => format!("{}: {}", label, text),
+                    _ => text.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("; ")
+        };
+        row.resize(width, String::new());
+        row.push(cell);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::table_extractor::CellValue;
+    use crate::core::test_support::{generate, part_xml};
+
+    #[test]
+    fn test_append_note_column() {
+        let mut rows = vec![
+            vec!["姓名".to_string(), "年龄".to_string()],
+            vec!["张三".to_string(), "28".to_string()],
+            vec!["李四".to_string()],
+        ];
+        let notes = HashMap::from([
+            ((0, 0), "表头批注".to_string()),
+            ((1, 1), "周岁".to_string()),
+            ((1, 0), "组长".to_string()),
+            ((2, 0), "实习".to_string()),
+        ]);
+        append_note_column(&mut rows, &notes, 1, "备注");
+
+        assert_eq!(rows[0], vec!["姓名", "年龄", "备注"]);
+        assert_eq!(rows[1], vec!["张三", "28", "姓名: 组长; 年龄: 周岁"]);
+        // 短行补齐，备注列保持对齐
+        assert_eq!(rows[2], vec!["李四", "", "姓名: 实习"]);
+
+        // 无表头时不加列名前缀
+        let mut rows = vec![vec!["A".to_string()]];
+        append_note_column(
+            &mut rows,
+            &HashMap::from([((0, 0), "n".to_string())]),
+            0,
+            "备注",
+        );
+        assert_eq!(rows[0], vec!["A", "n"]);
+    }
+
+    #[test]
+    fn test_write_sheet_notes() {
+        let mut table_data = TableData {
+            rows: vec![vec![CellValue::from("姓名")], vec![CellValue::from("张三")]],
+            header_row_count: 1,
+            ..Default::default()
+        };
+        table_data.notes.insert((0, 0), "填写全名".to_string());
+        table_data.notes.insert((1, 0), "组长".to_string());
+        table_data.sheet_options.note_options = NoteOptions {
+            author: Some("HR".to_string()),
+            width: Some(200),
+            ..Default::default()
+        };
+
+        let bytes = generate(&table_data);
+        let comments = part_xml(&bytes, "xl/comments1.xml");
+        let authors: Vec<_> = comments
+            .find_all("author")
+            .iter()
+            .map(|a| a.text.as_str())
+            .collect();
+        let text_of = |cell: &str| {
+            let comment = comments.find_by("comment", "ref", cell).unwrap();
+            let author_id: usize = comment.attr("authorId").unwrap().parse().unwrap();
+            assert_eq!(authors[author_id], "HR");
+            comment
+                .find_all("t")
+                .iter()
+                .map(|t| t.text.as_str())
+                .collect::<String>()
+        };
+        // 批注正文前带作者名前缀
+        assert_eq!(text_of("A1"), "HR:\n填写全名");
+        assert_eq!(text_of("A2"), "HR:\n组长");
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        assert!(archive.by_name("xl/drawings/vmlDrawing1.vml").is_ok());
+    }
+
+    #[test]
+    fn test_check_author() {
+        assert!(check_author("张三").is_ok());
+        assert!(check_author(&"a".repeat(53)).is_err());
+    }
+}
@@ -0,0 +1,291 @@
+/// 自动列宽模块
+///
+/// 按表头与抽样数据行的内容估算列宽（东亚全角字符按 2 个单位计，并按字号缩放），
+/// 用于未显式配置 `width` 的列，仅 XLSX 导出时使用
+use super::data_export::get_object_property;
+use super::table_extractor::TableData;
+use std::collections::HashSet;
+use wasm_bindgen::prelude::*;
+
+/// Excel 允许的最大列宽（字符）
+const EXCEL_MAX_WIDTH: f64 = 255.0;
+
+/// Excel 默认字号（磅），列宽以该字号下的字符宽度为单位
+const DEFAULT_FONT_SIZE: f64 = 11.0;
+
+/// 文本两侧留白（字符）
+const PADDING: f64 = 2.0;
+
+/// 自动列宽配置（`autoWidth`）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoWidthOptions {
+    /// 最小列宽（字符，默认 6）
+    pub min: f64,
+    /// 最大列宽（字符，默认 60）
+    pub max: f64,
+    /// 参与计算的数据行数（表头与表尾行总是参与，默认 1000）
+    pub sample_rows: usize,
+}
+
+impl Default for AutoWidthOptions {
+    fn default() -> Self {
+        Self {
+            min: 6.0,
+            max: 60.0,
+            sample_rows: 1000,
+        }
+    }
+}
+
+/// 解析 `autoWidth` 配置
+///
+/// - `true`：使用默认配置
+/// - 对象：`{ min?, max?, sampleRows? }`
+/// - `false` / `null` / `undefined`：不启用
+pub(crate) fn parse_auto_width(val: &JsValue) -> Result<Option<AutoWidthOptions>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(enabled) = val.as_bool() {
+        return Ok(enabled.then(AutoWidthOptions::default));
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("autoWidth 配置必须是布尔值或对象"));
+    }
+
+    let mut options = AutoWidthOptions::default();
+    if let Some(min) = get_object_property(val, "min")?.as_f64() {
+        options.min = min;
+    }
+    if let Some(max) = get_object_property(val, "max")?.as_f64() {
+        options.max = max;
+    }
+    match get_object_property(val, "sampleRows")?.as_f64() {
+        Some(n) if n.fract() == 0.0 && n >= 0.0 => options.sample_rows = n as usize,
+        Some(n) => {
+            return Err(JsValue::from_str(&format!(
+                "autoWidth.sampleRows 无效: {}，应为非负整数",
+                n
+            )));
+        }
+        None => {}
+    }
+
+    check_auto_width(&options).map_err(|e| JsValue::from_str(&e))?;
+    Ok(Some(options))
+}
+
+/// 校验列宽范围：0 ≤ min ≤ max ≤ 255
+fn check_auto_width(options: &AutoWidthOptions) -> Result<(), String> {
+    let valid = |w: f64| w.is_finite() && (0.0..=EXCEL_MAX_WIDTH).contains(&w);
+    if !valid(options.min) || !valid(options.max) {
+        return Err(format!(
+            "autoWidth 的 min / max 应在 0–{} 之间",
+            EXCEL_MAX_WIDTH
+        ));
+    }
+    if options.min > options.max {
+        return Err(format!(
+            "autoWidth 的 min ({}) 不能大于 max ({})",
+            options.min, options.max
+        ));
+    }
+    Ok(())
+}
+
+/// 是否为东亚全角字符（中日韩文字、全角标点、表情等，显示宽度按 2 计）
+fn is_wide_char(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD
+    )
+}
+
+/// 文本的显示宽度（多行文本取最长一行）
+fn display_width(text: &str) -> usize {
+    text.lines()
+        .map(|line| {
+            line.chars()
+                .map(|c| if is_wide_char(c) { 2 } else { 1 })
+                .sum::<usize>()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// 计算需要自动列宽的列宽度（索引 = 列号，None 表示不设置）
+///
+/// 工作表级 `autoWidth` 作用于所有列，列配置 `autoWidth` 只作用于该列；
+/// 显式配置了 `width` 的列保持不变。横向合并的单元格不参与计算
+pub(crate) fn auto_column_widths(table_data: &TableData, total_cols: usize) -> Vec<Option<f64>> {
+    let sheet_auto = table_data.sheet_options.auto_width;
+    if sheet_auto.is_none() && table_data.auto_width_columns.is_empty() {
+        return Vec::new();
+    }
+    let options = sheet_auto.unwrap_or_default();
+    let style_sheet = table_data.style_sheet.as_ref();
+
+    let mut targets = vec![sheet_auto.is_some(); total_cols];
+    for &col in &table_data.auto_width_columns {
+        if let Some(t) = targets.get_mut(col as usize) {
+            *t = true;
+        }
+    }
+    if let Some(ss) = style_sheet {
+        for (col, width) in ss.column_widths.iter().enumerate() {
+            if width.is_some()
+                && let Some(t) = targets.get_mut(col)
+            {
+                *t = false;
+            }
+        }
+    }
+
+    let spanned: HashSet<(u32, u16)> = table_data
+        .merge_ranges
+        .iter()
+        .filter(|m| m.last_col > m.first_col)
+        .map(|m| (m.first_row, m.first_col))
+        .collect();
+
+    // 表头 + 前 N 行数据 + 表尾
+    let hrc = table_data.header_row_count.min(table_data.rows.len());
+    let footer_start = table_data
+        .rows
+        .len()
+        .saturating_sub(table_data.footer_row_count)
+        .max(hrc);
+    let sample_end = hrc.saturating_add(options.sample_rows).min(footer_start);
+    let rows = (0..sample_end).chain(footer_start..table_data.rows.len());
+
+    let mut widths: Vec<f64> = vec![0.0; total_cols];
+    for i in rows {
+        let row = i as u32;
+        for (j, cell) in table_data.rows[i].iter().enumerate() {
+            if !targets.get(j).copied().unwrap_or(false) || spanned.contains(&(row, j as u16)) {
+                continue;
+            }
+            let units = display_width(&cell.as_text());
+            if units == 0 {
+                continue;
+            }
+            let font_size = style_sheet
+                .and_then(|ss| ss.resolve_style(row, j as u16, table_data.header_row_count))
+                .and_then(|s| s.font_size)
+                .unwrap_or(DEFAULT_FONT_SIZE);
+            let width = units as f64 * font_size / DEFAULT_FONT_SIZE + PADDING;
+            widths[j] = widths[j].max(width);
+        }
+    }
+
+    targets
+        .into_iter()
+        .zip(widths)
+        .map(|(target, width)| target.then(|| width.clamp(options.min, options.max)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::style::{CellStyle, StyleSheet};
+    use crate::core::table_extractor::{CellValue, MergeRange};
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("姓名"), 4);
+        assert_eq!(display_width("ＡＢ，。"), 8);
+        assert_eq!(display_width("한글"), 4);
+        assert_eq!(display_width("短\n较长的一行"), 10);
+        assert_eq!(display_width(""), 0);
+    }
+
+    #[test]
+    fn test_check_auto_width() {
+        assert!(check_auto_width(&AutoWidthOptions::default()).is_ok());
+        let bad = |min, max| AutoWidthOptions {
+            min,
+            max,
+            ..Default::default()
+        };
+        assert!(check_auto_width(&bad(10.0, 5.0)).is_err());
+        assert!(check_auto_width(&bad(-1.0, 5.0)).is_err());
+        assert!(check_auto_width(&bad(0.0, 300.0)).is_err());
+        assert!(check_auto_width(&bad(f64::NAN, 5.0)).is_err());
+    }
+
+    #[test]
+    fn test_auto_column_widths() {
+        let mut table_data = TableData {
+            rows: vec![
+                vec![
+                    CellValue::from("基本信息"),
+                    CellValue::Empty,
+                    CellValue::from("备注"),
+                ],
+                vec![
+                    CellValue::from("订单号"),
+                    CellValue::from("客户名称"),
+                    CellValue::from("x"),
+                ],
+                vec![
+                    CellValue::from("SO-2024-000001"),
+                    CellValue::from("张三"),
+                    CellValue::from("y"),
+                ],
+                vec![
+                    CellValue::from("很长".repeat(20)),
+                    CellValue::Empty,
+                    CellValue::Empty,
+                ],
+            ],
+            merge_ranges: vec![MergeRange::new(0, 0, 0, 1)],
+            header_row_count: 2,
+            ..Default::default()
+        };
+        // 未启用时不计算
+        assert!(auto_column_widths(&table_data, 3).is_empty());
+
+        table_data.sheet_options.auto_width = Some(AutoWidthOptions {
+            sample_rows: 1,
+            ..Default::default()
+        });
+        table_data.style_sheet = Some(StyleSheet {
+            column_widths: vec![None, None, Some(30.0)],
+            header_style: Some(CellStyle {
+                font_size: Some(22.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let widths = auto_column_widths(&table_data, 3);
+        // 第 1 列：合并表头「基本信息」与超出抽样的长值不参与，
+        // 表头「订单号」6 × 2 倍字号 + 2 = 14，数据「SO-2024-000001」14 + 2 = 16
+        assert_eq!(widths[0], Some(16.0));
+        // 第 2 列：表头「客户名称」8 × 2 倍字号 + 2 = 18
+        assert_eq!(widths[1], Some(18.0));
+        // 显式 width 的列不变
+        assert_eq!(widths[2], None);
+
+        // 只启用列级 autoWidth：长值按默认最大宽度截断
+        table_data.sheet_options.auto_width = None;
+        table_data.style_sheet = None;
+        table_data.auto_width_columns = vec![0];
+        let widths = auto_column_widths(&table_data, 3);
+        assert_eq!(widths, vec![Some(60.0), None, None]);
+    }
+}
@@ -0,0 +1,240 @@
+/// 页面计算样式采集模块
+///
+/// 通过 `getComputedStyle` 读取单元格在页面上的最终样式，映射为 CellStyle，
+/// 使导出的 Excel 与页面显示效果保持一致
+use super::style::{BorderConfig, BorderLine, CellStyle, HAlign, VAlign, parse_css_color};
+use web_sys::Element;
+
+/// 浏览器通用字体族（Excel 中无对应字体，跳过）
+const GENERIC_FONT_FAMILIES: &[&str] = &[
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+    "ui-serif",
+    "ui-sans-serif",
+    "ui-monospace",
+    "ui-rounded",
+    "emoji",
+    "math",
+    "fangsong",
+    "blinkmacsystemfont",
+];
+
+/// 读取单元格的计算样式并转换为 CellStyle
+///
+/// 单元格背景透明时回退为所在行的背景色（斑马纹等通常设置在 `<tr>` 上）。
+/// 与 Excel 默认效果一致的属性（常规字重、黑色文字、无边框等）不写入，
+/// 以便全局 headerStyle / cellStyle 中的对应设置继续生效
+pub(crate) fn computed_cell_style(cell: &Element) -> Option<CellStyle> {
+    let window = web_sys::window()?;
+    let style = window.get_computed_style(cell).ok()??;
+    let prop = |name: &str| {
+        style
+            .get_property_value(name)
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default()
+    };
+    let mut result = css_to_cell_style(prop);
+
+    if result.background_color.is_none()
+        && let Some(row) = cell.parent_element()
+        && let Ok(Some(row_style)) = window.get_computed_style(&row)
+        && let Ok(background) = row_style.get_property_value("background-color")
+    {
+        result.background_color = parse_css_color(background.trim());
+    }
+
+    (!result.is_empty()).then_some(result)
+}
+
+/// 由计算样式属性值推导单元格样式（计算值中的关键字均为小写，未设置时为空串）
+fn css_to_cell_style(prop: impl Fn(&str) -> String) -> CellStyle {
+    let bold = match prop("font-weight").as_str() {
+        "bold" | "bolder" => Some(true),
+        w => w.parse::<f64>().ok().filter(|w| *w >= 600.0).map(|_| true),
+    };
+    let italic = matches!(prop("font-style").as_str(), "italic" | "oblique").then_some(true);
+    // 像素换算为磅（1px = 0.75pt），按 0.5 磅取整
+    let font_size = prop("font-size")
+        .strip_suffix("px")
+        .and_then(|n| n.parse::<f64>().ok())
+        .filter(|px| *px > 0.0)
+        .map(|px| (px * 0.75 * 2.0).round() / 2.0);
+    let font_color = parse_css_color(&prop("color")).filter(|c| c != "#000000");
+    let align = match prop("text-align").as_str() {
+        "center" | "-webkit-center" | "-moz-center" | "-internal-center" => Some(HAlign::Center),
+        "right" | "end" | "-webkit-right" | "-moz-right" => Some(HAlign::Right),
+        "left" | "-webkit-left" | "-moz-left" => Some(HAlign::Left),
+        _ => None,
+    };
+    let vertical_align = match prop("vertical-align").as_str() {
+        "top" | "text-top" => Some(VAlign::Top),
+        "middle" => Some(VAlign::Center),
+        "bottom" | "text-bottom" => Some(VAlign::Bottom),
+        _ => None,
+    };
+
+    let side = |name: &str| {
+        border_line(
+            &prop(&format!("border-{}-style", name)),
+            &prop(&format!("border-{}-width", name)),
+        )
+    };
+    let (top, bottom, left, right) = (side("top"), side("bottom"), side("left"), side("right"));
+    let has_border = top.is_some() || bottom.is_some() || left.is_some() || right.is_some();
+    let border = has_border.then_some(BorderConfig::Individual {
+        top,
+        bottom,
+        left,
+        right,
+    });
+
+    CellStyle {
+        bold,
+        italic,
+        font_size,
+        font_name: font_family(&prop("font-family")),
+        font_color,
+        background_color: parse_css_color(&prop("background-color")),
+        align,
+        vertical_align,
+        border,
+        ..Default::default()
+    }
+}
+
+/// 取字体族列表中第一个具体字体名（跳过通用字体族与浏览器私有字体）
+fn font_family(value: &str) -> Option<String> {
+    value
+        .split(',')
+        .map(|name| name.trim().trim_matches(|c| c == '"' || c == '\''))
+        .find(|name| {
+            !name.is_empty()
+                && !name.starts_with('-')
+                && !GENERIC_FONT_FAMILIES.contains(&name.to_ascii_lowercase().as_str())
+        })
+        .map(str::to_string)
+}
+
+/// 由边框样式与宽度推导 Excel 边框线条（宽度 ≤1px 细线，≤2px 中等，其余粗线）
+fn border_line(style: &str, width: &str) -> Option<BorderLine> {
+    let width = width
+        .strip_suffix("px")
+        .and_then(|n| n.parse::<f64>().ok())
+        .unwrap_or(0.0);
+    if width <= 0.0 {
+        return None;
+    }
+    match style {
+        "" | "none" | "hidden" => None,
+        "dashed" => Some(BorderLine::Dashed),
+        "dotted" => Some(BorderLine::Dotted),
+        "double" => Some(BorderLine::Double),
+        _ if width <= 1.0 => Some(BorderLine::Thin),
+        _ if width <= 2.0 => Some(BorderLine::Medium),
+        _ => Some(BorderLine::Thick),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn style_of(props: &[(&str, &str)]) -> CellStyle {
+        let map: HashMap<&str, &str> = props.iter().copied().collect();
+        css_to_cell_style(|name| map.get(name).copied().unwrap_or_default().to_string())
+    }
+
+    #[test]
+    fn test_css_to_cell_style() {
+        let style = style_of(&[
+            ("font-weight", "700"),
+            ("font-style", "italic"),
+            ("font-size", "14.6667px"),
+            (
+                "font-family",
+                "-apple-system, BlinkMacSystemFont, \"Microsoft YaHei\", sans-serif",
+            ),
+            ("color", "rgb(255, 255, 255)"),
+            ("background-color", "rgb(64, 158, 255)"),
+            ("text-align", "-internal-center"),
+            ("vertical-align", "middle"),
+            ("border-top-style", "solid"),
+            ("border-top-width", "1px"),
+            ("border-bottom-style", "dashed"),
+            ("border-bottom-width", "2px"),
+            ("border-left-style", "solid"),
+            ("border-left-width", "3px"),
+            ("border-right-style", "none"),
+            ("border-right-width", "1px"),
+        ]);
+        assert_eq!(style.bold, Some(true));
+        assert_eq!(style.italic, Some(true));
+        assert_eq!(style.font_size, Some(11.0));
+        assert_eq!(style.font_name.as_deref(), Some("Microsoft YaHei"));
+        assert_eq!(style.font_color.as_deref(), Some("#FFFFFF"));
+        assert_eq!(style.background_color.as_deref(), Some("#409EFF"));
+        assert_eq!(style.align, Some(HAlign::Center));
+        assert_eq!(style.vertical_align, Some(VAlign::Center));
+        assert_eq!(
+            style.border,
+            Some(BorderConfig::Individual {
+                top: Some(BorderLine::Thin),
+                bottom: Some(BorderLine::Dashed),
+                left: Some(BorderLine::Thick),
+                right: None,
+            })
+        );
+
+        // 与 Excel 默认一致的属性不写入
+        let style = style_of(&[
+            ("font-weight", "400"),
+            ("font-style", "normal"),
+            ("color", "rgb(0, 0, 0)"),
+            ("background-color", "rgba(0, 0, 0, 0)"),
+            ("text-align", "start"),
+            ("vertical-align", "baseline"),
+            ("border-top-style", "solid"),
+            ("border-top-width", "0px"),
+        ]);
+        assert!(style.is_empty());
+    }
+
+    #[test]
+    fn test_css_to_cell_style_ignores_unsupported_values() {
+        // 无法映射的关键字与畸形取值直接忽略，不影响其余属性
+        let style = style_of(&[
+            ("font-weight", "lighter"),
+            ("font-style", "inherit"),
+            ("font-size", "1.2em"),
+            ("font-family", "system-ui, -apple-system, sans-serif"),
+            ("color", "currentcolor"),
+            ("text-align", "justify"),
+            ("vertical-align", "sub"),
+            ("border-top-style", "solid"),
+            ("border-top-width", "medium"),
+            ("border-bottom-style", "groove"),
+            ("border-bottom-width", "-1px"),
+            ("background-color", "rgb(255, 0, 0)"),
+        ]);
+        assert_eq!(style.bold, None);
+        assert_eq!(style.italic, None);
+        assert_eq!(style.font_size, None);
+        assert_eq!(style.font_name, None);
+        assert_eq!(style.font_color, None);
+        assert_eq!(style.align, None);
+        assert_eq!(style.vertical_align, None);
+        assert_eq!(style.border, None);
+        assert_eq!(style.background_color.as_deref(), Some("#FF0000"));
+
+        assert_eq!(font_family("\"SimSun\""), Some("SimSun".to_string()));
+        assert_eq!(font_family(""), None);
+        assert_eq!(border_line("groove", "4px"), Some(BorderLine::Thick));
+        assert_eq!(border_line("hidden", "2px"), None);
+        assert_eq!(border_line("solid", ""), None);
+    }
+}
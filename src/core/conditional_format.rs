@@ -0,0 +1,742 @@
+/// 条件格式模块
+///
+/// 解析列级 / 工作表级 `conditionalFormats` 配置，导出 XLSX 时写为 Excel 原生条件格式
+/// （修改单元格后 Excel 自动重新评估），CSV 导出时忽略
+use super::data_export::get_object_property;
+use super::export_xlsx::data_row_range;
+use super::style::{CellStyle, normalize_hex_color, parse_cell_style};
+use super::table_extractor::TableData;
+use rust_xlsxwriter::utility::column_name_to_number;
+use rust_xlsxwriter::{
+    ConditionalFormat2ColorScale, ConditionalFormat3ColorScale, ConditionalFormatCell,
+    ConditionalFormatCellRule, ConditionalFormatDataBar, ConditionalFormatDuplicate,
+    ConditionalFormatText, ConditionalFormatTextRule, ConditionalFormatTop,
+    ConditionalFormatTopRule, ConditionalFormatValue, Format, Formula, Worksheet, XlsxError,
+};
+use wasm_bindgen::prelude::*;
+
+/// 单个工作表最多的条件格式规则数，防止超大配置
+const MAX_RULES: usize = 1000;
+
+/// cellIs 比较运算符（与 OOXML `operator` 属性同名）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellIsOperator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Between,
+    NotBetween,
+}
+
+impl CellIsOperator {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "equal" => Some(Self::Equal),
+            "notEqual" => Some(Self::NotEqual),
+            "greaterThan" => Some(Self::GreaterThan),
+            "greaterThanOrEqual" => Some(Self::GreaterThanOrEqual),
+            "lessThan" => Some(Self::LessThan),
+            "lessThanOrEqual" => Some(Self::LessThanOrEqual),
+            "between" => Some(Self::Between),
+            "notBetween" => Some(Self::NotBetween),
+            _ => None,
+        }
+    }
+
+    /// 是否需要两个比较值
+    fn is_range(self) -> bool {
+        matches!(self, Self::Between | Self::NotBetween)
+    }
+}
+
+/// 文本规则运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextOperator {
+    Contains,
+    NotContains,
+    BeginsWith,
+    EndsWith,
+}
+
+/// cellIs 比较值
+#[derive(Debug, Clone, PartialEq)]
+pub enum CfValue {
+    Number(f64),
+    Text(String),
+    /// 以 `=` 开头的公式 / 单元格引用（如 `=$H$1`）
+    Formula(String),
+}
+
+/// 条件格式规则
+#[derive(Debug, Clone, PartialEq)]
+pub enum CfRule {
+    /// 单元格值比较
+    CellIs {
+        operator: CellIsOperator,
+        values: Vec<CfValue>,
+    },
+    /// 文本包含 / 不包含 / 开头 / 结尾
+    Text {
+        operator: TextOperator,
+        text: String,
+    },
+    /// 前 / 后 N 项（或 N%）
+    Top {
+        rank: u16,
+        bottom: bool,
+        percent: bool,
+    },
+    /// 重复值（`unique` 为 true 时为唯一值）
+    Duplicate { unique: bool },
+    /// 双色 / 三色刻度（未设置的颜色使用 Excel 默认值）
+    ColorScale {
+        min_color: Option<String>,
+        mid_color: Option<String>,
+        max_color: Option<String>,
+        three_color: bool,
+    },
+    /// 数据条
+    DataBar { color: Option<String>, solid: bool },
+}
+
+/// 条件格式作用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfTarget {
+    /// 全部数据行 × 全部列（不含表头与表尾）
+    Data,
+    /// 指定列的数据行
+    Column(u16),
+    /// 显式区域 (first_row, first_col, last_row, last_col)
+    Range(u32, u16, u32, u16),
+}
+
+/// 一条条件格式配置
+#[derive(Debug, Clone)]
+pub struct ConditionalFormatRule {
+    /// 作用范围
+    pub target: CfTarget,
+    /// 规则
+    pub rule: CfRule,
+    /// 命中时的样式（色阶 / 数据条忽略）
+    pub style: Option<CellStyle>,
+}
+
+/// 解析条件格式数组
+///
+/// 每条规则：`{ type, style?, ...规则参数 }`，`type` 取值：
+/// - `cellIs`：`{ operator, value }`，`between` / `notBetween` 时 `value` 为 `[min, max]`
+/// - `text`：`{ operator?: 'contains' | 'notContains' | 'beginsWith' | 'endsWith', text }`
+/// - `top`：`{ rank?, bottom?, percent? }`（默认前 10 项）
+/// - `duplicate`：`{ unique? }`
+/// - `colorScale`：`{ minColor?, midColor?, maxColor? }`，设置 `midColor` 时为三色刻度
+/// - `dataBar`：`{ color?, solid? }`
+///
+/// `default_target` 为未指定 `range` / `column` 时的作用范围；
+/// `allow_target` 为 false 时（列级配置）不读取 `range` / `column`
+pub(crate) fn parse_conditional_formats(
+    val: &JsValue,
+    default_target: CfTarget,
+    allow_target: bool,
+) -> Result<Vec<ConditionalFormatRule>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(Vec::new());
+    }
+    if !js_sys::Array::is_array(val) {
+        return Err(JsValue::from_str("conditionalFormats 必须是数组"));
+    }
+
+    let array = js_sys::Array::from(val);
+    if array.length() as usize > MAX_RULES {
+        return Err(JsValue::from_str(&format!(
+            "conditionalFormats 规则数超过上限 ({})",
+            MAX_RULES
+        )));
+    }
+
+    let mut rules = Vec::with_capacity(array.length() as usize);
+    for (i, item) in array.iter().enumerate() {
+        let invalid =
+            |msg: String| JsValue::from_str(&format!("第 {} 条条件格式无效: {}", i + 1, msg));
+        if !item.is_object() {
+            return Err(invalid("必须是对象".to_string()));
+        }
+
+        let target = if allow_target {
+            parse_target(&item)
+                .map_err(invalid)?
+                .unwrap_or(default_target)
+        } else {
+            default_target
+        };
+        let rule = parse_rule(&item).map_err(invalid)?;
+        let style = parse_cell_style(&get_object_property(&item, "style")?);
+        let needs_style = !matches!(rule, CfRule::ColorScale { .. } | CfRule::DataBar { .. });
+        if needs_style && style.is_none() {
+            return Err(invalid("缺少 style".to_string()));
+        }
+
+        rules.push(ConditionalFormatRule {
+            target,
+            rule,
+            style,
+        });
+    }
+    Ok(rules)
+}
+
+/// 读取字符串属性（读取异常转为错误文本）
+fn get_string(obj: &JsValue, key: &str) -> Result<Option<String>, String> {
+    js_sys::Reflect::get(obj, &JsValue::from_str(key))
+        .map(|v| v.as_string())
+        .map_err(|e| format!("读取 {} 失败: {:?}", key, e))
+}
+
+/// 读取布尔属性
+fn get_bool(obj: &JsValue, key: &str) -> Result<Option<bool>, String> {
+    js_sys::Reflect::get(obj, &JsValue::from_str(key))
+        .map(|v| v.as_bool())
+        .map_err(|e| format!("读取 {} 失败: {:?}", key, e))
+}
+
+/// 解析工作表级规则的 `range`（A1 区域）或 `column`（0 起始列号）
+fn parse_target(item: &JsValue) -> Result<Option<CfTarget>, String> {
+    if let Some(range) = get_string(item, "range")? {
+        return parse_a1_range(&range)
+            .map(|(r1, c1, r2, c2)| Some(CfTarget::Range(r1, c1, r2, c2)))
+            .ok_or_else(|| format!("range 无效: '{}'，应为 A1 格式区域（如 'B2:D20'）", range));
+    }
+    let column = js_sys::Reflect::get(item, &JsValue::from_str("column"))
+        .map_err(|e| format!("读取 column 失败: {:?}", e))?;
+    column
+        .as_f64()
+        .map(|c| target_column(c).map(CfTarget::Column))
+        .transpose()
+}
+
+/// 校验 `column`（0 起始列号）
+fn target_column(c: f64) -> Result<u16, String> {
+    if c.fract() == 0.0 && (0.0..=16383.0).contains(&c) {
+        Ok(c as u16)
+    } else {
+        Err(format!("column 无效: {}，应为 0–16383 的整数", c))
+    }
+}
+
+/// 解析规则类型与参数
+fn parse_rule(item: &JsValue) -> Result<CfRule, String> {
+    let rule_type = get_string(item, "type")?.ok_or("缺少 type")?;
+    match rule_type.as_str() {
+        "cellIs" => {
+            let op = get_string(item, "operator")?.ok_or("cellIs 缺少 operator")?;
+            let operator = CellIsOperator::parse(&op).ok_or_else(|| {
+                format!(
+                    "operator 无效: '{}'，仅支持 equal / notEqual / greaterThan / greaterThanOrEqual / lessThan / lessThanOrEqual / between / notBetween",
+                    op
+                )
+            })?;
+            let value = js_sys::Reflect::get(item, &JsValue::from_str("value"))
+                .map_err(|e| format!("读取 value 失败: {:?}", e))?;
+            let values = if operator.is_range() {
+                if !js_sys::Array::is_array(&value) || js_sys::Array::from(&value).length() != 2 {
+                    return Err(format!("{} 的 value 必须是 [min, max]", op));
+                }
+                js_sys::Array::from(&value)
+                    .iter()
+                    .map(|v| parse_cf_value(&v))
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                vec![parse_cf_value(&value)?]
+            };
+            Ok(CfRule::CellIs { operator, values })
+        }
+        "text" => {
+            let operator = text_operator(get_string(item, "operator")?.as_deref())?;
+            let text = get_string(item, "text")?
+                .filter(|t| !t.is_empty())
+                .ok_or("text 规则缺少 text")?;
+            Ok(CfRule::Text { operator, text })
+        }
+        "top" => {
+            let percent = get_bool(item, "percent")?.unwrap_or(false);
+            let rank = match js_sys::Reflect::get(item, &JsValue::from_str("rank"))
+                .map_err(|e| format!("读取 rank 失败: {:?}", e))?
+                .as_f64()
+            {
+                None => 10,
+                Some(n) => check_rank(n, percent)?,
+            };
+            Ok(CfRule::Top {
+                rank,
+                bottom: get_bool(item, "bottom")?.unwrap_or(false),
+                percent,
+            })
+        }
+        "duplicate" => Ok(CfRule::Duplicate {
+            unique: get_bool(item, "unique")?.unwrap_or(false),
+        }),
+        "colorScale" => {
+            let mid_color = parse_color(item, "midColor")?;
+            Ok(CfRule::ColorScale {
+                min_color: parse_color(item, "minColor")?,
+                three_color: mid_color.is_some(),
+                mid_color,
+                max_color: parse_color(item, "maxColor")?,
+            })
+        }
+        "dataBar" => Ok(CfRule::DataBar {
+            color: parse_color(item, "color")?,
+            solid: get_bool(item, "solid")?.unwrap_or(false),
+        }),
+        other => Err(format!(
+            "type 无效: '{}'，仅支持 cellIs / text / top / duplicate / colorScale / dataBar",
+            other
+        )),
+    }
+}
+
+/// 解析文本规则运算符（缺省为 contains）
+fn text_operator(op: Option<&str>) -> Result<TextOperator, String> {
+    match op {
+        None | Some("contains") => Ok(TextOperator::Contains),
+        Some("notContains") => Ok(TextOperator::NotContains),
+        Some("beginsWith") => Ok(TextOperator::BeginsWith),
+        Some("endsWith") => Ok(TextOperator::EndsWith),
+        Some(op) => Err(format!(
+            "text 的 operator 无效: '{}'，仅支持 contains / notContains / beginsWith / endsWith",
+            op
+        )),
+    }
+}
+
+/// 前 N 项（1–1000）/ 前 N%（1–100）的上限
+fn max_rank(percent: bool) -> u16 {
+    if percent { 100 } else { 1000 }
+}
+
+/// 校验 top 规则的 `rank`
+fn check_rank(n: f64, percent: bool) -> Result<u16, String> {
+    if n.fract() == 0.0 && n >= 1.0 && n <= max_rank(percent) as f64 {
+        Ok(n as u16)
+    } else {
+        Err(format!(
+            "rank 无效: {}，应为 1–{} 的整数",
+            n,
+            max_rank(percent)
+        ))
+    }
+}
+
+/// 解析 cellIs 比较值：数字、字符串，或以 `=` 开头的公式
+fn parse_cf_value(val: &JsValue) -> Result<CfValue, String> {
+    if let Some(n) = val.as_f64() {
+        if n.is_finite() {
+            return Ok(CfValue::Number(n));
+        }
+    } else if let Some(s) = val.as_string() {
+        return Ok(match s.strip_prefix('=') {
+            Some(formula) if !formula.is_empty() => CfValue::Formula(s),
+            _ => CfValue::Text(s),
+        });
+    }
+    Err(format!("value 必须是有限数字或字符串: {:?}", val))
+}
+
+/// 解析并规范化颜色（`#RGB` / `#RRGGBB`）
+fn parse_color(item: &JsValue, key: &str) -> Result<Option<String>, String> {
+    get_string(item, key)?
+        .map(|color| check_color(key, &color))
+        .transpose()
+}
+
+/// 校验并规范化颜色为 `#RRGGBB`
+fn check_color(key: &str, color: &str) -> Result<String, String> {
+    let normalized = normalize_hex_color(color);
+    if normalized.len() == 7 && normalized.starts_with('#') {
+        Ok(normalized)
+    } else {
+        Err(format!("{} 无效: '{}'，应为 #RGB 或 #RRGGBB", key, color))
+    }
+}
+
+/// 解析 A1 格式的单元格或区域（如 `B2`、`$B$2:D20`），返回 0 起始的行列
+fn parse_a1_range(range: &str) -> Option<(u32, u16, u32, u16)> {
+    fn parse_cell(cell: &str) -> Option<(u32, u16)> {
+        let cell = cell.trim().replace('$', "");
+        let split = cell.find(|c: char| c.is_ascii_digit())?;
+        let (letters, digits) = cell.split_at(split);
+        if letters.is_empty()
+            || letters.len() > 3
+            || !letters.chars().all(|c| c.is_ascii_alphabetic())
+            || !digits.chars().all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let col = column_name_to_number(&letters.to_ascii_uppercase());
+        let row: u32 = digits.parse().ok()?;
+        if row == 0 || row > 1_048_576 || col > 16_383 {
+            return None;
+        }
+        Some((row - 1, col))
+    }
+
+    let (start, end) = range.split_once(':').unwrap_or((range, range));
+    let (r1, c1) = parse_cell(start)?;
+    let (r2, c2) = parse_cell(end)?;
+    Some((r1.min(r2), c1.min(c2), r1.max(r2), c1.max(c2)))
+}
+
+/// 条件格式样式转换为 Format
+///
+/// Excel 条件格式只支持字体颜色 / 粗体 / 斜体、填充、边框与数字格式，其余属性丢弃
+fn to_dxf_format(style: &CellStyle) -> Format {
+    CellStyle {
+        bold: style.bold,
+        italic: style.italic,
+        font_color: style.font_color.clone(),
+        background_color: style.background_color.clone(),
+        border: style.border.clone(),
+        number_format: style.number_format.clone(),
+        ..Default::default()
+    }
+    .to_format()
+}
+
+/// 转换为 rust_xlsxwriter 的条件格式值
+fn to_cf_value(value: &CfValue) -> ConditionalFormatValue {
+    match value {
+        CfValue::Number(n) => (*n).into(),
+        CfValue::Text(s) => s.as_str().into(),
+        CfValue::Formula(f) => Formula::new(f).into(),
+    }
+}
+
+/// 解析规则的作用区域；无可覆盖区域时返回 None
+fn resolve_target(
+    target: CfTarget,
+    table_data: &TableData,
+    total_cols: usize,
+) -> Option<(u32, u16, u32, u16)> {
+    match target {
+        CfTarget::Range(r1, c1, r2, c2) => Some((r1, c1, r2, c2)),
+        CfTarget::Column(col) => {
+            let (first_row, last_row) = data_row_range(table_data)?;
+            Some((first_row, col, last_row, col))
+        }
+        CfTarget::Data => {
+            let (first_row, last_row) = data_row_range(table_data)?;
+            let last_col = u16::try_from(total_cols.checked_sub(1)?).ok()?;
+            Some((first_row, 0, last_row, last_col))
+        }
+    }
+}
+
+/// 写入单条条件格式
+fn add_rule(
+    worksheet: &mut Worksheet,
+    (r1, c1, r2, c2): (u32, u16, u32, u16),
+    rule: &ConditionalFormatRule,
+) -> Result<(), XlsxError> {
+    let format = rule.style.as_ref().map(to_dxf_format).unwrap_or_default();
+    match &rule.rule {
+        CfRule::CellIs { operator, values } => {
+            let first = values.first().map(to_cf_value).unwrap_or_else(|| 0.into());
+            let second = values.get(1).map(to_cf_value).unwrap_or_else(|| 0.into());
+            let cell_rule = match operator {
+                CellIsOperator::Equal => ConditionalFormatCellRule::EqualTo(first),
+                CellIsOperator::NotEqual => ConditionalFormatCellRule::NotEqualTo(first),
+                CellIsOperator::GreaterThan => ConditionalFormatCellRule::GreaterThan(first),
+                CellIsOperator::GreaterThanOrEqual => {
+                    ConditionalFormatCellRule::GreaterThanOrEqualTo(first)
+                }
+                CellIsOperator::LessThan => ConditionalFormatCellRule::LessThan(first),
+                CellIsOperator::LessThanOrEqual => {
+                    ConditionalFormatCellRule::LessThanOrEqualTo(first)
+                }
+                CellIsOperator::Between => ConditionalFormatCellRule::Between(first, second),
+                CellIsOperator::NotBetween => ConditionalFormatCellRule::NotBetween(first, second),
+            };
+            let cf = ConditionalFormatCell::new()
+                .set_rule(cell_rule)
+                .set_format(format);
+            worksheet.add_conditional_format(r1, c1, r2, c2, &cf)?;
+        }
+        CfRule::Text { operator, text } => {
+            let text = text.clone();
+            let text_rule = match operator {
+                TextOperator::Contains => ConditionalFormatTextRule::Contains(text),
+                TextOperator::NotContains => ConditionalFormatTextRule::DoesNotContain(text),
+                TextOperator::BeginsWith => ConditionalFormatTextRule::BeginsWith(text),
+                TextOperator::EndsWith => ConditionalFormatTextRule::EndsWith(text),
+            };
+            let cf = ConditionalFormatText::new()
+                .set_rule(text_rule)
+                .set_format(format);
+            worksheet.add_conditional_format(r1, c1, r2, c2, &cf)?;
+        }
+        CfRule::Top {
+            rank,
+            bottom,
+            percent,
+        } => {
+            let top_rule = match (bottom, percent) {
+                (false, false) => ConditionalFormatTopRule::Top(*rank),
+                (true, false) => ConditionalFormatTopRule::Bottom(*rank),
+                (false, true) => ConditionalFormatTopRule::TopPercent(*rank),
+                (true, true) => ConditionalFormatTopRule::BottomPercent(*rank),
+            };
+            let cf = ConditionalFormatTop::new()
+                .set_rule(top_rule)
+                .set_format(format);
+            worksheet.add_conditional_format(r1, c1, r2, c2, &cf)?;
+        }
+        CfRule::Duplicate { unique } => {
+            let mut cf = ConditionalFormatDuplicate::new().set_format(format);
+            if *unique {
+                cf = cf.invert();
+            }
+            worksheet.add_conditional_format(r1, c1, r2, c2, &cf)?;
+        }
+        CfRule::ColorScale {
+            min_color,
+            mid_color,
+            max_color,
+            three_color: true,
+        } => {
+            let mut cf = ConditionalFormat3ColorScale::new();
+            if let Some(color) = min_color {
+                cf = cf.set_minimum_color(color.as_str());
+            }
+            if let Some(color) = mid_color {
+                cf = cf.set_midpoint_color(color.as_str());
+            }
+            if let Some(color) = max_color {
+                cf = cf.set_maximum_color(color.as_str());
+            }
+            worksheet.add_conditional_format(r1, c1, r2, c2, &cf)?;
+        }
+        CfRule::ColorScale {
+            min_color,
+            max_color,
+            ..
+        } => {
+            let mut cf = ConditionalFormat2ColorScale::new();
+            if let Some(color) = min_color {
+                cf = cf.set_minimum_color(color.as_str());
+            }
+            if let Some(color) = max_color {
+                cf = cf.set_maximum_color(color.as_str());
+            }
+            worksheet.add_conditional_format(r1, c1, r2, c2, &cf)?;
+        }
+        CfRule::DataBar { color, solid } => {
+            let mut cf = ConditionalFormatDataBar::new().set_solid_fill(*solid);
+            if let Some(color) = color {
+                cf = cf.set_fill_color(color.as_str());
+            }
+            worksheet.add_conditional_format(r1, c1, r2, c2, &cf)?;
+        }
+    }
+    Ok(())
+}
+
+/// 将列级与工作表级条件格式写入工作表
+pub(crate) fn apply_conditional_formats(
+    worksheet: &mut Worksheet,
+    table_data: &TableData,
+    total_cols: usize,
+) -> Result<(), JsValue> {
+    let rules = table_data
+        .conditional_formats
+        .iter()
+        .chain(&table_data.sheet_options.conditional_formats);
+    for rule in rules {
+        let Some(range) = resolve_target(rule.target, table_data, total_cols) else {
+            continue;
+        };
+        add_rule(worksheet, range, rule)
+            .map_err(|e| JsValue::from_str(&format!("设置条件格式失败: {}", e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::table_extractor::CellValue;
+    use crate::core::test_support::{generate, part_xml, sheet_xml};
+
+    #[test]
+    fn test_parse_a1_range() {
+        assert_eq!(parse_a1_range("B2"), Some((1, 1, 1, 1)));
+        assert_eq!(parse_a1_range("$B$2:d20"), Some((1, 1, 19, 3)));
+        assert_eq!(parse_a1_range("D20:B2"), Some((1, 1, 19, 3)));
+        assert_eq!(
+            parse_a1_range("XFD1048576"),
+            Some((1_048_575, 16_383, 1_048_575, 16_383))
+        );
+        assert_eq!(parse_a1_range("A0"), None);
+        assert_eq!(parse_a1_range("XFE1"), None);
+        assert_eq!(parse_a1_range("1A"), None);
+        assert_eq!(parse_a1_range("Sheet1!A1"), None);
+    }
+
+    #[test]
+    fn test_parse_rule_values() {
+        assert_eq!(
+            CellIsOperator::parse("notBetween"),
+            Some(CellIsOperator::NotBetween)
+        );
+        assert!(CellIsOperator::NotBetween.is_range());
+        assert!(!CellIsOperator::Equal.is_range());
+        assert_eq!(CellIsOperator::parse("eq"), None);
+
+        assert_eq!(text_operator(None), Ok(TextOperator::Contains));
+        assert_eq!(text_operator(Some("endsWith")), Ok(TextOperator::EndsWith));
+        assert!(text_operator(Some("startsWith")).is_err());
+
+        assert_eq!(check_rank(1000.0, false), Ok(1000));
+        assert!(check_rank(1001.0, false).is_err());
+        assert_eq!(check_rank(100.0, true), Ok(100));
+        assert!(check_rank(101.0, true).is_err());
+        assert!(check_rank(0.0, false).is_err());
+        assert!(check_rank(2.5, false).is_err());
+
+        assert_eq!(target_column(16383.0), Ok(16383));
+        assert!(target_column(16384.0).is_err());
+        assert!(target_column(-1.0).is_err());
+        assert!(target_column(0.5).is_err());
+
+        assert_eq!(check_color("color", "#f00"), Ok("#ff0000".to_string()));
+        assert_eq!(check_color("color", "#409eff"), Ok("#409eff".to_string()));
+        assert!(check_color("minColor", "red").is_err());
+        assert!(check_color("minColor", "#12345").is_err());
+        assert!(check_color("minColor", "").is_err());
+    }
+
+    #[test]
+    fn test_write_sheet_conditional_formats() {
+        let mut table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("名称"), CellValue::from("数量")],
+                vec![CellValue::from("A"), CellValue::Number(5.0)],
+                vec![CellValue::from("B"), CellValue::Number(50.0)],
+                vec![CellValue::from("合计"), CellValue::Number(55.0)],
+            ],
+            header_row_count: 1,
+            footer_row_count: 1,
+            ..Default::default()
+        };
+        let red = CellStyle {
+            font_color: Some("#F00".to_string()),
+            font_size: Some(20.0),
+            ..Default::default()
+        };
+        table_data.conditional_formats.push(ConditionalFormatRule {
+            target: CfTarget::Column(1),
+            rule: CfRule::CellIs {
+                operator: CellIsOperator::Between,
+                values: vec![CfValue::Number(10.0), CfValue::Formula("=$D$1".to_string())],
+            },
+            style: Some(red),
+        });
+        table_data
+            .sheet_options
+            .conditional_formats
+            .push(ConditionalFormatRule {
+                target: CfTarget::Data,
+                rule: CfRule::DataBar {
+                    color: None,
+                    solid: true,
+                },
+                style: None,
+            });
+        table_data
+            .sheet_options
+            .conditional_formats
+            .push(ConditionalFormatRule {
+                target: CfTarget::Range(1, 0, 2, 0),
+                rule: CfRule::Text {
+                    operator: TextOperator::Contains,
+                    text: "A".to_string(),
+                },
+                style: Some(CellStyle {
+                    bold: Some(true),
+                    ..Default::default()
+                }),
+            });
+
+        let bytes = generate(&table_data);
+        let sheet = part_xml(&bytes, "xl/worksheets/sheet1.xml");
+        let rule_at = |sqref: &str| {
+            sheet
+                .find_by("conditionalFormatting", "sqref", sqref)
+                .and_then(|cf| cf.child("cfRule"))
+                .unwrap_or_else(|| panic!("缺少 {} 的条件格式", sqref))
+        };
+
+        // 列级规则只覆盖数据行，不含合计行
+        let cell_is = rule_at("B2:B3");
+        assert_eq!(cell_is.attr("type"), Some("cellIs"));
+        assert_eq!(cell_is.attr("operator"), Some("between"));
+        let formulas: Vec<_> = cell_is
+            .find_all("formula")
+            .iter()
+            .map(|f| f.text.as_str())
+            .collect();
+        assert_eq!(formulas, vec!["10", "$D$1"]);
+
+        assert_eq!(rule_at("A2:B3").attr("type"), Some("dataBar"));
+
+        let text = rule_at("A2:A3");
+        assert_eq!(text.attr("type"), Some("containsText"));
+        assert_eq!(text.attr("text"), Some("A"));
+
+        // dxf 中只保留条件格式支持的属性
+        let styles = part_xml(&bytes, "xl/styles.xml");
+        let dxfs = styles.child("dxfs").unwrap();
+        assert_eq!(dxfs.attr("count"), Some("2"));
+        let red_dxf = &dxfs.children[cell_is.attr("dxfId").unwrap().parse::<usize>().unwrap()];
+        assert_eq!(red_dxf.find("color").unwrap().attr("rgb"), Some("FFFF0000"));
+        assert!(red_dxf.find("sz").is_none());
+    }
+
+    #[test]
+    fn test_write_sheet_conditional_formats_without_data_rows() {
+        // 仅表头时列级与全数据区规则没有作用区域，显式区域照常写入
+        let mut table_data = TableData {
+            rows: vec![vec![CellValue::from("数量")]],
+            header_row_count: 1,
+            ..Default::default()
+        };
+        let rule = |target| ConditionalFormatRule {
+            target,
+            rule: CfRule::Duplicate { unique: false },
+            style: Some(CellStyle {
+                bold: Some(true),
+                ..Default::default()
+            }),
+        };
+        table_data
+            .conditional_formats
+            .push(rule(CfTarget::Column(0)));
+        table_data
+            .sheet_options
+            .conditional_formats
+            .push(rule(CfTarget::Data));
+        table_data
+            .sheet_options
+            .conditional_formats
+            .push(rule(CfTarget::Range(9, 0, 19, 0)));
+
+        let sheet = sheet_xml(&table_data);
+        let sqrefs: Vec<_> = sheet
+            .find_all("conditionalFormatting")
+            .iter()
+            .filter_map(|cf| cf.attr("sqref"))
+            .collect();
+        assert_eq!(sqrefs, vec!["A10:A20"]);
+    }
+}
@@ -0,0 +1,263 @@
+/// CSV 方言模块
+///
+/// 解析 `csv` 配置（分隔符、引号、引用策略、换行符、转义字符），
+/// 为所有 CSV / TSV 导出路径创建统一配置的写入器
+use super::ExportFormat;
+use super::data_export::get_object_property;
+use csv::{QuoteStyle, Terminator, Writer, WriterBuilder};
+use std::io::Write;
+use wasm_bindgen::prelude::*;
+
+/// CSV 方言配置（`csv`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvDialect {
+    /// 字段分隔符（默认 `,`，TSV 为制表符）
+    pub delimiter: u8,
+    /// 引号字符（默认 `"`）
+    pub quote: u8,
+    /// 引用策略（默认仅在需要时加引号）
+    pub quote_style: CsvQuoteStyle,
+    /// 行结束符（默认 `\n`）
+    pub terminator: CsvTerminator,
+    /// 字段内引号的转义字符，`None` 表示双写引号（默认）
+    pub escape: Option<u8>,
+}
+
+/// 引用策略（`quoteStyle`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsvQuoteStyle {
+    /// 字段包含分隔符、引号或换行时加引号（`necessary`）
+    #[default]
+    Necessary,
+    /// 所有字段都加引号（`always`）
+    Always,
+    /// 非数字字段加引号（`nonNumeric`）
+    NonNumeric,
+    /// 从不加引号（`never`），字段中的分隔符与换行原样输出
+    Never,
+}
+
+/// 行结束符（`terminator`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsvTerminator {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// `\r`
+    Cr,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        CsvDialect {
+            delimiter: b',',
+            quote: b'"',
+            quote_style: CsvQuoteStyle::default(),
+            terminator: CsvTerminator::default(),
+            escape: None,
+        }
+    }
+}
+
+impl CsvDialect {
+    /// 指定导出格式的默认方言：TSV 以制表符分隔，其余同 CSV
+    pub fn for_format(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Tsv => CsvDialect {
+                delimiter: b'\t',
+                ..Default::default()
+            },
+            _ => CsvDialect::default(),
+        }
+    }
+
+    /// 按方言创建 CSV 写入器
+    pub(crate) fn writer<W: Write>(&self, inner: W) -> Writer<W> {
+        let mut builder = WriterBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .quote_style(match self.quote_style {
+                CsvQuoteStyle::Necessary => QuoteStyle::Necessary,
+                CsvQuoteStyle::Always => QuoteStyle::Always,
+                CsvQuoteStyle::NonNumeric => QuoteStyle::NonNumeric,
+                CsvQuoteStyle::Never => QuoteStyle::Never,
+            })
+            .terminator(match self.terminator {
+                CsvTerminator::Lf => Terminator::Any(b'\n'),
+                CsvTerminator::CrLf => Terminator::CRLF,
+                CsvTerminator::Cr => Terminator::Any(b'\r'),
+            });
+        if let Some(escape) = self.escape {
+            builder.double_quote(false).escape(escape);
+        }
+        builder.from_writer(inner)
+    }
+}
+
+/// 解析 `csv` 配置，未设置的字段取 `format` 的默认方言
+///
+/// `csv` 为对象：`{ delimiter?, quote?, quoteStyle?, terminator?, escape? }`，
+/// 分隔符、引号与转义字符须为单个 ASCII 字符
+pub(crate) fn parse_csv_dialect(
+    obj: &JsValue,
+    format: ExportFormat,
+) -> Result<CsvDialect, JsValue> {
+    let mut dialect = CsvDialect::for_format(format);
+    let val = get_object_property(obj, "csv")?;
+    if val.is_null() || val.is_undefined() {
+        return Ok(dialect);
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("csv 配置必须是对象"));
+    }
+
+    let string_field = |key: &str| -> Result<Option<String>, JsValue> {
+        let v = get_object_property(&val, key)?;
+        if v.is_null() || v.is_undefined() {
+            return Ok(None);
+        }
+        v.as_string()
+            .map(Some)
+            .ok_or_else(|| JsValue::from_str(&format!("csv.{} 必须是字符串", key)))
+    };
+
+    if let Some(s) = string_field("delimiter")? {
+        dialect.delimiter = single_ascii("delimiter", &s).map_err(|e| JsValue::from_str(&e))?;
+    }
+    if let Some(s) = string_field("quote")? {
+        dialect.quote = single_ascii("quote", &s).map_err(|e| JsValue::from_str(&e))?;
+    }
+    if let Some(s) = string_field("quoteStyle")? {
+        dialect.quote_style = parse_quote_style(&s).map_err(|e| JsValue::from_str(&e))?;
+    }
+    if let Some(s) = string_field("terminator")? {
+        dialect.terminator = parse_terminator(&s).map_err(|e| JsValue::from_str(&e))?;
+    }
+    if let Some(s) = string_field("escape")? {
+        let escape = single_ascii("escape", &s).map_err(|e| JsValue::from_str(&e))?;
+        dialect.escape = (escape != dialect.quote).then_some(escape);
+    }
+
+    validate_dialect(&dialect).map_err(|e| JsValue::from_str(&e))?;
+    Ok(dialect)
+}
+
+/// 单个 ASCII 字符
+fn single_ascii(key: &str, s: &str) -> Result<u8, String> {
+    match s.as_bytes() {
+        [b] if b.is_ascii() => Ok(*b),
+        _ => Err(format!("csv.{} 必须是单个 ASCII 字符，实际为 {:?}", key, s)),
+    }
+}
+
+fn parse_quote_style(s: &str) -> Result<CsvQuoteStyle, String> {
+    match s {
+        "necessary" => Ok(CsvQuoteStyle::Necessary),
+        "always" => Ok(CsvQuoteStyle::Always),
+        "nonNumeric" => Ok(CsvQuoteStyle::NonNumeric),
+        "never" => Ok(CsvQuoteStyle::Never),
+        other => Err(format!(
+            "csv.quoteStyle 值非法：{}。仅支持 necessary、always、nonNumeric、never",
+            other
+        )),
+    }
+}
+
+fn parse_terminator(s: &str) -> Result<CsvTerminator, String> {
+    match s {
+        "\n" => Ok(CsvTerminator::Lf),
+        "\r\n" => Ok(CsvTerminator::CrLf),
+        "\r" => Ok(CsvTerminator::Cr),
+        other => Err(format!(
+            "csv.terminator 值非法：{:?}。仅支持 \"\\n\"、\"\\r\\n\"、\"\\r\"",
+            other
+        )),
+    }
+}
+
+/// 分隔符不能是换行符，也不能与引号或转义字符相同
+fn validate_dialect(dialect: &CsvDialect) -> Result<(), String> {
+    if matches!(dialect.delimiter, b'\r' | b'\n') {
+        return Err("csv.delimiter 不能是换行符".to_string());
+    }
+    if dialect.delimiter == dialect.quote || Some(dialect.delimiter) == dialect.escape {
+        return Err("csv.delimiter 不能与 quote 或 escape 相同".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dialect: &CsvDialect, rows: &[&[&str]]) -> String {
+        let mut wtr = dialect.writer(Vec::new());
+        for row in rows {
+            wtr.write_record(*row).unwrap();
+        }
+        String::from_utf8(wtr.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_dialect_output() {
+        let rows: &[&[&str]] = &[&["名称", "金额"], &["A;B", "1,5"], &["说\"明\"", "2"]];
+
+        assert_eq!(
+            write(&CsvDialect::default(), rows),
+            "名称,金额\nA;B,\"1,5\"\n\"说\"\"明\"\"\",2\n"
+        );
+
+        let european = CsvDialect {
+            delimiter: b';',
+            quote_style: CsvQuoteStyle::Always,
+            terminator: CsvTerminator::CrLf,
+            ..Default::default()
+        };
+        assert_eq!(
+            write(&european, rows),
+            "\"名称\";\"金额\"\r\n\"A;B\";\"1,5\"\r\n\"说\"\"明\"\"\";\"2\"\r\n"
+        );
+
+        let escaped = CsvDialect {
+            escape: Some(b'\\'),
+            quote_style: CsvQuoteStyle::NonNumeric,
+            ..Default::default()
+        };
+        assert_eq!(
+            write(&escaped, rows),
+            "\"名称\",\"金额\"\n\"A;B\",\"1,5\"\n\"说\\\"明\\\"\",2\n"
+        );
+
+        assert_eq!(
+            write(&CsvDialect::for_format(ExportFormat::Tsv), rows),
+            "名称\t金额\nA;B\t1,5\n\"说\"\"明\"\"\"\t2\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_dialect_fields() {
+        assert_eq!(single_ascii("delimiter", ";"), Ok(b';'));
+        assert_eq!(single_ascii("delimiter", "\t"), Ok(b'\t'));
+        assert!(single_ascii("delimiter", "；").is_err());
+        assert!(single_ascii("delimiter", ";;").is_err());
+        assert!(single_ascii("quote", "").is_err());
+
+        assert_eq!(
+            parse_quote_style("nonNumeric"),
+            Ok(CsvQuoteStyle::NonNumeric)
+        );
+        assert!(parse_quote_style("minimal").is_err());
+        assert_eq!(parse_terminator("\r\n"), Ok(CsvTerminator::CrLf));
+        assert!(parse_terminator("crlf").is_err());
+
+        let clash = CsvDialect {
+            delimiter: b'"',
+            ..Default::default()
+        };
+        assert!(validate_dialect(&clash).is_err());
+        assert!(validate_dialect(&CsvDialect::for_format(ExportFormat::Tsv)).is_ok());
+    }
+}
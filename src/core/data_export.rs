@@ -2,7 +2,17 @@
 ///
 /// 提供从 JavaScript 对象数组 + 表头配置直接导出文件的功能，
 /// 支持嵌套表头（多行表头 + 合并单元格）
-use super::table_extractor::{MergeRange, TableData};
+use super::cell_image::{CellImage, ImageBudget, decode_data_image, fill_image_alt};
+use super::conditional_format::{CfTarget, ConditionalFormatRule, parse_conditional_formats};
+use super::data_validation::{ColumnValidation, parse_column_validation};
+use super::outline::{OutlineOptions, RowOutline, summary_below_order};
+use super::rich_text::parse_rich_text_runs;
+use super::row_style::{RowStyleOptions, RowStyler, RowStyles};
+use super::table_extractor::{
+    CellDateTime, CellFormula, CellLink, CellValue, MergeRange, TableData,
+};
+use crate::validation::sanitize_hyperlink;
+use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::*;
 
 /// 最大递归深度限制，防止恶意构造的深层嵌套数据导致栈溢出
@@ -14,23 +24,195 @@ const MAX_DATA_CELLS: usize = 5_000_000;
 /// 表头最大单元格数
 const MAX_HEADER_CELLS: usize = 100_000;
 
-/// extract_data_rows 返回类型（数据行, 合并区域, 单元格样式覆盖）
-type ExtractedDataRows = (
-    Vec<Vec<String>>,
-    Vec<MergeRange>,
-    std::collections::HashMap<(u32, u16), super::style::CellStyle>,
-);
+/// extract_data_rows 的提取结果
+#[derive(Default)]
+struct ExtractedDataRows {
+    /// 数据行
+    rows: Vec<Vec<CellValue>>,
+    /// 数据区域的合并区域
+    merge_ranges: Vec<MergeRange>,
+    /// 单元格级样式覆盖
+    cell_overrides: std::collections::HashMap<(u32, u16), super::style::CellStyle>,
+    /// 单元格批注
+    notes: std::collections::HashMap<(u32, u16), String>,
+    /// 单元格图片
+    images: std::collections::HashMap<(u32, u16), CellImage>,
+    /// 行级样式与行高
+    row_styles: RowStyles,
+}
 
 /// 安全地从 JS 对象中获取属性值
 ///
 /// 与 `Reflect::get(...).unwrap_or(JsValue::NULL)` 不同，本函数会区分
 /// "字段不存在（返回 NULL）"和"读取异常（返回 Err）"两种情况，
 /// 避免 getter 异常或 Proxy 异常被静默吞掉。
-fn get_object_property(obj: &JsValue, key: &str) -> Result<JsValue, JsValue> {
+pub(crate) fn get_object_property(obj: &JsValue, key: &str) -> Result<JsValue, JsValue> {
     js_sys::Reflect::get(obj, &JsValue::from_str(key))
         .map_err(|e| JsValue::from_str(&format!("读取对象属性 '{}' 时发生异常: {:?}", key, e)))
 }
 
+/// 列数据类型（决定单元格值的解析方式与默认数字格式、对齐）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    /// 文本：数字/布尔/日期按文本输出，XLSX 使用文本格式 `@`
+    Text,
+    /// 数值：数字文本解析为数字
+    Number,
+    /// 整数：数值四舍五入到整数
+    Integer,
+    /// 货币：默认 2 位小数，带货币符号
+    Currency,
+    /// 百分比：`0.25` 显示为 `25%`，文本 `"25%"` 解析为 0.25
+    Percent,
+    /// 仅日期：ISO 字符串解析为日期，Date 对象截断时间部分
+    Date,
+    /// 日期时间：ISO 字符串解析为日期时间
+    DateTime,
+    /// 布尔：`true`/`false`、`是`/`否`、`1`/`0` 等解析为布尔值
+    Boolean,
+}
+
+/// 货币列未指定 currencySymbol 时的默认符号
+const DEFAULT_CURRENCY_SYMBOL: &str = "¥";
+
+/// 数值文本中允许出现的前导货币符号
+const CURRENCY_SYMBOLS: &[char] = &['¥', '￥', '$', '€', '£'];
+
+/// precision 允许的最大小数位数
+const MAX_PRECISION: u8 = 15;
+
+impl ColumnType {
+    /// `type` 字段支持的取值（用于错误信息）
+    const NAMES: &'static str =
+        "text / number / integer / currency / percent / date / datetime / boolean";
+
+    /// 从 `type` 字段解析
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(ColumnType::Text),
+            "number" => Some(ColumnType::Number),
+            "integer" => Some(ColumnType::Integer),
+            "currency" => Some(ColumnType::Currency),
+            "percent" => Some(ColumnType::Percent),
+            "date" => Some(ColumnType::Date),
+            "datetime" => Some(ColumnType::DateTime),
+            "boolean" => Some(ColumnType::Boolean),
+            _ => None,
+        }
+    }
+
+    /// 默认数字格式（日期类型由 `dateFormat` 决定，这里返回 None）
+    fn number_format(self, precision: Option<u8>, currency_symbol: Option<&str>) -> Option<String> {
+        let decimals = |p: u8| {
+            if p == 0 {
+                String::new()
+            } else {
+                format!(".{}", "0".repeat(p as usize))
+            }
+        };
+
+        match self {
+            ColumnType::Text => Some("@".to_string()),
+            ColumnType::Number => precision.map(|p| format!("#,##0{}", decimals(p))),
+            ColumnType::Integer => Some("#,##0".to_string()),
+            ColumnType::Currency => {
+                let body = format!("#,##0{}", decimals(precision.unwrap_or(2)));
+                // 引号会破坏格式字符串，直接剔除
+                let symbol: String = currency_symbol
+                    .unwrap_or(DEFAULT_CURRENCY_SYMBOL)
+                    .chars()
+                    .filter(|c| *c != '"')
+                    .collect();
+                if symbol.is_empty() {
+                    Some(body)
+                } else {
+                    Some(format!("\"{}\"{}", symbol, body))
+                }
+            }
+            ColumnType::Percent => Some(format!("0{}%", decimals(precision.unwrap_or(0)))),
+            ColumnType::Date | ColumnType::DateTime | ColumnType::Boolean => None,
+        }
+    }
+
+    /// 默认水平对齐
+    fn align(self) -> Option<super::style::HAlign> {
+        use super::style::HAlign;
+        match self {
+            ColumnType::Text => Some(HAlign::Left),
+            ColumnType::Number
+            | ColumnType::Integer
+            | ColumnType::Currency
+            | ColumnType::Percent => Some(HAlign::Right),
+            ColumnType::Boolean => Some(HAlign::Center),
+            ColumnType::Date | ColumnType::DateTime => None,
+        }
+    }
+
+    /// 由列类型推导的默认数据单元格样式（数字格式 + 对齐）
+    fn default_style(
+        self,
+        precision: Option<u8>,
+        currency_symbol: Option<&str>,
+    ) -> Option<super::style::CellStyle> {
+        let number_format = self.number_format(precision, currency_symbol);
+        let align = self.align();
+        if number_format.is_none() && align.is_none() {
+            return None;
+        }
+        Some(super::style::CellStyle {
+            number_format,
+            align,
+            ..Default::default()
+        })
+    }
+}
+
+/// 列聚合方式（表尾合计行）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AggregateKind {
+    /// 求和
+    Sum,
+    /// 平均值
+    Avg,
+    /// 非空单元格计数
+    Count,
+    /// 最小值
+    Min,
+    /// 最大值
+    Max,
+}
+
+impl AggregateKind {
+    /// 从 `aggregate` 字段解析
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "sum" => Some(AggregateKind::Sum),
+            "avg" => Some(AggregateKind::Avg),
+            "count" => Some(AggregateKind::Count),
+            "min" => Some(AggregateKind::Min),
+            "max" => Some(AggregateKind::Max),
+            _ => None,
+        }
+    }
+
+    /// 对应的 Excel 函数名
+    fn function_name(self) -> &'static str {
+        match self {
+            AggregateKind::Sum => "SUM",
+            AggregateKind::Avg => "AVERAGE",
+            AggregateKind::Count => "COUNTA",
+            AggregateKind::Min => "MIN",
+            AggregateKind::Max => "MAX",
+        }
+    }
+}
+
+/// Excel 函数最多接受的参数个数
+const MAX_FUNCTION_ARGS: usize = 255;
+
+/// Excel 公式最大长度
+const MAX_FORMULA_LEN: usize = 8192;
+
 /// 解析后的列节点
 #[derive(Default)]
 struct ColumnNode {
@@ -46,6 +228,36 @@ struct ColumnNode {
     style: Option<super::style::CellStyle>,
     /// 表头单元格样式（仅叶子节点有效）
     header_style: Option<super::style::CellStyle>,
+    /// 列数据类型（仅叶子节点有效）
+    col_type: Option<ColumnType>,
+    /// 小数位数（number / currency / percent 列有效）
+    precision: Option<u8>,
+    /// 货币符号（currency 列有效，默认 ¥）
+    currency_symbol: Option<String>,
+    /// 表尾聚合方式（仅叶子节点有效）
+    aggregate: Option<AggregateKind>,
+    /// 表尾标签文本（如 "合计"，仅叶子节点有效）
+    footer_label: Option<String>,
+    /// 数据验证（仅叶子节点有效）
+    validation: Option<ColumnValidation>,
+    /// 条件格式（仅叶子节点有效）
+    conditional_formats: Vec<ConditionalFormatRule>,
+    /// 表头单元格批注
+    header_note: Option<String>,
+    /// 是否按内容自动计算列宽（仅叶子节点有效，显式 width 优先）
+    auto_width: bool,
+}
+
+/// 校验列的 `type` 取值，无法识别时报错并列出支持的取值
+fn check_column_type(column: &str, value: &str) -> Result<ColumnType, String> {
+    ColumnType::parse(value).ok_or_else(|| {
+        format!(
+            "列 '{}' 的 type 无效: '{}'，仅支持 {}",
+            column,
+            value,
+            ColumnType::NAMES
+        )
+    })
 }
 
 /// 从 JsValue 递归解析列配置
@@ -125,12 +337,75 @@ fn parse_column_node_with_depth(
 
     // 解析数据单元格样式
     let style_val = get_object_property(item, "style")?;
-    let style = super::style::parse_cell_style(&style_val);
+    let mut style = super::style::parse_cell_style(&style_val);
+
+    // 解析 locked（false 表示工作表保护时该列数据单元格可编辑）
+    if let Some(locked) = get_object_property(item, "locked")?.as_bool() {
+        style.get_or_insert_with(Default::default).locked = Some(locked);
+    }
 
     // 解析表头单元格样式
     let header_style_val = get_object_property(item, "headerStyle")?;
     let header_style = super::style::parse_cell_style(&header_style_val);
 
+    // 解析列数据类型（分组列没有 key 时以标题指代）
+    let col_type = get_object_property(item, "type")?
+        .as_string()
+        .map(|t| check_column_type(key.as_deref().unwrap_or(&title), &t))
+        .transpose()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    // 解析小数位数
+    let precision = match get_object_property(item, "precision")?.as_f64() {
+        Some(p) if p.fract() == 0.0 && (0.0..=MAX_PRECISION as f64).contains(&p) => Some(p as u8),
+        Some(p) => {
+            return Err(JsValue::from_str(&format!(
+                "列 '{}' 的 precision 无效: {}，应为 0–{} 的整数",
+                title, p, MAX_PRECISION
+            )));
+        }
+        None => None,
+    };
+
+    let currency_symbol = get_object_property(item, "currencySymbol")?.as_string();
+
+    // 解析表尾聚合方式与标签
+    let aggregate = match get_object_property(item, "aggregate")?.as_string() {
+        Some(a) => Some(AggregateKind::parse(&a).ok_or_else(|| {
+            JsValue::from_str(&format!(
+                "列 '{}' 的 aggregate 无效: '{}'，仅支持 sum / avg / count / min / max",
+                title, a
+            ))
+        })?),
+        None => None,
+    };
+    let footer_label = get_object_property(item, "footerLabel")?.as_string();
+
+    // 解析数据验证
+    let validation = parse_column_validation(&get_object_property(item, "validation")?, &title)?;
+
+    // 解析条件格式（作用列在收集叶子列时确定）
+    let conditional_formats = parse_conditional_formats(
+        &get_object_property(item, "conditionalFormats")?,
+        CfTarget::Data,
+        false,
+    )
+    .map_err(|e| {
+        JsValue::from_str(&format!(
+            "列 '{}': {}",
+            title,
+            e.as_string().unwrap_or_default()
+        ))
+    })?;
+
+    let header_note = get_object_property(item, "headerNote")?
+        .as_string()
+        .filter(|s| !s.trim().is_empty());
+
+    let auto_width = get_object_property(item, "autoWidth")?
+        .as_bool()
+        .unwrap_or(false);
+
     Ok(ColumnNode {
         title,
         key,
@@ -138,6 +413,15 @@ fn parse_column_node_with_depth(
         width,
         style,
         header_style,
+        col_type,
+        precision,
+        currency_symbol,
+        aggregate,
+        footer_label,
+        validation,
+        conditional_formats,
+        header_note,
+        auto_width,
     })
 }
 
@@ -181,6 +465,135 @@ fn collect_leaf_keys(nodes: &[ColumnNode]) -> Vec<String> {
     keys
 }
 
+/// 按顺序收集所有叶子节点的数据类型
+fn collect_leaf_types(nodes: &[ColumnNode]) -> Vec<Option<ColumnType>> {
+    let mut types = Vec::new();
+    for node in nodes {
+        if node.children.is_empty() {
+            types.push(node.col_type);
+        } else {
+            types.extend(collect_leaf_types(&node.children));
+        }
+    }
+    types
+}
+
+/// 叶子列的表尾配置
+struct LeafFooter {
+    /// 聚合方式
+    aggregate: Option<AggregateKind>,
+    /// 标签文本
+    label: Option<String>,
+}
+
+/// 按顺序收集所有叶子节点的表尾配置
+fn collect_leaf_footers(nodes: &[ColumnNode]) -> Vec<LeafFooter> {
+    let mut footers = Vec::new();
+    for node in nodes {
+        if node.children.is_empty() {
+            footers.push(LeafFooter {
+                aggregate: node.aggregate,
+                label: node.footer_label.clone(),
+            });
+        } else {
+            footers.extend(collect_leaf_footers(&node.children));
+        }
+    }
+    footers
+}
+
+/// 按顺序收集所有叶子节点的数据验证配置
+fn collect_leaf_validations(nodes: &[ColumnNode]) -> Vec<Option<ColumnValidation>> {
+    let mut validations = Vec::new();
+    for node in nodes {
+        if node.children.is_empty() {
+            validations.push(node.validation.clone());
+        } else {
+            validations.extend(collect_leaf_validations(&node.children));
+        }
+    }
+    validations
+}
+
+/// 按顺序收集叶子节点的条件格式，作用范围设为所在叶子列
+fn collect_leaf_conditional_formats(nodes: &[ColumnNode]) -> Vec<ConditionalFormatRule> {
+    fn walk(nodes: &[ColumnNode], col: &mut u16, out: &mut Vec<ConditionalFormatRule>) {
+        for node in nodes {
+            if node.children.is_empty() {
+                out.extend(
+                    node.conditional_formats
+                        .iter()
+                        .map(|rule| ConditionalFormatRule {
+                            target: CfTarget::Column(*col),
+                            ..rule.clone()
+                        }),
+                );
+                *col = col.saturating_add(1);
+            } else {
+                walk(&node.children, col, out);
+            }
+        }
+    }
+
+    let mut rules = Vec::new();
+    walk(nodes, &mut 0, &mut rules);
+    rules
+}
+
+/// 收集列配置的表头批注，位置与 `fill_header_cells` 写入标题的单元格一致
+fn collect_header_notes(nodes: &[ColumnNode]) -> std::collections::HashMap<(u32, u16), String> {
+    fn walk(
+        nodes: &[ColumnNode],
+        row: u32,
+        col_start: u16,
+        out: &mut std::collections::HashMap<(u32, u16), String>,
+    ) {
+        let mut col = col_start;
+        for node in nodes {
+            if let Some(ref note) = node.header_note {
+                out.insert((row, col), note.clone());
+            }
+            if !node.children.is_empty() {
+                walk(&node.children, row + 1, col, out);
+            }
+            col = col.saturating_add(calc_leaf_count(node) as u16);
+        }
+    }
+
+    let mut notes = std::collections::HashMap::new();
+    walk(nodes, 0, 0, &mut notes);
+    notes
+}
+
+/// 收集启用了 `autoWidth` 的叶子列索引
+fn collect_auto_width_columns(nodes: &[ColumnNode]) -> Vec<u16> {
+    fn walk(nodes: &[ColumnNode], col: &mut u16, out: &mut Vec<u16>) {
+        for node in nodes {
+            if node.children.is_empty() {
+                if node.auto_width {
+                    out.push(*col);
+                }
+                *col = col.saturating_add(1);
+            } else {
+                walk(&node.children, col, out);
+            }
+        }
+    }
+
+    let mut cols = Vec::new();
+    walk(nodes, &mut 0, &mut cols);
+    cols
+}
+
+/// 将叶子列数据验证转换为（列索引, 验证配置）列表
+fn indexed_validations(validations: Vec<Option<ColumnValidation>>) -> Vec<(u16, ColumnValidation)> {
+    validations
+        .into_iter()
+        .enumerate()
+        .filter_map(|(col, v)| Some((u16::try_from(col).ok()?, v?)))
+        .collect()
+}
+
 /// 叶子列的样式信息
 struct LeafColumnStyle {
     /// 列宽
@@ -196,9 +609,18 @@ fn collect_leaf_styles(nodes: &[ColumnNode]) -> Vec<LeafColumnStyle> {
     let mut styles = Vec::new();
     for node in nodes {
         if node.children.is_empty() {
+            // 列类型推导的默认样式在下，用户配置的 style 覆盖其上
+            let type_style = node
+                .col_type
+                .and_then(|t| t.default_style(node.precision, node.currency_symbol.as_deref()));
+            let style = match (type_style, &node.style) {
+                (Some(base), Some(user)) => Some(base.merge(user)),
+                (Some(base), None) => Some(base),
+                (None, user) => user.clone(),
+            };
             styles.push(LeafColumnStyle {
                 width: node.width,
-                style: node.style.clone(),
+                style,
                 header_style: node.header_style.clone(),
             });
         } else {
@@ -320,23 +742,43 @@ fn fill_header_cells(
     col - col_start
 }
 
+/// 表头文本行转为单元格行
+fn header_rows_to_cells(header_rows: Vec<Vec<String>>) -> Vec<Vec<CellValue>> {
+    header_rows
+        .into_iter()
+        .map(|row| row.into_iter().map(CellValue::Text).collect())
+        .collect()
+}
+
 /// 单元格解析结果，包含值和合并信息
 struct CellInfo {
-    /// 单元格文本内容
-    value: String,
+    /// 单元格值
+    value: CellValue,
     /// 列跨度（默认 1，0 表示被左侧单元格覆盖）
     col_span: u32,
     /// 行跨度（默认 1，0 表示被上方单元格覆盖）
     row_span: u32,
     /// 单元格级别样式覆盖
     style: Option<super::style::CellStyle>,
+    /// 单元格批注
+    note: Option<String>,
+    /// 单元格图片（原始 JS 值，提取数据行时解码）
+    image: Option<JsValue>,
+    /// 图片替代文本
+    alt: Option<String>,
+    /// 富文本片段（原始 JS 值，提取数据行时解析）
+    runs: Option<JsValue>,
 }
 
 /// 解析单元格值，支持普通值和带 colSpan/rowSpan 的对象
 ///
-/// 支持两种格式：
+/// 支持三种格式：
 /// 1. 普通值: `"张三"`, `28`, `true` 等
 /// 2. 对象值: `{ value: "张三", colSpan: 2, rowSpan: 3 }`
+/// 3. 公式: `{ formula: "=SUM(C{firstRow}:C{lastRow})", value?: 1200 }`，`value` 为缓存结果
+/// 4. 批注: `{ value: "张三", note: "组长" }`
+/// 5. 图片: `{ image: Uint8Array | "data:image/png;base64,...", alt?: "缩略图" }`
+/// 6. 富文本: `{ runs: [{ text: "逾期", style: { bold: true, fontColor: "#FF0000" } }, { text: " 3 天" }] }`
 ///
 /// 当 colSpan 或 rowSpan 为 0 时，表示该单元格被其他单元格的合并覆盖
 fn parse_cell_value(val: &JsValue) -> CellInfo {
@@ -352,10 +794,47 @@ fn parse_cell_value(val: &JsValue) -> CellInfo {
         let row_span_js = js_sys::Reflect::get(val, &JsValue::from_str("rowSpan"))
             .ok()
             .filter(|v| !v.is_undefined());
-
-        // 只有当对象包含 value、colSpan 或 rowSpan 属性时，才按合并单元格处理
-        if value_js.is_some() || col_span_js.is_some() || row_span_js.is_some() {
-            let value = value_js.map(|v| js_value_to_string(&v)).unwrap_or_default();
+        let formula = read_formula(val);
+        let link = js_sys::Reflect::get(val, &JsValue::from_str("link"))
+            .ok()
+            .and_then(|v| v.as_string());
+        let note = js_sys::Reflect::get(val, &JsValue::from_str("note"))
+            .ok()
+            .and_then(|v| v.as_string())
+            .filter(|s| !s.trim().is_empty());
+        let image = js_sys::Reflect::get(val, &JsValue::from_str("image"))
+            .ok()
+            .filter(|v| !v.is_undefined() && !v.is_null());
+        let runs = js_sys::Reflect::get(val, &JsValue::from_str("runs"))
+            .ok()
+            .filter(|v| !v.is_undefined() && !v.is_null());
+
+        // 只有当对象包含 value、formula、link、note、image、runs、colSpan 或 rowSpan 属性时，才按特殊单元格处理
+        if value_js.is_some()
+            || formula.is_some()
+            || link.is_some()
+            || note.is_some()
+            || image.is_some()
+            || runs.is_some()
+            || col_span_js.is_some()
+            || row_span_js.is_some()
+        {
+            let value = value_js.map(|v| js_value_to_cell(&v)).unwrap_or_default();
+            let value = match (formula, link) {
+                (Some(formula), _) => CellValue::Formula(CellFormula {
+                    formula,
+                    cached: Box::new(value),
+                }),
+                // 不安全的链接（如 javascript:）降级为普通文本
+                (None, Some(link)) => match sanitize_hyperlink(&link) {
+                    Ok(url) => CellValue::Link(CellLink {
+                        url,
+                        text: value.into_text(),
+                    }),
+                    Err(_) => value,
+                },
+                (None, None) => value,
+            };
 
             let col_span = col_span_js
                 .and_then(|v| v.as_f64())
@@ -372,21 +851,317 @@ fn parse_cell_value(val: &JsValue) -> CellInfo {
                 .ok()
                 .and_then(|v| super::style::parse_cell_style(&v));
 
+            let alt = match image {
+                Some(_) => js_sys::Reflect::get(val, &JsValue::from_str("alt"))
+                    .ok()
+                    .and_then(|v| v.as_string()),
+                None => None,
+            };
+
             return CellInfo {
                 value,
                 col_span,
                 row_span,
                 style,
+                note,
+                image,
+                alt,
+                runs,
             };
         }
     }
 
     // 普通值
     CellInfo {
-        value: js_value_to_string(val),
+        value: js_value_to_cell(val),
         col_span: 1,
         row_span: 1,
         style: None,
+        note: None,
+        image: None,
+        alt: None,
+        runs: None,
+    }
+}
+
+/// 读取对象的 `formula` 字段（非空字符串才视为公式）
+fn read_formula(val: &JsValue) -> Option<String> {
+    js_sys::Reflect::get(val, &JsValue::from_str("formula"))
+        .ok()
+        .and_then(|v| v.as_string())
+        .filter(|f| !f.trim().is_empty())
+}
+
+/// 解析 `{ formula, value? }` 公式对象（树形模式使用），非公式对象返回 None
+fn parse_formula_object(val: &JsValue) -> Option<CellValue> {
+    if !val.is_object() || val.is_null() {
+        return None;
+    }
+    let formula = read_formula(val)?;
+    let cached = js_sys::Reflect::get(val, &JsValue::from_str("value"))
+        .map(|v| js_value_to_cell(&v))
+        .unwrap_or_default();
+    Some(CellValue::Formula(CellFormula {
+        formula,
+        cached: Box::new(cached),
+    }))
+}
+
+/// 公式占位符展开上下文（行号均为 Excel 1-based）
+struct FormulaContext<'a> {
+    /// 叶子列的 key 列表（`{col:key}` 查找用）
+    keys: &'a [String],
+    /// 数据区首行行号
+    first_row: u32,
+    /// 数据区末行行号
+    last_row: u32,
+}
+
+/// 展开公式中相对数据区的引用占位符
+///
+/// - `{row}` / `{row-1}` / `{row+2}`：当前行（及相对偏移）的行号
+/// - `{firstRow}` / `{lastRow}`：数据区首/末行行号
+/// - `{col}`：当前列字母；`{col:key}`：指定 key 列的列字母
+///
+/// 其他 `{...}` 内容（如数组常量 `{1,2,3}`）原样保留
+fn expand_formula(
+    formula: &str,
+    ctx: &FormulaContext<'_>,
+    row: u32,
+    col: u16,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(formula.len());
+    let mut rest = formula;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return Ok(out);
+        };
+        let token = &after[..end];
+
+        let replacement = match token {
+            "row" => Some(row.to_string()),
+            "firstRow" => Some(ctx.first_row.to_string()),
+            "lastRow" => Some(ctx.last_row.to_string()),
+            "col" => Some(rust_xlsxwriter::utility::column_number_to_name(col)),
+            _ => {
+                if let Some(key) = token.strip_prefix("col:") {
+                    let idx = ctx
+                        .keys
+                        .iter()
+                        .position(|k| k == key)
+                        .ok_or_else(|| format!("公式引用了不存在的列 key '{}'", key))?;
+                    Some(rust_xlsxwriter::utility::column_number_to_name(idx as u16))
+                } else if let Some(offset) = token.strip_prefix("row") {
+                    let target = offset
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|_| offset.starts_with(['+', '-']))
+                        .map(|n| i64::from(row) + n)
+                        .ok_or_else(|| format!("公式占位符无效: {{{}}}", token))?;
+                    if target < 1 {
+                        return Err(format!("公式占位符 {{{}}} 指向了第 1 行之前", token));
+                    }
+                    Some(target.to_string())
+                } else {
+                    None
+                }
+            }
+        };
+
+        match replacement {
+            Some(r) => out.push_str(&r),
+            None => {
+                out.push('{');
+                out.push_str(token);
+                out.push('}');
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// 展开数据行中所有公式单元格的占位符
+///
+/// # 参数
+/// * `data_rows` - 数据行（不含表头）
+/// * `keys` - 叶子列的 key 列表
+/// * `header_row_count` - 表头行数（数据区在工作表中的行偏移）
+fn resolve_formulas(
+    data_rows: &mut [Vec<CellValue>],
+    keys: &[String],
+    header_row_count: usize,
+) -> Result<(), JsValue> {
+    let ctx = FormulaContext {
+        keys,
+        first_row: header_row_count as u32 + 1,
+        last_row: (header_row_count + data_rows.len()) as u32,
+    };
+
+    for (i, row) in data_rows.iter_mut().enumerate() {
+        let excel_row = ctx.first_row + i as u32;
+        for (j, cell) in row.iter_mut().enumerate() {
+            if let CellValue::Formula(f) = cell {
+                f.formula = expand_formula(&f.formula, &ctx, excel_row, j as u16).map_err(|e| {
+                    JsValue::from_str(&format!("第 {} 行第 {} 列{}", i + 1, j + 1, e))
+                })?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 在 rows 末尾追加表尾合计行，并登记表尾单元格样式
+///
+/// XLSX 中聚合单元格为引用数据区的公式，缓存结果即计算值（CSV 输出计算值）；
+/// 参与聚合的行无法在函数参数/公式长度限制内表示时退化为静态计算值。
+///
+/// # 参数
+/// * `rows` - 完整行数据（表头 + 数据行）
+/// * `header_row_count` - 表头行数
+/// * `source_rows` - 参与聚合的数据行索引（相对数据区，升序；树形模式仅叶子行）
+/// * `footers` - 叶子列的表尾配置
+/// * `cell_overrides` - 单元格级样式覆盖（表尾样式写入此处）
+///
+/// # 返回值
+/// 追加的表尾行数（没有任何列声明 aggregate 时为 0）
+fn append_footer_row(
+    rows: &mut Vec<Vec<CellValue>>,
+    header_row_count: usize,
+    source_rows: &[usize],
+    footers: &[LeafFooter],
+    cell_overrides: &mut std::collections::HashMap<(u32, u16), super::style::CellStyle>,
+) -> usize {
+    if footers.iter().all(|f| f.aggregate.is_none()) {
+        return 0;
+    }
+
+    let data_rows = &rows[header_row_count..];
+    let first_row = header_row_count + 1;
+    let footer_row = rows.len() as u32;
+
+    let mut cells = Vec::with_capacity(footers.len());
+    for (col, footer) in footers.iter().enumerate() {
+        let cell = match (footer.aggregate, &footer.label) {
+            (Some(kind), _) => {
+                let values = source_rows
+                    .iter()
+                    .filter_map(|&r| data_rows.get(r).and_then(|row| row.get(col)));
+                let computed = aggregate_cells(kind, values);
+                let col_name = rust_xlsxwriter::utility::column_number_to_name(col as u16);
+                let ranges = row_ranges(&col_name, source_rows, first_row);
+                let formula = format!("={}({})", kind.function_name(), ranges.join(","));
+                if ranges.is_empty()
+                    || ranges.len() > MAX_FUNCTION_ARGS
+                    || formula.len() > MAX_FORMULA_LEN
+                {
+                    computed
+                } else {
+                    CellValue::Formula(CellFormula {
+                        formula,
+                        cached: Box::new(computed),
+                    })
+                }
+            }
+            (None, Some(label)) => CellValue::Text(label.clone()),
+            (None, None) => CellValue::Empty,
+        };
+
+        cell_overrides.insert((footer_row, col as u16), footer_style(footer.aggregate));
+        cells.push(cell);
+    }
+
+    rows.push(cells);
+    1
+}
+
+/// 计算聚合值（与 Excel 函数语义一致：仅数字参与 SUM/AVERAGE/MIN/MAX，COUNTA 统计非空单元格）
+fn aggregate_cells<'a>(
+    kind: AggregateKind,
+    cells: impl Iterator<Item = &'a CellValue>,
+) -> CellValue {
+    let mut count = 0usize;
+    let mut numbers = Vec::new();
+    for cell in cells {
+        if !matches!(cell, CellValue::Empty) {
+            count += 1;
+        }
+        if let Some(n) = numeric_value(cell) {
+            numbers.push(n);
+        }
+    }
+
+    match kind {
+        AggregateKind::Sum => CellValue::Number(numbers.iter().sum()),
+        AggregateKind::Count => CellValue::Number(count as f64),
+        // AVERAGE 无数字时为 #DIV/0!，这里输出空值
+        AggregateKind::Avg if numbers.is_empty() => CellValue::Empty,
+        AggregateKind::Avg => CellValue::Number(numbers.iter().sum::<f64>() / numbers.len() as f64),
+        // MIN/MAX 无数字时 Excel 返回 0
+        AggregateKind::Min => {
+            CellValue::Number(numbers.iter().copied().reduce(f64::min).unwrap_or(0.0))
+        }
+        AggregateKind::Max => {
+            CellValue::Number(numbers.iter().copied().reduce(f64::max).unwrap_or(0.0))
+        }
+    }
+}
+
+/// 单元格的数值（公式取缓存结果）
+fn numeric_value(cell: &CellValue) -> Option<f64> {
+    match cell {
+        CellValue::Number(n) => Some(*n),
+        CellValue::Formula(f) => numeric_value(&f.cached),
+        _ => None,
+    }
+}
+
+/// 将升序的数据行索引压缩为连续区间引用（如 `["B3:B5", "B8"]`）
+fn row_ranges(col_name: &str, rows: &[usize], first_row: usize) -> Vec<String> {
+    let mut ranges = Vec::new();
+    let mut iter = rows.iter().copied().peekable();
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while iter.peek() == Some(&(end + 1)) {
+            end += 1;
+            iter.next();
+        }
+        if start == end {
+            ranges.push(format!("{}{}", col_name, first_row + start));
+        } else {
+            ranges.push(format!(
+                "{}{}:{}{}",
+                col_name,
+                first_row + start,
+                col_name,
+                first_row + end
+            ));
+        }
+    }
+    ranges
+}
+
+/// 表尾单元格样式：加粗 + 上边框，计数列使用整数格式
+fn footer_style(aggregate: Option<AggregateKind>) -> super::style::CellStyle {
+    use super::style::{BorderConfig, BorderLine, CellStyle};
+
+    CellStyle {
+        bold: Some(true),
+        border: Some(BorderConfig::Individual {
+            top: Some(BorderLine::Thin),
+            bottom: None,
+            left: None,
+            right: None,
+        }),
+        number_format: (aggregate == Some(AggregateKind::Count)).then(|| "#,##0".to_string()),
+        ..Default::default()
     }
 }
 
@@ -395,20 +1170,24 @@ fn parse_cell_value(val: &JsValue) -> CellInfo {
 /// # 参数
 /// * `data` - JS 对象数组
 /// * `keys` - 叶子列的 key 列表
+/// * `types` - 叶子列的数据类型（与 keys 一一对应）
 /// * `header_row_count` - 表头行数（用于 MergeRange 的行偏移）
+/// * `row_style` - 行级样式配置（逐行求值）
 ///
 /// # 返回值
-/// (二维字符串数组, 数据区域的合并区域列表, 单元格级样式覆盖)
+/// 数据行及其合并区域、单元格级样式覆盖、批注、图片与行级样式
 fn extract_data_rows(
     data: &JsValue,
     keys: &[String],
+    types: &[Option<ColumnType>],
     header_row_count: usize,
+    row_style: &RowStyleOptions,
 ) -> Result<ExtractedDataRows, JsValue> {
     let array = js_sys::Array::from(data);
     let length = array.length();
 
     if length == 0 {
-        return Ok((Vec::new(), Vec::new(), std::collections::HashMap::new()));
+        return Ok(ExtractedDataRows::default());
     }
 
     // 数据区总单元格上限（与表头保护对称）
@@ -427,26 +1206,75 @@ fn extract_data_rows(
     let mut rows = Vec::with_capacity(row_count);
     let mut merge_ranges = Vec::new();
     let mut cell_overrides = std::collections::HashMap::new();
+    let mut notes = std::collections::HashMap::new();
+    let mut images = std::collections::HashMap::new();
+    let mut image_budget = ImageBudget::default();
+    let mut row_styler = RowStyler::new(row_style, header_row_count);
 
     for i in 0..length {
         let item = array.get(i);
         let mut row = Vec::with_capacity(col_count);
+        row_styler.push(&item)?;
 
         for (col_idx, key) in keys.iter().enumerate() {
             let val = get_object_property(&item, key)?;
             let cell_info = parse_cell_value(&val);
 
             if cell_info.col_span == 0 || cell_info.row_span == 0 {
-                // 被其他单元格的合并覆盖，输出空字符串
-                row.push(String::new());
+                // 被其他单元格的合并覆盖，输出空单元格
+                row.push(CellValue::Empty);
             } else {
-                row.push(cell_info.value);
-
-                // 收集单元格级样式
+                // 富文本优先级低于公式与链接
+                let value = match cell_info.runs {
+                    Some(ref runs)
+                        if !matches!(
+                            cell_info.value,
+                            CellValue::Formula(_) | CellValue::Link(_)
+                        ) =>
+                    {
+                        let runs = parse_rich_text_runs(runs).map_err(|e| {
+                            JsValue::from_str(&format!(
+                                "第 {} 行 '{}' 列的富文本无效: {}",
+                                i + 1,
+                                key,
+                                e.as_string().unwrap_or_default()
+                            ))
+                        })?;
+                        if runs.is_empty() {
+                            CellValue::Empty
+                        } else {
+                            CellValue::RichText(runs)
+                        }
+                    }
+                    _ => cell_info.value,
+                };
+                let col_type = types.get(col_idx).copied().flatten();
+                let mut cell = coerce_cell(value, col_type);
+
+                // 收集单元格级样式与批注
+                let abs_row = (i as usize + header_row_count) as u32;
                 if let Some(style) = cell_info.style {
-                    let abs_row = (i as usize + header_row_count) as u32;
                     cell_overrides.insert((abs_row, col_idx as u16), style);
                 }
+                if let Some(note) = cell_info.note {
+                    notes.insert((abs_row, col_idx as u16), note);
+                }
+                // 无效或超出上限的图片跳过（与 DOM 图片一致），单元格无文本时以 alt 代替
+                if let Some(ref image) = cell_info.image {
+                    match decode_data_image(image, &mut image_budget) {
+                        Some(data) => {
+                            images.insert(
+                                (abs_row, col_idx as u16),
+                                CellImage {
+                                    data,
+                                    alt: cell_info.alt.clone(),
+                                },
+                            );
+                        }
+                        None => fill_image_alt(&mut cell, cell_info.alt.as_ref()),
+                    }
+                }
+                row.push(cell);
 
                 // 生成合并区域（colSpan>1 或 rowSpan>1）
                 // 增加防护：确保 row_span 和 col_span 至少为 1，防止计算下溢
@@ -470,7 +1298,14 @@ fn extract_data_rows(
         rows.push(row);
     }
 
-    Ok((rows, merge_ranges, cell_overrides))
+    Ok(ExtractedDataRows {
+        rows,
+        merge_ranges,
+        cell_overrides,
+        notes,
+        images,
+        row_styles: row_styler.finish(),
+    })
 }
 
 /// 将 span 数值转为 u32：非有限/负值视为 0，小数截断
@@ -482,24 +1317,159 @@ fn parse_span_value(n: f64) -> u32 {
     }
 }
 
-/// 将 JS 值转换为字符串
-pub(crate) fn js_value_to_string(val: &JsValue) -> String {
+/// 将 JS 值转换为带类型的单元格值
+///
+/// 数字/布尔保留原生类型（XLSX 写为数字/布尔单元格），其文本形式与 CSV 输出一致
+pub(crate) fn js_value_to_cell(val: &JsValue) -> CellValue {
     if val.is_null() || val.is_undefined() {
-        String::new()
+        CellValue::Empty
     } else if let Some(s) = val.as_string() {
-        s
+        CellValue::Text(s)
     } else if let Some(n) = val.as_f64() {
-        format_js_number(n)
+        // NaN/Infinity 无法写入 Excel，CSV 中也输出为空
+        if n.is_finite() {
+            CellValue::Number(n)
+        } else {
+            CellValue::Empty
+        }
     } else if let Some(b) = val.as_bool() {
-        b.to_string()
+        CellValue::Bool(b)
+    } else if let Some(date) = val.dyn_ref::<js_sys::Date>() {
+        js_date_to_cell(date)
     } else {
         // Symbol、BigInt 等其他类型，使用 Debug 格式输出
-        format!("{:?}", val)
+        CellValue::Text(format!("{:?}", val))
+    }
+}
+
+/// 将 JS Date 转换为日期单元格（按本地时间取值）
+///
+/// 无效日期（Invalid Date）视为空值；年份超出 0–9999 时按 ISO 文本输出
+fn js_date_to_cell(date: &js_sys::Date) -> CellValue {
+    if !date.get_time().is_finite() {
+        return CellValue::Empty;
+    }
+
+    let year = date.get_full_year();
+    let value = u16::try_from(year).ok().and_then(|year| {
+        CellDateTime::from_ymd(year, (date.get_month() + 1) as u8, date.get_date() as u8)?
+            .and_hms_milli(
+                date.get_hours() as u8,
+                date.get_minutes() as u8,
+                date.get_seconds() as u8,
+                date.get_milliseconds() as u16,
+            )
+    });
+
+    match value {
+        // 零点视为纯日期，按日期格式显示
+        Some(dt) if dt.hour == 0 && dt.minute == 0 && dt.second == 0 && dt.millisecond == 0 => {
+            CellValue::DateTime(dt.date_only())
+        }
+        Some(dt) => CellValue::DateTime(dt),
+        None => CellValue::Text(String::from(date.to_iso_string())),
+    }
+}
+
+/// 按列类型转换单元格值
+///
+/// 无法按列类型解析的值保持原样输出
+fn coerce_cell(cell: CellValue, col_type: Option<ColumnType>) -> CellValue {
+    match col_type {
+        None => cell,
+        Some(t @ (ColumnType::Date | ColumnType::DateTime)) => coerce_date_cell(cell, t),
+        Some(t) => coerce_scalar_cell(cell, t),
+    }
+}
+
+/// 日期列转换：ISO-8601 字符串解析为日期；带时区的字符串交给 JS Date 换算为本地时间
+fn coerce_date_cell(cell: CellValue, col_type: ColumnType) -> CellValue {
+    let cell = match cell {
+        CellValue::Text(s) => match CellDateTime::parse_iso(&s) {
+            Some(iso) if iso.has_zone => {
+                js_date_to_cell(&js_sys::Date::new(&JsValue::from_str(&s)))
+            }
+            Some(iso) => CellValue::DateTime(iso.value),
+            None => CellValue::Text(s),
+        },
+        other => other,
+    };
+
+    match (cell, col_type) {
+        (CellValue::DateTime(dt), ColumnType::Date) => CellValue::DateTime(dt.date_only()),
+        (CellValue::DateTime(dt), ColumnType::DateTime) => CellValue::DateTime(dt.with_time_flag()),
+        (other, _) => other,
+    }
+}
+
+/// 非日期列转换（文本 / 数值 / 布尔）
+fn coerce_scalar_cell(cell: CellValue, col_type: ColumnType) -> CellValue {
+    match col_type {
+        ColumnType::Text => match cell {
+            CellValue::Number(_) | CellValue::Bool(_) | CellValue::DateTime(_) => {
+                CellValue::Text(cell.into_text())
+            }
+            other => other,
+        },
+        ColumnType::Number | ColumnType::Currency | ColumnType::Percent | ColumnType::Integer => {
+            let cell = match cell {
+                CellValue::Text(s) => match parse_numeric_text(&s) {
+                    Some(n) => CellValue::Number(n),
+                    None => CellValue::Text(s),
+                },
+                other => other,
+            };
+            match cell {
+                CellValue::Number(n) if col_type == ColumnType::Integer => {
+                    CellValue::Number(n.round())
+                }
+                other => other,
+            }
+        }
+        ColumnType::Boolean => match cell {
+            CellValue::Text(s) => match parse_bool_text(&s) {
+                Some(b) => CellValue::Bool(b),
+                None => CellValue::Text(s),
+            },
+            CellValue::Number(n) if n == 0.0 || n == 1.0 => CellValue::Bool(n == 1.0),
+            other => other,
+        },
+        ColumnType::Date | ColumnType::DateTime => cell,
+    }
+}
+
+/// 解析数值文本：支持千分位逗号、前导货币符号与 `%` 后缀（如 `"-¥1,234.5"`、`"12.5%"`）
+fn parse_numeric_text(s: &str) -> Option<f64> {
+    let t = s.trim();
+    let (negative, t) = match t.strip_prefix('-') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, t),
+    };
+    let t = t.trim_start_matches(CURRENCY_SYMBOLS).trim_start();
+    let (t, scale) = match t.strip_suffix('%') {
+        Some(rest) => (rest.trim_end(), 0.01),
+        None => (t, 1.0),
+    };
+    // 只接受数字开头，排除 "inf"、"NaN" 等 Rust 可解析的特殊值
+    if !t.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let n = t.replace(',', "").parse::<f64>().ok()?;
+    let n = if negative { -n } else { n } * scale;
+    n.is_finite().then_some(n)
+}
+
+/// 解析布尔文本（不区分大小写）
+fn parse_bool_text(s: &str) -> Option<bool> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" | "是" => Some(true),
+        "false" | "no" | "n" | "0" | "否" => Some(false),
+        _ => None,
     }
 }
 
 /// 稳定的 JS 数字格式化，避免 `0.30000000000000004` 与无意义科学计数
-fn format_js_number(n: f64) -> String {
+pub(crate) fn format_js_number(n: f64) -> String {
     if !n.is_finite() {
         return String::new();
     }
@@ -527,22 +1497,60 @@ fn format_js_number(n: f64) -> String {
     }
 }
 
+/// 树形数据拍平结果（各字段按行一一对应）
+#[derive(Default)]
+struct FlatTree {
+    /// 行数据
+    rows: Vec<Vec<CellValue>>,
+    /// 叶子行（无子节点）在 rows 中的索引（表尾聚合使用）
+    leaf_rows: Vec<usize>,
+    /// 每行的层级深度（根节点为 0）
+    depths: Vec<usize>,
+    /// 每行的源数据对象（行级样式求值使用）
+    items: Vec<JsValue>,
+}
+
+impl FlatTree {
+    /// 将父节点行移到其子孙行之后（大纲汇总行位于下方）
+    fn move_parents_below(&mut self) {
+        let order = summary_below_order(&self.depths);
+        let mut new_index = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            new_index[old] = new;
+        }
+
+        let mut rows: Vec<Option<Vec<CellValue>>> = std::mem::take(&mut self.rows)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.rows = order.iter().filter_map(|&i| rows[i].take()).collect();
+        self.depths = order.iter().map(|&i| self.depths[i]).collect();
+        self.items = order.iter().map(|&i| self.items[i].clone()).collect();
+        for row in &mut self.leaf_rows {
+            *row = new_index[*row];
+        }
+        self.leaf_rows.sort_unstable();
+    }
+}
+
 /// 递归遍历树形数据，将嵌套的 children 拍平为行数据
 ///
 /// # 参数
 /// * `data` - JS 对象数组（可能包含 children）
 /// * `keys` - 叶子列的 key 列表
-/// * `indent_key` - 需要缩进的列的 key（可选）
+/// * `types` - 叶子列的数据类型（与 keys 一一对应）
+/// * `indent_key` - 需要以空格缩进的列的 key（可选）
 /// * `children_key` - 子节点字段名
 /// * `depth` - 当前递归深度
-/// * `rows` - 输出行数据（可变引用，递归中累积）
+/// * `out` - 拍平结果（可变引用，递归中累积）
 fn flatten_tree_data(
     data: &JsValue,
     keys: &[String],
+    types: &[Option<ColumnType>],
     indent_key: Option<&str>,
     children_key: &str,
     depth: usize,
-    rows: &mut Vec<Vec<String>>,
+    out: &mut FlatTree,
 ) -> Result<(), JsValue> {
     if depth >= MAX_DEPTH {
         return Err(JsValue::from_str(&format!(
@@ -558,26 +1566,32 @@ fn flatten_tree_data(
         let item = array.get(i);
         let mut row = Vec::with_capacity(keys.len());
 
-        for key in keys {
+        for (col_idx, key) in keys.iter().enumerate() {
             let val = get_object_property(&item, key)?;
-            let mut cell_text = js_value_to_string(&val);
+            let col_type = types.get(col_idx).copied().flatten();
+            let mut cell = match parse_formula_object(&val) {
+                Some(formula) => formula,
+                None => coerce_cell(js_value_to_cell(&val), col_type),
+            };
 
-            // 对指定的缩进列添加层级缩进（每层 4 个空格）
+            // 对指定的缩进列添加层级缩进（每层 4 个空格），缩进后按文本输出
             if let Some(ik) = indent_key
                 && key == ik
                 && depth > 0
             {
                 let indent = "    ".repeat(depth);
-                cell_text = format!("{}{}", indent, cell_text);
+                cell = CellValue::Text(format!("{}{}", indent, cell.as_text()));
             }
 
-            row.push(cell_text);
+            row.push(cell);
         }
 
-        rows.push(row);
+        out.rows.push(row);
+        out.depths.push(depth);
+        out.items.push(item.clone());
 
         // 树形拍平后的累计单元格数也要受上限约束
-        match rows.len().checked_mul(keys.len()) {
+        match out.rows.len().checked_mul(keys.len()) {
             Some(total) if total <= MAX_DATA_CELLS => {}
             _ => {
                 return Err(JsValue::from_str(&format!(
@@ -588,19 +1602,23 @@ fn flatten_tree_data(
         }
 
         // 递归处理子节点
+        let row_idx = out.rows.len() - 1;
         let children_raw = get_object_property(&item, children_key)?;
-        if !children_raw.is_undefined() && !children_raw.is_null() {
-            let child_arr = js_sys::Array::from(&children_raw);
-            if child_arr.length() > 0 {
-                flatten_tree_data(
-                    &children_raw,
-                    keys,
-                    indent_key,
-                    children_key,
-                    depth + 1,
-                    rows,
-                )?;
-            }
+        let has_children = !children_raw.is_undefined()
+            && !children_raw.is_null()
+            && js_sys::Array::from(&children_raw).length() > 0;
+        if has_children {
+            flatten_tree_data(
+                &children_raw,
+                keys,
+                types,
+                indent_key,
+                children_key,
+                depth + 1,
+                out,
+            )?;
+        } else {
+            out.leaf_rows.push(row_idx);
         }
     }
 
@@ -611,12 +1629,15 @@ fn flatten_tree_data(
 ///
 /// 将嵌套的 children 结构递归遍历为扁平的行数据，
 /// 可选在指定列添加层级缩进以体现树形层次关系。
+/// 启用 `outline` 时层级写为 Excel 行分组，缩进列改用单元格缩进格式。
 ///
 /// # 参数
 /// * `columns` - JS 表头配置数组
 /// * `data` - JS 树形数据数组（可包含 children）
 /// * `indent_column` - 需要缩进的列的 key（可选，如 "name"）
 /// * `children_key` - 子节点字段名（默认 "children"）
+/// * `row_style` - 行级样式配置（斑马纹按拍平后的行计数）
+/// * `outline` - 大纲配置（仅 XLSX 导出时传入）
 ///
 /// # 返回值
 /// 包含表头和拍平后的数据行的 TableData
@@ -625,6 +1646,8 @@ pub fn build_table_data_from_tree(
     data: &JsValue,
     indent_column: Option<&str>,
     children_key: &str,
+    row_style: &RowStyleOptions,
+    outline: Option<OutlineOptions>,
 ) -> Result<TableData, JsValue> {
     // 1. 解析列配置
     let column_nodes = parse_columns(columns)?;
@@ -635,33 +1658,97 @@ pub fn build_table_data_from_tree(
     // 3. 构建多行表头和合并区域
     let (header_rows, merge_ranges) = build_header_rows(&column_nodes, max_depth)?;
 
-    // 4. 收集叶子 key 和样式
+    // 4. 收集叶子 key、类型和样式
     let leaf_keys = collect_leaf_keys(&column_nodes);
+    let leaf_types = collect_leaf_types(&column_nodes);
     let leaf_styles = collect_leaf_styles(&column_nodes);
 
-    // 5. 递归拍平树形数据
-    let mut data_rows = Vec::new();
+    let leaf_footers = collect_leaf_footers(&column_nodes);
+    let data_validations = indexed_validations(collect_leaf_validations(&column_nodes));
+    let conditional_formats = collect_leaf_conditional_formats(&column_nodes);
+    let auto_width_columns = collect_auto_width_columns(&column_nodes);
+    let notes = collect_header_notes(&column_nodes);
+
+    // 5. 递归拍平树形数据（大纲模式下不添加空格缩进）
+    let mut tree = FlatTree::default();
     flatten_tree_data(
         data,
         &leaf_keys,
-        indent_column,
+        &leaf_types,
+        if outline.is_some() {
+            None
+        } else {
+            indent_column
+        },
         children_key,
         0,
-        &mut data_rows,
+        &mut tree,
     )?;
+    if outline.is_some_and(|o| o.summary_below) {
+        tree.move_parents_below();
+    }
+    let FlatTree {
+        rows: mut data_rows,
+        leaf_rows,
+        depths,
+        items,
+    } = tree;
+
+    // 按最终行顺序求值行级样式
+    let mut row_styler = RowStyler::new(row_style, max_depth);
+    for item in &items {
+        row_styler.push(item)?;
+    }
 
-    // 6. 合并表头行和数据行
-    let mut rows = header_rows;
+    // 6. 展开公式占位符，合并表头行和数据行
+    resolve_formulas(&mut data_rows, &leaf_keys, max_depth)?;
+    let mut rows = header_rows_to_cells(header_rows);
     rows.extend(data_rows);
 
-    // 7. 构建 StyleSheet（来自列配置）
-    let style_sheet = build_column_style_sheet(&leaf_styles);
+    // 7. 大纲模式下以单元格缩进体现层级，再追加表尾合计行（仅聚合叶子行，避免父子行重复计入）
+    let mut cell_overrides = std::collections::HashMap::new();
+    if outline.is_some()
+        && let Some(col) = indent_column.and_then(|ik| leaf_keys.iter().position(|k| k == ik))
+    {
+        for (i, &depth) in depths.iter().enumerate().filter(|(_, d)| **d > 0) {
+            cell_overrides.insert(
+                ((max_depth + i) as u32, col as u16),
+                super::style::CellStyle {
+                    indent: Some(depth.min(super::style::MAX_INDENT as usize) as u8),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+    let footer_row_count = append_footer_row(
+        &mut rows,
+        max_depth,
+        &leaf_rows,
+        &leaf_footers,
+        &mut cell_overrides,
+    );
+
+    // 8. 构建 StyleSheet（来自列配置 + 行级样式 + 表尾样式）
+    let style_sheet =
+        build_column_style_sheet_with_overrides(&leaf_styles, row_styler.finish(), cell_overrides);
 
     Ok(TableData {
         rows,
         merge_ranges,
         header_row_count: max_depth,
+        footer_row_count,
         style_sheet,
+        sheet_options: Default::default(),
+        data_validations,
+        conditional_formats,
+        notes,
+        images: Default::default(),
+        auto_width_columns,
+        outline: outline.map(|options| RowOutline {
+            options,
+            first_row: max_depth as u32,
+            depths,
+        }),
     })
 }
 
@@ -675,12 +1762,14 @@ pub fn build_table_data_from_tree(
 /// # 参数
 /// * `columns` - JS 表头配置数组
 /// * `data` - JS 数据对象数组
+/// * `row_style` - 行级样式配置
 ///
 /// # 返回值
 /// 包含表头和数据行及合并区域的 TableData
 pub fn build_table_data_from_array(
     columns: &JsValue,
     data: &JsValue,
+    row_style: &RowStyleOptions,
 ) -> Result<TableData, JsValue> {
     // 1. 解析列配置
     let column_nodes = parse_columns(columns)?;
@@ -692,48 +1781,78 @@ pub fn build_table_data_from_array(
     let (header_rows, mut merge_ranges) = build_header_rows(&column_nodes, max_depth)?;
     let header_row_count = header_rows.len();
 
-    // 4. 收集叶子 key 和样式
+    // 4. 收集叶子 key、类型和样式
     let leaf_keys = collect_leaf_keys(&column_nodes);
+    let leaf_types = collect_leaf_types(&column_nodes);
     let leaf_styles = collect_leaf_styles(&column_nodes);
 
-    // 5. 提取数据行（含数据区域合并信息和单元格样式）
-    let (data_rows, data_merge_ranges, cell_overrides) =
-        extract_data_rows(data, &leaf_keys, header_row_count)?;
+    let leaf_footers = collect_leaf_footers(&column_nodes);
+    let data_validations = indexed_validations(collect_leaf_validations(&column_nodes));
+    let conditional_formats = collect_leaf_conditional_formats(&column_nodes);
+    let auto_width_columns = collect_auto_width_columns(&column_nodes);
 
-    // 6. 合并表头行和数据行
-    let mut rows = header_rows;
+    // 5. 提取数据行（含数据区域合并信息和单元格样式）
+    let ExtractedDataRows {
+        rows: mut data_rows,
+        merge_ranges: data_merge_ranges,
+        mut cell_overrides,
+        mut notes,
+        images,
+        row_styles,
+    } = extract_data_rows(data, &leaf_keys, &leaf_types, header_row_count, row_style)?;
+    notes.extend(collect_header_notes(&column_nodes));
+
+    // 6. 展开公式占位符，合并表头行和数据行
+    resolve_formulas(&mut data_rows, &leaf_keys, header_row_count)?;
+    let source_rows: Vec<usize> = (0..data_rows.len()).collect();
+    let mut rows = header_rows_to_cells(header_rows);
     rows.extend(data_rows);
 
-    // 7. 合并表头合并区域和数据合并区域
+    // 7. 追加表尾合计行
+    let footer_row_count = append_footer_row(
+        &mut rows,
+        header_row_count,
+        &source_rows,
+        &leaf_footers,
+        &mut cell_overrides,
+    );
+
+    // 8. 合并表头合并区域和数据合并区域
     merge_ranges.extend(data_merge_ranges);
 
-    // 8. 构建 StyleSheet（来自列配置 + 单元格级覆盖）
-    let style_sheet = build_column_style_sheet_with_overrides(&leaf_styles, cell_overrides);
+    // 9. 构建 StyleSheet（来自列配置 + 行级样式 + 单元格级覆盖）
+    let style_sheet =
+        build_column_style_sheet_with_overrides(&leaf_styles, row_styles, cell_overrides);
 
     Ok(TableData {
         rows,
         merge_ranges,
         header_row_count: max_depth,
+        footer_row_count,
         style_sheet,
+        sheet_options: Default::default(),
+        data_validations,
+        conditional_formats,
+        notes,
+        images,
+        auto_width_columns,
+        outline: None,
     })
 }
 
-/// 从叶子列样式构建 StyleSheet（不含单元格级覆盖）
-fn build_column_style_sheet(leaf_styles: &[LeafColumnStyle]) -> Option<super::style::StyleSheet> {
-    build_column_style_sheet_with_overrides(leaf_styles, std::collections::HashMap::new())
-}
-
-/// 从叶子列样式和单元格级覆盖构建 StyleSheet
+/// 从叶子列样式、行级样式和单元格级覆盖构建 StyleSheet
 fn build_column_style_sheet_with_overrides(
     leaf_styles: &[LeafColumnStyle],
+    row_styles: RowStyles,
     cell_overrides: std::collections::HashMap<(u32, u16), super::style::CellStyle>,
 ) -> Option<super::style::StyleSheet> {
     let has_columns = leaf_styles
         .iter()
         .any(|s| s.width.is_some() || s.style.is_some() || s.header_style.is_some());
+    let has_rows = !row_styles.index.is_empty() || !row_styles.heights.is_empty();
     let has_overrides = !cell_overrides.is_empty();
 
-    if !has_columns && !has_overrides {
+    if !has_columns && !has_rows && !has_overrides {
         return None;
     }
 
@@ -741,6 +1860,9 @@ fn build_column_style_sheet_with_overrides(
         column_widths: leaf_styles.iter().map(|s| s.width).collect(),
         column_styles: leaf_styles.iter().map(|s| s.style.clone()).collect(),
         column_header_styles: leaf_styles.iter().map(|s| s.header_style.clone()).collect(),
+        row_styles: row_styles.styles,
+        row_style_index: row_styles.index,
+        row_heights: row_styles.heights,
         cell_overrides,
         ..Default::default()
     })
@@ -763,6 +1885,176 @@ mod tests {
         assert_ne!(format_js_number(2.5e-9), "0");
     }
 
+    #[test]
+    fn test_coerce_scalar_cell_by_column_type() {
+        assert_eq!(
+            coerce_scalar_cell(CellValue::from("-¥1,234.5"), ColumnType::Currency),
+            CellValue::Number(-1234.5)
+        );
+        assert_eq!(
+            coerce_scalar_cell(CellValue::from("12.5%"), ColumnType::Percent),
+            CellValue::Number(0.125)
+        );
+        assert_eq!(
+            coerce_scalar_cell(CellValue::Number(2.5), ColumnType::Integer),
+            CellValue::Number(3.0)
+        );
+        assert_eq!(
+            coerce_scalar_cell(CellValue::from("是"), ColumnType::Boolean),
+            CellValue::Bool(true)
+        );
+        assert_eq!(
+            coerce_scalar_cell(CellValue::Number(0.0), ColumnType::Boolean),
+            CellValue::Bool(false)
+        );
+        // 文本列保留前导零等原样文本，数字转为文本
+        assert_eq!(
+            coerce_scalar_cell(CellValue::Number(42.0), ColumnType::Text),
+            CellValue::from("42")
+        );
+        // 无法解析的值原样输出
+        assert_eq!(
+            coerce_scalar_cell(CellValue::from("N/A"), ColumnType::Number),
+            CellValue::from("N/A")
+        );
+        assert_eq!(
+            coerce_scalar_cell(CellValue::from("inf"), ColumnType::Number),
+            CellValue::from("inf")
+        );
+    }
+
+    #[test]
+    fn test_column_type_default_style() {
+        use super::super::style::HAlign;
+
+        let style = ColumnType::Currency.default_style(None, None).unwrap();
+        assert_eq!(style.number_format.as_deref(), Some("\"¥\"#,##0.00"));
+        assert_eq!(style.align, Some(HAlign::Right));
+        assert_eq!(
+            ColumnType::Currency.number_format(Some(0), Some("$")),
+            Some("\"$\"#,##0".to_string())
+        );
+        assert_eq!(
+            ColumnType::Percent.number_format(Some(1), None),
+            Some("0.0%".to_string())
+        );
+        assert_eq!(ColumnType::Number.number_format(None, None), None);
+        assert_eq!(
+            ColumnType::Number.number_format(Some(2), None),
+            Some("#,##0.00".to_string())
+        );
+        // 日期列的数字格式由 dateFormat 决定
+        assert!(ColumnType::Date.default_style(None, None).is_none());
+    }
+
+    #[test]
+    fn test_append_footer_row() {
+        let mut rows = vec![
+            vec![CellValue::from("名称"), CellValue::from("金额")],
+            vec![CellValue::from("A"), CellValue::Number(1.5)],
+            vec![CellValue::from("B"), CellValue::from("N/A")],
+            vec![CellValue::from("C"), CellValue::Number(2.0)],
+        ];
+        let footers = vec![
+            LeafFooter {
+                aggregate: None,
+                label: Some("合计".to_string()),
+            },
+            LeafFooter {
+                aggregate: Some(AggregateKind::Sum),
+                label: None,
+            },
+        ];
+        let mut overrides = std::collections::HashMap::new();
+
+        // 仅聚合第 1、3 个数据行（树形模式的叶子行）
+        let count = append_footer_row(&mut rows, 1, &[0, 2], &footers, &mut overrides);
+        assert_eq!(count, 1);
+        assert_eq!(rows[4][0], CellValue::from("合计"));
+        assert_eq!(
+            rows[4][1],
+            CellValue::Formula(CellFormula {
+                formula: "=SUM(B2,B4)".to_string(),
+                cached: Box::new(CellValue::Number(3.5)),
+            })
+        );
+        assert_eq!(overrides[&(4, 1)].bold, Some(true));
+
+        // 无 aggregate 时不追加表尾
+        let footers = vec![LeafFooter {
+            aggregate: None,
+            label: Some("合计".to_string()),
+        }];
+        assert_eq!(
+            append_footer_row(&mut rows, 1, &[0], &footers, &mut overrides),
+            0
+        );
+        assert_eq!(rows.len(), 5);
+    }
+
+    #[test]
+    fn test_aggregate_cells_and_ranges() {
+        let cells = [
+            CellValue::Number(3.0),
+            CellValue::from("x"),
+            CellValue::Empty,
+            CellValue::Number(-1.0),
+        ];
+        assert_eq!(
+            aggregate_cells(AggregateKind::Avg, cells.iter()),
+            CellValue::Number(1.0)
+        );
+        assert_eq!(
+            aggregate_cells(AggregateKind::Count, cells.iter()),
+            CellValue::Number(3.0)
+        );
+        assert_eq!(
+            aggregate_cells(AggregateKind::Min, cells.iter()),
+            CellValue::Number(-1.0)
+        );
+        assert_eq!(
+            aggregate_cells(AggregateKind::Avg, [].iter()),
+            CellValue::Empty
+        );
+
+        assert_eq!(
+            row_ranges("C", &[0, 1, 2, 4], 3),
+            vec!["C3:C5".to_string(), "C7".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_expand_formula_placeholders() {
+        let keys = vec!["name".to_string(), "amount".to_string()];
+        let ctx = FormulaContext {
+            keys: &keys,
+            first_row: 3,
+            last_row: 12,
+        };
+
+        assert_eq!(
+            expand_formula(
+                "=SUM({col:amount}{firstRow}:{col:amount}{lastRow})",
+                &ctx,
+                13,
+                1
+            ),
+            Ok("=SUM(B3:B12)".to_string())
+        );
+        assert_eq!(
+            expand_formula("={col}{row-1}+{col}{row+1}*{row}", &ctx, 5, 1),
+            Ok("=B4+B6*5".to_string())
+        );
+        // 数组常量等非占位符内容原样保留
+        assert_eq!(
+            expand_formula("=SUM({1,2,3})+{unknown", &ctx, 5, 0),
+            Ok("=SUM({1,2,3})+{unknown".to_string())
+        );
+        assert!(expand_formula("={col:missing}1", &ctx, 5, 0).is_err());
+        assert!(expand_formula("=A{row-5}", &ctx, 5, 0).is_err());
+        assert!(expand_formula("=A{rowx}", &ctx, 5, 0).is_err());
+    }
+
     #[test]
     fn test_parse_span_value_edge_cases() {
         assert_eq!(parse_span_value(2.0), 2);
@@ -772,6 +2064,21 @@ mod tests {
         assert_eq!(parse_span_value(f64::INFINITY), 0);
     }
 
+    #[test]
+    fn test_check_column_type() {
+        assert_eq!(check_column_type("date", "date"), Ok(ColumnType::Date));
+        assert_eq!(
+            check_column_type("at", "datetime"),
+            Ok(ColumnType::DateTime)
+        );
+
+        // 拼写错误需报错，并指出列 key 与支持的取值
+        let err = check_column_type("amount", "nubmer").unwrap_err();
+        assert!(err.contains("'amount'"), "{err}");
+        assert!(err.contains("'nubmer'"), "{err}");
+        assert!(err.contains(ColumnType::NAMES), "{err}");
+    }
+
     /// 测试列树深度计算
     #[test]
     fn test_calc_depth_single_level() {
@@ -1084,6 +2391,31 @@ mod tests {
         assert_eq!(consumed, 3); // A + B + C = 3 列
     }
 
+    #[test]
+    fn test_collect_header_notes() {
+        let leaf = |title: &str, note: Option<&str>| ColumnNode {
+            title: title.to_string(),
+            key: Some(title.to_lowercase()),
+            header_note: note.map(str::to_string),
+            ..Default::default()
+        };
+        let nodes = vec![
+            leaf("A", Some("必填")),
+            ColumnNode {
+                title: "Group".to_string(),
+                children: vec![leaf("B", None), leaf("C", Some("单位: 元"))],
+                header_note: Some("分组说明".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let notes = collect_header_notes(&nodes);
+        assert_eq!(notes.len(), 3);
+        assert_eq!(notes[&(0, 0)], "必填");
+        assert_eq!(notes[&(0, 1)], "分组说明");
+        assert_eq!(notes[&(1, 2)], "单位: 元");
+    }
+
     /// 测试空子节点列表的叶子 key 收集
     #[test]
     fn test_collect_leaf_keys_empty() {
@@ -1134,28 +2466,112 @@ mod tests {
     #[cfg(target_arch = "wasm32")]
     #[test]
     fn test_js_value_to_string_null() {
-        assert_eq!(js_value_to_string(&JsValue::NULL), "");
-        assert_eq!(js_value_to_string(&JsValue::UNDEFINED), "");
+        assert_eq!(js_value_to_cell(&JsValue::NULL), CellValue::Empty);
+        assert_eq!(js_value_to_cell(&JsValue::UNDEFINED), CellValue::Empty);
     }
 
     #[cfg(target_arch = "wasm32")]
     #[test]
     fn test_js_value_to_string_number() {
-        assert_eq!(js_value_to_string(&JsValue::from_f64(42.0)), "42");
-        assert_eq!(js_value_to_string(&JsValue::from_f64(3.14)), "3.14");
+        assert_eq!(js_value_to_cell(&JsValue::from_f64(42.0)).as_text(), "42");
+        assert_eq!(js_value_to_cell(&JsValue::from_f64(2.5)).as_text(), "2.5");
     }
 
     #[cfg(target_arch = "wasm32")]
     #[test]
     fn test_js_value_to_string_bool() {
-        assert_eq!(js_value_to_string(&JsValue::from_bool(true)), "true");
-        assert_eq!(js_value_to_string(&JsValue::from_bool(false)), "false");
+        assert_eq!(
+            js_value_to_cell(&JsValue::from_bool(true)).as_text(),
+            "true"
+        );
+        assert_eq!(
+            js_value_to_cell(&JsValue::from_bool(false)).as_text(),
+            "false"
+        );
     }
 
     #[cfg(target_arch = "wasm32")]
     #[test]
     fn test_js_value_to_string_string() {
-        assert_eq!(js_value_to_string(&JsValue::from_str("hello")), "hello");
+        assert_eq!(
+            js_value_to_cell(&JsValue::from_str("hello")).as_text(),
+            "hello"
+        );
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn test_js_value_to_cell_native_types() {
+        assert_eq!(
+            js_value_to_cell(&JsValue::from_f64(42.5)),
+            CellValue::Number(42.5)
+        );
+        assert_eq!(
+            js_value_to_cell(&JsValue::from_bool(true)),
+            CellValue::Bool(true)
+        );
+        // 非有限数字无法写入 Excel，归为空单元格
+        assert_eq!(
+            js_value_to_cell(&JsValue::from_f64(f64::NAN)),
+            CellValue::Empty
+        );
+        assert_eq!(
+            js_value_to_cell(&JsValue::from_f64(f64::INFINITY)),
+            CellValue::Empty
+        );
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn test_js_value_to_cell_date() {
+        // 本地时间 2024-03-05 08:09:10
+        let date = js_sys::Date::new_with_year_month_day_hr_min_sec(2024, 2, 5, 8, 9, 10);
+        let expected = CellDateTime::from_ymd(2024, 3, 5)
+            .and_then(|d| d.and_hms_milli(8, 9, 10, 0))
+            .unwrap();
+        assert_eq!(
+            js_value_to_cell(&date.into()),
+            CellValue::DateTime(expected)
+        );
+
+        // 零点视为纯日期
+        let midnight = js_sys::Date::new_with_year_month_day(2024, 2, 5);
+        assert_eq!(
+            js_value_to_cell(&midnight.into()),
+            CellValue::DateTime(expected.date_only())
+        );
+
+        // Invalid Date 归为空单元格
+        let invalid = js_sys::Date::new(&JsValue::from_str("not a date"));
+        assert_eq!(js_value_to_cell(&invalid.into()), CellValue::Empty);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn test_coerce_cell_date_column() {
+        let dt = CellDateTime::from_ymd(2024, 3, 5)
+            .and_then(|d| d.and_hms_milli(8, 9, 0, 0))
+            .unwrap();
+        assert_eq!(
+            coerce_cell(
+                CellValue::from("2024-03-05T08:09"),
+                Some(ColumnType::DateTime)
+            ),
+            CellValue::DateTime(dt)
+        );
+        assert_eq!(
+            coerce_cell(CellValue::from("2024-03-05T08:09"), Some(ColumnType::Date)),
+            CellValue::DateTime(dt.date_only())
+        );
+        // 非日期列、无法解析的文本保持原样
+        assert_eq!(
+            coerce_cell(CellValue::from("2024-03-05"), None),
+            CellValue::from("2024-03-05")
+        );
+        assert_eq!(
+            coerce_cell(CellValue::from("待定"), Some(ColumnType::Date)),
+            CellValue::from("待定")
+        );
     }
 
     /// 测试 parse_cell_value - 普通字符串值
@@ -1164,7 +2580,7 @@ mod tests {
     fn test_parse_cell_value_plain_string() {
         let val = JsValue::from_str("张三");
         let info = parse_cell_value(&val);
-        assert_eq!(info.value, "张三");
+        assert_eq!(info.value.as_text(), "张三");
         assert_eq!(info.col_span, 1);
         assert_eq!(info.row_span, 1);
     }
@@ -1175,7 +2591,7 @@ mod tests {
     fn test_parse_cell_value_plain_number() {
         let val = JsValue::from_f64(42.0);
         let info = parse_cell_value(&val);
-        assert_eq!(info.value, "42");
+        assert_eq!(info.value, CellValue::Number(42.0));
         assert_eq!(info.col_span, 1);
         assert_eq!(info.row_span, 1);
     }
@@ -1185,12 +2601,12 @@ mod tests {
     #[test]
     fn test_parse_cell_value_null() {
         let info_null = parse_cell_value(&JsValue::NULL);
-        assert_eq!(info_null.value, "");
+        assert_eq!(info_null.value.as_text(), "");
         assert_eq!(info_null.col_span, 1);
         assert_eq!(info_null.row_span, 1);
 
         let info_undef = parse_cell_value(&JsValue::UNDEFINED);
-        assert_eq!(info_undef.value, "");
+        assert_eq!(info_undef.value.as_text(), "");
         assert_eq!(info_undef.col_span, 1);
         assert_eq!(info_undef.row_span, 1);
     }
@@ -1210,7 +2626,7 @@ mod tests {
         let val = JsValue::from(obj);
 
         let info = parse_cell_value(&val);
-        assert_eq!(info.value, "张三");
+        assert_eq!(info.value.as_text(), "张三");
         assert_eq!(info.col_span, 1);
         assert_eq!(info.row_span, 2);
     }
@@ -1230,7 +2646,7 @@ mod tests {
         let val = JsValue::from(obj);
 
         let info = parse_cell_value(&val);
-        assert_eq!(info.value, "北京");
+        assert_eq!(info.value.as_text(), "北京");
         assert_eq!(info.col_span, 3);
         assert_eq!(info.row_span, 1);
     }
@@ -1245,7 +2661,7 @@ mod tests {
         let val = JsValue::from(obj);
 
         let info = parse_cell_value(&val);
-        assert_eq!(info.value, "");
+        assert_eq!(info.value.as_text(), "");
         assert_eq!(info.col_span, 1);
         assert_eq!(info.row_span, 0);
     }
@@ -1266,7 +2682,7 @@ mod tests {
         let val = JsValue::from(obj);
 
         let info = parse_cell_value(&val);
-        assert_eq!(info.value, "合并");
+        assert_eq!(info.value.as_text(), "合并");
         assert_eq!(info.col_span, 2);
         assert_eq!(info.row_span, 3);
     }
@@ -1307,12 +2723,54 @@ mod tests {
         root.push(&item1.into());
 
         let keys = vec!["name".to_string()];
-        let mut rows = Vec::new();
-        flatten_tree_data(&root.into(), &keys, None, "children", 0, &mut rows).unwrap();
+        let mut tree = FlatTree::default();
+        flatten_tree_data(&root.into(), &keys, &[], None, "children", 0, &mut tree).unwrap();
+        let rows = tree.rows;
 
         assert_eq!(rows.len(), 2);
-        assert_eq!(rows[0][0], "A");
-        assert_eq!(rows[1][0], "B");
+        assert_eq!(rows[0][0].as_text(), "A");
+        assert_eq!(rows[1][0].as_text(), "B");
+    }
+
+    /// 测试数据单元格中的无效图片被跳过而不是中断导出
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn test_extract_data_rows_skips_invalid_images() {
+        let image_cell = |src: &str, alt: Option<&str>| {
+            let cell = js_sys::Object::new();
+            js_sys::Reflect::set(&cell, &JsValue::from_str("image"), &JsValue::from_str(src))
+                .unwrap();
+            if let Some(alt) = alt {
+                js_sys::Reflect::set(&cell, &JsValue::from_str("alt"), &JsValue::from_str(alt))
+                    .unwrap();
+            }
+            cell
+        };
+        let item = js_sys::Object::new();
+        js_sys::Reflect::set(
+            &item,
+            &JsValue::from_str("broken"),
+            &image_cell("data:image/png;base64,@@@@", Some("缩略图")),
+        )
+        .unwrap();
+        js_sys::Reflect::set(
+            &item,
+            &JsValue::from_str("photo"),
+            &image_cell(
+                "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=",
+                None,
+            ),
+        )
+        .unwrap();
+        let data = js_sys::Array::new();
+        data.push(&item.into());
+
+        let keys = vec!["broken".to_string(), "photo".to_string()];
+        let extracted = extract_data_rows(&data.into(), &keys, &[None, None], 1).unwrap();
+        // 无效图片以 alt 代替，同一行的有效图片照常嵌入
+        assert_eq!(extracted.rows[0][0], CellValue::from("缩略图"));
+        assert!(!extracted.images.contains_key(&(1, 0)));
+        assert!(extracted.images.contains_key(&(1, 1)));
     }
 
     /// 测试 flatten_tree_data - 带缩进
@@ -1345,13 +2803,23 @@ mod tests {
         root.push(&item1.into());
 
         let keys = vec!["name".to_string()];
-        let mut rows = Vec::new();
-        flatten_tree_data(&root.into(), &keys, Some("name"), "children", 0, &mut rows).unwrap();
+        let mut tree = FlatTree::default();
+        flatten_tree_data(
+            &root.into(),
+            &keys,
+            &[],
+            Some("name"),
+            "children",
+            0,
+            &mut tree,
+        )
+        .unwrap();
+        let rows = tree.rows;
 
         assert_eq!(rows.len(), 3);
-        assert_eq!(rows[0][0], "根"); // 根节点，depth=0，无缩进
-        assert_eq!(rows[1][0], "    子"); // depth=1，4 个空格
-        assert_eq!(rows[2][0], "        孙"); // depth=2，8 个空格
+        assert_eq!(rows[0][0].as_text(), "根"); // 根节点，depth=0，无缩进
+        assert_eq!(rows[1][0].as_text(), "    子"); // depth=1，4 个空格
+        assert_eq!(rows[2][0].as_text(), "        孙"); // depth=2，8 个空格
     }
 
     /// 测试 flatten_tree_data - 自定义 children_key
@@ -1372,13 +2840,14 @@ mod tests {
         root.push(&item1.into());
 
         let keys = vec!["name".to_string()];
-        let mut rows = Vec::new();
+        let mut tree = FlatTree::default();
         // 使用 "subs" 而非默认 "children"
-        flatten_tree_data(&root.into(), &keys, None, "subs", 0, &mut rows).unwrap();
+        flatten_tree_data(&root.into(), &keys, &[], None, "subs", 0, &mut tree).unwrap();
+        let rows = tree.rows;
 
         assert_eq!(rows.len(), 2);
-        assert_eq!(rows[0][0], "A");
-        assert_eq!(rows[1][0], "B");
+        assert_eq!(rows[0][0].as_text(), "A");
+        assert_eq!(rows[1][0].as_text(), "B");
     }
 
     /// 测试 flatten_tree_data - 空数组
@@ -1387,8 +2856,9 @@ mod tests {
     fn test_flatten_tree_data_empty() {
         let root = js_sys::Array::new();
         let keys = vec!["name".to_string()];
-        let mut rows = Vec::new();
-        flatten_tree_data(&root.into(), &keys, None, "children", 0, &mut rows).unwrap();
+        let mut tree = FlatTree::default();
+        flatten_tree_data(&root.into(), &keys, &[], None, "children", 0, &mut tree).unwrap();
+        let rows = tree.rows;
         assert!(rows.is_empty());
     }
 
@@ -1405,13 +2875,14 @@ mod tests {
         }
 
         let keys = vec!["name".to_string()];
-        let mut rows = Vec::new();
-        flatten_tree_data(&root.into(), &keys, None, "children", 0, &mut rows).unwrap();
+        let mut tree = FlatTree::default();
+        flatten_tree_data(&root.into(), &keys, &[], None, "children", 0, &mut tree).unwrap();
+        let rows = tree.rows;
 
         assert_eq!(rows.len(), 3);
-        assert_eq!(rows[0][0], "A");
-        assert_eq!(rows[1][0], "B");
-        assert_eq!(rows[2][0], "C");
+        assert_eq!(rows[0][0].as_text(), "A");
+        assert_eq!(rows[1][0].as_text(), "B");
+        assert_eq!(rows[2][0].as_text(), "C");
     }
 
     /// 测试 flatten_tree_data - 缩进不影响非目标列
@@ -1434,13 +2905,23 @@ mod tests {
         root.push(&item.into());
 
         let keys = vec!["name".to_string(), "age".to_string()];
-        let mut rows = Vec::new();
-        flatten_tree_data(&root.into(), &keys, Some("name"), "children", 0, &mut rows).unwrap();
+        let mut tree = FlatTree::default();
+        flatten_tree_data(
+            &root.into(),
+            &keys,
+            &[],
+            Some("name"),
+            "children",
+            0,
+            &mut tree,
+        )
+        .unwrap();
+        let rows = tree.rows;
 
         assert_eq!(rows.len(), 2);
         // name 列：子节点有缩进
-        assert_eq!(rows[1][0], "    B");
+        assert_eq!(rows[1][0].as_text(), "    B");
         // age 列：子节点没有缩进
-        assert_eq!(rows[1][1], "5");
+        assert_eq!(rows[1][1].as_text(), "5");
     }
 }
@@ -0,0 +1,527 @@
+/// 列数据验证模块
+///
+/// 解析列配置中的 `validation`（下拉列表、数值范围、日期范围、文本长度），
+/// 导出 XLSX 时写入 Excel 数据验证，CSV 导出时忽略
+use super::data_export::{get_object_property, js_value_to_cell};
+use super::export_xlsx::{data_row_range, to_excel_datetime};
+use super::table_extractor::{CellDateTime, CellValue, TableData};
+use rust_xlsxwriter::{
+    DataValidation, DataValidationErrorStyle, DataValidationRule, Worksheet, XlsxError,
+};
+use wasm_bindgen::prelude::*;
+
+/// Excel 下拉列表（逗号拼接后）的最大长度
+const MAX_LIST_LEN: usize = 255;
+
+/// 数据验证规则
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationRule {
+    /// 下拉列表
+    List(Vec<String>),
+    /// 数值范围（`integer` 为 true 时仅允许整数）
+    Number {
+        min: Option<f64>,
+        max: Option<f64>,
+        integer: bool,
+    },
+    /// 日期范围（闭区间，任一端可省略）
+    DateRange {
+        start: Option<CellDateTime>,
+        end: Option<CellDateTime>,
+    },
+    /// 最大文本长度
+    MaxLength(u32),
+}
+
+/// 输入非法值时的提示级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationErrorStyle {
+    /// 禁止输入（默认）
+    #[default]
+    Stop,
+    /// 警告，可选择继续
+    Warning,
+    /// 仅提示
+    Information,
+}
+
+impl ValidationErrorStyle {
+    /// 从 `errorStyle` 字段解析
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "stop" => Some(Self::Stop),
+            "warning" => Some(Self::Warning),
+            "information" => Some(Self::Information),
+            _ => None,
+        }
+    }
+}
+
+/// 单列数据验证配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnValidation {
+    /// 验证规则
+    pub rule: ValidationRule,
+    /// 是否允许空值（默认 true）
+    pub allow_blank: bool,
+    /// 选中单元格时的提示标题
+    pub input_title: Option<String>,
+    /// 选中单元格时的提示内容
+    pub input_message: Option<String>,
+    /// 输入非法值时的错误标题
+    pub error_title: Option<String>,
+    /// 输入非法值时的错误内容
+    pub error_message: Option<String>,
+    /// 错误提示级别
+    pub error_style: ValidationErrorStyle,
+}
+
+impl ColumnValidation {
+    /// 创建仅包含规则的验证配置
+    pub fn new(rule: ValidationRule) -> Self {
+        Self {
+            rule,
+            allow_blank: true,
+            input_title: None,
+            input_message: None,
+            error_title: None,
+            error_message: None,
+            error_style: ValidationErrorStyle::default(),
+        }
+    }
+}
+
+/// 解析列配置中的 `validation`
+///
+/// 支持以下规则（只能指定一种）：
+/// - `{ list: ['男', '女'] }`：下拉列表
+/// - `{ min?, max?, integer? }`：数值范围
+/// - `{ dateRange: [start, end] }`：日期范围，端点为 Date 或 ISO-8601 字符串，可为 null
+/// - `{ maxLength }`：最大文本长度
+///
+/// 附加字段：`allowBlank`、`inputTitle`、`inputMessage`、`errorTitle`、`errorMessage`、
+/// `errorStyle`（`stop` / `warning` / `information`）
+pub(crate) fn parse_column_validation(
+    val: &JsValue,
+    title: &str,
+) -> Result<Option<ColumnValidation>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str(&format!(
+            "列 '{}' 的 validation 必须是对象",
+            title
+        )));
+    }
+    let invalid =
+        |msg: String| JsValue::from_str(&format!("列 '{}' 的 validation 无效: {}", title, msg));
+
+    let list = get_object_property(val, "list")?;
+    let min = get_object_property(val, "min")?;
+    let max = get_object_property(val, "max")?;
+    let integer = get_object_property(val, "integer")?;
+    let date_range = get_object_property(val, "dateRange")?;
+    let max_length = get_object_property(val, "maxLength")?;
+
+    let is_set = |v: &JsValue| !v.is_null() && !v.is_undefined();
+    let has_number = is_set(&min) || is_set(&max) || is_set(&integer);
+    let kinds = [
+        is_set(&list),
+        has_number,
+        is_set(&date_range),
+        is_set(&max_length),
+    ];
+    match kinds.iter().filter(|&&k| k).count() {
+        0 => {
+            return Err(invalid(
+                "缺少规则，应指定 list、min/max、dateRange 或 maxLength".to_string(),
+            ));
+        }
+        1 => {}
+        _ => {
+            return Err(invalid(
+                "只能指定 list、min/max、dateRange、maxLength 中的一种".to_string(),
+            ));
+        }
+    }
+
+    let rule = if is_set(&list) {
+        if !js_sys::Array::is_array(&list) {
+            return Err(invalid("list 必须是数组".to_string()));
+        }
+        let items: Vec<String> = js_sys::Array::from(&list)
+            .iter()
+            .map(|v| js_value_to_cell(&v).as_text().into_owned())
+            .collect();
+        ValidationRule::List(items)
+    } else if has_number {
+        let read_bound = |v: &JsValue, key: &str| -> Result<Option<f64>, JsValue> {
+            match v.as_f64() {
+                Some(n) if n.is_finite() => Ok(Some(n)),
+                _ if !is_set(v) => Ok(None),
+                _ => Err(invalid(format!("{} 必须是有限数字", key))),
+            }
+        };
+        ValidationRule::Number {
+            min: read_bound(&min, "min")?,
+            max: read_bound(&max, "max")?,
+            integer: integer.as_bool().unwrap_or(false),
+        }
+    } else if is_set(&date_range) {
+        if !js_sys::Array::is_array(&date_range) {
+            return Err(invalid("dateRange 必须是 [start, end] 数组".to_string()));
+        }
+        let range = js_sys::Array::from(&date_range);
+        let read_date = |v: JsValue| -> Result<Option<CellDateTime>, JsValue> {
+            if !is_set(&v) {
+                return Ok(None);
+            }
+            js_value_to_date(&v)
+                .map(Some)
+                .ok_or_else(|| invalid(format!("dateRange 日期无效: {:?}", v)))
+        };
+        ValidationRule::DateRange {
+            start: read_date(range.get(0))?,
+            end: read_date(range.get(1))?,
+        }
+    } else {
+        ValidationRule::MaxLength(
+            max_length
+                .as_f64()
+                .and_then(max_length_value)
+                .ok_or_else(|| invalid("maxLength 必须是非负整数".to_string()))?,
+        )
+    };
+
+    let mut validation = ColumnValidation::new(rule);
+    if let Some(allow_blank) = get_object_property(val, "allowBlank")?.as_bool() {
+        validation.allow_blank = allow_blank;
+    }
+    validation.input_title = get_object_property(val, "inputTitle")?.as_string();
+    validation.input_message = get_object_property(val, "inputMessage")?.as_string();
+    validation.error_title = get_object_property(val, "errorTitle")?.as_string();
+    validation.error_message = get_object_property(val, "errorMessage")?.as_string();
+    if let Some(style) = get_object_property(val, "errorStyle")?.as_string() {
+        validation.error_style = ValidationErrorStyle::parse(&style).ok_or_else(|| {
+            invalid(format!(
+                "errorStyle 无效: '{}'，仅支持 stop / warning / information",
+                style
+            ))
+        })?;
+    }
+
+    check_column_validation(&validation).map_err(invalid)?;
+    Ok(Some(validation))
+}
+
+/// 校验 `maxLength`（非负整数）
+fn max_length_value(n: f64) -> Option<u32> {
+    (n.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&n)).then_some(n as u32)
+}
+
+/// 将 JS Date / ISO-8601 字符串解析为日期
+///
+/// 带时区的字符串交给 JS Date 换算为本地时间
+fn js_value_to_date(val: &JsValue) -> Option<CellDateTime> {
+    match js_value_to_cell(val) {
+        CellValue::DateTime(dt) => Some(dt),
+        CellValue::Text(s) => match CellDateTime::parse_iso(&s)? {
+            iso if iso.has_zone => match js_value_to_cell(&js_sys::Date::new(val).into()) {
+                CellValue::DateTime(dt) => Some(dt),
+                _ => None,
+            },
+            iso => Some(iso.value),
+        },
+        _ => None,
+    }
+}
+
+/// 校验验证规则是否可写入 Excel
+fn check_column_validation(validation: &ColumnValidation) -> Result<(), String> {
+    match &validation.rule {
+        ValidationRule::List(items) => {
+            if items.is_empty() {
+                return Err("list 不能为空".to_string());
+            }
+            // Excel 以逗号分隔选项，选项本身不能含逗号
+            if let Some(item) = items.iter().find(|s| s.contains(',')) {
+                return Err(format!("list 选项不能包含逗号: '{}'", item));
+            }
+            // Excel 将列表以逗号拼接存储，双引号需转义
+            let len = items
+                .iter()
+                .map(|s| s.chars().count() + s.matches('"').count())
+                .sum::<usize>()
+                + items.len()
+                - 1;
+            if len > MAX_LIST_LEN {
+                return Err(format!(
+                    "list 拼接后长度为 {}，超过 Excel 限制 ({} 字符)",
+                    len, MAX_LIST_LEN
+                ));
+            }
+        }
+        ValidationRule::Number { min, max, integer } => {
+            if min.is_none() && max.is_none() && !integer {
+                return Err("需指定 min 或 max".to_string());
+            }
+            if let (Some(min), Some(max)) = (min, max)
+                && min > max
+            {
+                return Err(format!("min ({}) 不能大于 max ({})", min, max));
+            }
+            if *integer {
+                for bound in [min, max].into_iter().flatten() {
+                    if bound.fract() != 0.0 || *bound < i32::MIN as f64 || *bound > i32::MAX as f64
+                    {
+                        return Err(format!(
+                            "integer 模式下 min / max 必须是 32 位整数: {}",
+                            bound
+                        ));
+                    }
+                }
+            }
+        }
+        ValidationRule::DateRange { start, end } => {
+            if start.is_none() && end.is_none() {
+                return Err("dateRange 至少需要指定一端".to_string());
+            }
+            for dt in [start, end].into_iter().flatten() {
+                if to_excel_datetime(dt).is_none() {
+                    return Err(format!(
+                        "dateRange 日期超出 Excel 范围: {}",
+                        dt.format(dt.default_pattern())
+                    ));
+                }
+            }
+            if let (Some(start), Some(end)) = (start, end)
+                && start > end
+            {
+                return Err("dateRange 起始日期不能晚于结束日期".to_string());
+            }
+        }
+        ValidationRule::MaxLength(_) => {}
+    }
+    Ok(())
+}
+
+/// 将闭区间端点转换为验证规则
+fn range_rule<T: rust_xlsxwriter::IntoDataValidationValue>(
+    min: Option<T>,
+    max: Option<T>,
+) -> Option<DataValidationRule<T>> {
+    match (min, max) {
+        (Some(min), Some(max)) => Some(DataValidationRule::Between(min, max)),
+        (Some(min), None) => Some(DataValidationRule::GreaterThanOrEqualTo(min)),
+        (None, Some(max)) => Some(DataValidationRule::LessThanOrEqualTo(max)),
+        (None, None) => None,
+    }
+}
+
+/// 构建 rust_xlsxwriter 的数据验证对象
+fn build_data_validation(validation: &ColumnValidation) -> Result<DataValidation, XlsxError> {
+    let mut dv = DataValidation::new();
+    dv = match &validation.rule {
+        ValidationRule::List(items) => dv.allow_list_strings(items)?,
+        ValidationRule::Number {
+            min,
+            max,
+            integer: true,
+        } => dv.allow_whole_number(
+            range_rule(min.map(|n| n as i32), max.map(|n| n as i32))
+                .unwrap_or(DataValidationRule::Between(i32::MIN, i32::MAX)),
+        ),
+        ValidationRule::Number { min, max, .. } => dv.allow_decimal_number(
+            range_rule(*min, *max).unwrap_or(DataValidationRule::Between(f64::MIN, f64::MAX)),
+        ),
+        ValidationRule::DateRange { start, end } => {
+            let start = start.as_ref().and_then(to_excel_datetime);
+            let end = end.as_ref().and_then(to_excel_datetime);
+            match range_rule(start, end) {
+                Some(rule) => dv.allow_date(rule),
+                None => dv,
+            }
+        }
+        ValidationRule::MaxLength(n) => {
+            dv.allow_text_length(DataValidationRule::LessThanOrEqualTo(*n))
+        }
+    };
+
+    dv = dv.ignore_blank(validation.allow_blank);
+    if let Some(ref title) = validation.input_title {
+        dv = dv.set_input_title(title.as_str())?;
+    }
+    if let Some(ref message) = validation.input_message {
+        dv = dv.set_input_message(message.as_str())?;
+    }
+    if let Some(ref title) = validation.error_title {
+        dv = dv.set_error_title(title.as_str())?;
+    }
+    if let Some(ref message) = validation.error_message {
+        dv = dv.set_error_message(message.as_str())?;
+    }
+    dv = dv.set_error_style(match validation.error_style {
+        ValidationErrorStyle::Stop => DataValidationErrorStyle::Stop,
+        ValidationErrorStyle::Warning => DataValidationErrorStyle::Warning,
+        ValidationErrorStyle::Information => DataValidationErrorStyle::Information,
+    });
+    Ok(dv)
+}
+
+/// 将列数据验证写入工作表
+pub(crate) fn apply_data_validations(
+    worksheet: &mut Worksheet,
+    table_data: &TableData,
+) -> Result<(), JsValue> {
+    if table_data.data_validations.is_empty() {
+        return Ok(());
+    }
+    let Some((first_row, last_row)) = data_row_range(table_data) else {
+        return Ok(());
+    };
+
+    for (col, validation) in &table_data.data_validations {
+        let dv = build_data_validation(validation)
+            .map_err(|e| JsValue::from_str(&format!("设置数据验证失败: {}", e)))?;
+        worksheet
+            .add_data_validation(first_row, *col, last_row, *col, &dv)
+            .map_err(|e| JsValue::from_str(&format!("设置数据验证失败: {}", e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::sheet_xml;
+
+    #[test]
+    fn test_check_column_validation() {
+        let list = |items: &[&str]| {
+            ColumnValidation::new(ValidationRule::List(
+                items.iter().map(|s| s.to_string()).collect(),
+            ))
+        };
+        assert!(check_column_validation(&list(&["男", "女"])).is_ok());
+        assert!(check_column_validation(&list(&[])).is_err());
+        assert!(check_column_validation(&list(&["A,B"])).is_err());
+        let long = "很".repeat(128);
+        assert!(check_column_validation(&list(&[&long, &long])).is_err());
+
+        let number =
+            |min, max, integer| ColumnValidation::new(ValidationRule::Number { min, max, integer });
+        assert!(check_column_validation(&number(Some(0.0), Some(100.0), true)).is_ok());
+        assert!(check_column_validation(&number(None, None, true)).is_ok());
+        assert!(check_column_validation(&number(None, None, false)).is_err());
+        assert!(check_column_validation(&number(Some(10.0), Some(1.0), false)).is_err());
+        assert!(check_column_validation(&number(Some(0.5), None, true)).is_err());
+
+        let date = |y| CellDateTime::from_ymd(y, 1, 1);
+        let range = |start, end| ColumnValidation::new(ValidationRule::DateRange { start, end });
+        assert!(check_column_validation(&range(date(2024), date(2025))).is_ok());
+        assert!(check_column_validation(&range(date(2025), date(2024))).is_err());
+        assert!(check_column_validation(&range(None, None)).is_err());
+        assert!(check_column_validation(&range(date(1800), None)).is_err());
+    }
+
+    #[test]
+    fn test_parse_validation_values() {
+        assert_eq!(
+            ValidationErrorStyle::parse("warning"),
+            Some(ValidationErrorStyle::Warning)
+        );
+        assert_eq!(ValidationErrorStyle::parse("Warning"), None);
+        assert_eq!(ValidationErrorStyle::parse("error"), None);
+
+        assert_eq!(max_length_value(20.0), Some(20));
+        assert_eq!(max_length_value(0.0), Some(0));
+        assert_eq!(max_length_value(-1.0), None);
+        assert_eq!(max_length_value(2.5), None);
+        assert_eq!(max_length_value(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_check_integer_bounds() {
+        let number = |min, max| {
+            ColumnValidation::new(ValidationRule::Number {
+                min,
+                max,
+                integer: true,
+            })
+        };
+        assert!(
+            check_column_validation(&number(Some(i32::MIN as f64), Some(i32::MAX as f64))).is_ok()
+        );
+        assert!(check_column_validation(&number(None, Some(i32::MAX as f64 + 1.0))).is_err());
+        assert!(check_column_validation(&number(Some(i32::MIN as f64 - 1.0), None)).is_err());
+    }
+
+    #[test]
+    fn test_build_data_validation_rejects_long_prompts() {
+        // Excel 限制提示标题 32 个字符、提示内容 255 个字符
+        let mut validation = ColumnValidation::new(ValidationRule::MaxLength(10));
+        validation.input_title = Some("标".repeat(32));
+        validation.input_message = Some("容".repeat(255));
+        assert!(build_data_validation(&validation).is_ok());
+
+        validation.input_title = Some("标".repeat(33));
+        assert!(build_data_validation(&validation).is_err());
+
+        validation.input_title = None;
+        validation.error_message = Some("容".repeat(256));
+        assert!(build_data_validation(&validation).is_err());
+    }
+
+    #[test]
+    fn test_write_sheet_data_validation() {
+        let mut table_data = TableData {
+            rows: vec![vec![CellValue::from("姓名"), CellValue::from("性别")]],
+            header_row_count: 1,
+            ..Default::default()
+        };
+        table_data.sheet_options.template_rows = Some(50);
+        let mut validation = ColumnValidation::new(ValidationRule::List(vec![
+            "男".to_string(),
+            "女".to_string(),
+        ]));
+        validation.input_title = Some("性别".to_string());
+        validation.error_style = ValidationErrorStyle::Warning;
+        table_data.data_validations.push((1, validation));
+        table_data
+            .data_validations
+            .push((0, ColumnValidation::new(ValidationRule::MaxLength(20))));
+
+        let sheet = sheet_xml(&table_data);
+        let validations = sheet.child("dataValidations").unwrap();
+        assert_eq!(validations.attr("count"), Some("2"));
+
+        let gender = sheet.find_by("dataValidation", "sqref", "B2:B51").unwrap();
+        assert_eq!(gender.attr("type"), Some("list"));
+        assert_eq!(gender.attr("errorStyle"), Some("warning"));
+        assert_eq!(gender.attr("promptTitle"), Some("性别"));
+        assert_eq!(gender.child("formula1").unwrap().text, "\"男,女\"");
+
+        let name = sheet.find_by("dataValidation", "sqref", "A2:A51").unwrap();
+        assert_eq!(name.attr("type"), Some("textLength"));
+        assert_eq!(name.attr("operator"), Some("lessThanOrEqual"));
+        // 默认级别为 stop，不写 errorStyle
+        assert_eq!(name.attr("errorStyle"), None);
+        assert_eq!(name.child("formula1").unwrap().text, "20");
+    }
+
+    #[test]
+    fn test_write_sheet_data_validation_without_data_rows() {
+        // 仅表头且未设置 templateRows 时没有可验证的区域
+        let mut table_data = TableData {
+            rows: vec![vec![CellValue::from("性别")]],
+            header_row_count: 1,
+            ..Default::default()
+        };
+        table_data.data_validations.push((
+            0,
+            ColumnValidation::new(ValidationRule::List(vec!["男".to_string()])),
+        ));
+        assert!(sheet_xml(&table_data).child("dataValidations").is_none());
+    }
+}
@@ -1104,6 +1104,7 @@ fn local_name_str(full_name: &[u8]) -> &str {
 // ============================================================================
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
@@ -1350,7 +1351,7 @@ mod tests {
         ws.write_string(0, 1, "数据").unwrap();
         for i in 1..=count {
             ws.write_number(i as u32, 0, i as f64).unwrap();
-            ws.write_string(i as u32, 1, &format!("行{i}")).unwrap();
+            ws.write_string(i as u32, 1, format!("行{i}")).unwrap();
         }
         wb.save_to_buffer().unwrap()
     }
@@ -1465,7 +1466,7 @@ mod tests {
 
         // 长文本
         ws.write_string(7, 0, "长文本").unwrap();
-        ws.write_string(7, 1, &"测试".repeat(100)).unwrap();
+        ws.write_string(7, 1, "测试".repeat(100)).unwrap();
 
         wb.save_to_buffer().unwrap()
     }
@@ -1805,7 +1806,7 @@ mod tests {
         let ws = wb.add_worksheet();
         ws.set_name("Sheet1").unwrap();
         for col in 0..20u16 {
-            ws.write_string(0, col, &format!("列{col}")).unwrap();
+            ws.write_string(0, col, format!("列{col}")).unwrap();
         }
         let xlsx_data = wb.save_to_buffer().unwrap();
 
@@ -2059,11 +2060,9 @@ mod tests {
         // 每行的 Some 单元格数（不含 None）+ colspan 占位 应等于 4
         for (i, row) in sheet.rows.iter().enumerate() {
             let mut col_slots = 0u32;
-            for cell in &row.cells {
-                match cell {
-                    Some(c) => col_slots += c.col_span.unwrap_or(1),
-                    None => {} // None 被 HTML 跳过，不占列槽
-                }
+            // None 被 HTML 跳过，不占列槽
+            for c in row.cells.iter().flatten() {
+                col_slots += c.col_span.unwrap_or(1);
             }
             assert_eq!(
                 col_slots, 4,
@@ -1310,6 +1310,7 @@ fn format_with_thousands(value: f64, decimals: usize) -> String {
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
@@ -1,9 +1,10 @@
 /// CSV 导出模块
 ///
-/// 提供 CSV 格式的表格导出功能
+/// 提供 CSV / TSV 格式的表格导出功能
+use super::ExportFormat;
+use super::csv_dialect::CsvDialect;
 use crate::resource::{trigger_blob_download, trigger_bytes_download};
 use crate::utils::report_progress;
-use csv::Writer;
 use std::io::Cursor;
 use wasm_bindgen::prelude::*;
 
@@ -16,6 +17,7 @@ use wasm_bindgen::prelude::*;
 /// * `progress_callback` - 可选的进度回调函数
 /// * `strict_progress` - 是否启用严格进度回调模式
 /// * `with_bom` - 是否添加 UTF-8 BOM
+/// * `dialect` - CSV 方言（分隔符、引号、换行符等）
 ///
 /// # 返回值
 /// * `Ok(Vec<u8>)` - 生成的 CSV 字节
@@ -25,6 +27,7 @@ pub fn generate_csv_bytes(
     progress_callback: Option<&js_sys::Function>,
     strict_progress: bool,
     with_bom: bool,
+    dialect: &CsvDialect,
 ) -> Result<Vec<u8>, JsValue> {
     let total_rows = table_data.len();
 
@@ -34,7 +37,7 @@ pub fn generate_csv_bytes(
     }
 
     // 创建一个 CSV 写入器
-    let mut wtr = Writer::from_writer(Cursor::new(Vec::new()));
+    let mut wtr = dialect.writer(Cursor::new(Vec::new()));
 
     // 写入所有数据，并报告进度
     for (index, row_data) in table_data.into_iter().enumerate() {
@@ -80,7 +83,7 @@ pub fn generate_csv_bytes(
     }
 }
 
-/// 导出为 CSV 格式（生成文件并触发下载）
+/// 导出为 CSV / TSV 格式（生成文件并触发下载）
 ///
 /// # 参数
 /// * `table_data` - 表格数据（二维字符串数组）
@@ -88,6 +91,8 @@ pub fn generate_csv_bytes(
 /// * `progress_callback` - 可选的进度回调函数
 /// * `with_bom` - 是否添加 UTF-8 BOM
 /// * `strict_progress` - 是否启用严格进度回调模式
+/// * `format` - 文件类型（`Csv` 或 `Tsv`），决定扩展名与 MIME 类型
+/// * `dialect` - CSV 方言
 ///
 /// # 返回值
 /// * `Ok(())` - 导出成功
@@ -98,47 +103,65 @@ pub fn export_as_csv(
     progress_callback: Option<js_sys::Function>,
     with_bom: bool,
     strict_progress: bool,
+    format: ExportFormat,
+    dialect: &CsvDialect,
 ) -> Result<(), JsValue> {
     let bytes = generate_csv_bytes(
         table_data,
         progress_callback.as_ref(),
         strict_progress,
         with_bom,
+        dialect,
     )?;
 
     // 创建并下载文件（BOM 已在 bytes 中处理）
-    create_and_download_csv(&bytes, filename)
+    create_and_download_csv(&bytes, filename, format)
 }
 
-/// 创建 CSV Blob 并触发下载
+/// CSV / TSV 文件的 MIME 类型与扩展名
+fn text_file_type(format: ExportFormat) -> (&'static str, &'static str) {
+    match format {
+        ExportFormat::Tsv => ("text/tab-separated-values;charset=utf-8", "tsv"),
+        _ => ("text/csv;charset=utf-8", "csv"),
+    }
+}
+
+/// 创建 CSV / TSV Blob 并触发下载
 ///
 /// # 参数
 /// * `data` - CSV 数据字节（可能已包含 BOM）
 /// * `filename` - 可选的导出文件名
+/// * `format` - 文件类型（`Csv` 或 `Tsv`）
 pub(crate) fn create_and_download_csv(
     data: &[u8],
     filename: Option<String>,
+    format: ExportFormat,
 ) -> Result<(), JsValue> {
+    let (mime_type, extension) = text_file_type(format);
     trigger_bytes_download(
         data,
-        "text/csv;charset=utf-8",
+        mime_type,
         filename,
-        "table_export.csv",
-        "csv",
+        &format!("table_export.{}", extension),
+        extension,
     )
 }
 
-/// 从多个 Blob 片段拼接 CSV 并触发下载
+/// 从多个 Blob 片段拼接 CSV / TSV 并触发下载
+///
+/// `default_stem` 为未指定文件名时使用的默认文件名（不含扩展名）
 pub(crate) fn create_and_download_csv_parts(
     parts: &js_sys::Array,
     filename: Option<String>,
-    default_name: &str,
+    default_stem: &str,
+    format: ExportFormat,
 ) -> Result<(), JsValue> {
+    let (mime_type, extension) = text_file_type(format);
     trigger_blob_download(
         parts,
-        "text/csv;charset=utf-8",
+        mime_type,
         filename,
-        default_name,
-        "csv",
+        &format!("{}.{}", default_stem, extension),
+        extension,
     )
 }
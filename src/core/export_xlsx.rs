@@ -1,19 +1,37 @@
 /// Excel XLSX 导出模块
 ///
 /// 提供 Excel XLSX 格式的表格导出功能，支持单元格样式
-use super::style::StyleSheet;
-use super::table_extractor::TableData;
+use super::cell_image::apply_images;
+use super::cell_note::apply_notes;
+use super::column_width::auto_column_widths;
+use super::conditional_format::apply_conditional_formats;
+use super::data_validation::apply_data_validations;
+use super::outline::apply_outline;
+use super::print_options::apply_print_options;
+use super::protection::apply_sheet_protection;
+use super::rich_text::write_rich_text;
+use super::sheet_options::ExcelTableOptions;
+use super::style::normalize_hex_color;
+use super::style::{CellStyle, StyleSheet};
+use super::table_extractor::{CellDateTime, CellLink, CellValue, TableData};
+use super::workbook_options::WorkbookOptions;
 use crate::resource::trigger_bytes_download;
 use crate::utils::report_progress;
-use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use crate::validation::sanitize_sheet_names;
+use rust_xlsxwriter::{ExcelDateTime, Format, Formula, Table, TableColumn, Url, Worksheet};
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
 /// Excel 最大行号（0-based，共 1048576 行）
-const EXCEL_MAX_ROW: u32 = 1_048_575;
+pub(crate) const EXCEL_MAX_ROW: u32 = 1_048_575;
 /// Excel 最大列号（0-based，共 16384 列）
 const EXCEL_MAX_COL: u16 = 16_383;
 
+/// 日期单元格默认数字格式
+const DEFAULT_DATE_FORMAT: &str = "yyyy-mm-dd";
+/// 日期时间单元格默认数字格式
+const DEFAULT_DATETIME_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+
 /// XLSX MIME 类型
 const XLSX_MIME: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
 
@@ -55,21 +73,210 @@ fn resolve_freeze_pane(
     }
 }
 
-/// 应用列宽配置
-fn apply_column_widths(
+/// 应用列宽配置（显式 width 优先，其余列按 autoWidth 计算）
+fn apply_column_widths(worksheet: &mut Worksheet, table_data: &TableData) -> Result<(), JsValue> {
+    let total_cols = table_data.rows.iter().map(Vec::len).max().unwrap_or(0);
+    let auto_widths = auto_column_widths(table_data, total_cols);
+    let explicit_widths = table_data
+        .style_sheet
+        .as_ref()
+        .map(|ss| ss.column_widths.as_slice())
+        .unwrap_or_default();
+
+    for col_idx in 0..explicit_widths.len().max(auto_widths.len()) {
+        let width = explicit_widths
+            .get(col_idx)
+            .copied()
+            .flatten()
+            .or_else(|| auto_widths.get(col_idx).copied().flatten());
+        if let Some(w) = width {
+            worksheet
+                .set_column_width(col_idx as u16, w)
+                .map_err(|e| JsValue::from_str(&format!("设置列宽失败: {}", e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// 应用工作表显示设置（标签颜色、隐藏、缩放）
+fn apply_sheet_view(worksheet: &mut Worksheet, table_data: &TableData) {
+    let options = &table_data.sheet_options;
+    if let Some(ref color) = options.tab_color {
+        worksheet.set_tab_color(color.as_str());
+    }
+    if let Some(zoom) = options.zoom {
+        worksheet.set_zoom(zoom);
+    }
+    if options.hidden {
+        worksheet.set_hidden(true);
+    }
+}
+
+/// 第一个可见工作表的下标（隐藏的工作表不能作为活动工作表，至少保留一个可见工作表）
+fn first_visible_sheet<'a>(
+    mut sheets: impl Iterator<Item = &'a TableData>,
+) -> Result<usize, JsValue> {
+    sheets
+        .position(|table_data| !table_data.sheet_options.hidden)
+        .ok_or_else(|| JsValue::from_str("至少需要保留一个可见的工作表"))
+}
+
+/// 按单元格类型写入（数字/布尔写为原生单元格，空值仅在有样式时写入空白格）
+///
+/// `base_style` 为该位置解析出的样式，仅富文本使用（片段继承其字体）
+fn write_cell(
     worksheet: &mut Worksheet,
-    style_sheet: Option<&StyleSheet>,
+    row: u32,
+    col: u16,
+    value: &CellValue,
+    format: Option<&Format>,
+    base_style: Option<&CellStyle>,
 ) -> Result<(), JsValue> {
-    if let Some(ss) = style_sheet {
-        for (col_idx, width) in ss.column_widths.iter().enumerate() {
-            if let Some(w) = width {
-                worksheet
-                    .set_column_width(col_idx as u16, *w)
-                    .map_err(|e| JsValue::from_str(&format!("设置列宽失败: {}", e)))?;
+    let result = match (value, format) {
+        (CellValue::Empty, None) => return Ok(()),
+        (CellValue::Empty, Some(fmt)) => worksheet.write_blank(row, col, fmt),
+        (CellValue::Text(s), None) => worksheet.write_string(row, col, s),
+        (CellValue::Text(s), Some(fmt)) => worksheet.write_string_with_format(row, col, s, fmt),
+        (CellValue::Number(n), None) => worksheet.write_number(row, col, *n),
+        (CellValue::Number(n), Some(fmt)) => worksheet.write_number_with_format(row, col, *n, fmt),
+        (CellValue::Bool(b), None) => worksheet.write_boolean(row, col, *b),
+        (CellValue::Bool(b), Some(fmt)) => worksheet.write_boolean_with_format(row, col, *b, fmt),
+        (CellValue::DateTime(dt), format) => match (to_excel_datetime(dt), format) {
+            (Some(edt), None) => worksheet.write_datetime(row, col, &edt),
+            (Some(edt), Some(fmt)) => worksheet.write_datetime_with_format(row, col, &edt, fmt),
+            // Excel 不支持 1900 年之前的日期，按文本写入
+            (None, None) => worksheet.write_string(row, col, dt.format(dt.default_pattern())),
+            (None, Some(fmt)) => {
+                worksheet.write_string_with_format(row, col, dt.format(dt.default_pattern()), fmt)
+            }
+        },
+        (CellValue::Formula(f), format) => {
+            // 缓存结果供不重新计算的查看器（预览、部分移动端）直接显示
+            let mut formula = Formula::new(&f.formula);
+            if *f.cached != CellValue::Empty {
+                formula = formula.set_result(f.cached.as_text());
             }
+            match format {
+                Some(fmt) => worksheet.write_formula_with_format(row, col, formula, fmt),
+                None => worksheet.write_formula(row, col, formula),
+            }
+        }
+        (CellValue::Link(link), format) => match format {
+            Some(fmt) => worksheet.write_url_with_format(row, col, to_xlsx_url(link), fmt),
+            None => worksheet.write_url(row, col, to_xlsx_url(link)),
+        },
+        (CellValue::RichText(runs), format) => {
+            write_rich_text(worksheet, row, col, runs, base_style, format)
+        }
+    };
+    result
+        .map(|_| ())
+        .map_err(|e| JsValue::from_str(&format!("写入 Excel 单元格失败: {}", e)))
+}
+
+/// 转换为 Excel 日期时间（超出 Excel 日期范围时返回 None）
+pub(crate) fn to_excel_datetime(dt: &CellDateTime) -> Option<ExcelDateTime> {
+    let date = ExcelDateTime::from_ymd(dt.year, dt.month, dt.day).ok()?;
+    if dt.has_time {
+        date.and_hms_milli(dt.hour.into(), dt.minute, dt.second, dt.millisecond)
+            .ok()
+    } else {
+        Some(date)
+    }
+}
+
+/// 转换为 rust_xlsxwriter 的 Url（`#` 开头的地址转为工作簿内部链接）
+fn to_xlsx_url(link: &CellLink) -> Url {
+    let url = match link.url.strip_prefix('#') {
+        Some(anchor) => Url::new(format!("internal:{}", anchor)),
+        None => Url::new(link.url.as_str()),
+    };
+    if link.text.is_empty() {
+        url
+    } else {
+        url.set_text(link.text.as_str())
+    }
+}
+
+/// 单元格 Format 类别：同一位置不同值类型需要补齐不同的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum FormatKind {
+    /// 仅使用样式表
+    Plain,
+    /// 日期：补齐日期数字格式
+    Date { has_time: bool },
+    /// 超链接：补齐链接样式（蓝色下划线）
+    Link,
+}
+
+impl FormatKind {
+    fn of(value: &CellValue) -> Self {
+        match value {
+            CellValue::DateTime(dt) => FormatKind::Date {
+                has_time: dt.has_time,
+            },
+            CellValue::Link(_) => FormatKind::Link,
+            _ => FormatKind::Plain,
+        }
+    }
+}
+
+/// 按值类型解析指定位置的 Format
+fn resolve_cell_format(
+    style_sheet: Option<&StyleSheet>,
+    row: u32,
+    col: u16,
+    header_row_count: usize,
+    kind: FormatKind,
+) -> Option<Format> {
+    match kind {
+        FormatKind::Plain => style_sheet.and_then(|ss| ss.resolve(row, col, header_row_count)),
+        FormatKind::Date { has_time } => Some(resolve_date_format(
+            style_sheet,
+            row,
+            col,
+            header_row_count,
+            has_time,
+        )),
+        FormatKind::Link => {
+            let style = style_sheet
+                .and_then(|ss| ss.resolve_style(row, col, header_row_count))
+                .unwrap_or_default();
+            // 链接样式会覆盖字体颜色，显式设置的颜色需重新应用
+            let format = style.to_format().set_hyperlink();
+            Some(match style.font_color {
+                Some(ref color) => format.set_font_color(normalize_hex_color(color).as_str()),
+                None => format,
+            })
         }
     }
-    Ok(())
+}
+
+/// 解析日期单元格的 Format：在已有样式上补齐日期数字格式
+///
+/// 数字格式优先级：单元格/列级 numberFormat → 样式表 date_format → 默认格式
+fn resolve_date_format(
+    style_sheet: Option<&StyleSheet>,
+    row: u32,
+    col: u16,
+    header_row_count: usize,
+    has_time: bool,
+) -> Format {
+    let mut style = style_sheet
+        .and_then(|ss| ss.resolve_style(row, col, header_row_count))
+        .unwrap_or_default();
+    let number_format = style_sheet
+        .and_then(|ss| {
+            ss.explicit_number_format(row, col, header_row_count)
+                .or(ss.date_format.as_deref())
+        })
+        .unwrap_or(if has_time {
+            DEFAULT_DATETIME_FORMAT
+        } else {
+            DEFAULT_DATE_FORMAT
+        });
+    style.number_format = Some(number_format.to_string());
+    style.to_format()
 }
 
 /// 应用合并单元格，并保留样式
@@ -79,24 +286,25 @@ fn apply_merge_ranges(
     style_sheet: Option<&StyleSheet>,
 ) -> Result<(), JsValue> {
     for merge in &table_data.merge_ranges {
-        let text = table_data
+        let value = table_data
             .rows
             .get(merge.first_row as usize)
-            .and_then(|row| row.get(merge.first_col as usize))
-            .map(|s| s.as_str())
-            .unwrap_or("");
+            .and_then(|row| row.get(merge.first_col as usize));
+        // merge_range 只能写入字符串，非文本值先以空串合并，再覆盖首单元格
+        let text = match value {
+            Some(CellValue::Text(s)) => s.as_str(),
+            _ => "",
+        };
 
         // 获取合并区域首单元格的样式
-        let merge_format = if let Some(ss) = style_sheet {
-            ss.resolve(
-                merge.first_row,
-                merge.first_col,
-                table_data.header_row_count,
-            )
-            .unwrap_or_else(Format::new)
-        } else {
-            Format::new()
-        };
+        let merge_format = resolve_cell_format(
+            style_sheet,
+            merge.first_row,
+            merge.first_col,
+            table_data.header_row_count,
+            value.map(FormatKind::of).unwrap_or(FormatKind::Plain),
+        )
+        .unwrap_or_default();
 
         worksheet
             .merge_range(
@@ -108,10 +316,217 @@ fn apply_merge_ranges(
                 &merge_format,
             )
             .map_err(|e| JsValue::from_str(&format!("合并单元格失败: {}", e)))?;
+
+        if let Some(v) = value.filter(|v| !matches!(v, CellValue::Text(_) | CellValue::Empty)) {
+            let base_style = match v {
+                CellValue::RichText(_) => style_sheet.and_then(|ss| {
+                    ss.resolve_style(
+                        merge.first_row,
+                        merge.first_col,
+                        table_data.header_row_count,
+                    )
+                }),
+                _ => None,
+            };
+            write_cell(
+                worksheet,
+                merge.first_row,
+                merge.first_col,
+                v,
+                Some(&merge_format),
+                base_style.as_ref(),
+            )?;
+        }
     }
     Ok(())
 }
 
+/// 计算 Excel 表格区域的末行/末列，并校验表头布局
+///
+/// Excel 表格至少包含一行数据（可为空行）；开启汇总行时区域再向下延伸一行
+fn excel_table_range(
+    table_data: &TableData,
+    options: &ExcelTableOptions,
+    total_cols: usize,
+) -> Result<(u32, u16), String> {
+    let header_row_count = table_data.header_row_count;
+    if header_row_count != 1 {
+        return Err(format!(
+            "导出为 Excel 表格需要单行表头（当前表头行数: {}）",
+            header_row_count
+        ));
+    }
+    if total_cols == 0 {
+        return Err("没有可导出的数据".to_string());
+    }
+
+    let data_rows = table_data
+        .rows
+        .len()
+        .saturating_sub(header_row_count + table_data.footer_row_count);
+    let last_data_row = header_row_count + data_rows.max(1) - 1;
+    let last_row = if options.total_row {
+        last_data_row + 1
+    } else {
+        last_data_row
+    };
+    if last_row > EXCEL_MAX_ROW as usize {
+        return Err("行数超过 Excel 限制 (1048576)".to_string());
+    }
+    let (last_row, last_col) = (last_row as u32, (total_cols - 1) as u16);
+
+    if let Some(merge) = table_data
+        .merge_ranges
+        .iter()
+        .find(|m| m.first_row <= last_row && m.first_col <= last_col)
+    {
+        return Err(format!(
+            "导出为 Excel 表格时不支持合并单元格（{}{}:{}{}）",
+            rust_xlsxwriter::utility::column_number_to_name(merge.first_col),
+            merge.first_row + 1,
+            rust_xlsxwriter::utility::column_number_to_name(merge.last_col),
+            merge.last_row + 1
+        ));
+    }
+
+    Ok((last_row, last_col))
+}
+
+/// 将表头 + 数据区转为 Excel 表格（ListObject）
+///
+/// Excel 表格只支持单行表头且不能包含合并单元格；表头名称需唯一且非空，
+/// 重名/空白表头会自动改名并回写到表头单元格。
+/// 开启汇总行时，数据后的表尾合计行作为汇总行，否则追加一行空白汇总行。
+fn apply_excel_table(
+    worksheet: &mut Worksheet,
+    table_data: &TableData,
+    options: &ExcelTableOptions,
+    total_cols: usize,
+) -> Result<(), JsValue> {
+    let (last_row, last_col) =
+        excel_table_range(table_data, options, total_cols).map_err(|e| JsValue::from_str(&e))?;
+    let header_row_count = table_data.header_row_count;
+
+    let header_row = table_data.rows.first();
+    let mut seen = std::collections::HashSet::new();
+    let mut columns = Vec::with_capacity(total_cols);
+    for col in 0..total_cols {
+        let text = header_row
+            .and_then(|r| r.get(col))
+            .map(|c| c.as_text().into_owned())
+            .unwrap_or_default();
+        let base = if text.trim().is_empty() {
+            format!("列{}", col + 1)
+        } else {
+            text
+        };
+        // 表头名称不区分大小写唯一
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !seen.insert(name.to_lowercase()) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+
+        let mut column = TableColumn::new().set_header(name);
+        if let Some(format) = resolve_cell_format(
+            table_data.style_sheet.as_ref(),
+            0,
+            col as u16,
+            header_row_count,
+            FormatKind::Plain,
+        ) {
+            column = column.set_header_format(format);
+        }
+        columns.push(column);
+    }
+
+    let mut table = Table::new()
+        .set_columns(&columns)
+        .set_style(options.style)
+        .set_total_row(options.total_row)
+        .set_banded_rows(options.banded_rows);
+    if let Some(ref name) = options.name {
+        table = table.set_name(name.as_str());
+    }
+
+    worksheet
+        .add_table(0, 0, last_row, last_col, &table)
+        .map_err(|e| JsValue::from_str(&format!("创建 Excel 表格失败: {}", e)))?;
+    Ok(())
+}
+
+/// 计算数据行区间 (first_row, last_row)，供数据验证 / 条件格式等列级设置使用
+///
+/// 默认覆盖全部数据行（不含表尾）；指定 `templateRows` 时至少覆盖该行数，
+/// 用于生成可填写的空白导入模板。无可覆盖行时返回 None
+pub(crate) fn data_row_range(table_data: &TableData) -> Option<(u32, u32)> {
+    let first_row = table_data.header_row_count;
+    let data_rows = table_data
+        .rows
+        .len()
+        .saturating_sub(first_row + table_data.footer_row_count);
+    let rows = data_rows.max(table_data.sheet_options.template_rows.unwrap_or(0));
+    if rows == 0 {
+        return None;
+    }
+    let last_row = (first_row + rows - 1).min(EXCEL_MAX_ROW as usize);
+    if first_row > last_row {
+        return None;
+    }
+    Some((first_row as u32, last_row as u32))
+}
+
+/// 计算自动筛选区域 (first_row, first_col, last_row, last_col)
+///
+/// 筛选行为最后一行表头（多级表头取最底层），覆盖到最后一行数据与最后一列；
+/// 表尾合计行不参与筛选。未启用、无表头或已转为 Excel 表格（自带筛选）时返回 None
+fn auto_filter_range(table_data: &TableData, total_cols: usize) -> Option<(u32, u16, u32, u16)> {
+    let header_row_count = table_data.header_row_count;
+    let enabled = table_data
+        .sheet_options
+        .auto_filter
+        .unwrap_or(header_row_count > 0);
+    if !enabled
+        || header_row_count == 0
+        || total_cols == 0
+        || table_data.sheet_options.table.is_some()
+    {
+        return None;
+    }
+
+    let header_row = header_row_count - 1;
+    let last_row = table_data
+        .rows
+        .len()
+        .saturating_sub(table_data.footer_row_count + 1)
+        .max(header_row)
+        .min(EXCEL_MAX_ROW as usize);
+    let last_col = (total_cols - 1).min(EXCEL_MAX_COL as usize);
+
+    Some((header_row as u32, 0, last_row as u32, last_col as u16))
+}
+
+/// 应用行高配置
+fn apply_row_heights(
+    worksheet: &mut Worksheet,
+    style_sheet: Option<&StyleSheet>,
+) -> Result<(), JsValue> {
+    let Some(ss) = style_sheet else {
+        return Ok(());
+    };
+    for (&row, &height) in &ss.row_heights {
+        worksheet
+            .set_row_height(row, height)
+            .map_err(|e| JsValue::from_str(&format!("设置行高失败: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// 单元格覆盖样式的 Format 缓存：(列, 是否表头, 值类别, 行级样式, 覆盖样式)
+type OverrideFormatCache<'a> =
+    HashMap<(u16, bool, FormatKind, Option<usize>, &'a CellStyle), Option<Format>>;
+
 /// 将单个工作表的数据/合并/冻结写入 worksheet（供单表与多表复用）
 fn write_sheet(
     worksheet: &mut Worksheet,
@@ -120,14 +535,22 @@ fn write_sheet(
     progress: Option<SheetProgress<'_>>,
 ) -> Result<(), JsValue> {
     let style_sheet = table_data.style_sheet.as_ref();
-    apply_column_widths(worksheet, style_sheet)?;
+    apply_column_widths(worksheet, table_data)?;
 
     let total_rows = table_data.rows.len();
     let mut max_cols = 0usize;
 
-    // 无单元格覆盖时缓存「全局+列级」Format，避免逐格 clone/merge/to_format
-    let mut header_fmt_cache: HashMap<u16, Option<Format>> = HashMap::new();
-    let mut data_fmt_cache: HashMap<u16, Option<Format>> = HashMap::new();
+    // 无单元格覆盖时按 (列, 是否表头, 值类别, 行级样式) 缓存 Format，避免逐格 clone/merge/to_format
+    let mut fmt_cache: HashMap<(u16, bool, FormatKind, Option<usize>), Option<Format>> =
+        HashMap::new();
+    // 单元格覆盖样式按样式内容缓存，相同样式共用同一个 Format；
+    // 无列级样式时不区分列，使整张表内相同的页面样式只生成一次
+    let mut override_cache: OverrideFormatCache<'_> = HashMap::new();
+    let has_column_styles = style_sheet.is_some_and(|ss| {
+        ss.column_styles.iter().any(Option::is_some)
+            || ss.column_header_styles.iter().any(Option::is_some)
+    });
+    let header_row_count = table_data.header_row_count;
 
     for (i, row_data) in table_data.rows.iter().enumerate() {
         // 在 usize 下比较，避免先 as u32 造成截断后漏检
@@ -137,41 +560,51 @@ fn write_sheet(
         max_cols = max_cols.max(row_data.len());
         let is_header = i < table_data.header_row_count;
         let row = i as u32;
+        let row_slot = style_sheet.and_then(|ss| ss.row_style_slot(row));
 
-        for (j, cell_text) in row_data.iter().enumerate() {
+        for (j, cell) in row_data.iter().enumerate() {
             // 同上：在 usize 下比较，避免 as u16 截断
             if j > EXCEL_MAX_COL as usize {
                 return Err(JsValue::from_str("列数超过 Excel 限制 (16384)"));
             }
             let col = j as u16;
 
-            let format = if let Some(ss) = style_sheet {
-                if ss.cell_overrides.contains_key(&(row, col)) {
-                    ss.resolve(row, col, table_data.header_row_count)
-                } else {
-                    let cache = if is_header {
-                        &mut header_fmt_cache
-                    } else {
-                        &mut data_fmt_cache
-                    };
-                    cache
-                        .entry(col)
-                        .or_insert_with(|| ss.resolve_column(is_header, col))
-                        .clone()
-                }
-            } else {
+            let kind = FormatKind::of(cell);
+            let cell_override = style_sheet.and_then(|ss| ss.cell_overrides.get(&(row, col)));
+
+            let format = if let Some(cell_style) = cell_override {
+                let col_key = if has_column_styles { col } else { 0 };
+                override_cache
+                    .entry((col_key, is_header, kind, row_slot, cell_style))
+                    .or_insert_with(|| {
+                        resolve_cell_format(style_sheet, row, col, header_row_count, kind)
+                    })
+                    .clone()
+            } else if style_sheet.is_none() && kind == FormatKind::Plain {
                 None
+            } else {
+                fmt_cache
+                    .entry((col, is_header, kind, row_slot))
+                    .or_insert_with(|| {
+                        resolve_cell_format(style_sheet, row, col, header_row_count, kind)
+                    })
+                    .clone()
             };
 
-            if let Some(ref fmt) = format {
-                worksheet
-                    .write_string_with_format(row, col, cell_text, fmt)
-                    .map_err(|e| JsValue::from_str(&format!("写入 Excel 单元格失败: {}", e)))?;
-            } else {
-                worksheet
-                    .write_string(row, col, cell_text)
-                    .map_err(|e| JsValue::from_str(&format!("写入 Excel 单元格失败: {}", e)))?;
-            }
+            let base_style = match cell {
+                CellValue::RichText(_) => {
+                    style_sheet.and_then(|ss| ss.resolve_style(row, col, header_row_count))
+                }
+                _ => None,
+            };
+            write_cell(
+                worksheet,
+                row,
+                col,
+                cell,
+                format.as_ref(),
+                base_style.as_ref(),
+            )?;
         }
 
         if let Some(ref p) = progress
@@ -184,9 +617,31 @@ fn write_sheet(
     }
 
     apply_merge_ranges(worksheet, table_data, style_sheet)?;
+    apply_row_heights(worksheet, style_sheet)?;
+    apply_outline(worksheet, table_data)?;
+
+    if let Some(ref table) = table_data.sheet_options.table {
+        apply_excel_table(worksheet, table_data, table, max_cols)?;
+    }
+
+    apply_data_validations(worksheet, table_data)?;
+    apply_conditional_formats(worksheet, table_data, max_cols)?;
+    apply_notes(worksheet, table_data)?;
+    apply_images(worksheet, table_data)?;
+    apply_print_options(worksheet, table_data)?;
+    apply_sheet_protection(worksheet, table_data)?;
+    apply_sheet_view(worksheet, table_data);
+
+    if let Some((first_row, first_col, last_row, last_col)) =
+        auto_filter_range(table_data, max_cols)
+    {
+        worksheet
+            .autofilter(first_row, first_col, last_row, last_col)
+            .map_err(|e| JsValue::from_str(&format!("设置自动筛选失败: {}", e)))?;
+    }
 
     if let Some((fr, fc)) = resolve_freeze_pane(
-        freeze_pane,
+        table_data.sheet_options.freeze_pane.or(freeze_pane),
         table_data.header_row_count,
         total_rows,
         max_cols,
@@ -208,6 +663,7 @@ fn write_sheet(
 /// * `progress_callback` - 可选的进度回调函数
 /// * `strict_progress` - 是否启用严格进度回调模式
 /// * `freeze_pane` - 可选的冻结窗格位置 (freeze_row, freeze_col)，为 None 时自动根据 header_row_count 冻结
+/// * `workbook_options` - 工作簿级选项（文档属性、工作簿结构保护等）
 ///
 /// # 返回值
 /// * `Ok(Vec<u8>)` - 生成的 XLSX 字节
@@ -217,12 +673,15 @@ pub fn generate_xlsx_bytes(
     progress_callback: Option<&js_sys::Function>,
     strict_progress: bool,
     freeze_pane: Option<(u32, u16)>,
+    workbook_options: &WorkbookOptions,
 ) -> Result<Vec<u8>, JsValue> {
+    first_visible_sheet(std::iter::once(table_data))?;
+
     if let Some(callback) = progress_callback {
         report_progress(callback, 0.0, strict_progress)?;
     }
 
-    let mut workbook = Workbook::new();
+    let mut workbook = workbook_options.new_workbook();
     let worksheet = workbook.add_worksheet();
 
     let progress = progress_callback.map(|callback| SheetProgress {
@@ -238,9 +697,7 @@ pub fn generate_xlsx_bytes(
         report_progress(callback, 90.0, strict_progress)?;
     }
 
-    let xlsx_bytes = workbook
-        .save_to_buffer()
-        .map_err(|e| JsValue::from_str(&format!("生成 Excel 文件失败: {}", e)))?;
+    let xlsx_bytes = workbook_options.save(&mut workbook)?;
 
     if xlsx_bytes.is_empty() {
         return Err(JsValue::from_str("没有可导出的数据"));
@@ -256,12 +713,14 @@ pub fn export_as_xlsx(
     progress_callback: Option<js_sys::Function>,
     strict_progress: bool,
     freeze_pane: Option<(u32, u16)>,
+    workbook_options: &WorkbookOptions,
 ) -> Result<(), JsValue> {
     let xlsx_bytes = generate_xlsx_bytes(
         &table_data,
         progress_callback.as_ref(),
         strict_progress,
         freeze_pane,
+        workbook_options,
     )?;
     create_and_download_xlsx(&xlsx_bytes, filename)
 }
@@ -272,20 +731,25 @@ pub fn generate_xlsx_multi_bytes(
     progress_callback: Option<&js_sys::Function>,
     strict_progress: bool,
     freeze_pane: Option<(u32, u16)>,
+    workbook_options: &WorkbookOptions,
 ) -> Result<Vec<u8>, JsValue> {
     if sheets_data.is_empty() {
         return Err(JsValue::from_str("没有可导出的工作表数据"));
     }
 
     let total_sheets = sheets_data.len();
+    let first_visible = first_visible_sheet(sheets_data.iter().map(|(_, data)| data))?;
 
     if let Some(callback) = progress_callback {
         report_progress(callback, 0.0, strict_progress)?;
     }
 
-    let mut workbook = Workbook::new();
+    let mut workbook = workbook_options.new_workbook();
+    let sheet_names = sanitize_sheet_names(sheets_data.iter().map(|(name, _)| name.as_str()));
 
-    for (sheet_idx, (sheet_name, table_data)) in sheets_data.iter().enumerate() {
+    for (sheet_idx, ((_, table_data), sheet_name)) in
+        sheets_data.iter().zip(&sheet_names).enumerate()
+    {
         let worksheet = workbook.add_worksheet();
         worksheet
             .set_name(sheet_name)
@@ -305,13 +769,19 @@ pub fn generate_xlsx_multi_bytes(
         write_sheet(worksheet, table_data, freeze_pane, progress)?;
     }
 
+    // 首个工作表被隐藏时改为激活第一个可见工作表（否则保存时会强制激活并取消隐藏首个工作表）
+    if first_visible > 0 {
+        workbook
+            .worksheet_from_index(first_visible)
+            .map_err(|e| JsValue::from_str(&format!("设置活动工作表失败: {}", e)))?
+            .set_active(true);
+    }
+
     if let Some(callback) = progress_callback {
         report_progress(callback, 90.0, strict_progress)?;
     }
 
-    let xlsx_bytes = workbook
-        .save_to_buffer()
-        .map_err(|e| JsValue::from_str(&format!("生成 Excel 文件失败: {}", e)))?;
+    let xlsx_bytes = workbook_options.save(&mut workbook)?;
 
     if xlsx_bytes.is_empty() {
         return Err(JsValue::from_str("没有可导出的数据"));
@@ -327,12 +797,14 @@ pub fn export_as_xlsx_multi(
     progress_callback: Option<js_sys::Function>,
     strict_progress: bool,
     freeze_pane: Option<(u32, u16)>,
+    workbook_options: &WorkbookOptions,
 ) -> Result<(), JsValue> {
     let xlsx_bytes = generate_xlsx_multi_bytes(
         &sheets_data,
         progress_callback.as_ref(),
         strict_progress,
         freeze_pane,
+        workbook_options,
     )?;
     create_and_download_xlsx(&xlsx_bytes, filename)
 }
@@ -366,3 +838,461 @@ pub(crate) fn write_sheet_with_progress(
     });
     write_sheet(worksheet, table_data, freeze_pane, progress)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::table_extractor::MergeRange;
+    use super::*;
+    use calamine::{Data, Reader, Xlsx};
+    use std::io::Cursor;
+
+    /// 读取生成文件第一个工作表的单元格区域
+    fn read_first_sheet(bytes: Vec<u8>) -> calamine::Range<Data> {
+        let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes)).unwrap();
+        workbook.worksheet_range_at(0).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_write_sheet_typed_cells() {
+        let table_data = TableData {
+            rows: vec![
+                vec![
+                    CellValue::from("名称"),
+                    CellValue::from("数量"),
+                    CellValue::from("启用"),
+                ],
+                vec![
+                    CellValue::from("苹果"),
+                    CellValue::Number(12.5),
+                    CellValue::Bool(true),
+                ],
+                vec![
+                    CellValue::from("梨"),
+                    CellValue::Empty,
+                    CellValue::Bool(false),
+                ],
+            ],
+            header_row_count: 1,
+            ..Default::default()
+        };
+
+        let range = read_first_sheet(
+            generate_xlsx_bytes(&table_data, None, false, None, &WorkbookOptions::default())
+                .unwrap(),
+        );
+        assert_eq!(range.get_value((0, 1)), Some(&Data::String("数量".into())));
+        assert_eq!(range.get_value((1, 1)), Some(&Data::Float(12.5)));
+        assert_eq!(range.get_value((1, 2)), Some(&Data::Bool(true)));
+        assert_eq!(range.get_value((2, 1)), Some(&Data::Empty));
+        assert_eq!(range.get_value((2, 2)), Some(&Data::Bool(false)));
+    }
+
+    #[test]
+    fn test_merge_range_keeps_number_type() {
+        let table_data = TableData {
+            rows: vec![
+                vec![CellValue::Number(100.0), CellValue::Empty],
+                vec![CellValue::from("a"), CellValue::from("b")],
+            ],
+            merge_ranges: vec![MergeRange::new(0, 0, 0, 1)],
+            ..Default::default()
+        };
+
+        let range = read_first_sheet(
+            generate_xlsx_bytes(&table_data, None, false, None, &WorkbookOptions::default())
+                .unwrap(),
+        );
+        assert_eq!(range.get_value((0, 0)), Some(&Data::Float(100.0)));
+    }
+
+    #[test]
+    fn test_write_sheet_date_cells() {
+        let date = CellDateTime::from_ymd(2024, 3, 5).unwrap();
+        let datetime = date.and_hms_milli(12, 0, 0, 0).unwrap();
+        let table_data = TableData {
+            rows: vec![vec![
+                CellValue::DateTime(date),
+                CellValue::DateTime(datetime),
+                // Excel 不支持 1900 年之前的日期，回退为文本
+                CellValue::DateTime(CellDateTime::from_ymd(1899, 12, 1).unwrap()),
+            ]],
+            style_sheet: Some(StyleSheet {
+                date_format: Some("yyyy/mm/dd".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let range = read_first_sheet(
+            generate_xlsx_bytes(&table_data, None, false, None, &WorkbookOptions::default())
+                .unwrap(),
+        );
+        match range.get_value((0, 0)) {
+            Some(Data::DateTime(dt)) => assert_eq!(dt.as_f64(), 45356.0),
+            other => panic!("期望日期单元格，实际为 {:?}", other),
+        }
+        match range.get_value((0, 1)) {
+            Some(Data::DateTime(dt)) => assert_eq!(dt.as_f64(), 45356.5),
+            other => panic!("期望日期单元格，实际为 {:?}", other),
+        }
+        assert_eq!(
+            range.get_value((0, 2)),
+            Some(&Data::String("1899-12-01".into()))
+        );
+    }
+
+    #[test]
+    fn test_write_sheet_formula_cells() {
+        use super::super::table_extractor::CellFormula;
+
+        let table_data = TableData {
+            rows: vec![
+                vec![CellValue::Number(1.0)],
+                vec![CellValue::Number(2.0)],
+                vec![CellValue::Formula(CellFormula {
+                    formula: "=SUM(A1:A2)".to_string(),
+                    cached: Box::new(CellValue::Number(3.0)),
+                })],
+            ],
+            ..Default::default()
+        };
+
+        let bytes =
+            generate_xlsx_bytes(&table_data, None, false, None, &WorkbookOptions::default())
+                .unwrap();
+        let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes.clone())).unwrap();
+        let sheet_name = workbook.sheet_names()[0].clone();
+        let formulas = workbook.worksheet_formula(&sheet_name).unwrap();
+        assert_eq!(formulas.get_value((2, 0)), Some(&"SUM(A1:A2)".to_string()));
+
+        // 缓存结果作为单元格值
+        let range = read_first_sheet(bytes);
+        assert_eq!(range.get_value((2, 0)), Some(&Data::Float(3.0)));
+
+        // CSV 输出缓存值
+        assert_eq!(table_data.into_rows()[2], vec!["3".to_string()]);
+    }
+
+    #[test]
+    fn test_write_sheet_link_cells() {
+        use super::super::table_extractor::CellLink;
+        use std::io::Read;
+
+        let table_data = TableData {
+            rows: vec![vec![
+                CellValue::Link(CellLink {
+                    url: "https://example.com/a".to_string(),
+                    text: "官网".to_string(),
+                }),
+                CellValue::Link(CellLink {
+                    url: "#Sheet1!A1".to_string(),
+                    text: String::new(),
+                }),
+            ]],
+            ..Default::default()
+        };
+
+        let bytes =
+            generate_xlsx_bytes(&table_data, None, false, None, &WorkbookOptions::default())
+                .unwrap();
+        let range = read_first_sheet(bytes.clone());
+        assert_eq!(
+            range.get_value((0, 0)),
+            Some(&Data::String("官网".to_string()))
+        );
+        // 无显示文本时显示链接地址
+        assert_eq!(
+            range.get_value((0, 1)),
+            Some(&Data::String("Sheet1!A1".to_string()))
+        );
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut rels = String::new();
+        archive
+            .by_name("xl/worksheets/_rels/sheet1.xml.rels")
+            .unwrap()
+            .read_to_string(&mut rels)
+            .unwrap();
+        assert!(rels.contains("https://example.com/a"));
+        // 内部链接不产生外部关系
+        assert!(!rels.contains("Sheet1!A1"));
+
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        assert!(sheet.contains("location=\"Sheet1!A1\""));
+    }
+
+    /// 读取 zip 包中的文本文件
+    fn read_zip_text(bytes: &[u8], name: &str) -> String {
+        use std::io::Read;
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut text = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn test_write_sheet_cell_style_overrides() {
+        let mut table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("名称"), CellValue::from("数量")],
+                vec![CellValue::from("苹果"), CellValue::Number(3.0)],
+            ],
+            header_row_count: 1,
+            ..Default::default()
+        };
+        let page_style = CellStyle {
+            background_color: Some("#409EFF".to_string()),
+            ..Default::default()
+        };
+        table_data.insert_cell_style(1, 0, page_style.clone());
+        table_data.insert_cell_style(1, 1, page_style);
+        // 注入全局样式时保留已记录的单元格样式
+        let header = CellStyle {
+            bold: Some(true),
+            ..Default::default()
+        };
+        table_data.apply_global_styles(Some(&header), None);
+
+        let bytes =
+            generate_xlsx_bytes(&table_data, None, false, None, &WorkbookOptions::default())
+                .unwrap();
+        let sheet = read_zip_text(&bytes, "xl/worksheets/sheet1.xml");
+        let style_of = |cell: &str| {
+            let start = sheet.find(&format!("<c r=\"{}\" s=\"", cell)).unwrap() + cell.len() + 11;
+            sheet[start..].split('"').next().unwrap().to_string()
+        };
+        assert_eq!(style_of("A1"), style_of("B1"));
+        assert_eq!(style_of("A2"), style_of("B2"));
+        assert_ne!(style_of("A1"), style_of("A2"));
+        assert!(read_zip_text(&bytes, "xl/styles.xml").contains("FF409EFF"));
+    }
+
+    #[test]
+    fn test_write_sheets_view_options() {
+        let sheet = || TableData {
+            rows: vec![
+                vec![CellValue::from("名称"), CellValue::from("数量")],
+                vec![CellValue::from("苹果"), CellValue::Number(3.0)],
+                vec![CellValue::from("梨"), CellValue::Number(5.0)],
+            ],
+            header_row_count: 1,
+            ..Default::default()
+        };
+        let mut summary = sheet();
+        summary.sheet_options.hidden = true;
+        summary.sheet_options.tab_color = Some("#FF0000".to_string());
+        let mut detail = sheet();
+        detail.sheet_options.zoom = Some(150);
+        detail.sheet_options.freeze_pane = Some((2, 1));
+
+        let bytes = generate_xlsx_multi_bytes(
+            &[("汇总".to_string(), summary), ("明细".to_string(), detail)],
+            None,
+            false,
+            None,
+            &WorkbookOptions::default(),
+        )
+        .unwrap();
+
+        // 首个工作表隐藏时激活第一个可见工作表
+        let workbook = read_zip_text(&bytes, "xl/workbook.xml");
+        assert!(workbook.contains("state=\"hidden\""));
+        assert!(workbook.contains("activeTab=\"1\""));
+
+        let summary = read_zip_text(&bytes, "xl/worksheets/sheet1.xml");
+        assert!(summary.contains("<tabColor rgb=\"FFFF0000\"/>"));
+        // 未设置冻结时按表头行自动冻结
+        assert!(summary.contains("ySplit=\"1\""));
+
+        let detail = read_zip_text(&bytes, "xl/worksheets/sheet2.xml");
+        assert!(detail.contains("zoomScale=\"150\""));
+        assert!(detail.contains("xSplit=\"1\" ySplit=\"2\""));
+    }
+
+    #[test]
+    fn test_write_sheets_fixes_names() {
+        let sheet = || TableData {
+            rows: vec![vec![CellValue::from("a")]],
+            ..Default::default()
+        };
+        let bytes = generate_xlsx_multi_bytes(
+            &[
+                ("明细".to_string(), sheet()),
+                ("明细".to_string(), sheet()),
+                ("2024/01".to_string(), sheet()),
+            ],
+            None,
+            false,
+            None,
+            &WorkbookOptions::default(),
+        )
+        .unwrap();
+
+        let workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["明细", "明细(2)", "2024_01"]);
+    }
+
+    #[test]
+    fn test_write_sheet_excel_table() {
+        use super::super::sheet_options::ExcelTableOptions;
+
+        let mut table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("名称"), CellValue::from("名称")],
+                vec![CellValue::from("A"), CellValue::Number(1.0)],
+                vec![CellValue::from("合计"), CellValue::Number(1.0)],
+            ],
+            header_row_count: 1,
+            footer_row_count: 1,
+            ..Default::default()
+        };
+        table_data.sheet_options.table = Some(ExcelTableOptions {
+            total_row: true,
+            name: Some("Sales".to_string()),
+            ..Default::default()
+        });
+
+        let bytes =
+            generate_xlsx_bytes(&table_data, None, false, None, &WorkbookOptions::default())
+                .unwrap();
+        let xml = read_zip_text(&bytes, "xl/tables/table1.xml");
+        // 表尾合计行作为汇总行
+        assert!(xml.contains("ref=\"A1:B3\""));
+        assert!(xml.contains("totalsRowCount=\"1\""));
+        assert!(xml.contains("name=\"Sales\""));
+        assert!(xml.contains("TableStyleMedium9"));
+        // 重名表头自动改名并回写
+        assert!(xml.contains("name=\"名称2\""));
+        let range = read_first_sheet(bytes);
+        assert_eq!(
+            range.get_value((0, 1)),
+            Some(&Data::String("名称2".to_string()))
+        );
+        assert_eq!(
+            range.get_value((2, 0)),
+            Some(&Data::String("合计".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_excel_table_rejects_merged_header() {
+        use super::super::sheet_options::ExcelTableOptions;
+
+        let mut table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("分组"), CellValue::Empty],
+                vec![CellValue::from("A"), CellValue::from("B")],
+                vec![CellValue::Number(1.0), CellValue::Number(2.0)],
+            ],
+            merge_ranges: vec![MergeRange::new(0, 0, 0, 1)],
+            header_row_count: 2,
+            ..Default::default()
+        };
+        let table = ExcelTableOptions::default();
+
+        // 多行表头
+        assert!(excel_table_range(&table_data, &table, 2).is_err());
+        // 单行表头但含合并
+        table_data.rows.remove(0);
+        table_data.header_row_count = 1;
+        assert!(excel_table_range(&table_data, &table, 2).is_err());
+        // 无合并时区域覆盖表头 + 数据
+        table_data.merge_ranges.clear();
+        assert_eq!(excel_table_range(&table_data, &table, 2), Ok((1, 1)));
+    }
+
+    #[test]
+    fn test_auto_filter_range() {
+        use super::super::sheet_options::ExcelTableOptions;
+
+        let mut table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("分组"), CellValue::Empty, CellValue::Empty],
+                vec![
+                    CellValue::from("A"),
+                    CellValue::from("B"),
+                    CellValue::from("C"),
+                ],
+                vec![
+                    CellValue::Number(1.0),
+                    CellValue::Number(2.0),
+                    CellValue::Number(3.0),
+                ],
+                vec![
+                    CellValue::Number(4.0),
+                    CellValue::Number(5.0),
+                    CellValue::Number(6.0),
+                ],
+                vec![
+                    CellValue::from("合计"),
+                    CellValue::Number(7.0),
+                    CellValue::Number(9.0),
+                ],
+            ],
+            merge_ranges: vec![MergeRange::new(0, 0, 0, 2)],
+            header_row_count: 2,
+            footer_row_count: 1,
+            ..Default::default()
+        };
+
+        // 默认开启：最底层表头到最后一行数据，不含合计行
+        assert_eq!(auto_filter_range(&table_data, 3), Some((1, 0, 3, 2)));
+        let bytes =
+            generate_xlsx_bytes(&table_data, None, false, None, &WorkbookOptions::default())
+                .unwrap();
+        let xml = read_zip_text(&bytes, "xl/worksheets/sheet1.xml");
+        assert!(xml.contains("<autoFilter ref=\"A2:C4\"/>"));
+
+        // 显式关闭
+        table_data.sheet_options.auto_filter = Some(false);
+        assert_eq!(auto_filter_range(&table_data, 3), None);
+
+        // Excel 表格自带筛选，不重复设置
+        table_data.sheet_options.auto_filter = None;
+        table_data.sheet_options.table = Some(ExcelTableOptions::default());
+        assert_eq!(auto_filter_range(&table_data, 3), None);
+
+        // 无表头时不启用；仅有表头时只筛选表头行
+        let mut table_data = TableData {
+            rows: vec![vec![CellValue::from("A"), CellValue::from("B")]],
+            ..Default::default()
+        };
+        assert_eq!(auto_filter_range(&table_data, 2), None);
+        table_data.header_row_count = 1;
+        assert_eq!(auto_filter_range(&table_data, 2), Some((0, 0, 0, 1)));
+    }
+
+    #[test]
+    fn test_data_row_range() {
+        let mut table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("状态")],
+                vec![CellValue::from("启用")],
+                vec![CellValue::from("停用")],
+            ],
+            header_row_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(data_row_range(&table_data), Some((1, 2)));
+
+        table_data.sheet_options.template_rows = Some(100);
+        assert_eq!(data_row_range(&table_data), Some((1, 100)));
+
+        // 空白模板：仅表头
+        table_data.rows.truncate(1);
+        table_data.sheet_options.template_rows = None;
+        assert_eq!(data_row_range(&table_data), None);
+        table_data.sheet_options.template_rows = Some(10);
+        assert_eq!(data_row_range(&table_data), Some((1, 10)));
+    }
+}
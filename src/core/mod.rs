@@ -1,11 +1,31 @@
 /// 核心导出协调模块
 ///
 /// 提供统一的导出接口，协调各个导出模块
+pub(crate) mod cell_image;
+pub(crate) mod cell_note;
+pub(crate) mod column_width;
+pub(crate) mod computed_style;
+pub(crate) mod conditional_format;
+pub(crate) mod csv_dialect;
 mod data_export;
+pub(crate) mod data_validation;
 pub(crate) mod export_csv;
 pub(crate) mod export_xlsx;
+pub(crate) mod outline;
+pub(crate) mod print_options;
+pub(crate) mod protection;
+pub(crate) mod rich_text;
+pub(crate) mod row_style;
+pub(crate) mod sheet_options;
 pub(crate) mod style;
 pub(crate) mod table_extractor;
+mod template_export;
+#[cfg(test)]
+pub(crate) mod test_support;
+mod workbook_append;
+mod workbook_export;
+pub(crate) mod workbook_options;
+pub(crate) mod xlsx_package;
 
 // Excel 预览模块
 pub mod excel_reader;
@@ -18,10 +38,13 @@ pub(crate) use export_xlsx::create_and_download_xlsx;
 use export_xlsx::{export_as_xlsx, export_as_xlsx_multi, generate_xlsx_bytes};
 use table_extractor::extract_table_data;
 pub(crate) use table_extractor::{
-    MergeRange, RowSpanTracker, TableData, TableRowSources, extract_table_data_with_merge,
-    process_row_cells,
+    CellValue, DomCaptureOptions, MergeRange, RowSpanTracker, TableData, TableRowSources,
+    cells_to_text_rows, extract_table_data_with_merge, process_row_cells,
 };
+pub use template_export::{export_from_template, generate_template_bytes};
 use wasm_bindgen::prelude::*;
+pub use workbook_append::append_sheets_to_workbook;
+pub use workbook_export::{export_workbook, generate_workbook_bytes};
 
 /// 导出格式枚举
 #[wasm_bindgen]
@@ -32,6 +55,8 @@ pub enum ExportFormat {
     Csv,
     /// Excel XLSX 格式
     Xlsx,
+    /// TSV 格式（制表符分隔）
+    Tsv,
 }
 
 /// 统一的表格导出函数（带进度回调）
@@ -41,12 +66,38 @@ pub enum ExportFormat {
 /// # 参数
 /// * `table_id` - 要导出的 HTML 表格元素的 ID
 /// * `filename` - 可选的导出文件名（不包含扩展名时会自动添加）
-/// * `format` - 导出格式（Csv 或 Xlsx），默认为 Csv
+/// * `format` - 导出格式（Csv、Tsv 或 Xlsx），默认为 Csv
 /// * `exclude_hidden` - 可选，是否排除隐藏的行和列（默认为 false）
 /// * `progress_callback` - 可选的进度回调函数，接收 0-100 的进度值
-/// * `with_bom` - 可选，是否添加 UTF-8 BOM（默认为 false，仅对 CSV 有效）
+/// * `with_bom` - 可选，是否添加 UTF-8 BOM（默认为 false，仅对 CSV / TSV 有效）
 /// * `strict_progress_callback` - 可选，是否启用严格进度回调模式（默认 false）。
 ///   启用后回调失败会中断导出并返回错误，否则仅 console.warn
+/// * `header_style` - 可选，全局表头样式（仅 XLSX 有效）
+/// * `cell_style` - 可选，全局数据行样式（仅 XLSX 有效）
+/// * `options` - 可选的扩展配置对象（除 `csv`、`csvNoteColumn` 外仅 XLSX 有效），混合 DOM 采集
+///   （`capture*`、`richText`、`preserveStyles`）、工作表与工作簿（`properties`、`workbookProtection`）三类字段，
+///   `export_table_to_xlsx_batch` 的 `options` 沿用同名字段：
+///   - `csv`: CSV 方言 `{ delimiter?, quote?, quoteStyle?, terminator?, escape? }`（仅 CSV / TSV 有效）
+///   - `captureLinks`: 是否将单元格内第一个 `<a href>` 导出为可点击链接（默认 false）
+///   - `captureNotes`: 是否将单元格的 `data-note`（优先）或 `title` 属性导出为批注（默认 false）
+///   - `noteOptions`: 批注外观 `{ author?, width?, height?, visible? }`
+///   - `csvNoteColumn`: CSV 导出时将批注追加为末尾备注列（`true` 或列标题，需开启 `captureNotes`）
+///   - `captureImages`: 是否将单元格内第一个 `<img>` 的 data URL（PNG / JPEG / GIF）嵌入为图片（默认 false）
+///   - `imageOptions`: 图片缩放 `{ fit?: 'cell' | 'row', maxHeight? }`
+///   - `richText`: 是否将单元格内的 `<b>`、`<i>`、`<u>`、`<s>`、`<sub>` / `<sup>`、文字颜色等内联格式导出为富文本（默认 false）
+///   - `preserveStyles`: 是否读取单元格的页面计算样式（字体、颜色、背景、对齐、边框）并导出（默认 false），
+///     与 `header_style` / `cell_style` 同时设置时页面样式优先
+///   - `autoWidth`: 按内容自动计算列宽，`true` 或 `{ min, max, sampleRows }`
+///   - `table`: 转为 Excel 表格（ListObject），`true` 或 `{ style, totalRow, bandedRows, name }`
+///   - `autoFilter`: 是否在最后一行表头上启用自动筛选（默认存在表头时启用）
+///   - `conditionalFormats`: 条件格式规则数组，可用 `range` / `column` 指定作用范围（默认全部数据区）
+///   - `properties`: 文档属性 `{ title?, author?, company?, …, created?, custom? }`
+///   - `protection`: 工作表保护，`true` 或 `{ password?, allowSort?, allowFilter?, allowFormatColumns?, … }`
+///   - `workbookProtection`: 工作簿结构保护，`true` 或 `{ password? }`
+///   - `print`: 打印设置 `{ landscape?, paper?, margins?, fitToPages?, scale?, centerHorizontally?, gridlines?,
+///     repeatHeaderRows?, header?, footer? }`，表头行默认在每页顶端重复
+///   - `freezeRows` / `freezeCols`: 冻结前 N 行 / N 列（默认按 `<thead>` 行数冻结）
+///   - `tabColor`: 工作表标签颜色；`zoom`: 显示缩放比例（10–400）
 ///
 /// # 返回值
 /// * `Ok(())` - 导出成功
@@ -70,6 +121,11 @@ pub enum ExportFormat {
 /// export_table('my-table', '报表', ExportFormat.Xlsx, true, (progress) => {
 ///     document.getElementById('progress').style.width = `${progress}%`;
 /// });
+///
+/// // 导出为 Excel，保留单元格中的链接
+/// export_table('my-table', '报表', ExportFormat.Xlsx, false, null, null, null, null, null, {
+///     captureLinks: true,
+/// });
 /// ```
 #[wasm_bindgen]
 #[allow(clippy::too_many_arguments)]
@@ -83,6 +139,7 @@ pub fn export_table(
     strict_progress_callback: Option<bool>,
     header_style: Option<JsValue>,
     cell_style: Option<JsValue>,
+    options: Option<JsValue>,
 ) -> Result<(), JsValue> {
     let format = format.unwrap_or_default();
     let exclude_hidden = exclude_hidden.unwrap_or(false);
@@ -98,31 +155,54 @@ pub fn export_table(
     let hs = header_style.as_ref().and_then(style::parse_cell_style);
     let cs = cell_style.as_ref().and_then(style::parse_cell_style);
 
+    // 解析扩展配置
+    let capture = options
+        .as_ref()
+        .map(parse_dom_capture_options)
+        .unwrap_or_default();
+    let sheet_options = match options {
+        Some(ref obj) => sheet_options::parse_sheet_options(obj)?,
+        None => Default::default(),
+    };
+    let csv_note_column = options.as_ref().and_then(cell_note::parse_csv_note_column);
+    let csv_dialect = match options {
+        Some(ref obj) => csv_dialect::parse_csv_dialect(obj, format)?,
+        None => csv_dialect::CsvDialect::for_format(format),
+    };
+    let workbook_options = match options {
+        Some(ref obj) => workbook_options::parse_workbook_options(obj)?,
+        None => Default::default(),
+    };
+
     // 根据格式导出
     match format {
-        ExportFormat::Csv => {
-            // CSV 不支持合并单元格，使用简化提取
-            let table_data = extract_table_data(table_id, exclude_hidden)?;
+        ExportFormat::Csv | ExportFormat::Tsv => {
+            // CSV 不支持合并单元格，使用简化提取；需要备注列时按完整提取采集批注
+            let table_data = match csv_note_column {
+                Some(ref title) if capture.notes => table_data_to_csv_rows(
+                    extract_table_data_with_merge(table_id, exclude_hidden, &capture)?,
+                    None,
+                    Some(title),
+                ),
+                _ => extract_table_data(table_id, exclude_hidden)?,
+            };
             export_as_csv(
                 table_data,
                 filename,
                 progress_callback,
                 with_bom,
                 strict_progress,
+                format,
+                &csv_dialect,
             )
         }
         ExportFormat::Xlsx => {
             // XLSX 支持合并单元格，提取完整数据
-            let mut table_data = extract_table_data_with_merge(table_id, exclude_hidden)?;
-
-            // 注入全局样式
-            if hs.is_some() || cs.is_some() {
-                table_data.style_sheet = Some(style::StyleSheet {
-                    header_style: hs,
-                    data_style: cs,
-                    ..Default::default()
-                });
-            }
+            let mut table_data = extract_table_data_with_merge(table_id, exclude_hidden, &capture)?;
+            table_data.sheet_options = sheet_options;
+
+            // 注入全局样式（保留采集到的页面样式）
+            table_data.apply_global_styles(hs.as_ref(), cs.as_ref());
 
             export_as_xlsx(
                 table_data,
@@ -130,6 +210,7 @@ pub fn export_table(
                 progress_callback,
                 strict_progress,
                 None,
+                &workbook_options,
             )
         }
     }
@@ -143,6 +224,80 @@ struct SheetConfig {
     sheet_name: Option<String>,
     /// 是否排除隐藏行列
     exclude_hidden: bool,
+    /// DOM 额外采集项（链接等）
+    capture: DomCaptureOptions,
+    /// 工作表级选项（Excel 表格、冻结窗格、标签颜色等）
+    sheet_options: sheet_options::SheetOptions,
+    /// 工作表表头样式（与全局表头样式合并，本表设置优先）
+    header_style: Option<style::CellStyle>,
+    /// 工作表数据行样式（与全局数据行样式合并，本表设置优先）
+    cell_style: Option<style::CellStyle>,
+    /// 列宽（按列下标，null 表示该列不设置）
+    column_widths: Vec<Option<f64>>,
+}
+
+/// Excel 列宽上限（字符数）
+const MAX_COLUMN_WIDTH: f64 = 255.0;
+
+/// 解析 `columnWidths` 数组：元素为 0–255 的数字，`null` / `undefined` 表示该列不设置
+fn parse_column_widths(val: &JsValue) -> Result<Vec<Option<f64>>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(Vec::new());
+    }
+    if !js_sys::Array::is_array(val) {
+        return Err(JsValue::from_str("columnWidths 必须是数组"));
+    }
+
+    js_sys::Array::from(val)
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            if item.is_null() || item.is_undefined() {
+                return Ok(None);
+            }
+            match item.as_f64() {
+                Some(w) if (0.0..=MAX_COLUMN_WIDTH).contains(&w) => Ok(Some(w)),
+                _ => Err(JsValue::from_str(&format!(
+                    "columnWidths 第 {} 项无效，应为 0–{} 的数字",
+                    idx + 1,
+                    MAX_COLUMN_WIDTH
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// 合并全局样式与工作表样式（工作表中已设置的属性优先）
+fn merge_sheet_style(
+    global: Option<&style::CellStyle>,
+    sheet: Option<&style::CellStyle>,
+) -> Option<style::CellStyle> {
+    match (global, sheet) {
+        (Some(g), Some(s)) => Some(g.merge(s)),
+        (g, s) => s.or(g).cloned(),
+    }
+}
+
+/// 从 JS 对象解析 DOM 额外采集项（export_table 的 options 与工作表配置共用）
+fn parse_dom_capture_options(obj: &JsValue) -> DomCaptureOptions {
+    if obj.is_null() || obj.is_undefined() {
+        return DomCaptureOptions::default();
+    }
+
+    let flag = |key: &str| {
+        js_sys::Reflect::get(obj, &JsValue::from_str(key))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    };
+
+    DomCaptureOptions {
+        links: flag("captureLinks"),
+        notes: flag("captureNotes"),
+        images: flag("captureImages"),
+        rich_text: flag("richText"),
+        styles: flag("preserveStyles"),
+    }
 }
 
 /// 从 JsValue 数组解析工作表配置列表
@@ -159,45 +314,86 @@ fn parse_sheet_configs(sheets: &JsValue) -> Result<Vec<SheetConfig>, JsValue> {
         return Err(JsValue::from_str("工作表配置数组不能为空"));
     }
 
-    let mut configs = Vec::with_capacity(length as usize);
+    (0..length)
+        .map(|i| parse_sheet_config(&array.get(i), i as usize))
+        .collect()
+}
 
-    for i in 0..length {
-        let item = array.get(i);
+/// 解析单个 DOM 工作表配置（`index` 为配置在数组中的下标，用于错误信息）
+fn parse_sheet_config(item: &JsValue, index: usize) -> Result<SheetConfig, JsValue> {
+    // 提取 tableId（必填）
+    let table_id = js_sys::Reflect::get(item, &JsValue::from_str("tableId"))
+        .ok()
+        .and_then(|v| v.as_string())
+        .ok_or_else(|| {
+            JsValue::from_str(&format!("第 {} 个工作表配置缺少有效的 tableId", index + 1))
+        })?;
 
-        // 提取 tableId（必填）
-        let table_id = js_sys::Reflect::get(&item, &JsValue::from_str("tableId"))
-            .ok()
-            .and_then(|v| v.as_string())
-            .ok_or_else(|| {
-                JsValue::from_str(&format!("第 {} 个工作表配置缺少有效的 tableId", i + 1))
-            })?;
+    if table_id.is_empty() {
+        return Err(JsValue::from_str(&format!(
+            "第 {} 个工作表配置的 tableId 不能为空",
+            index + 1
+        )));
+    }
 
-        if table_id.is_empty() {
-            return Err(JsValue::from_str(&format!(
-                "第 {} 个工作表配置的 tableId 不能为空",
-                i + 1
-            )));
-        }
+    // 提取 sheetName（可选）
+    let sheet_name = js_sys::Reflect::get(item, &JsValue::from_str("sheetName"))
+        .ok()
+        .and_then(|v| v.as_string());
 
-        // 提取 sheetName（可选）
-        let sheet_name = js_sys::Reflect::get(&item, &JsValue::from_str("sheetName"))
-            .ok()
-            .and_then(|v| v.as_string());
+    // 提取 excludeHidden（可选，默认 false）
+    let exclude_hidden = js_sys::Reflect::get(item, &JsValue::from_str("excludeHidden"))
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
 
-        // 提取 excludeHidden（可选，默认 false）
-        let exclude_hidden = js_sys::Reflect::get(&item, &JsValue::from_str("excludeHidden"))
-            .ok()
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
+    // 提取工作表样式（可选）
+    let header_style = js_sys::Reflect::get(item, &JsValue::from_str("headerStyle"))
+        .ok()
+        .and_then(|v| style::parse_cell_style(&v));
+    let cell_style = js_sys::Reflect::get(item, &JsValue::from_str("cellStyle"))
+        .ok()
+        .and_then(|v| style::parse_cell_style(&v));
+
+    // 提取 columnWidths（可选）
+    let column_widths = js_sys::Reflect::get(item, &JsValue::from_str("columnWidths"))
+        .map_err(|e| JsValue::from_str(&format!("读取 columnWidths 配置失败: {:?}", e)))
+        .and_then(|v| parse_column_widths(&v))?;
+
+    Ok(SheetConfig {
+        table_id,
+        sheet_name,
+        exclude_hidden,
+        capture: parse_dom_capture_options(item),
+        sheet_options: sheet_options::parse_sheet_options(item)?,
+        header_style,
+        cell_style,
+        column_widths,
+    })
+}
 
-        configs.push(SheetConfig {
-            table_id,
-            sheet_name,
-            exclude_hidden,
-        });
+/// 按工作表配置从 DOM 提取数据，并注入全局 / 工作表样式、列宽与工作表级选项
+fn build_dom_sheet(
+    config: &SheetConfig,
+    header_style: Option<&style::CellStyle>,
+    cell_style: Option<&style::CellStyle>,
+) -> Result<table_extractor::TableData, JsValue> {
+    let mut table_data =
+        extract_table_data_with_merge(&config.table_id, config.exclude_hidden, &config.capture)?;
+
+    table_data.apply_global_styles(
+        merge_sheet_style(header_style, config.header_style.as_ref()).as_ref(),
+        merge_sheet_style(cell_style, config.cell_style.as_ref()).as_ref(),
+    );
+    if !config.column_widths.is_empty() {
+        table_data
+            .style_sheet
+            .get_or_insert_with(Default::default)
+            .column_widths = config.column_widths.clone();
     }
+    table_data.sheet_options = config.sheet_options.clone();
 
-    Ok(configs)
+    Ok(table_data)
 }
 
 /// 多工作表导出为 Excel XLSX 文件
@@ -205,9 +401,17 @@ fn parse_sheet_configs(sheets: &JsValue) -> Result<Vec<SheetConfig>, JsValue> {
 /// 将多个 HTML 表格导出到同一个 Excel 文件的不同工作表中
 ///
 /// # 参数
-/// * `sheets` - JS 数组，每个元素包含 { tableId: string, sheetName?: string, excludeHidden?: boolean }
+/// * `sheets` - JS 数组，每个元素包含 { tableId: string, sheetName?: string, excludeHidden?: boolean, captureLinks?: boolean, captureNotes?: boolean, noteOptions?: object, captureImages?: boolean, imageOptions?: object, richText?: boolean, preserveStyles?: boolean, autoWidth?: boolean | object, table?: boolean | object, autoFilter?: boolean, conditionalFormats?: object[], protection?: boolean | object, print?: object, headerStyle?: object, cellStyle?: object, columnWidths?: (number | null)[], freezeRows?: number, freezeCols?: number, tabColor?: string, hidden?: boolean, zoom?: number }
+///   工作表的 headerStyle / cellStyle 与全局样式合并（本表设置优先），hidden 的工作表至少保留一个可见
+///   工作表名称中的非法字符替换为 `_`，超过 31 个字符截断，重名时追加 `(2)` 等序号
 /// * `filename` - 可选的导出文件名
 /// * `progress_callback` - 可选的进度回调函数，接收 0-100 的进度值
+/// * `strict_progress_callback` - 可选，是否启用严格进度回调模式（默认 false）
+/// * `header_style` - 可选，全局表头样式
+/// * `cell_style` - 可选，全局数据行样式
+/// * `options` - 可选的工作簿级配置对象：
+///   - `properties`: 文档属性 `{ title?, author?, company?, …, created?, custom? }`
+///   - `workbookProtection`: 工作簿结构保护，`true` 或 `{ password? }`
 ///
 /// # 示例
 /// ```javascript
@@ -230,24 +434,20 @@ pub fn export_tables_xlsx(
     strict_progress_callback: Option<bool>,
     header_style: Option<JsValue>,
     cell_style: Option<JsValue>,
+    options: Option<JsValue>,
 ) -> Result<(), JsValue> {
     let strict_progress = strict_progress_callback.unwrap_or(false);
 
     // 解析全局样式
     let hs = header_style.as_ref().and_then(style::parse_cell_style);
     let cs = cell_style.as_ref().and_then(style::parse_cell_style);
-    let global_ss = if hs.is_some() || cs.is_some() {
-        Some(style::StyleSheet {
-            header_style: hs,
-            data_style: cs,
-            ..Default::default()
-        })
-    } else {
-        None
-    };
 
     // 解析配置
     let configs = parse_sheet_configs(&sheets)?;
+    let workbook_options = match options {
+        Some(ref obj) => workbook_options::parse_workbook_options(obj)?,
+        None => Default::default(),
+    };
 
     // 逐个表格提取数据
     let mut sheets_data: Vec<(String, table_extractor::TableData)> =
@@ -259,12 +459,7 @@ pub fn export_tables_xlsx(
             .clone()
             .unwrap_or_else(|| format!("Sheet{}", idx + 1));
 
-        let mut table_data =
-            extract_table_data_with_merge(&config.table_id, config.exclude_hidden)?;
-
-        // 注入全局样式
-        table_data.style_sheet = global_ss.clone();
-
+        let table_data = build_dom_sheet(config, hs.as_ref(), cs.as_ref())?;
         sheets_data.push((sheet_name, table_data));
     }
 
@@ -275,18 +470,19 @@ pub fn export_tables_xlsx(
         progress_callback,
         strict_progress,
         None,
+        &workbook_options,
     )
 }
 
-/// 从 JS 二维数组解析为 Rust 二维字符串数组
+/// 从 JS 二维数组解析为 Rust 二维单元格数组
 ///
 /// # 参数
-/// * `data` - JS 二维数组 (Array<Array<string>>)
+/// * `data` - JS 二维数组 (Array<Array<any>>)
 ///
 /// # 返回值
-/// * `Ok(Vec<Vec<String>>)` - 解析成功
+/// * `Ok(Vec<Vec<CellValue>>)` - 解析成功（数字/布尔保留原生类型）
 /// * `Err(JsValue)` - 解析失败
-pub(crate) fn parse_js_array_data(data: &JsValue) -> Result<Vec<Vec<String>>, JsValue> {
+pub(crate) fn parse_js_array_data(data: &JsValue) -> Result<Vec<Vec<CellValue>>, JsValue> {
     // 验证 data 是否为数组
     if !js_sys::Array::is_array(data) {
         return Err(JsValue::from_str("data 必须是数组"));
@@ -329,7 +525,7 @@ pub(crate) fn parse_js_array_data(data: &JsValue) -> Result<Vec<Vec<String>>, Js
         let mut row_data = Vec::with_capacity(col_count as usize);
         for j in 0..col_count {
             let cell_val = inner_array.get(j);
-            row_data.push(data_export::js_value_to_string(&cell_val));
+            row_data.push(data_export::js_value_to_cell(&cell_val));
         }
 
         result.push(row_data);
@@ -348,12 +544,40 @@ pub(crate) fn parse_js_array_data(data: &JsValue) -> Result<Vec<Vec<String>>, Js
 /// * `options` - 可选的配置对象，包含以下字段：
 ///   - `columns`: 表头配置数组，支持嵌套 children 实现多级表头
 ///   - `filename`: 导出文件名
-///   - `format`: 导出格式（ExportFormat.Csv 或 ExportFormat.Xlsx），默认 Csv
+///   - `format`: 导出格式（ExportFormat.Csv、ExportFormat.Tsv 或 ExportFormat.Xlsx），默认 Csv
 ///   - `progressCallback`: 进度回调函数，接收 0-100 的进度值
 ///   - `indentColumn`: 树形模式下需要缩进的列的 key
 ///   - `childrenKey`: 传入此参数启用树形数据模式，指定子节点字段名
-///   - `withBom`: 是否添加 UTF-8 BOM（仅 CSV 有效）
+///   - `withBom`: 是否添加 UTF-8 BOM（仅 CSV / TSV 有效）
+///   - `csv`: CSV 方言 `{ delimiter?, quote?, quoteStyle?, terminator?, escape? }`（仅 CSV / TSV 有效），
+///     `quoteStyle` 为 `necessary` / `always` / `nonNumeric` / `never`，`terminator` 为 `"\n"` / `"\r\n"` / `"\r"`
 ///   - `strictProgressCallback`: 回调失败是否立刻中断导出（默认 false）
+///   - `dateFormat`: 日期单元格默认数字格式（仅 XLSX 有效）
+///   - `csvDateFormat`: 日期单元格渲染模式（仅 CSV 有效，如 "YYYY/MM/DD"）
+///   - `table`: 转为 Excel 表格（仅 XLSX 有效），`true` 或 `{ style, totalRow, bandedRows, name }`
+///   - `autoFilter`: 是否在最后一行表头上启用自动筛选（仅 XLSX 有效，默认存在表头时启用）
+///   - `templateRows`: 导入模板的可填写行数，列数据验证至少覆盖表头下的这么多行（仅 XLSX 有效）
+///   - `conditionalFormats`: 工作表级条件格式（仅 XLSX 有效），列级规则写在列配置中
+///   - `noteOptions`: 批注外观 `{ author?, width?, height?, visible? }`（仅 XLSX 有效），
+///     批注来自单元格对象的 `note` 与列配置的 `headerNote`
+///   - `csvNoteColumn`: 将批注追加为末尾备注列（仅 CSV 有效，`true` 或列标题）
+///   - `imageOptions`: 图片缩放 `{ fit?: 'cell' | 'row', maxHeight? }`（仅 XLSX 有效），
+///     图片来自单元格对象的 `image`（`Uint8Array` 或 data URL）
+///   - 单元格对象 `{ runs: [{ text, style? }] }` 写为富文本（仅 XLSX 有效，CSV 输出拼接后的文本）
+///   - `autoWidth`: 按内容自动计算列宽（仅 XLSX 有效），`true` 或 `{ min, max, sampleRows }`，
+///     列配置中的 `autoWidth: true` 只作用于该列
+///   - `outline`: 树形数据写为 Excel 行分组（仅 XLSX 有效，需配合 `childrenKey`），
+///     `true` 或 `{ collapsed?, summaryBelow? }`，缩进列改用单元格缩进格式
+///   - `rowStyle`: 行级样式（仅 XLSX 有效），`{ zebra?: { odd?, even? }, rules?: [{ when: { key, equals }, style?, height? }], height? }`
+///   - `properties`: 文档属性（仅 XLSX 有效），`{ title?, subject?, author?, manager?, company?, category?,
+///     keywords?, comment?, status?, hyperlinkBase?, created?, custom? }`，`custom` 为自定义属性
+///     `{ 名称: string | number | boolean | Date }`
+///   - `protection`: 工作表保护（仅 XLSX 有效），`true` 或 `{ password?, allowSort?, allowFilter?, allowFormatColumns?, … }`，
+///     列配置 `locked: false` 的列整列可编辑
+///   - `workbookProtection`: 工作簿结构保护（仅 XLSX 有效），`true` 或 `{ password? }`
+///   - `print`: 打印设置（仅 XLSX 有效），`{ landscape?, paper?, margins?, fitToPages?, scale?, centerHorizontally?,
+///     centerVertically?, gridlines?, repeatHeaderRows?, header?, footer? }`，表头行默认在每页顶端重复
+///   - `tabColor`: 工作表标签颜色（仅 XLSX 有效）；`zoom`: 显示缩放比例（仅 XLSX 有效，10–400）
 ///
 /// # 返回值
 /// * `Ok(())` - 导出成功
@@ -408,6 +632,7 @@ pub fn export_data(data: JsValue, options: Option<JsValue>) -> Result<(), JsValu
 }
 
 /// 导出数据配置项（从 options 对象解析后的结果）
+#[derive(Default)]
 pub(crate) struct ExportDataOptions {
     pub(crate) columns: Option<JsValue>,
     pub(crate) filename: Option<String>,
@@ -418,14 +643,26 @@ pub(crate) struct ExportDataOptions {
     pub(crate) with_bom: bool,
     /// 是否启用严格进度回调模式
     pub(crate) strict_progress: bool,
-    /// 冻结行数（XLSX 有效，None 表示自动根据表头行数）
-    pub(crate) freeze_rows: Option<u32>,
-    /// 冻结列数（XLSX 有效，默认 0）
-    pub(crate) freeze_cols: Option<u16>,
     /// 表头全局样式（XLSX 有效）
     pub(crate) header_style: Option<style::CellStyle>,
     /// 数据行全局样式（XLSX 有效）
     pub(crate) cell_style: Option<style::CellStyle>,
+    /// 日期单元格默认数字格式（XLSX 有效，如 "yyyy/mm/dd"）
+    pub(crate) date_format: Option<String>,
+    /// 日期单元格渲染模式（CSV 有效，如 "YYYY/MM/DD HH:mm"）
+    pub(crate) csv_date_format: Option<String>,
+    /// 工作表级选项（XLSX 有效）
+    pub(crate) sheet_options: sheet_options::SheetOptions,
+    /// 批注备注列标题（CSV 有效，None 表示忽略批注）
+    pub(crate) csv_note_column: Option<String>,
+    /// 行级样式：斑马纹、按字段取值匹配的规则与行高（XLSX 有效）
+    pub(crate) row_style: row_style::RowStyleOptions,
+    /// 树形数据写为 Excel 行分组（XLSX 有效，需配合 childrenKey）
+    pub(crate) outline: Option<outline::OutlineOptions>,
+    /// 工作簿级选项：文档属性（XLSX 有效）
+    pub(crate) workbook_options: workbook_options::WorkbookOptions,
+    /// CSV 方言（CSV / TSV 有效）
+    pub(crate) csv_dialect: csv_dialect::CsvDialect,
 }
 
 /// 从 options JsValue 对象中解析 export_data 的配置项
@@ -434,22 +671,7 @@ pub(crate) fn parse_export_data_options(
 ) -> Result<ExportDataOptions, JsValue> {
     let options = match options {
         Some(ref opt) if !opt.is_null() && !opt.is_undefined() => opt,
-        _ => {
-            return Ok(ExportDataOptions {
-                columns: None,
-                filename: None,
-                format: ExportFormat::default(),
-                progress_callback: None,
-                indent_column: None,
-                children_key: None,
-                with_bom: false,
-                strict_progress: false,
-                freeze_rows: None,
-                freeze_cols: None,
-                header_style: None,
-                cell_style: None,
-            });
-        }
+        _ => return Ok(ExportDataOptions::default()),
     };
 
     // 解析 columns
@@ -462,32 +684,8 @@ pub(crate) fn parse_export_data_options(
         .ok()
         .and_then(|v| v.as_string());
 
-    // 解析 format（ExportFormat 在 wasm_bindgen 中编码为数字：0 = Csv, 1 = Xlsx）
-    // 严格校验：仅接受 0 (Csv) 和 1 (Xlsx)，其他值返回明确错误
-    let format_val = js_sys::Reflect::get(options, &JsValue::from_str("format"))
-        .ok()
-        .filter(|v| !v.is_undefined() && !v.is_null());
-
-    let format = match format_val {
-        Some(v) => {
-            let n = v.as_f64().ok_or_else(|| {
-                JsValue::from_str(
-                    "format 参数类型错误：期望数字（ExportFormat.Csv = 0, ExportFormat.Xlsx = 1）",
-                )
-            })?;
-            match n as u32 {
-                0 => ExportFormat::Csv,
-                1 => ExportFormat::Xlsx,
-                other => {
-                    return Err(JsValue::from_str(&format!(
-                        "format 参数值非法：{}。仅支持 ExportFormat.Csv (0) 和 ExportFormat.Xlsx (1)",
-                        other
-                    )));
-                }
-            }
-        }
-        None => ExportFormat::default(),
-    };
+    // 解析 format
+    let format = parse_export_format(options)?;
 
     // 解析 progressCallback
     let progress_callback = js_sys::Reflect::get(options, &JsValue::from_str("progressCallback"))
@@ -518,20 +716,6 @@ pub(crate) fn parse_export_data_options(
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
-    // 解析 freezeRows（校验有限非负值）
-    let freeze_rows = js_sys::Reflect::get(options, &JsValue::from_str("freezeRows"))
-        .ok()
-        .and_then(|v| v.as_f64())
-        .filter(|n| n.is_finite() && *n >= 0.0)
-        .map(|n| n as u32);
-
-    // 解析 freezeCols（校验有限非负值）
-    let freeze_cols = js_sys::Reflect::get(options, &JsValue::from_str("freezeCols"))
-        .ok()
-        .and_then(|v| v.as_f64())
-        .filter(|n| n.is_finite() && *n >= 0.0)
-        .map(|n| n as u16);
-
     // 解析 headerStyle（全局表头样式）
     let header_style = js_sys::Reflect::get(options, &JsValue::from_str("headerStyle"))
         .ok()
@@ -542,6 +726,40 @@ pub(crate) fn parse_export_data_options(
         .ok()
         .and_then(|v| style::parse_cell_style(&v));
 
+    // 解析 dateFormat（XLSX 日期数字格式）
+    let date_format = js_sys::Reflect::get(options, &JsValue::from_str("dateFormat"))
+        .ok()
+        .and_then(|v| v.as_string())
+        .filter(|s| !s.is_empty());
+
+    // 解析 csvDateFormat（CSV 日期渲染模式）
+    let csv_date_format = js_sys::Reflect::get(options, &JsValue::from_str("csvDateFormat"))
+        .ok()
+        .and_then(|v| v.as_string())
+        .filter(|s| !s.is_empty());
+
+    // 解析工作表级选项（table、freezeRows / freezeCols 等）
+    let sheet_options = sheet_options::parse_sheet_options(options)?;
+
+    // 解析 csvNoteColumn（CSV 批注备注列）
+    let csv_note_column = cell_note::parse_csv_note_column(options);
+
+    // 解析 rowStyle（行级样式）
+    let row_style = js_sys::Reflect::get(options, &JsValue::from_str("rowStyle"))
+        .map_err(|e| JsValue::from_str(&format!("读取 rowStyle 配置失败: {:?}", e)))?;
+    let row_style = row_style::parse_row_style(&row_style)?;
+
+    // 解析 outline（树形大纲）
+    let outline = js_sys::Reflect::get(options, &JsValue::from_str("outline"))
+        .map_err(|e| JsValue::from_str(&format!("读取 outline 配置失败: {:?}", e)))?;
+    let outline = outline::parse_outline(&outline)?;
+
+    // 解析工作簿级选项（properties 等）
+    let workbook_options = workbook_options::parse_workbook_options(options)?;
+
+    // 解析 csv（CSV 方言，TSV 默认以制表符分隔）
+    let csv_dialect = csv_dialect::parse_csv_dialect(options, format)?;
+
     Ok(ExportDataOptions {
         columns,
         filename,
@@ -551,24 +769,53 @@ pub(crate) fn parse_export_data_options(
         children_key,
         with_bom,
         strict_progress,
-        freeze_rows,
-        freeze_cols,
         header_style,
         cell_style,
+        date_format,
+        csv_date_format,
+        sheet_options,
+        csv_note_column,
+        row_style,
+        outline,
+        workbook_options,
+        csv_dialect,
     })
 }
 
+/// 解析 options 中的 `format` 字段
+///
+/// ExportFormat 在 wasm_bindgen 中编码为数字：0 = Csv, 1 = Xlsx, 2 = Tsv，
+/// 严格校验，其他值返回明确错误
+pub(crate) fn parse_export_format(options: &JsValue) -> Result<ExportFormat, JsValue> {
+    let format_val = js_sys::Reflect::get(options, &JsValue::from_str("format"))
+        .ok()
+        .filter(|v| !v.is_undefined() && !v.is_null());
+
+    let Some(v) = format_val else {
+        return Ok(ExportFormat::default());
+    };
+    let n = v.as_f64().ok_or_else(|| {
+        JsValue::from_str(
+            "format 参数类型错误：期望数字（ExportFormat.Csv = 0, ExportFormat.Xlsx = 1, ExportFormat.Tsv = 2）",
+        )
+    })?;
+    match n as u32 {
+        0 => Ok(ExportFormat::Csv),
+        1 => Ok(ExportFormat::Xlsx),
+        2 => Ok(ExportFormat::Tsv),
+        other => Err(JsValue::from_str(&format!(
+            "format 参数值非法：{}。仅支持 ExportFormat.Csv (0)、ExportFormat.Xlsx (1) 和 ExportFormat.Tsv (2)",
+            other
+        ))),
+    }
+}
+
 /// export_data 的内部实现
 pub(crate) fn export_data_impl(data: JsValue, opts: ExportDataOptions) -> Result<(), JsValue> {
     let sp = opts.strict_progress;
 
-    // 构建冻结窗格配置：只要用户显式传了任一参数，就使用用户配置
-    let freeze_pane = match (opts.freeze_rows, opts.freeze_cols) {
-        (Some(r), Some(c)) => Some((r, c)),
-        (Some(r), None) => Some((r, 0)),
-        (None, Some(c)) => Some((0, c)),
-        (None, None) => None, // 自动根据 header_row_count 决定
-    };
+    // 冻结窗格由 sheet_options 统一解析（None 时自动根据 header_row_count 决定）
+    let freeze_pane = opts.sheet_options.freeze_pane;
 
     // 全局样式配置
     let global_header_style = opts.header_style;
@@ -580,20 +827,34 @@ pub(crate) fn export_data_impl(data: JsValue, opts: ExportDataOptions) -> Result
     if let Some(cols) = opts.columns {
         // 判断是否为树形数据模式（提供了 children_key）
         if let Some(ck) = opts.children_key {
-            let mut table_data =
-                build_table_data_from_tree(&cols, &data, opts.indent_column.as_deref(), &ck)?;
+            let mut table_data = build_table_data_from_tree(
+                &cols,
+                &data,
+                opts.indent_column.as_deref(),
+                &ck,
+                &opts.row_style,
+                opts.outline.filter(|_| opts.format == ExportFormat::Xlsx),
+            )?;
             merge_global_styles(
                 &mut table_data,
                 global_header_style.clone(),
                 global_cell_style.clone(),
             );
+            apply_date_format(&mut table_data, opts.date_format);
+            table_data.sheet_options = opts.sheet_options;
             return match opts.format {
-                ExportFormat::Csv => export_as_csv(
-                    table_data.rows,
+                ExportFormat::Csv | ExportFormat::Tsv => export_as_csv(
+                    table_data_to_csv_rows(
+                        table_data,
+                        opts.csv_date_format.as_deref(),
+                        opts.csv_note_column.as_deref(),
+                    ),
                     opts.filename,
                     opts.progress_callback,
                     opts.with_bom,
                     sp,
+                    opts.format,
+                    &opts.csv_dialect,
                 ),
                 ExportFormat::Xlsx => export_as_xlsx(
                     table_data,
@@ -601,23 +862,32 @@ pub(crate) fn export_data_impl(data: JsValue, opts: ExportDataOptions) -> Result
                     opts.progress_callback,
                     sp,
                     freeze_pane,
+                    &opts.workbook_options,
                 ),
             };
         }
 
         // 有 columns 配置：使用 data_export 模块解析嵌套表头
-        let mut table_data = build_table_data_from_array(&cols, &data)?;
+        let mut table_data = build_table_data_from_array(&cols, &data, &opts.row_style)?;
         merge_global_styles(&mut table_data, global_header_style, global_cell_style);
+        apply_date_format(&mut table_data, opts.date_format);
+        table_data.sheet_options = opts.sheet_options;
 
         return match opts.format {
-            ExportFormat::Csv => {
+            ExportFormat::Csv | ExportFormat::Tsv => {
                 // CSV 不支持合并单元格，直接用行数据
                 export_as_csv(
-                    table_data.rows,
+                    table_data_to_csv_rows(
+                        table_data,
+                        opts.csv_date_format.as_deref(),
+                        opts.csv_note_column.as_deref(),
+                    ),
                     opts.filename,
                     opts.progress_callback,
                     opts.with_bom,
                     sp,
+                    opts.format,
+                    &opts.csv_dialect,
                 )
             }
             ExportFormat::Xlsx => {
@@ -628,6 +898,7 @@ pub(crate) fn export_data_impl(data: JsValue, opts: ExportDataOptions) -> Result
                     opts.progress_callback,
                     sp,
                     freeze_pane,
+                    &opts.workbook_options,
                 )
             }
         };
@@ -636,42 +907,73 @@ pub(crate) fn export_data_impl(data: JsValue, opts: ExportDataOptions) -> Result
     // 无 columns，按二维数组处理
     let rows = parse_js_array_data(&data)?;
     match opts.format {
-        ExportFormat::Csv => export_as_csv(
-            rows,
+        ExportFormat::Csv | ExportFormat::Tsv => export_as_csv(
+            cells_to_text_rows(rows, opts.csv_date_format.as_deref()),
             opts.filename,
             opts.progress_callback,
             opts.with_bom,
             sp,
+            opts.format,
+            &opts.csv_dialect,
         ),
         ExportFormat::Xlsx => {
-            // 构建全局样式表
-            let style_sheet = if global_header_style.is_some() || global_cell_style.is_some() {
-                Some(style::StyleSheet {
-                    header_style: global_header_style,
-                    data_style: global_cell_style,
-                    ..Default::default()
-                })
-            } else {
-                None
-            };
-
-            let table_data = table_extractor::TableData {
+            let mut table_data = table_extractor::TableData {
                 rows,
-                merge_ranges: Vec::new(),
-                header_row_count: 0,
-                style_sheet,
+                ..Default::default()
             };
+            // 构建全局样式表
+            merge_global_styles(&mut table_data, global_header_style, global_cell_style);
+            apply_date_format(&mut table_data, opts.date_format);
+            table_data.sheet_options = opts.sheet_options;
             export_as_xlsx(
                 table_data,
                 opts.filename,
                 opts.progress_callback,
                 sp,
                 freeze_pane,
+                &opts.workbook_options,
             )
         }
     }
 }
 
+/// 按 export_data 的配置构建表格数据，并注入全局样式、日期格式与工作表级选项
+///
+/// 提供 columns 时按对象数组（有 childrenKey 时按树形数据）解析，否则按二维数组解析
+fn build_data_table(
+    data: &JsValue,
+    opts: &ExportDataOptions,
+) -> Result<table_extractor::TableData, JsValue> {
+    let mut table_data = match (&opts.columns, &opts.children_key) {
+        // 树形数据模式
+        (Some(cols), Some(ck)) => build_table_data_from_tree(
+            cols,
+            data,
+            opts.indent_column.as_deref(),
+            ck,
+            &opts.row_style,
+            opts.outline.filter(|_| opts.format == ExportFormat::Xlsx),
+        )?,
+        // 对象数组 + columns 配置
+        (Some(cols), None) => build_table_data_from_array(cols, data, &opts.row_style)?,
+        // 二维数组模式
+        (None, _) => table_extractor::TableData {
+            rows: parse_js_array_data(data)?,
+            ..Default::default()
+        },
+    };
+
+    merge_global_styles(
+        &mut table_data,
+        opts.header_style.clone(),
+        opts.cell_style.clone(),
+    );
+    apply_date_format(&mut table_data, opts.date_format.clone());
+    table_data.sheet_options = opts.sheet_options.clone();
+
+    Ok(table_data)
+}
+
 /// 将全局样式（headerStyle / cellStyle）合并到 TableData 的 StyleSheet 中
 ///
 /// 如果 TableData 已有 StyleSheet（来自列配置），将全局样式注入为 header_style / data_style；
@@ -706,6 +1008,34 @@ fn merge_global_styles(
     }
 }
 
+/// 将 TableData 转为 CSV 文本行，`note_column` 不为 None 时追加批注备注列
+fn table_data_to_csv_rows(
+    table_data: table_extractor::TableData,
+    date_format: Option<&str>,
+    note_column: Option<&str>,
+) -> Vec<Vec<String>> {
+    let mut rows = cells_to_text_rows(table_data.rows, date_format);
+    if let Some(title) = note_column {
+        cell_note::append_note_column(
+            &mut rows,
+            &table_data.notes,
+            table_data.header_row_count,
+            title,
+        );
+    }
+    rows
+}
+
+/// 将日期默认数字格式（dateFormat）注入 TableData 的 StyleSheet
+fn apply_date_format(table_data: &mut table_extractor::TableData, date_format: Option<String>) {
+    if date_format.is_some() {
+        table_data
+            .style_sheet
+            .get_or_insert_with(Default::default)
+            .date_format = date_format;
+    }
+}
+
 /// 从 JavaScript 数组生成文件字节（不触发下载，供 Web Worker 使用）
 ///
 /// 与 `export_data` 功能相同，但不创建 Blob 和下载链接，
@@ -740,56 +1070,30 @@ pub fn generate_data_bytes(
 ) -> Result<js_sys::Uint8Array, JsValue> {
     let opts = parse_export_data_options(options)?;
     let sp = opts.strict_progress;
-    let global_header_style = opts.header_style;
-    let global_cell_style = opts.cell_style;
-
-    // 根据是否提供 columns 决定处理方式
-    let (mut table_data, format, with_bom) = if let Some(cols) = opts.columns {
-        if let Some(ck) = opts.children_key {
-            // 树形数据模式
-            let td = build_table_data_from_tree(&cols, &data, opts.indent_column.as_deref(), &ck)?;
-            (td, opts.format, opts.with_bom)
-        } else {
-            // 对象数组 + columns 配置
-            let td = build_table_data_from_array(&cols, &data)?;
-            (td, opts.format, opts.with_bom)
-        }
-    } else {
-        // 二维数组模式（样式由下方 merge_global_styles 统一注入）
-        let rows = parse_js_array_data(&data)?;
-        let td = table_extractor::TableData {
-            rows,
-            merge_ranges: Vec::new(),
-            header_row_count: 0,
-            style_sheet: None,
-        };
-        (td, opts.format, opts.with_bom)
-    };
+    let table_data = build_data_table(&data, &opts)?;
 
-    // 合并全局样式到 table_data
-    merge_global_styles(&mut table_data, global_header_style, global_cell_style);
-
-    // 构建冻结窗格配置
-    let freeze_pane = match (opts.freeze_rows, opts.freeze_cols) {
-        (Some(r), Some(c)) => Some((r, c)),
-        (Some(r), None) => Some((r, 0)),
-        (None, Some(c)) => Some((0, c)),
-        (None, None) => None,
-    };
+    // 冻结窗格由 sheet_options 统一解析
+    let freeze_pane = table_data.sheet_options.freeze_pane;
 
     // 根据格式生成字节
-    let bytes = match format {
-        ExportFormat::Csv => generate_csv_bytes(
-            table_data.rows,
+    let bytes = match opts.format {
+        ExportFormat::Csv | ExportFormat::Tsv => generate_csv_bytes(
+            table_data_to_csv_rows(
+                table_data,
+                opts.csv_date_format.as_deref(),
+                opts.csv_note_column.as_deref(),
+            ),
             opts.progress_callback.as_ref(),
             sp,
-            with_bom,
+            opts.with_bom,
+            &opts.csv_dialect,
         )?,
         ExportFormat::Xlsx => generate_xlsx_bytes(
             &table_data,
             opts.progress_callback.as_ref(),
             sp,
             freeze_pane,
+            &opts.workbook_options,
         )?,
     };
 
@@ -0,0 +1,206 @@
+/// 树形大纲模块
+///
+/// `outline` 模式下将树形数据的层级写为 Excel 行分组（大纲级别），支持折叠与汇总行位置，
+/// 缩进列改用单元格缩进格式而非空格前缀，仅 XLSX 导出时使用
+use super::data_export::get_object_property;
+use super::table_extractor::TableData;
+use rust_xlsxwriter::Worksheet;
+use wasm_bindgen::prelude::*;
+
+/// Excel 支持的最大大纲级别（更深的层级并入第 7 级）
+const EXCEL_MAX_OUTLINE_LEVEL: usize = 7;
+
+/// 大纲配置（`outline`）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutlineOptions {
+    /// 是否折叠全部分组（仅显示根节点行）
+    pub collapsed: bool,
+    /// 汇总行（父节点行）是否位于明细行（子节点行）下方，默认位于上方
+    pub summary_below: bool,
+}
+
+/// 解析 `outline` 配置
+///
+/// - `true`：展开全部分组，父节点行位于子节点行上方
+/// - 对象：`{ collapsed?, summaryBelow? }`
+/// - `false` / `null` / `undefined`：不启用
+pub(crate) fn parse_outline(val: &JsValue) -> Result<Option<OutlineOptions>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(enabled) = val.as_bool() {
+        return Ok(enabled.then(OutlineOptions::default));
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("outline 配置必须是布尔值或对象"));
+    }
+
+    Ok(Some(OutlineOptions {
+        collapsed: get_object_property(val, "collapsed")?
+            .as_bool()
+            .unwrap_or(false),
+        summary_below: get_object_property(val, "summaryBelow")?
+            .as_bool()
+            .unwrap_or(false),
+    }))
+}
+
+/// 数据行的大纲信息
+#[derive(Debug, Clone, Default)]
+pub struct RowOutline {
+    /// 大纲配置
+    pub options: OutlineOptions,
+    /// 第一条数据行的绝对行号（即表头行数）
+    pub first_row: u32,
+    /// 每条数据行的层级深度（根节点为 0）
+    pub depths: Vec<usize>,
+}
+
+/// 将父节点行移到其子孙行之后，返回新顺序下每个位置对应的原行下标
+///
+/// `depths` 为先序遍历（父节点在前）得到的层级深度
+pub(crate) fn summary_below_order(depths: &[usize]) -> Vec<usize> {
+    let mut order = Vec::with_capacity(depths.len());
+    // 尚未输出的祖先行
+    let mut pending: Vec<usize> = Vec::new();
+
+    for (i, &depth) in depths.iter().enumerate() {
+        while let Some(&parent) = pending.last() {
+            if depths[parent] < depth {
+                break;
+            }
+            order.push(parent);
+            pending.pop();
+        }
+        if depths.get(i + 1).is_some_and(|&next| next > depth) {
+            pending.push(i);
+        } else {
+            order.push(i);
+        }
+    }
+    order.extend(pending.into_iter().rev());
+    order
+}
+
+/// 按层级计算行分组：(级别, 起始下标, 结束下标)
+///
+/// 第 L 级分组为深度 ≥ L 的连续行，嵌套分组由多次分组叠加出大纲级别
+fn outline_groups(depths: &[usize]) -> Vec<(usize, usize, usize)> {
+    let max_level = depths
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .min(EXCEL_MAX_OUTLINE_LEVEL);
+
+    let mut groups = Vec::new();
+    for level in 1..=max_level {
+        let mut start = None;
+        for (i, &depth) in depths.iter().enumerate() {
+            match (depth >= level, start) {
+                (true, None) => start = Some(i),
+                (false, Some(first)) => {
+                    groups.push((level, first, i - 1));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(first) = start {
+            groups.push((level, first, depths.len() - 1));
+        }
+    }
+    groups
+}
+
+/// 将树形层级写为工作表行分组
+pub(crate) fn apply_outline(
+    worksheet: &mut Worksheet,
+    table_data: &TableData,
+) -> Result<(), JsValue> {
+    let Some(ref outline) = table_data.outline else {
+        return Ok(());
+    };
+
+    worksheet.group_symbols_above(!outline.options.summary_below);
+    for (_, first, last) in outline_groups(&outline.depths) {
+        let first_row = outline.first_row + first as u32;
+        let last_row = outline.first_row + last as u32;
+        let result = if outline.options.collapsed {
+            worksheet.group_rows_collapsed(first_row, last_row)
+        } else {
+            worksheet.group_rows(first_row, last_row)
+        };
+        result.map_err(|e| JsValue::from_str(&format!("设置行分组失败: {}", e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::table_extractor::CellValue;
+    use crate::core::test_support::sheet_xml;
+
+    #[test]
+    fn test_summary_below_order() {
+        // 先序：P, A, a1, B, Q
+        assert_eq!(summary_below_order(&[0, 1, 2, 1, 0]), vec![2, 1, 3, 0, 4]);
+        // 末尾的父节点按由深到浅输出
+        assert_eq!(summary_below_order(&[0, 1, 2]), vec![2, 1, 0]);
+        assert_eq!(summary_below_order(&[0, 0]), vec![0, 1]);
+        assert!(summary_below_order(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_outline_groups() {
+        // P, A, a1, B, Q, b
+        let groups = outline_groups(&[0, 1, 2, 1, 0, 1]);
+        assert_eq!(groups, vec![(1, 1, 3), (1, 5, 5), (2, 2, 2)]);
+
+        // 超过 7 级的层级并入第 7 级
+        let depths: Vec<usize> = (0..10).collect();
+        let groups = outline_groups(&depths);
+        assert_eq!(groups.len(), 7);
+        assert_eq!(groups[6], (7, 7, 9));
+        assert!(outline_groups(&[0, 0]).is_empty());
+    }
+
+    #[test]
+    fn test_write_sheet_outline() {
+        let table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("名称")],
+                vec![CellValue::from("总部")],
+                vec![CellValue::from("研发部")],
+                vec![CellValue::from("前端组")],
+                vec![CellValue::from("分部")],
+            ],
+            header_row_count: 1,
+            outline: Some(RowOutline {
+                options: OutlineOptions {
+                    collapsed: true,
+                    summary_below: false,
+                },
+                first_row: 1,
+                depths: vec![0, 1, 2, 0],
+            }),
+            ..Default::default()
+        };
+
+        let sheet = sheet_xml(&table_data);
+        assert_eq!(
+            sheet.find("outlinePr").unwrap().attr("summaryBelow"),
+            Some("0")
+        );
+        let row = |r: &str| {
+            let row = sheet.find_by("row", "r", r).unwrap();
+            (row.attr("hidden"), row.attr("outlineLevel"))
+        };
+        // 根节点行保持可见，折叠的子节点行隐藏
+        assert_eq!(row("2"), (None, None));
+        assert_eq!(row("3"), (Some("1"), Some("1")));
+        assert_eq!(row("4"), (Some("1"), Some("2")));
+        assert_eq!(row("5"), (None, None));
+    }
+}
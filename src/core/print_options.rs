@@ -0,0 +1,498 @@
+/// 打印设置模块
+///
+/// 解析 `print` 配置（纸张方向与大小、页边距、缩放、居中、网格线、页眉页脚）并写入工作表页面设置；
+/// 表头行默认设为打印标题行，每页顶端重复，仅 XLSX 导出时使用
+use super::data_export::get_object_property;
+use super::table_extractor::TableData;
+use rust_xlsxwriter::Worksheet;
+use wasm_bindgen::prelude::*;
+
+/// 常用纸张名称与 Excel 纸张代码
+const PAPER_SIZES: [(&str, u8); 8] = [
+    ("letter", 1),
+    ("tabloid", 3),
+    ("legal", 5),
+    ("a3", 8),
+    ("a4", 9),
+    ("a5", 11),
+    ("b4", 12),
+    ("b5", 13),
+];
+
+/// Excel 支持的最大纸张代码
+const MAX_PAPER_CODE: f64 = 118.0;
+
+/// 页眉页脚的最大长度（字符）
+const MAX_HEADER_FOOTER_LEN: usize = 255;
+
+/// 页边距（英寸，为 None 时使用 Excel 默认值）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PrintMargins {
+    pub left: Option<f64>,
+    pub right: Option<f64>,
+    pub top: Option<f64>,
+    pub bottom: Option<f64>,
+    pub header: Option<f64>,
+    pub footer: Option<f64>,
+}
+
+/// 打印设置（`print`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintOptions {
+    /// 是否横向打印（默认纵向）
+    pub landscape: bool,
+    /// Excel 纸张代码（为 None 时使用打印机默认纸张）
+    pub paper_size: Option<u8>,
+    /// 页边距
+    pub margins: PrintMargins,
+    /// 缩放到 (宽, 高) 页，0 表示该方向不限页数
+    pub fit_to_pages: Option<(u16, u16)>,
+    /// 缩放百分比（10–400，与 fit_to_pages 互斥）
+    pub scale: Option<u16>,
+    /// 是否水平居中
+    pub center_horizontally: bool,
+    /// 是否垂直居中
+    pub center_vertically: bool,
+    /// 是否打印网格线
+    pub gridlines: bool,
+    /// 是否将表头行设为每页顶端重复的打印标题行（默认 true）
+    pub repeat_header_rows: bool,
+    /// 页眉（支持 `&P` 页码、`&N` 总页数、`&D` 日期、`&T` 时间、`&L` / `&C` / `&R` 分区等 Excel 代码）
+    pub header: Option<String>,
+    /// 页脚（语法同页眉）
+    pub footer: Option<String>,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            landscape: false,
+            paper_size: None,
+            margins: PrintMargins::default(),
+            fit_to_pages: None,
+            scale: None,
+            center_horizontally: false,
+            center_vertically: false,
+            gridlines: false,
+            repeat_header_rows: true,
+            header: None,
+            footer: None,
+        }
+    }
+}
+
+/// 解析 `print` 配置
+///
+/// 对象：`{ landscape?, paper?, margins?, fitToPages?, scale?, centerHorizontally?, centerVertically?,
+/// gridlines?, repeatHeaderRows?, header?, footer? }`；`null` / `undefined` 时使用默认设置
+///
+/// # 示例
+/// ```javascript
+/// {
+///   print: {
+///     landscape: true,
+///     paper: 'A3',
+///     fitToPages: { width: 1 },
+///     footer: '&C第 &P 页 / 共 &N 页&R&D',
+///   },
+/// }
+/// ```
+pub(crate) fn parse_print_options(val: &JsValue) -> Result<Option<PrintOptions>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("print 配置必须是对象"));
+    }
+
+    let flag = |key: &str, default: bool| -> Result<bool, JsValue> {
+        Ok(get_object_property(val, key)?.as_bool().unwrap_or(default))
+    };
+
+    let options = PrintOptions {
+        landscape: flag("landscape", false)?,
+        paper_size: parse_paper_size(&get_object_property(val, "paper")?)?,
+        margins: parse_margins(&get_object_property(val, "margins")?)?,
+        fit_to_pages: parse_fit_to_pages(&get_object_property(val, "fitToPages")?)?,
+        scale: get_object_property(val, "scale")?
+            .as_f64()
+            .map(check_scale)
+            .transpose()
+            .map_err(|e| JsValue::from_str(&e))?,
+        center_horizontally: flag("centerHorizontally", false)?,
+        center_vertically: flag("centerVertically", false)?,
+        gridlines: flag("gridlines", false)?,
+        repeat_header_rows: flag("repeatHeaderRows", true)?,
+        header: parse_header_footer(&get_object_property(val, "header")?, "header")?,
+        footer: parse_header_footer(&get_object_property(val, "footer")?, "footer")?,
+    };
+
+    check_print_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    Ok(Some(options))
+}
+
+/// 校验缩放百分比（10–400 的整数）
+fn check_scale(n: f64) -> Result<u16, String> {
+    if n.fract() == 0.0 && (10.0..=400.0).contains(&n) {
+        Ok(n as u16)
+    } else {
+        Err(format!("print.scale 无效: {}，应为 10–400 的整数", n))
+    }
+}
+
+/// 校验相互冲突的设置
+fn check_print_options(options: &PrintOptions) -> Result<(), String> {
+    if options.scale.is_some() && options.fit_to_pages.is_some() {
+        return Err("print.scale 与 print.fitToPages 不能同时设置".to_string());
+    }
+    Ok(())
+}
+
+/// 解析纸张：名称（`A4`、`Letter` 等，不区分大小写）或 Excel 纸张代码
+fn parse_paper_size(val: &JsValue) -> Result<Option<u8>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    let code = match val.as_f64() {
+        Some(n) => paper_code(n),
+        None => paper_by_name(&val.as_string().unwrap_or_default()),
+    };
+    code.map(Some).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 校验 Excel 纸张代码
+fn paper_code(n: f64) -> Result<u8, String> {
+    if n.fract() == 0.0 && (1.0..=MAX_PAPER_CODE).contains(&n) {
+        Ok(n as u8)
+    } else {
+        Err(format!(
+            "print.paper 无效: {}，纸张代码应为 1–{} 的整数",
+            n, MAX_PAPER_CODE
+        ))
+    }
+}
+
+/// 按纸张名称查找纸张代码（不区分大小写）
+fn paper_by_name(name: &str) -> Result<u8, String> {
+    PAPER_SIZES
+        .iter()
+        .find(|(paper, _)| paper.eq_ignore_ascii_case(name.trim()))
+        .map(|&(_, code)| code)
+        .ok_or_else(|| {
+            format!(
+                "print.paper 无效: '{}'，应为 A3 / A4 / A5 / B4 / B5 / Letter / Legal / Tabloid 或纸张代码",
+                name
+            )
+        })
+}
+
+/// 解析页边距 `{ left?, right?, top?, bottom?, header?, footer? }`（英寸）
+fn parse_margins(val: &JsValue) -> Result<PrintMargins, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(PrintMargins::default());
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("print.margins 必须是对象"));
+    }
+
+    let margin = |key: &str| -> Result<Option<f64>, JsValue> {
+        get_object_property(val, key)?
+            .as_f64()
+            .map(|n| check_margin(key, n))
+            .transpose()
+            .map_err(|e| JsValue::from_str(&e))
+    };
+
+    Ok(PrintMargins {
+        left: margin("left")?,
+        right: margin("right")?,
+        top: margin("top")?,
+        bottom: margin("bottom")?,
+        header: margin("header")?,
+        footer: margin("footer")?,
+    })
+}
+
+/// 校验页边距（非负有限数，英寸）
+fn check_margin(key: &str, n: f64) -> Result<f64, String> {
+    if n.is_finite() && n >= 0.0 {
+        Ok(n)
+    } else {
+        Err(format!(
+            "print.margins.{} 无效: {}，应为非负数（英寸）",
+            key, n
+        ))
+    }
+}
+
+/// 解析缩放页数：数字 N 表示宽度缩放到 N 页（高度不限），或 `{ width?, height? }`（默认 1 / 0）
+fn parse_fit_to_pages(val: &JsValue) -> Result<Option<(u16, u16)>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+
+    let pages = |v: Option<f64>, key: &str, default: u16| -> Result<u16, JsValue> {
+        v.map_or(Ok(default), |n| page_count(key, n))
+            .map_err(|e| JsValue::from_str(&e))
+    };
+
+    if let Some(n) = val.as_f64() {
+        return Ok(Some((pages(Some(n), "", 1)?, 0)));
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("print.fitToPages 必须是数字或对象"));
+    }
+    Ok(Some((
+        pages(get_object_property(val, "width")?.as_f64(), ".width", 1)?,
+        pages(get_object_property(val, "height")?.as_f64(), ".height", 0)?,
+    )))
+}
+
+/// 校验缩放页数（非负整数，`key` 为错误提示中的字段后缀）
+fn page_count(key: &str, n: f64) -> Result<u16, String> {
+    if n.fract() == 0.0 && (0.0..=f64::from(u16::MAX)).contains(&n) {
+        Ok(n as u16)
+    } else {
+        Err(format!("print.fitToPages{} 无效: {}，应为非负整数", key, n))
+    }
+}
+
+/// 解析页眉 / 页脚文本
+fn parse_header_footer(val: &JsValue, key: &str) -> Result<Option<String>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    let text = val
+        .as_string()
+        .ok_or_else(|| JsValue::from_str(&format!("print.{} 必须是字符串", key)))?;
+    check_header_footer(key, &text).map_err(|e| JsValue::from_str(&e))?;
+    Ok(Some(text))
+}
+
+/// 校验页眉 / 页脚长度
+fn check_header_footer(key: &str, text: &str) -> Result<(), String> {
+    if text.chars().count() > MAX_HEADER_FOOTER_LEN {
+        return Err(format!(
+            "print.{} 不能超过 {} 个字符",
+            key, MAX_HEADER_FOOTER_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// 写入页面设置，并将表头行设为打印标题行（未配置 `print` 时同样生效）
+pub(crate) fn apply_print_options(
+    worksheet: &mut Worksheet,
+    table_data: &TableData,
+) -> Result<(), JsValue> {
+    let default_options = PrintOptions::default();
+    let options = table_data
+        .sheet_options
+        .print
+        .as_ref()
+        .unwrap_or(&default_options);
+
+    let header_rows = table_data.header_row_count.min(table_data.rows.len());
+    if options.repeat_header_rows && header_rows > 0 {
+        worksheet
+            .set_repeat_rows(0, header_rows as u32 - 1)
+            .map_err(|e| JsValue::from_str(&format!("设置打印标题行失败: {}", e)))?;
+    }
+
+    if options.landscape {
+        worksheet.set_landscape();
+    }
+    if let Some(paper_size) = options.paper_size {
+        worksheet.set_paper_size(paper_size);
+    }
+
+    // rust_xlsxwriter 以负值表示保持默认页边距
+    let m = options.margins;
+    if m != PrintMargins::default() {
+        let or_default = |v: Option<f64>| v.unwrap_or(-1.0);
+        worksheet.set_margins(
+            or_default(m.left),
+            or_default(m.right),
+            or_default(m.top),
+            or_default(m.bottom),
+            or_default(m.header),
+            or_default(m.footer),
+        );
+    }
+
+    if let Some((width, height)) = options.fit_to_pages {
+        worksheet.set_print_fit_to_pages(width, height);
+    }
+    if let Some(scale) = options.scale {
+        worksheet.set_print_scale(scale);
+    }
+    if options.center_horizontally {
+        worksheet.set_print_center_horizontally(true);
+    }
+    if options.center_vertically {
+        worksheet.set_print_center_vertically(true);
+    }
+    if options.gridlines {
+        worksheet.set_print_gridlines(true);
+    }
+    if let Some(ref header) = options.header {
+        worksheet.set_header(header);
+    }
+    if let Some(ref footer) = options.footer {
+        worksheet.set_footer(footer);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::table_extractor::CellValue;
+    use crate::core::test_support::{generate, part_xml};
+
+    fn two_row_header() -> TableData {
+        TableData {
+            rows: vec![
+                vec![CellValue::from("基本信息"), CellValue::Empty],
+                vec![CellValue::from("姓名"), CellValue::from("部门")],
+                vec![CellValue::from("张三"), CellValue::from("研发部")],
+            ],
+            header_row_count: 2,
+            ..Default::default()
+        }
+    }
+
+    /// 读取工作簿中的打印标题行
+    fn print_titles(bytes: &[u8]) -> Option<String> {
+        part_xml(bytes, "xl/workbook.xml")
+            .find_by("definedName", "name", "_xlnm.Print_Titles")
+            .map(|e| e.text.clone())
+    }
+
+    #[test]
+    fn test_write_print_options() {
+        let mut table_data = two_row_header();
+
+        // 未配置 print 时表头行同样设为打印标题行
+        assert_eq!(
+            print_titles(&generate(&table_data)).as_deref(),
+            Some("Sheet1!$1:$2")
+        );
+
+        table_data.sheet_options.print = Some(PrintOptions {
+            landscape: true,
+            paper_size: Some(8),
+            margins: PrintMargins {
+                left: Some(0.3),
+                ..Default::default()
+            },
+            fit_to_pages: Some((1, 0)),
+            center_horizontally: true,
+            gridlines: true,
+            repeat_header_rows: false,
+            footer: Some("&C第 &P 页 / 共 &N 页&R&D".to_string()),
+            ..Default::default()
+        });
+        let bytes = generate(&table_data);
+        assert_eq!(print_titles(&bytes), None);
+
+        let sheet = part_xml(&bytes, "xl/worksheets/sheet1.xml");
+        let page_setup_pr = sheet.find("pageSetUpPr").unwrap();
+        assert_eq!(page_setup_pr.attr("fitToPage"), Some("1"));
+
+        let print_options = sheet.child("printOptions").unwrap();
+        assert_eq!(print_options.attr("horizontalCentered"), Some("1"));
+        assert_eq!(print_options.attr("verticalCentered"), None);
+        assert_eq!(print_options.attr("gridLines"), Some("1"));
+
+        let margins = sheet.child("pageMargins").unwrap();
+        assert_eq!(margins.attr("left"), Some("0.3"));
+        // 未设置的页边距保持 Excel 默认值
+        assert_eq!(margins.attr("right"), Some("0.7"));
+
+        let page_setup = sheet.child("pageSetup").unwrap();
+        assert_eq!(page_setup.attr("paperSize"), Some("8"));
+        assert_eq!(page_setup.attr("fitToWidth"), None);
+        assert_eq!(page_setup.attr("fitToHeight"), Some("0"));
+        assert_eq!(page_setup.attr("orientation"), Some("landscape"));
+
+        let footer = sheet.find("oddFooter").unwrap();
+        assert_eq!(footer.text, "&C第 &P 页 / 共 &N 页&R&D");
+        assert!(sheet.find("oddHeader").is_none());
+    }
+
+    #[test]
+    fn test_write_print_scale() {
+        let mut table_data = two_row_header();
+        table_data.sheet_options.print = Some(PrintOptions {
+            scale: Some(75),
+            center_vertically: true,
+            ..Default::default()
+        });
+        let bytes = generate(&table_data);
+        let sheet = part_xml(&bytes, "xl/worksheets/sheet1.xml");
+
+        assert_eq!(sheet.child("pageSetup").unwrap().attr("scale"), Some("75"));
+        assert!(sheet.find("pageSetUpPr").is_none());
+        assert_eq!(
+            sheet
+                .child("printOptions")
+                .unwrap()
+                .attr("verticalCentered"),
+            Some("1")
+        );
+        // 默认仍重复表头行
+        assert_eq!(print_titles(&bytes).as_deref(), Some("Sheet1!$1:$2"));
+
+        // 无表头时不设置打印标题行
+        table_data.header_row_count = 0;
+        assert_eq!(print_titles(&generate(&table_data)), None);
+    }
+
+    #[test]
+    fn test_paper_size() {
+        assert_eq!(paper_by_name("A4"), Ok(9));
+        assert_eq!(paper_by_name(" letter "), Ok(1));
+        assert!(paper_by_name("A6").is_err());
+        assert!(paper_by_name("").is_err());
+
+        assert_eq!(paper_code(9.0), Ok(9));
+        assert_eq!(paper_code(MAX_PAPER_CODE), Ok(118));
+        assert!(paper_code(0.0).is_err());
+        assert!(paper_code(119.0).is_err());
+        assert!(paper_code(8.5).is_err());
+    }
+
+    #[test]
+    fn test_check_print_values() {
+        assert_eq!(check_scale(10.0), Ok(10));
+        assert_eq!(check_scale(400.0), Ok(400));
+        assert!(check_scale(9.0).is_err());
+        assert!(check_scale(401.0).is_err());
+        assert!(check_scale(75.5).is_err());
+        assert!(check_scale(f64::NAN).is_err());
+
+        assert_eq!(check_margin("left", 0.0), Ok(0.0));
+        assert!(check_margin("left", -0.1).is_err());
+        assert!(check_margin("top", f64::INFINITY).is_err());
+
+        assert_eq!(page_count("", 0.0), Ok(0));
+        assert_eq!(page_count(".width", 2.0), Ok(2));
+        assert!(page_count(".width", -1.0).is_err());
+        assert!(page_count(".height", 1.5).is_err());
+        assert!(page_count(".height", 65536.0).is_err());
+
+        assert!(check_header_footer("header", &"页".repeat(MAX_HEADER_FOOTER_LEN)).is_ok());
+        let err = check_header_footer("footer", &"页".repeat(MAX_HEADER_FOOTER_LEN + 1));
+        assert!(err.unwrap_err().contains("print.footer"));
+
+        // scale 与 fitToPages 互斥
+        let conflict = PrintOptions {
+            scale: Some(80),
+            fit_to_pages: Some((1, 0)),
+            ..Default::default()
+        };
+        assert!(check_print_options(&conflict).is_err());
+        assert!(check_print_options(&PrintOptions::default()).is_ok());
+    }
+}
@@ -0,0 +1,278 @@
+/// 保护模块
+///
+/// 工作表保护（`protection`，可选密码与允许的操作）、可编辑单元格（样式 `locked: false`）
+/// 以及工作簿结构保护（`workbookProtection`，禁止增删、重命名工作表），仅 XLSX 导出时使用
+use super::data_export::get_object_property;
+use super::table_extractor::TableData;
+use super::xlsx_package::rewrite_part;
+use rust_xlsxwriter::{Format, ProtectionOptions, Worksheet};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// 取 ProtectionOptions 中某个开关的可变引用
+type ProtectionFlag = fn(&mut ProtectionOptions) -> &mut bool;
+
+/// 允许的操作：(配置字段, 对应的 ProtectionOptions 开关)
+const PROTECTION_FLAGS: [(&str, ProtectionFlag); 15] = [
+    ("selectLockedCells", |o| &mut o.select_locked_cells),
+    ("selectUnlockedCells", |o| &mut o.select_unlocked_cells),
+    ("allowFormatCells", |o| &mut o.format_cells),
+    ("allowFormatColumns", |o| &mut o.format_columns),
+    ("allowFormatRows", |o| &mut o.format_rows),
+    ("allowInsertColumns", |o| &mut o.insert_columns),
+    ("allowInsertRows", |o| &mut o.insert_rows),
+    ("allowInsertLinks", |o| &mut o.insert_links),
+    ("allowDeleteColumns", |o| &mut o.delete_columns),
+    ("allowDeleteRows", |o| &mut o.delete_rows),
+    ("allowSort", |o| &mut o.sort),
+    ("allowFilter", |o| &mut o.use_autofilter),
+    ("allowPivotTables", |o| &mut o.use_pivot_tables),
+    ("allowEditScenarios", |o| &mut o.edit_scenarios),
+    ("allowEditObjects", |o| &mut o.edit_objects),
+];
+
+/// 工作表保护配置（`protection`）
+#[derive(Clone, Default)]
+pub struct SheetProtection {
+    /// 取消保护所需的密码（为 None 时无需密码）
+    pub password: Option<String>,
+    /// 保护状态下允许的操作（默认仅可选择单元格）
+    pub options: ProtectionOptions,
+}
+
+impl fmt::Debug for SheetProtection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // ProtectionOptions 未实现 Debug，且密码不应出现在日志中
+        f.debug_struct("SheetProtection")
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .finish_non_exhaustive()
+    }
+}
+
+/// 解析 `protection` 配置
+///
+/// - `true`：无密码保护，仅允许选择单元格
+/// - 对象：`{ password?, selectLockedCells?, selectUnlockedCells?, allowFormatCells?, allowFormatColumns?,
+///   allowFormatRows?, allowInsertColumns?, allowInsertRows?, allowInsertLinks?, allowDeleteColumns?,
+///   allowDeleteRows?, allowSort?, allowFilter?, allowPivotTables?, allowEditScenarios?, allowEditObjects? }`
+/// - `false` / `null` / `undefined`：不保护
+pub(crate) fn parse_sheet_protection(val: &JsValue) -> Result<Option<SheetProtection>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(enabled) = val.as_bool() {
+        return Ok(enabled.then(SheetProtection::default));
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("protection 配置必须是布尔值或对象"));
+    }
+
+    let mut protection = SheetProtection {
+        password: parse_password(val, "protection")?,
+        ..Default::default()
+    };
+    for (key, flag) in PROTECTION_FLAGS {
+        if let Some(enabled) = get_object_property(val, key)?.as_bool() {
+            *flag(&mut protection.options) = enabled;
+        }
+    }
+    Ok(Some(protection))
+}
+
+/// 解析 `workbookProtection` 配置（锁定工作簿结构）
+///
+/// - `true`：无密码保护
+/// - 对象：`{ password? }`
+/// - `false` / `null` / `undefined`：不保护
+///
+/// 返回取消保护所需的密码（空字符串表示无密码）
+pub(crate) fn parse_workbook_protection(val: &JsValue) -> Result<Option<String>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(enabled) = val.as_bool() {
+        return Ok(enabled.then(String::new));
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str(
+            "workbookProtection 配置必须是布尔值或对象",
+        ));
+    }
+    Ok(Some(
+        parse_password(val, "workbookProtection")?.unwrap_or_default(),
+    ))
+}
+
+/// 读取 `password` 字段（Excel 限制 255 个字符，空字符串视为无密码）
+fn parse_password(val: &JsValue, owner: &str) -> Result<Option<String>, JsValue> {
+    let password = get_object_property(val, "password")?;
+    if password.is_null() || password.is_undefined() {
+        return Ok(None);
+    }
+    let password = password
+        .as_string()
+        .ok_or_else(|| JsValue::from_str(&format!("{}.password 必须是字符串", owner)))?;
+    if password.chars().count() > 255 {
+        return Err(JsValue::from_str(&format!(
+            "{}.password 不能超过 255 个字符",
+            owner
+        )));
+    }
+    Ok(Some(password).filter(|p| !p.is_empty()))
+}
+
+/// 写入工作表保护，并将数据样式 `locked: false` 的列整列设为可编辑
+///
+/// 整列格式使未写入的空白行（如导入模板的填写区）同样可编辑；
+/// 已写入的单元格按各自解析出的样式决定是否锁定
+pub(crate) fn apply_sheet_protection(
+    worksheet: &mut Worksheet,
+    table_data: &TableData,
+) -> Result<(), JsValue> {
+    let Some(ref protection) = table_data.sheet_options.protection else {
+        return Ok(());
+    };
+
+    if let Some(ss) = table_data.style_sheet.as_ref() {
+        let unlocked = Format::new().set_unlocked();
+        for (col, style) in ss.column_styles.iter().enumerate() {
+            if style.as_ref().and_then(|s| s.locked) == Some(false) {
+                worksheet
+                    .set_column_format(col as u16, &unlocked)
+                    .map_err(|e| JsValue::from_str(&format!("设置列格式失败: {}", e)))?;
+            }
+        }
+    }
+
+    worksheet.protect_with_options(&protection.options);
+    if let Some(ref password) = protection.password {
+        worksheet.protect_with_password(password);
+    }
+    Ok(())
+}
+
+/// 计算 Excel 旧式保护密码哈希（ECMA-376 Part 4 §14.7.1）
+fn hash_password(password: &str) -> u16 {
+    if password.is_empty() {
+        return 0;
+    }
+
+    let mut hash: u16 = 0;
+    for &byte in password.as_bytes().iter().rev() {
+        hash = ((hash >> 14) & 0x01) | ((hash << 1) & 0x7FFF);
+        hash ^= u16::from(byte);
+    }
+    hash = ((hash >> 14) & 0x01) | ((hash << 1) & 0x7FFF);
+    hash ^= password.len() as u16;
+    hash ^ 0xCE4B
+}
+
+/// 在生成的 XLSX 中写入工作簿结构保护（`<workbookProtection>` 位于 `<bookViews>` 之前）
+pub(crate) fn protect_workbook_structure(bytes: &[u8], password: &str) -> Result<Vec<u8>, JsValue> {
+    let element = if password.is_empty() {
+        "<workbookProtection lockStructure=\"1\"/>".to_string()
+    } else {
+        format!(
+            "<workbookProtection workbookPassword=\"{:04X}\" lockStructure=\"1\"/>",
+            hash_password(password)
+        )
+    };
+
+    rewrite_part(bytes, "xl/workbook.xml", |xml| {
+        let pos = xml
+            .find("<bookViews>")
+            .or_else(|| xml.find("<sheets>"))
+            .ok_or_else(|| "xl/workbook.xml 缺少 <sheets> 元素".to_string())?;
+        let mut xml = xml;
+        xml.insert_str(pos, &element);
+        Ok(xml)
+    })
+    .map_err(|e| JsValue::from_str(&format!("设置工作簿保护失败: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::style::{CellStyle, StyleSheet};
+    use crate::core::table_extractor::CellValue;
+    use crate::core::test_support::{generate, parse_xml, part_xml};
+    use crate::core::xlsx_package::read_part;
+
+    #[test]
+    fn test_hash_password() {
+        // 与 Excel 对同一密码生成的哈希一致
+        assert_eq!(hash_password("password"), 0x83AF);
+        assert_eq!(hash_password(""), 0);
+    }
+
+    #[test]
+    fn test_write_sheet_protection() {
+        let unlocked = CellStyle {
+            locked: Some(false),
+            ..Default::default()
+        };
+        let mut table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("编号"), CellValue::from("数量")],
+                vec![CellValue::from("A-1"), CellValue::Number(3.0)],
+            ],
+            header_row_count: 1,
+            style_sheet: Some(StyleSheet {
+                column_styles: vec![None, Some(unlocked)],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut protection = SheetProtection {
+            password: Some("password".to_string()),
+            ..Default::default()
+        };
+        protection.options.sort = true;
+        protection.options.use_autofilter = true;
+        table_data.sheet_options.protection = Some(protection);
+
+        let bytes = generate(&table_data);
+        let sheet = part_xml(&bytes, "xl/worksheets/sheet1.xml");
+        let protection = sheet.child("sheetProtection").unwrap();
+        assert_eq!(protection.attr("password"), Some("83AF"));
+        assert_eq!(protection.attr("sheet"), Some("1"));
+        // 允许的操作写为 0（不受保护）
+        assert_eq!(protection.attr("sort"), Some("0"));
+        assert_eq!(protection.attr("autoFilter"), Some("0"));
+        assert_eq!(protection.attr("formatCells"), None);
+
+        // 可编辑列整列解锁，其数据单元格同样解锁
+        let styles = part_xml(&bytes, "xl/styles.xml");
+        let xfs = &styles.child("cellXfs").unwrap().children;
+        let locked = |style: Option<&str>| {
+            let xf = &xfs[style.map_or(0, |s| s.parse::<usize>().unwrap())];
+            xf.child("protection").and_then(|p| p.attr("locked")) != Some("0")
+        };
+        let col = sheet.find_by("col", "min", "2").unwrap();
+        assert_eq!(col.attr("max"), Some("2"));
+        assert!(!locked(col.attr("style")));
+        assert!(!locked(sheet.find_by("c", "r", "B2").unwrap().attr("s")));
+        assert!(locked(sheet.find_by("c", "r", "A2").unwrap().attr("s")));
+    }
+
+    #[test]
+    fn test_protect_workbook_structure() {
+        let table_data = TableData {
+            rows: vec![vec![CellValue::from("名称")]],
+            ..Default::default()
+        };
+        let bytes = generate(&table_data);
+
+        let protected = protect_workbook_structure(&bytes, "password").unwrap();
+        let workbook = read_part(&protected, "xl/workbook.xml").unwrap();
+        assert!(workbook.contains(
+            "<workbookProtection workbookPassword=\"83AF\" lockStructure=\"1\"/><bookViews>"
+        ));
+
+        let protected = protect_workbook_structure(&bytes, "").unwrap();
+        let workbook = parse_xml(&read_part(&protected, "xl/workbook.xml").unwrap());
+        let protection = workbook.child("workbookProtection").unwrap();
+        assert_eq!(protection.attr("lockStructure"), Some("1"));
+        assert_eq!(protection.attr("workbookPassword"), None);
+    }
+}
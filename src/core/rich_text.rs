@@ -0,0 +1,566 @@
+/// 富文本模块
+///
+/// 单元格内混排的粗体、颜色、删除线等片段（富文本）：DOM 导出时按需遍历单元格子节点提取内联格式，
+/// 数据导出时解析 `{ runs: [{ text, style }] }` 单元格对象，XLSX 写为 Excel 富文本字符串，CSV 输出纯文本
+use super::style::{CellStyle, normalize_hex_color, parse_css_color};
+use crate::utils::is_element_hidden;
+use rust_xlsxwriter::{Format, FormatScript, FormatUnderline, Worksheet, XlsxError};
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use web_sys::{Element, HtmlElement, Node};
+
+/// DOM 遍历的最大嵌套深度（更深的节点按所在层级的格式输出纯文本）
+const MAX_DOM_DEPTH: usize = 32;
+
+/// 单元格富文本片段数量上限
+const MAX_RUNS: u32 = 256;
+
+/// 上标 / 下标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontScript {
+    Superscript,
+    Subscript,
+}
+
+/// 富文本片段的字体格式（未设置的属性继承单元格样式）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunFont {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+    pub strikethrough: Option<bool>,
+    /// 字体颜色 (hex: "#RRGGBB" 或 "#RGB")
+    pub font_color: Option<String>,
+    /// 字号（磅）
+    pub font_size: Option<f64>,
+    pub font_name: Option<String>,
+    pub script: Option<FontScript>,
+}
+
+impl RunFont {
+    /// 合并两个格式：`other` 中已设置的属性覆盖 `self` 中的属性
+    pub fn merge(&self, other: &RunFont) -> RunFont {
+        RunFont {
+            bold: other.bold.or(self.bold),
+            italic: other.italic.or(self.italic),
+            underline: other.underline.or(self.underline),
+            strikethrough: other.strikethrough.or(self.strikethrough),
+            font_color: other.font_color.clone().or_else(|| self.font_color.clone()),
+            font_size: other.font_size.or(self.font_size),
+            font_name: other.font_name.clone().or_else(|| self.font_name.clone()),
+            script: other.script.or(self.script),
+        }
+    }
+
+    /// 是否未设置任何格式
+    pub fn is_empty(&self) -> bool {
+        *self == RunFont::default()
+    }
+
+    /// 转换为片段 Format：片段未设置的字体属性取自单元格样式，
+    /// 避免带格式的片段回退为默认字体
+    fn to_format(&self, base: Option<&CellStyle>) -> Format {
+        let font = match base {
+            Some(style) => RunFont {
+                bold: style.bold,
+                italic: style.italic,
+                font_color: style.font_color.clone(),
+                font_size: style.font_size,
+                font_name: style.font_name.clone(),
+                ..Default::default()
+            }
+            .merge(self),
+            None => self.clone(),
+        };
+
+        let mut format = Format::new();
+        if font.bold == Some(true) {
+            format = format.set_bold();
+        }
+        if font.italic == Some(true) {
+            format = format.set_italic();
+        }
+        if font.underline == Some(true) {
+            format = format.set_underline(FormatUnderline::Single);
+        }
+        if font.strikethrough == Some(true) {
+            format = format.set_font_strikethrough();
+        }
+        if let Some(ref color) = font.font_color {
+            format = format.set_font_color(normalize_hex_color(color).as_str());
+        }
+        if let Some(size) = font.font_size
+            && size.is_finite()
+            && (1.0..=409.0).contains(&size)
+        {
+            format = format.set_font_size(size);
+        }
+        if let Some(ref name) = font.font_name {
+            format = format.set_font_name(name);
+        }
+        if let Some(script) = font.script {
+            format = format.set_font_script(match script {
+                FontScript::Superscript => FormatScript::Superscript,
+                FontScript::Subscript => FormatScript::Subscript,
+            });
+        }
+        format
+    }
+}
+
+/// 富文本片段
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichTextRun {
+    /// 片段文本（非空）
+    pub text: String,
+    /// 片段字体格式
+    pub font: RunFont,
+}
+
+/// 拼接全部片段的纯文本（CSV 输出与列宽计算使用）
+pub fn rich_text_plain(runs: &[RichTextRun]) -> String {
+    runs.iter().map(|r| r.text.as_str()).collect()
+}
+
+/// 写入富文本单元格
+///
+/// `base` 为单元格解析出的样式（片段继承其字体），`format` 为单元格格式（对齐、背景、边框等）
+pub(crate) fn write_rich_text<'a>(
+    worksheet: &'a mut Worksheet,
+    row: u32,
+    col: u16,
+    runs: &[RichTextRun],
+    base: Option<&CellStyle>,
+    format: Option<&Format>,
+) -> Result<&'a mut Worksheet, XlsxError> {
+    let formats: Vec<Format> = runs.iter().map(|r| r.font.to_format(base)).collect();
+    let segments: Vec<(&Format, &str)> = formats
+        .iter()
+        .zip(runs)
+        .map(|(f, r)| (f, r.text.as_str()))
+        .collect();
+    match format {
+        Some(fmt) => worksheet.write_rich_string_with_format(row, col, &segments, fmt),
+        None => worksheet.write_rich_string(row, col, &segments),
+    }
+}
+
+/// 解析 `{ runs: [{ text, style? }] }` 单元格对象的 `runs`
+///
+/// `style` 接受 `{ bold?, italic?, underline?, strikethrough?, fontColor?, fontSize?, fontName?,
+/// superscript?, subscript? }`；空文本片段被忽略，相邻同格式片段合并
+pub(crate) fn parse_rich_text_runs(val: &JsValue) -> Result<Vec<RichTextRun>, JsValue> {
+    if !js_sys::Array::is_array(val) {
+        return Err(JsValue::from_str("runs 必须是数组"));
+    }
+    let array = js_sys::Array::from(val);
+    if array.length() > MAX_RUNS {
+        return Err(JsValue::from_str(&format!(
+            "runs 过多（{} 个），最多支持 {} 个",
+            array.length(),
+            MAX_RUNS
+        )));
+    }
+
+    let mut builder = RunBuilder::default();
+    for (i, run) in array.iter().enumerate() {
+        let text = match run.as_string() {
+            Some(text) => text,
+            None if run.is_object() => js_sys::Reflect::get(&run, &JsValue::from_str("text"))
+                .ok()
+                .and_then(|v| v.as_string())
+                .ok_or_else(|| JsValue::from_str(&format!("runs[{}].text 必须是字符串", i)))?,
+            None => {
+                return Err(JsValue::from_str(&format!(
+                    "runs[{}] 必须是字符串或对象 {{ text, style? }}",
+                    i
+                )));
+            }
+        };
+        let font = if run.is_object() {
+            js_sys::Reflect::get(&run, &JsValue::from_str("style"))
+                .map(|v| parse_run_font(&v))
+                .unwrap_or_default()
+        } else {
+            RunFont::default()
+        };
+        builder.push_raw(&text, &font);
+    }
+    Ok(builder.runs)
+}
+
+/// 解析片段样式对象
+fn parse_run_font(val: &JsValue) -> RunFont {
+    if !val.is_object() {
+        return RunFont::default();
+    }
+    let get = |key: &str| js_sys::Reflect::get(val, &JsValue::from_str(key)).ok();
+    let flag = |key: &str| get(key).and_then(|v| v.as_bool());
+
+    let script = if flag("superscript") == Some(true) {
+        Some(FontScript::Superscript)
+    } else if flag("subscript") == Some(true) {
+        Some(FontScript::Subscript)
+    } else {
+        None
+    };
+
+    RunFont {
+        bold: flag("bold"),
+        italic: flag("italic"),
+        underline: flag("underline"),
+        strikethrough: flag("strikethrough"),
+        font_color: get("fontColor").and_then(|v| v.as_string()),
+        font_size: get("fontSize").and_then(|v| v.as_f64()),
+        font_name: get("fontName").and_then(|v| v.as_string()),
+        script,
+    }
+}
+
+/// 逐段累积富文本片段，相邻同格式片段合并
+#[derive(Default)]
+struct RunBuilder {
+    runs: Vec<RichTextRun>,
+    /// DOM 遍历时尚未输出的折叠空白
+    pending_space: bool,
+}
+
+impl RunBuilder {
+    /// 原样追加文本（空文本忽略）
+    fn push_raw(&mut self, text: &str, font: &RunFont) {
+        if text.is_empty() {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some(last) if last.font == *font => last.text.push_str(text),
+            _ => self.runs.push(RichTextRun {
+                text: text.to_string(),
+                font: font.clone(),
+            }),
+        }
+    }
+
+    /// 是否位于行首（尚无输出或上一个字符为换行）
+    fn at_line_start(&self) -> bool {
+        self.runs.last().is_none_or(|r| r.text.ends_with('\n'))
+    }
+
+    /// 按 CSS 默认规则追加 DOM 文本：连续空白折叠为一个空格，行首空白丢弃
+    fn push_text(&mut self, text: &str, font: &RunFont) {
+        let mut collapsed = String::with_capacity(text.len());
+        for ch in text.chars() {
+            if ch.is_ascii_whitespace() {
+                self.pending_space = true;
+                continue;
+            }
+            if self.pending_space && !(collapsed.is_empty() && self.at_line_start()) {
+                collapsed.push(' ');
+            }
+            self.pending_space = false;
+            collapsed.push(ch);
+        }
+        self.push_raw(&collapsed, font);
+    }
+
+    /// 追加换行（`<br>` 与块级元素边界）
+    fn push_line_break(&mut self, font: &RunFont) {
+        self.pending_space = false;
+        self.push_raw("\n", font);
+    }
+
+    /// 结束块级元素：仅在当前行非空时换行
+    fn end_block(&mut self, font: &RunFont) {
+        if !self.at_line_start() {
+            self.push_line_break(font);
+        }
+    }
+
+    /// 去除末尾换行；不含任何格式时返回 None（按纯文本处理）
+    fn finish(mut self) -> Option<Vec<RichTextRun>> {
+        while let Some(last) = self.runs.last_mut() {
+            let trimmed = last.text.trim_end_matches('\n').len();
+            last.text.truncate(trimmed);
+            if !last.text.is_empty() {
+                break;
+            }
+            self.runs.pop();
+        }
+        self.runs
+            .iter()
+            .any(|r| !r.font.is_empty())
+            .then_some(self.runs)
+    }
+}
+
+/// 块级元素：前后换行
+fn is_block_tag(tag: &str) -> bool {
+    matches!(
+        tag,
+        "div" | "p" | "li" | "ul" | "ol" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "pre"
+    )
+}
+
+/// 提取单元格内的富文本片段
+///
+/// 识别 `<b>` / `<strong>`、`<i>` / `<em>`、`<u>` / `<ins>`、`<s>` / `<strike>` / `<del>`、
+/// `<sub>` / `<sup>`、`<font color>` 与元素内联样式（color、font-weight、font-style、
+/// text-decoration、font-size、vertical-align）；单元格内没有任何内联格式时返回 None
+pub(crate) fn extract_rich_text(cell: &Element) -> Option<Vec<RichTextRun>> {
+    let mut builder = RunBuilder::default();
+    walk_children(cell, &RunFont::default(), &mut builder, 0);
+    builder.finish()
+}
+
+/// 递归遍历子节点
+fn walk_children(parent: &Node, font: &RunFont, builder: &mut RunBuilder, depth: usize) {
+    let mut child = parent.first_child();
+    while let Some(node) = child {
+        match node.node_type() {
+            Node::TEXT_NODE => {
+                if let Some(text) = node.text_content() {
+                    builder.push_text(&text, font);
+                }
+            }
+            Node::ELEMENT_NODE => {
+                if let Some(element) = node.dyn_ref::<Element>() {
+                    walk_element(element, font, builder, depth);
+                }
+            }
+            _ => {}
+        }
+        child = node.next_sibling();
+    }
+}
+
+/// 处理单个元素：换行、隐藏元素与格式继承
+fn walk_element(element: &Element, font: &RunFont, builder: &mut RunBuilder, depth: usize) {
+    let tag = element.tag_name().to_ascii_lowercase();
+    match tag.as_str() {
+        "br" => return builder.push_line_break(font),
+        "script" | "style" | "template" | "noscript" => return,
+        _ => {}
+    }
+    if is_element_hidden(element) {
+        return;
+    }
+
+    let block = is_block_tag(&tag);
+    if block {
+        builder.end_block(font);
+    }
+    let font = font.merge(&element_font(element, &tag));
+    if depth < MAX_DOM_DEPTH {
+        walk_children(element, &font, builder, depth + 1);
+    } else if let Some(text) = element.text_content() {
+        builder.push_text(&text, &font);
+    }
+    if block {
+        builder.end_block(&font);
+    }
+}
+
+/// 元素自身带来的格式（标签语义 + 内联样式，内联样式优先）
+fn element_font(element: &Element, tag: &str) -> RunFont {
+    let mut font = RunFont::default();
+    match tag {
+        "b" | "strong" => font.bold = Some(true),
+        "i" | "em" | "cite" | "dfn" => font.italic = Some(true),
+        "u" | "ins" => font.underline = Some(true),
+        "s" | "strike" | "del" => font.strikethrough = Some(true),
+        "sup" => font.script = Some(FontScript::Superscript),
+        "sub" => font.script = Some(FontScript::Subscript),
+        "font" => {
+            font.font_color = element
+                .get_attribute("color")
+                .and_then(|c| parse_css_color(&c));
+        }
+        _ => {}
+    }
+
+    let Some(style) = element.dyn_ref::<HtmlElement>().map(HtmlElement::style) else {
+        return font;
+    };
+    let prop = |name: &str| {
+        style
+            .get_property_value(name)
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+    };
+    font.merge(&inline_style_font(
+        &prop("color"),
+        &prop("font-weight"),
+        &prop("font-style"),
+        &prop("text-decoration-line"),
+        &prop("font-size"),
+        &prop("vertical-align"),
+    ))
+}
+
+/// 由内联样式属性值推导格式（属性值已转为小写，未设置时为空串）
+fn inline_style_font(
+    color: &str,
+    weight: &str,
+    style: &str,
+    decoration: &str,
+    size: &str,
+    vertical_align: &str,
+) -> RunFont {
+    let bold = match weight {
+        "bold" | "bolder" => Some(true),
+        "normal" | "lighter" => Some(false),
+        w => w.parse::<f64>().ok().map(|w| w >= 600.0),
+    };
+    let italic = match style {
+        "italic" | "oblique" => Some(true),
+        "normal" => Some(false),
+        _ => None,
+    };
+    let (underline, strikethrough) = match decoration {
+        "" => (None, None),
+        "none" => (Some(false), Some(false)),
+        d => (
+            d.contains("underline").then_some(true),
+            d.contains("line-through").then_some(true),
+        ),
+    };
+    // 像素换算为磅（1px = 0.75pt）
+    let font_size = size
+        .strip_suffix("pt")
+        .and_then(|n| n.parse::<f64>().ok())
+        .or_else(|| {
+            size.strip_suffix("px")
+                .and_then(|n| n.parse::<f64>().ok())
+                .map(|px| px * 0.75)
+        });
+    let script = match vertical_align {
+        "super" => Some(FontScript::Superscript),
+        "sub" => Some(FontScript::Subscript),
+        _ => None,
+    };
+
+    RunFont {
+        bold,
+        italic,
+        underline,
+        strikethrough,
+        font_color: parse_css_color(color),
+        font_size,
+        font_name: None,
+        script,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::table_extractor::{CellValue, TableData};
+    use crate::core::test_support::{generate, part_xml};
+
+    fn bold() -> RunFont {
+        RunFont {
+            bold: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_run_builder_collapses_whitespace() {
+        let mut builder = RunBuilder::default();
+        builder.push_text("\n  逾期 ", &bold());
+        builder.push_text("  3\t天 ", &RunFont::default());
+        builder.push_line_break(&RunFont::default());
+        builder.push_text("  备注", &RunFont::default());
+        builder.end_block(&RunFont::default());
+        let runs = builder.finish().unwrap();
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].text, "逾期");
+        assert_eq!(runs[1].text, " 3 天\n备注");
+        assert_eq!(rich_text_plain(&runs), "逾期 3 天\n备注");
+
+        // 不含任何格式时按纯文本处理
+        let mut builder = RunBuilder::default();
+        builder.push_text("普通 文本", &RunFont::default());
+        assert!(builder.finish().is_none());
+    }
+
+    #[test]
+    fn test_inline_style_font() {
+        let font = inline_style_font(
+            "rgb(255, 0, 0)",
+            "700",
+            "italic",
+            "underline line-through",
+            "16px",
+            "",
+        );
+        assert_eq!(font.font_color.as_deref(), Some("#FF0000"));
+        assert_eq!(font.bold, Some(true));
+        assert_eq!(font.italic, Some(true));
+        assert_eq!(font.underline, Some(true));
+        assert_eq!(font.strikethrough, Some(true));
+        assert_eq!(font.font_size, Some(12.0));
+
+        let font = inline_style_font("", "400", "", "none", "", "super");
+        assert_eq!(font.bold, Some(false));
+        assert_eq!(font.underline, Some(false));
+        assert_eq!(font.script, Some(FontScript::Superscript));
+        assert!(inline_style_font("", "", "", "", "", "").is_empty());
+    }
+
+    #[test]
+    fn test_write_rich_text() {
+        let red_strike = RunFont {
+            strikethrough: Some(true),
+            font_color: Some("#F00".to_string()),
+            ..Default::default()
+        };
+        let table_data = TableData {
+            rows: vec![vec![CellValue::RichText(vec![
+                RichTextRun {
+                    text: "逾期".to_string(),
+                    font: bold(),
+                },
+                RichTextRun {
+                    text: " 3 天 ".to_string(),
+                    font: RunFont::default(),
+                },
+                RichTextRun {
+                    text: "原价".to_string(),
+                    font: red_strike,
+                },
+            ])]],
+            style_sheet: Some(crate::core::style::StyleSheet {
+                data_style: Some(CellStyle {
+                    font_size: Some(14.0),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let shared = part_xml(&generate(&table_data), "xl/sharedStrings.xml");
+        let runs = shared.find("si").unwrap().find_all("r");
+        let texts: Vec<_> = runs
+            .iter()
+            .map(|r| r.child("t").unwrap().text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["逾期", " 3 天 ", "原价"]);
+        assert_eq!(
+            runs[1].child("t").unwrap().attr("xml:space"),
+            Some("preserve")
+        );
+
+        // 片段字体继承单元格字号
+        let first = runs[0].child("rPr").unwrap();
+        assert!(first.child("b").is_some());
+        assert_eq!(first.child("sz").unwrap().attr("val"), Some("14"));
+        let last = runs[2].child("rPr").unwrap();
+        assert!(last.child("strike").is_some());
+        assert!(last.child("b").is_none());
+        assert_eq!(last.child("sz").unwrap().attr("val"), Some("14"));
+        assert_eq!(last.child("color").unwrap().attr("rgb"), Some("FFFF0000"));
+    }
+}
@@ -0,0 +1,427 @@
+/// 行级样式模块
+///
+/// 解析 export_data 的 `rowStyle` 配置（斑马纹、按字段取值匹配的行样式规则、行高），
+/// 在提取数据行时逐行求值，结果写入 StyleSheet 的行级样式层（仅 XLSX 导出时使用）
+use super::data_export::get_object_property;
+use super::style::{CellStyle, parse_cell_style};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// Excel 允许的最大行高（磅）
+const EXCEL_MAX_ROW_HEIGHT: f64 = 409.0;
+
+/// 行样式规则数量上限
+const MAX_ROW_RULES: u32 = 256;
+
+/// 斑马纹配置：奇数行 / 偶数行样式（按数据行从 1 开始计数）
+#[derive(Debug, Clone, Default)]
+pub struct ZebraStyle {
+    /// 第 1、3、5… 条数据行的样式
+    pub odd: Option<CellStyle>,
+    /// 第 2、4、6… 条数据行的样式
+    pub even: Option<CellStyle>,
+}
+
+/// 行样式规则：`{ when: { key, equals }, style?, height? }`
+#[derive(Debug, Clone)]
+pub struct RowStyleRule {
+    /// 参与匹配的字段名
+    pub key: String,
+    /// 期望值（按 `===` 比较，单元格对象比较其 `value`）
+    pub equals: JsValue,
+    /// 命中时的行样式
+    pub style: Option<CellStyle>,
+    /// 命中时的行高（磅）
+    pub height: Option<f64>,
+}
+
+/// 行级样式配置（`rowStyle`）
+#[derive(Debug, Clone, Default)]
+pub struct RowStyleOptions {
+    /// 斑马纹
+    pub zebra: Option<ZebraStyle>,
+    /// 按顺序求值的规则，后命中的规则覆盖先命中的规则
+    pub rules: Vec<RowStyleRule>,
+    /// 数据行默认行高（磅）
+    pub height: Option<f64>,
+}
+
+impl RowStyleOptions {
+    /// 是否未配置任何行级样式
+    pub fn is_empty(&self) -> bool {
+        self.zebra.is_none() && self.rules.is_empty() && self.height.is_none()
+    }
+}
+
+/// 解析 `rowStyle` 配置：`{ zebra?: { odd?, even? }, rules?: [...], height? }`
+pub(crate) fn parse_row_style(val: &JsValue) -> Result<RowStyleOptions, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(RowStyleOptions::default());
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("rowStyle 配置必须是对象"));
+    }
+
+    let zebra = get_object_property(val, "zebra")?;
+    let zebra = if zebra.is_null() || zebra.is_undefined() {
+        None
+    } else if zebra.is_object() {
+        Some(ZebraStyle {
+            odd: parse_cell_style(&get_object_property(&zebra, "odd")?),
+            even: parse_cell_style(&get_object_property(&zebra, "even")?),
+        })
+    } else {
+        return Err(JsValue::from_str(
+            "rowStyle.zebra 配置必须是对象 { odd?, even? }",
+        ));
+    };
+
+    let rules_val = get_object_property(val, "rules")?;
+    let mut rules = Vec::new();
+    if !rules_val.is_null() && !rules_val.is_undefined() {
+        if !js_sys::Array::is_array(&rules_val) {
+            return Err(JsValue::from_str("rowStyle.rules 必须是数组"));
+        }
+        let array = js_sys::Array::from(&rules_val);
+        if array.length() > MAX_ROW_RULES {
+            return Err(JsValue::from_str(&format!(
+                "rowStyle.rules 过多（{} 条），最多支持 {} 条",
+                array.length(),
+                MAX_ROW_RULES
+            )));
+        }
+        for (i, rule) in array.iter().enumerate() {
+            rules.push(parse_row_rule(&rule, i)?);
+        }
+    }
+
+    let height = parse_row_height(&get_object_property(val, "height")?)
+        .map_err(|e| JsValue::from_str(&format!("rowStyle.height {}", e)))?;
+
+    Ok(RowStyleOptions {
+        zebra,
+        rules,
+        height,
+    })
+}
+
+/// 解析单条行样式规则
+fn parse_row_rule(val: &JsValue, index: usize) -> Result<RowStyleRule, JsValue> {
+    if !val.is_object() {
+        return Err(JsValue::from_str(&format!(
+            "rowStyle.rules[{}] 必须是对象",
+            index
+        )));
+    }
+
+    let when = get_object_property(val, "when")?;
+    if !when.is_object() {
+        return Err(JsValue::from_str(&format!(
+            "rowStyle.rules[{}].when 必须是对象 {{ key, equals }}",
+            index
+        )));
+    }
+    let key = get_object_property(&when, "key")?
+        .as_string()
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| {
+            JsValue::from_str(&format!(
+                "rowStyle.rules[{}].when.key 必须是非空字符串",
+                index
+            ))
+        })?;
+    let has_equals = js_sys::Reflect::has(&when, &JsValue::from_str("equals")).map_err(|e| {
+        JsValue::from_str(&format!(
+            "读取 rowStyle.rules[{}].when 失败: {:?}",
+            index, e
+        ))
+    })?;
+    if !has_equals {
+        return Err(JsValue::from_str(&format!(
+            "rowStyle.rules[{}].when 缺少 equals",
+            index
+        )));
+    }
+
+    let style = parse_cell_style(&get_object_property(val, "style")?);
+    let height = parse_row_height(&get_object_property(val, "height")?)
+        .map_err(|e| JsValue::from_str(&format!("rowStyle.rules[{}].height {}", index, e)))?;
+    if style.is_none() && height.is_none() {
+        return Err(JsValue::from_str(&format!(
+            "rowStyle.rules[{}] 至少需要 style 或 height",
+            index
+        )));
+    }
+
+    Ok(RowStyleRule {
+        key,
+        equals: get_object_property(&when, "equals")?,
+        style,
+        height,
+    })
+}
+
+/// 解析行高（磅），未设置时返回 None
+fn parse_row_height(val: &JsValue) -> Result<Option<f64>, String> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    match val.as_f64() {
+        Some(h) => check_row_height(h).map(Some),
+        None => Err("必须是数值".to_string()),
+    }
+}
+
+/// 校验行高范围：0–409 磅
+fn check_row_height(height: f64) -> Result<f64, String> {
+    if height.is_finite() && (0.0..=EXCEL_MAX_ROW_HEIGHT).contains(&height) {
+        Ok(height)
+    } else {
+        Err(format!(
+            "无效: {}，应为 0–{} 的数值（磅）",
+            height, EXCEL_MAX_ROW_HEIGHT
+        ))
+    }
+}
+
+/// 行级样式求值结果（写入 StyleSheet 的行级样式层）
+#[derive(Debug, Default)]
+pub struct RowStyles {
+    /// 去重后的行样式
+    pub styles: Vec<CellStyle>,
+    /// 行号 → styles 下标
+    pub index: HashMap<u32, usize>,
+    /// 行号 → 行高（磅）
+    pub heights: HashMap<u32, f64>,
+}
+
+/// 逐行求值 `rowStyle`：按「斑马纹奇偶 + 命中规则」组合去重样式，便于按组合缓存 Format
+#[derive(Debug)]
+pub(crate) struct RowStyler<'a> {
+    options: &'a RowStyleOptions,
+    /// 第一条数据行的绝对行号（即表头行数）
+    first_row: u32,
+    /// 已处理的数据行数
+    count: u32,
+    /// (是否奇数行, 命中的规则下标) → styles 下标，None 表示组合后为空样式
+    combos: HashMap<(bool, Vec<usize>), Option<usize>>,
+    result: RowStyles,
+}
+
+impl<'a> RowStyler<'a> {
+    pub(crate) fn new(options: &'a RowStyleOptions, first_row: usize) -> Self {
+        Self {
+            options,
+            first_row: first_row as u32,
+            count: 0,
+            combos: HashMap::new(),
+            result: RowStyles::default(),
+        }
+    }
+
+    /// 求值下一条数据行（`item` 为该行的源数据对象）
+    pub(crate) fn push(&mut self, item: &JsValue) -> Result<(), JsValue> {
+        if self.options.is_empty() {
+            return Ok(());
+        }
+        let mut matched = Vec::new();
+        for (i, rule) in self.options.rules.iter().enumerate() {
+            let val = get_object_property(item, &rule.key)?;
+            if raw_value(val)? == rule.equals {
+                matched.push(i);
+            }
+        }
+        self.record(&matched);
+        Ok(())
+    }
+
+    /// 记录当前数据行的求值结果（`matched` 为命中的规则下标，按升序）
+    fn record(&mut self, matched: &[usize]) {
+        let row = self.first_row.saturating_add(self.count);
+        let odd = self.count % 2 == 0;
+        self.count += 1;
+
+        let options = self.options;
+        let result = &mut self.result;
+        let slot = *self
+            .combos
+            .entry((odd, matched.to_vec()))
+            .or_insert_with(|| {
+                let zebra = options
+                    .zebra
+                    .as_ref()
+                    .and_then(|z| if odd { z.odd.as_ref() } else { z.even.as_ref() });
+                let merged = zebra
+                    .into_iter()
+                    .chain(
+                        matched
+                            .iter()
+                            .filter_map(|&i| options.rules[i].style.as_ref()),
+                    )
+                    .fold(CellStyle::default(), |acc, s| acc.merge(s));
+                if merged.is_empty() {
+                    return None;
+                }
+                result.styles.push(merged);
+                Some(result.styles.len() - 1)
+            });
+        if let Some(idx) = slot {
+            self.result.index.insert(row, idx);
+        }
+
+        let height = matched
+            .iter()
+            .rev()
+            .find_map(|&i| self.options.rules[i].height)
+            .or(self.options.height);
+        if let Some(h) = height {
+            self.result.heights.insert(row, h);
+        }
+    }
+
+    pub(crate) fn finish(self) -> RowStyles {
+        self.result
+    }
+}
+
+/// 取字段的比较值：单元格对象 `{ value, ... }` 取其 `value`，其余原样返回
+fn raw_value(val: JsValue) -> Result<JsValue, JsValue> {
+    if val.is_object() && !js_sys::Array::is_array(&val) && !val.is_instance_of::<js_sys::Date>() {
+        let has_value = js_sys::Reflect::has(&val, &JsValue::from_str("value")).unwrap_or(false);
+        if has_value {
+            return get_object_property(&val, "value");
+        }
+    }
+    Ok(val)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::{generate, part_xml};
+
+    fn bg(color: &str) -> Option<CellStyle> {
+        Some(CellStyle {
+            background_color: Some(color.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn rule(style: Option<CellStyle>, height: Option<f64>) -> RowStyleRule {
+        RowStyleRule {
+            key: "status".to_string(),
+            equals: JsValue::NULL,
+            style,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_row_styler_record() {
+        let options = RowStyleOptions {
+            zebra: Some(ZebraStyle {
+                odd: None,
+                even: bg("#F5F5F5"),
+            }),
+            rules: vec![
+                rule(
+                    Some(CellStyle {
+                        font_color: Some("#FF0000".to_string()),
+                        ..Default::default()
+                    }),
+                    None,
+                ),
+                rule(bg("#FFFF00"), Some(30.0)),
+            ],
+            height: Some(18.0),
+        };
+        let mut styler = RowStyler::new(&options, 1);
+        styler.record(&[]);
+        styler.record(&[]);
+        styler.record(&[0]);
+        styler.record(&[0, 1]);
+        styler.record(&[]);
+        styler.record(&[]);
+        let result = styler.finish();
+
+        // 第 1 条数据行：奇数行无斑马纹且未命中规则
+        assert!(!result.index.contains_key(&1));
+        // 偶数行斑马纹，相同组合复用同一样式
+        assert_eq!(result.index[&2], result.index[&6]);
+        assert_eq!(
+            result.styles[result.index[&2]].background_color.as_deref(),
+            Some("#F5F5F5")
+        );
+        // 规则样式叠加在斑马纹之上，后命中的规则覆盖先命中的规则
+        let style = &result.styles[result.index[&4]];
+        assert_eq!(style.font_color.as_deref(), Some("#FF0000"));
+        assert_eq!(style.background_color.as_deref(), Some("#FFFF00"));
+        assert_eq!(result.styles.len(), 3);
+
+        // 规则行高优先于默认行高
+        assert_eq!(result.heights[&4], 30.0);
+        assert_eq!(result.heights[&3], 18.0);
+        assert_eq!(result.heights.len(), 6);
+    }
+
+    #[test]
+    fn test_check_row_height() {
+        assert_eq!(check_row_height(20.5), Ok(20.5));
+        assert!(check_row_height(410.0).is_err());
+        assert!(check_row_height(-1.0).is_err());
+        assert!(check_row_height(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_write_sheet_row_styles() {
+        use crate::core::style::StyleSheet;
+        use crate::core::table_extractor::{CellValue, TableData};
+
+        let mut style_sheet = StyleSheet {
+            data_style: Some(CellStyle {
+                bold: Some(true),
+                ..Default::default()
+            }),
+            row_styles: vec![bg("#FFFF00").unwrap()],
+            ..Default::default()
+        };
+        style_sheet.row_style_index.insert(2, 0);
+        style_sheet.row_heights.insert(2, 30.0);
+        let table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("状态")],
+                vec![CellValue::from("正常")],
+                vec![CellValue::from("逾期")],
+            ],
+            header_row_count: 1,
+            style_sheet: Some(style_sheet),
+            ..Default::default()
+        };
+
+        let bytes = generate(&table_data);
+        let sheet = part_xml(&bytes, "xl/worksheets/sheet1.xml");
+        let styles = part_xml(&bytes, "xl/styles.xml");
+
+        let row = sheet.find_by("row", "r", "3").unwrap();
+        assert_eq!(row.attr("ht"), Some("30"));
+        assert_eq!(row.attr("customHeight"), Some("1"));
+
+        // 行样式与数据样式合并：第 3 行与第 2 行使用不同的 Format，且带行背景色
+        let fill_of = |cell: &str| {
+            let xf_index: usize = sheet
+                .find_by("c", "r", cell)
+                .and_then(|c| c.attr("s"))
+                .unwrap()
+                .parse()
+                .unwrap();
+            let xf = &styles.child("cellXfs").unwrap().children[xf_index];
+            let fill_index: usize = xf.attr("fillId").unwrap().parse().unwrap();
+            let fill = &styles.child("fills").unwrap().children[fill_index];
+            fill.find("fgColor")
+                .and_then(|c| c.attr("rgb"))
+                .map(str::to_string)
+        };
+        assert_eq!(fill_of("A3").as_deref(), Some("FFFFFF00"));
+        assert_ne!(fill_of("A2"), fill_of("A3"));
+    }
+}
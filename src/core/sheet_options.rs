@@ -0,0 +1,329 @@
+/// 工作表级选项模块
+///
+/// 描述与单元格内容无关的工作表级 XLSX 设置（如 Excel 表格、自动筛选、条件格式），CSV 导出时忽略
+use super::cell_image::{ImageOptions, parse_image_options};
+use super::cell_note::{NoteOptions, parse_note_options};
+use super::column_width::{AutoWidthOptions, parse_auto_width};
+use super::conditional_format::{CfTarget, ConditionalFormatRule, parse_conditional_formats};
+use super::print_options::{PrintOptions, parse_print_options};
+use super::protection::{SheetProtection, parse_sheet_protection};
+use super::style::parse_css_color;
+use crate::validation::validate_table_name;
+use rust_xlsxwriter::TableStyle;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// `templateRows` 上限（Excel 最大行数减去一行表头）
+const EXCEL_MAX_DATA_ROWS: f64 = 1_048_575.0;
+
+/// Excel 支持的全部表格样式（用于按名称解析）
+const TABLE_STYLES: [TableStyle; 61] = {
+    use TableStyle::*;
+    [
+        None, Light1, Light2, Light3, Light4, Light5, Light6, Light7, Light8, Light9, Light10,
+        Light11, Light12, Light13, Light14, Light15, Light16, Light17, Light18, Light19, Light20,
+        Light21, Medium1, Medium2, Medium3, Medium4, Medium5, Medium6, Medium7, Medium8, Medium9,
+        Medium10, Medium11, Medium12, Medium13, Medium14, Medium15, Medium16, Medium17, Medium18,
+        Medium19, Medium20, Medium21, Medium22, Medium23, Medium24, Medium25, Medium26, Medium27,
+        Medium28, Dark1, Dark2, Dark3, Dark4, Dark5, Dark6, Dark7, Dark8, Dark9, Dark10, Dark11,
+    ]
+};
+
+/// Excel 表格（ListObject）配置
+#[derive(Clone)]
+pub struct ExcelTableOptions {
+    /// 表格样式（默认 TableStyleMedium9，与 Excel 一致）
+    pub style: TableStyle,
+    /// 是否显示汇总行
+    pub total_row: bool,
+    /// 是否显示镶边行（默认 true）
+    pub banded_rows: bool,
+    /// 表格名称（为 None 时自动命名为 Table1、Table2…）
+    pub name: Option<String>,
+}
+
+impl Default for ExcelTableOptions {
+    fn default() -> Self {
+        Self {
+            style: TableStyle::Medium9,
+            total_row: false,
+            banded_rows: true,
+            name: None,
+        }
+    }
+}
+
+impl fmt::Debug for ExcelTableOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // TableStyle 未实现 Debug，按样式名输出
+        f.debug_struct("ExcelTableOptions")
+            .field("style", &self.style.to_string())
+            .field("total_row", &self.total_row)
+            .field("banded_rows", &self.banded_rows)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// 工作表级选项（仅 XLSX 导出时使用）
+#[derive(Debug, Clone, Default)]
+pub struct SheetOptions {
+    /// 将表头 + 数据区转为 Excel 表格（ListObject）
+    pub table: Option<ExcelTableOptions>,
+    /// 是否在表头行上启用自动筛选（为 None 时存在表头即启用）
+    pub auto_filter: Option<bool>,
+    /// 导入模板的可填写行数：列数据验证至少覆盖表头下的这么多行
+    pub template_rows: Option<usize>,
+    /// 工作表级条件格式（未指定 range / column 时作用于全部数据区）
+    pub conditional_formats: Vec<ConditionalFormatRule>,
+    /// 批注外观（作者、尺寸、是否始终显示）
+    pub note_options: NoteOptions,
+    /// 图片缩放方式
+    pub image_options: ImageOptions,
+    /// 自动列宽（作用于未显式配置 width 的全部列）
+    pub auto_width: Option<AutoWidthOptions>,
+    /// 工作表保护（密码与允许的操作）
+    pub protection: Option<SheetProtection>,
+    /// 打印设置（纸张、页边距、缩放、页眉页脚等）
+    pub print: Option<PrintOptions>,
+    /// 冻结窗格 (行, 列)，优先于调用方传入的冻结设置（为 None 时沿用调用方设置或按表头自动冻结）
+    pub freeze_pane: Option<(u32, u16)>,
+    /// 工作表标签颜色（`#RRGGBB`）
+    pub tab_color: Option<String>,
+    /// 是否隐藏工作表
+    pub hidden: bool,
+    /// 显示缩放比例（10–400）
+    pub zoom: Option<u16>,
+}
+
+/// 从导出配置对象中解析工作表级选项（export_data / export_table / 工作表配置共用）
+///
+/// 读取字段：`table`、`autoFilter`、`templateRows`、`conditionalFormats`、`noteOptions`、`imageOptions`、`autoWidth`、
+/// `protection`、`print`、`freezeRows`、`freezeCols`、`tabColor`、`hidden`、`zoom`
+pub fn parse_sheet_options(obj: &JsValue) -> Result<SheetOptions, JsValue> {
+    if obj.is_null() || obj.is_undefined() {
+        return Ok(SheetOptions::default());
+    }
+
+    let table = js_sys::Reflect::get(obj, &JsValue::from_str("table"))
+        .map_err(|e| JsValue::from_str(&format!("读取 table 配置失败: {:?}", e)))?;
+
+    let auto_filter = js_sys::Reflect::get(obj, &JsValue::from_str("autoFilter"))
+        .ok()
+        .and_then(|v| v.as_bool());
+
+    let template_rows = js_sys::Reflect::get(obj, &JsValue::from_str("templateRows"))
+        .ok()
+        .and_then(|v| v.as_f64())
+        .map(check_template_rows)
+        .transpose()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let conditional_formats = js_sys::Reflect::get(obj, &JsValue::from_str("conditionalFormats"))
+        .map_err(|e| {
+        JsValue::from_str(&format!("读取 conditionalFormats 配置失败: {:?}", e))
+    })?;
+
+    let note_options = js_sys::Reflect::get(obj, &JsValue::from_str("noteOptions"))
+        .map_err(|e| JsValue::from_str(&format!("读取 noteOptions 配置失败: {:?}", e)))?;
+    let image_options = js_sys::Reflect::get(obj, &JsValue::from_str("imageOptions"))
+        .map_err(|e| JsValue::from_str(&format!("读取 imageOptions 配置失败: {:?}", e)))?;
+    let auto_width = js_sys::Reflect::get(obj, &JsValue::from_str("autoWidth"))
+        .map_err(|e| JsValue::from_str(&format!("读取 autoWidth 配置失败: {:?}", e)))?;
+    let protection = js_sys::Reflect::get(obj, &JsValue::from_str("protection"))
+        .map_err(|e| JsValue::from_str(&format!("读取 protection 配置失败: {:?}", e)))?;
+    let print = js_sys::Reflect::get(obj, &JsValue::from_str("print"))
+        .map_err(|e| JsValue::from_str(&format!("读取 print 配置失败: {:?}", e)))?;
+
+    // 只要设置了任一冻结参数即使用显式配置，未设置的一侧为 0
+    let freeze_count = |key: &str| {
+        js_sys::Reflect::get(obj, &JsValue::from_str(key))
+            .ok()
+            .and_then(|v| v.as_f64())
+            .filter(|n| n.is_finite() && *n >= 0.0)
+    };
+    let freeze_pane = match (freeze_count("freezeRows"), freeze_count("freezeCols")) {
+        (None, None) => None,
+        (rows, cols) => Some((rows.unwrap_or(0.0) as u32, cols.unwrap_or(0.0) as u16)),
+    };
+
+    let tab_color = js_sys::Reflect::get(obj, &JsValue::from_str("tabColor"))
+        .ok()
+        .and_then(|v| v.as_string())
+        .map(|color| check_tab_color(&color))
+        .transpose()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let hidden = js_sys::Reflect::get(obj, &JsValue::from_str("hidden"))
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let zoom = js_sys::Reflect::get(obj, &JsValue::from_str("zoom"))
+        .ok()
+        .and_then(|v| v.as_f64())
+        .map(check_zoom)
+        .transpose()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(SheetOptions {
+        table: parse_table_options(&table)?,
+        auto_filter,
+        template_rows,
+        conditional_formats: parse_conditional_formats(&conditional_formats, CfTarget::Data, true)?,
+        note_options: parse_note_options(&note_options)?,
+        image_options: parse_image_options(&image_options)?,
+        auto_width: parse_auto_width(&auto_width)?,
+        protection: parse_sheet_protection(&protection)?,
+        print: parse_print_options(&print)?,
+        freeze_pane,
+        tab_color,
+        hidden,
+        zoom,
+    })
+}
+
+/// 校验 `templateRows`（0 至 Excel 数据行上限的整数）
+fn check_template_rows(n: f64) -> Result<usize, String> {
+    if n.fract() == 0.0 && (0.0..=EXCEL_MAX_DATA_ROWS).contains(&n) {
+        Ok(n as usize)
+    } else {
+        Err(format!(
+            "templateRows 无效: {}，应为 0–{} 的整数",
+            n, EXCEL_MAX_DATA_ROWS
+        ))
+    }
+}
+
+/// 校验 `tabColor` 并规范化为 `#RRGGBB`
+fn check_tab_color(color: &str) -> Result<String, String> {
+    parse_css_color(color.trim()).ok_or_else(|| format!("tabColor 无效: '{}'", color))
+}
+
+/// 校验 `zoom`（10–400 的整数）
+fn check_zoom(n: f64) -> Result<u16, String> {
+    if n.fract() == 0.0 && (10.0..=400.0).contains(&n) {
+        Ok(n as u16)
+    } else {
+        Err(format!("zoom 无效: {}，应为 10–400 的整数", n))
+    }
+}
+
+/// 按名称解析表格样式
+///
+/// 支持完整名称（`TableStyleMedium9`）与简写（`Medium9`），不区分大小写
+fn parse_table_style(name: &str) -> Option<TableStyle> {
+    let name = name.trim();
+    TABLE_STYLES.iter().copied().find(|style| {
+        let full = style.to_string();
+        full.eq_ignore_ascii_case(name)
+            || full
+                .strip_prefix("TableStyle")
+                .is_some_and(|short| short.eq_ignore_ascii_case(name))
+    })
+}
+
+/// 解析 `table` 配置
+///
+/// - `true`：使用默认配置
+/// - 对象：`{ style?, totalRow?, bandedRows?, name? }`
+/// - `false` / `null` / `undefined`：不创建表格
+///
+/// # 示例
+/// ```javascript
+/// { table: { style: 'TableStyleMedium2', totalRow: true, name: 'Sales' } }
+/// ```
+fn parse_table_options(val: &JsValue) -> Result<Option<ExcelTableOptions>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(enabled) = val.as_bool() {
+        return Ok(enabled.then(ExcelTableOptions::default));
+    }
+    if !val.is_object() {
+        return Err(JsValue::from_str("table 配置必须是布尔值或对象"));
+    }
+
+    let mut options = ExcelTableOptions::default();
+
+    if let Some(style) = js_sys::Reflect::get(val, &JsValue::from_str("style"))
+        .ok()
+        .and_then(|v| v.as_string())
+    {
+        options.style = parse_table_style(&style).ok_or_else(|| {
+            JsValue::from_str(&format!(
+                "table.style 无效: '{}'，应为 TableStyleLight1–21 / TableStyleMedium1–28 / TableStyleDark1–11 / TableStyleNone",
+                style
+            ))
+        })?;
+    }
+
+    if let Some(total_row) = js_sys::Reflect::get(val, &JsValue::from_str("totalRow"))
+        .ok()
+        .and_then(|v| v.as_bool())
+    {
+        options.total_row = total_row;
+    }
+
+    if let Some(banded_rows) = js_sys::Reflect::get(val, &JsValue::from_str("bandedRows"))
+        .ok()
+        .and_then(|v| v.as_bool())
+    {
+        options.banded_rows = banded_rows;
+    }
+
+    if let Some(name) = js_sys::Reflect::get(val, &JsValue::from_str("name"))
+        .ok()
+        .and_then(|v| v.as_string())
+    {
+        validate_table_name(&name).map_err(|e| JsValue::from_str(&e))?;
+        options.name = Some(name);
+    }
+
+    Ok(Some(options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table_style() {
+        assert!(parse_table_style("TableStyleMedium9") == Some(TableStyle::Medium9));
+        assert!(parse_table_style("medium2") == Some(TableStyle::Medium2));
+        assert!(parse_table_style("TableStyleLight21") == Some(TableStyle::Light21));
+        assert!(parse_table_style("Dark11") == Some(TableStyle::Dark11));
+        assert!(parse_table_style("TableStyleNone") == Some(TableStyle::None));
+        assert!(parse_table_style("Medium29").is_none());
+        assert!(parse_table_style("").is_none());
+    }
+
+    #[test]
+    fn test_check_sheet_values() {
+        assert_eq!(check_zoom(10.0), Ok(10));
+        assert_eq!(check_zoom(400.0), Ok(400));
+        assert!(check_zoom(9.0).is_err());
+        assert!(check_zoom(401.0).is_err());
+        assert!(check_zoom(125.5).is_err());
+        assert!(check_zoom(f64::NAN).is_err());
+
+        assert_eq!(check_template_rows(0.0), Ok(0));
+        assert_eq!(
+            check_template_rows(EXCEL_MAX_DATA_ROWS),
+            Ok(EXCEL_MAX_DATA_ROWS as usize)
+        );
+        assert!(check_template_rows(EXCEL_MAX_DATA_ROWS + 1.0).is_err());
+        assert!(check_template_rows(-1.0).is_err());
+        assert!(check_template_rows(1.5).is_err());
+
+        assert_eq!(check_tab_color(" #f00 "), Ok("#FF0000".to_string()));
+        assert_eq!(
+            check_tab_color("rgb(64, 158, 255)"),
+            Ok("#409EFF".to_string())
+        );
+        assert_eq!(
+            check_tab_color("not-a-color"),
+            Err("tabColor 无效: 'not-a-color'".to_string())
+        );
+    }
+}
@@ -5,8 +5,11 @@
 use rust_xlsxwriter::{Format, FormatAlign, FormatBorder};
 use std::collections::HashMap;
 
+/// Excel 单元格最大缩进级别
+pub const MAX_INDENT: u8 = 15;
+
 /// 水平对齐方式
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum HAlign {
     /// 左对齐
     Left,
@@ -17,7 +20,7 @@ pub enum HAlign {
 }
 
 /// 垂直对齐方式
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum VAlign {
     /// 顶部对齐
     Top,
@@ -28,7 +31,7 @@ pub enum VAlign {
 }
 
 /// 边框线条类型
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BorderLine {
     /// 细线
     Thin,
@@ -80,7 +83,7 @@ impl std::str::FromStr for BorderLine {
 }
 
 /// 边框样式配置
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BorderConfig {
     /// 四边统一细线边框
     All,
@@ -96,7 +99,7 @@ pub enum BorderConfig {
 /// 单元格样式定义
 ///
 /// 所有字段均为 Option，未设置的属性不会覆盖更低优先级的样式
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct CellStyle {
     /// 粗体
     pub bold: Option<bool>,
@@ -120,6 +123,31 @@ pub struct CellStyle {
     pub number_format: Option<String>,
     /// 自动换行
     pub text_wrap: Option<bool>,
+    /// 缩进级别（0–15）
+    pub indent: Option<u8>,
+    /// 是否锁定（工作表保护时生效，`false` 表示可编辑）
+    pub locked: Option<bool>,
+}
+
+// 字号按位比较，使相同样式可作为缓存键共享同一个 Format
+impl Eq for CellStyle {}
+
+impl std::hash::Hash for CellStyle {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.bold.hash(state);
+        self.italic.hash(state);
+        self.font_size.map(f64::to_bits).hash(state);
+        self.font_name.hash(state);
+        self.font_color.hash(state);
+        self.background_color.hash(state);
+        self.align.hash(state);
+        self.vertical_align.hash(state);
+        self.border.hash(state);
+        self.number_format.hash(state);
+        self.text_wrap.hash(state);
+        self.indent.hash(state);
+        self.locked.hash(state);
+    }
 }
 
 impl CellStyle {
@@ -146,6 +174,8 @@ impl CellStyle {
                 .clone()
                 .or_else(|| self.number_format.clone()),
             text_wrap: other.text_wrap.or(self.text_wrap),
+            indent: other.indent.or(self.indent),
+            locked: other.locked.or(self.locked),
         }
     }
 
@@ -162,6 +192,8 @@ impl CellStyle {
             && self.border.is_none()
             && self.number_format.is_none()
             && self.text_wrap.is_none()
+            && self.indent.is_none()
+            && self.locked.is_none()
     }
 
     /// 转换为 rust_xlsxwriter 的 Format 对象
@@ -249,13 +281,23 @@ impl CellStyle {
             format = format.set_text_wrap();
         }
 
+        if let Some(indent) = self.indent
+            && indent > 0
+        {
+            format = format.set_indent(indent.min(MAX_INDENT));
+        }
+
+        if let Some(false) = self.locked {
+            format = format.set_unlocked();
+        }
+
         format
     }
 }
 
 /// 样式表，汇总一个 TableData 的所有样式信息
 ///
-/// 解析优先级：全局 → 列级 → 单元格级
+/// 解析优先级：全局 → 列级 → 行级 → 单元格级
 #[derive(Debug, Clone, Default)]
 pub struct StyleSheet {
     /// 表头行默认样式
@@ -266,10 +308,18 @@ pub struct StyleSheet {
     pub column_styles: Vec<Option<CellStyle>>,
     /// 列级表头样式（索引 = 列号）
     pub column_header_styles: Vec<Option<CellStyle>>,
+    /// 行级样式（去重后的样式列表，由 row_style_index 引用）
+    pub row_styles: Vec<CellStyle>,
+    /// 行级样式索引 row → row_styles 下标
+    pub row_style_index: HashMap<u32, usize>,
     /// 单元格级样式覆盖 (row, col) → style
     pub cell_overrides: HashMap<(u32, u16), CellStyle>,
     /// 列宽配置（索引 = 列号）
     pub column_widths: Vec<Option<f64>>,
+    /// 行高配置（磅）row → height
+    pub row_heights: HashMap<u32, f64>,
+    /// 日期单元格的默认数字格式（如 "yyyy/mm/dd"），None 时按是否含时间自动选择
+    pub date_format: Option<String>,
 }
 
 impl StyleSheet {
@@ -279,29 +329,37 @@ impl StyleSheet {
             && self.data_style.is_none()
             && self.column_styles.iter().all(|s| s.is_none())
             && self.column_header_styles.iter().all(|s| s.is_none())
+            && self.row_style_index.is_empty()
             && self.cell_overrides.is_empty()
             && self.column_widths.iter().all(|w| w.is_none())
+            && self.row_heights.is_empty()
+            && self.date_format.is_none()
     }
 
-    /// 仅解析「全局 + 列级」样式（不含单元格覆盖），便于按列缓存 Format
+    /// 仅解析「全局 + 列级」样式（不含行级与单元格覆盖），便于按列缓存 Format
     pub fn resolve_column(&self, is_header: bool, col: u16) -> Option<Format> {
+        self.resolve_column_style(is_header, col)
+            .map(|s| s.to_format())
+    }
+
+    /// 解析指定位置 (row, col) 的最终合并样式，并转换为 Format
+    ///
+    /// 合并优先级：全局 → 列级 → 行级 → 单元格级
+    /// 返回 None 表示该位置无任何样式设置
+    pub fn resolve(&self, row: u32, col: u16, header_row_count: usize) -> Option<Format> {
+        self.resolve_style(row, col, header_row_count)
+            .map(|s| s.to_format())
+    }
+
+    /// 解析「全局 + 列级」合并后的样式（空样式返回 None）
+    pub fn resolve_column_style(&self, is_header: bool, col: u16) -> Option<CellStyle> {
         let base = if is_header {
             self.header_style.as_ref()
         } else {
             self.data_style.as_ref()
         };
 
-        let col_style = if is_header {
-            self.column_header_styles
-                .get(col as usize)
-                .and_then(|s| s.as_ref())
-        } else {
-            self.column_styles
-                .get(col as usize)
-                .and_then(|s| s.as_ref())
-        };
-
-        let merged = match (base, col_style) {
+        let merged = match (base, self.column_style(is_header, col)) {
             (None, None) => return None,
             (Some(s), None) | (None, Some(s)) => s.clone(),
             (Some(base), Some(col)) => base.merge(col),
@@ -310,49 +368,71 @@ impl StyleSheet {
         if merged.is_empty() {
             return None;
         }
-        Some(merged.to_format())
+        Some(merged)
     }
 
-    /// 解析指定位置 (row, col) 的最终合并样式，并转换为 Format
-    ///
-    /// 合并优先级：全局 → 列级 → 单元格级
-    /// 返回 None 表示该位置无任何样式设置
-    pub fn resolve(&self, row: u32, col: u16, header_row_count: usize) -> Option<Format> {
+    /// 解析指定位置 (row, col) 的最终合并样式（空样式返回 None）
+    pub fn resolve_style(&self, row: u32, col: u16, header_row_count: usize) -> Option<CellStyle> {
         let is_header = (row as usize) < header_row_count;
+        let row_style = self.row_style(row);
+        let cell_style = self.cell_overrides.get(&(row, col));
 
-        // 无单元格覆盖时走列级路径（可被 write_sheet 缓存）
-        let Some(cell_style) = self.cell_overrides.get(&(row, col)) else {
-            return self.resolve_column(is_header, col);
-        };
-
-        let base = if is_header {
-            self.header_style.as_ref()
-        } else {
-            self.data_style.as_ref()
-        };
-
-        let col_style = if is_header {
-            self.column_header_styles
-                .get(col as usize)
-                .and_then(|s| s.as_ref())
-        } else {
-            self.column_styles
-                .get(col as usize)
-                .and_then(|s| s.as_ref())
-        };
+        // 无行级与单元格覆盖时走列级路径（可被 write_sheet 缓存）
+        if row_style.is_none() && cell_style.is_none() {
+            return self.resolve_column_style(is_header, col);
+        }
 
-        let merged = match (base, col_style) {
-            (None, None) => cell_style.clone(),
-            (Some(b), None) => b.merge(cell_style),
-            (None, Some(c)) => c.merge(cell_style),
-            (Some(b), Some(c)) => b.merge(c).merge(cell_style),
-        };
+        let merged = [row_style, cell_style].into_iter().flatten().fold(
+            self.resolve_column_style(is_header, col)
+                .unwrap_or_default(),
+            |acc, s| acc.merge(s),
+        );
 
         if merged.is_empty() {
             return None;
         }
+        Some(merged)
+    }
+
+    /// 列级、行级或单元格级显式设置的数字格式（不含全局样式）
+    ///
+    /// 日期单元格优先使用该格式，全局 cellStyle 的 numberFormat 不作用于日期
+    pub fn explicit_number_format(
+        &self,
+        row: u32,
+        col: u16,
+        header_row_count: usize,
+    ) -> Option<&str> {
+        let is_header = (row as usize) < header_row_count;
+        self.cell_overrides
+            .get(&(row, col))
+            .and_then(|s| s.number_format.as_deref())
+            .or_else(|| self.row_style(row).and_then(|s| s.number_format.as_deref()))
+            .or_else(|| {
+                self.column_style(is_header, col)
+                    .and_then(|s| s.number_format.as_deref())
+            })
+    }
+
+    /// 行级样式的下标（write_sheet 按下标缓存 Format）
+    pub fn row_style_slot(&self, row: u32) -> Option<usize> {
+        self.row_style_index.get(&row).copied()
+    }
+
+    /// 行级样式
+    fn row_style(&self, row: u32) -> Option<&CellStyle> {
+        self.row_style_slot(row)
+            .and_then(|i| self.row_styles.get(i))
+    }
 
-        Some(merged.to_format())
+    /// 列级样式（表头/数据）
+    fn column_style(&self, is_header: bool, col: u16) -> Option<&CellStyle> {
+        let styles = if is_header {
+            &self.column_header_styles
+        } else {
+            &self.column_styles
+        };
+        styles.get(col as usize).and_then(|s| s.as_ref())
     }
 }
 
@@ -383,6 +463,79 @@ pub fn normalize_hex_color(color: &str) -> String {
     }
 }
 
+/// CSS 基本颜色名与对应的 hex 颜色
+const CSS_NAMED_COLORS: [(&str, &str); 20] = [
+    ("black", "#000000"),
+    ("white", "#FFFFFF"),
+    ("red", "#FF0000"),
+    ("green", "#008000"),
+    ("blue", "#0000FF"),
+    ("yellow", "#FFFF00"),
+    ("orange", "#FFA500"),
+    ("purple", "#800080"),
+    ("gray", "#808080"),
+    ("grey", "#808080"),
+    ("silver", "#C0C0C0"),
+    ("maroon", "#800000"),
+    ("navy", "#000080"),
+    ("teal", "#008080"),
+    ("olive", "#808000"),
+    ("lime", "#00FF00"),
+    ("aqua", "#00FFFF"),
+    ("cyan", "#00FFFF"),
+    ("fuchsia", "#FF00FF"),
+    ("magenta", "#FF00FF"),
+];
+
+/// 将 CSS 颜色值转为 `#RRGGBB`
+///
+/// 支持 `#RGB` / `#RRGGBB`、`rgb()` / `rgba()`（逗号或空格分隔，分量可为百分比）与基本颜色名；
+/// 完全透明（`transparent`、alpha 为 0）或无法识别时返回 None
+pub fn parse_css_color(value: &str) -> Option<String> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let normalized = normalize_hex_color(hex);
+        return (normalized.len() == 7 && normalized.starts_with('#'))
+            .then(|| normalized.to_ascii_uppercase());
+    }
+
+    let lower = value.to_ascii_lowercase();
+    if let Some(&(_, hex)) = CSS_NAMED_COLORS.iter().find(|(name, _)| *name == lower) {
+        return Some(hex.to_string());
+    }
+
+    let args = lower
+        .strip_prefix("rgba(")
+        .or_else(|| lower.strip_prefix("rgb("))?
+        .strip_suffix(')')?;
+    let parts: Vec<&str> = args
+        .split([',', ' ', '/'])
+        .filter(|p| !p.is_empty())
+        .collect();
+    if !(3..=4).contains(&parts.len()) {
+        return None;
+    }
+
+    // 百分比按比例换算，其余按数值解析
+    let component = |part: &str, scale: f64| -> Option<f64> {
+        let n = match part.strip_suffix('%') {
+            Some(pct) => pct.parse::<f64>().ok()? / 100.0 * scale,
+            None => part.parse::<f64>().ok()?,
+        };
+        n.is_finite().then(|| n.clamp(0.0, scale))
+    };
+    if let Some(alpha) = parts.get(3)
+        && component(alpha, 1.0)? == 0.0
+    {
+        return None;
+    }
+    let mut hex = String::from("#");
+    for part in &parts[..3] {
+        hex.push_str(&format!("{:02X}", component(part, 255.0)?.round() as u8));
+    }
+    Some(hex)
+}
+
 /// 从 JsValue 解析单元格样式
 ///
 /// 接受 JS 对象格式：
@@ -462,6 +615,17 @@ pub fn parse_cell_style(val: &wasm_bindgen::JsValue) -> Option<CellStyle> {
         .ok()
         .and_then(|v| v.as_bool());
 
+    // 解析 indent（0–15 的整数，其余值忽略）
+    let indent = js_sys::Reflect::get(val, &wasm_bindgen::JsValue::from_str("indent"))
+        .ok()
+        .and_then(|v| v.as_f64())
+        .filter(|n| n.fract() == 0.0 && (0.0..=MAX_INDENT as f64).contains(n))
+        .map(|n| n as u8);
+
+    let locked = js_sys::Reflect::get(val, &wasm_bindgen::JsValue::from_str("locked"))
+        .ok()
+        .and_then(|v| v.as_bool());
+
     let style = CellStyle {
         bold,
         italic,
@@ -474,6 +638,8 @@ pub fn parse_cell_style(val: &wasm_bindgen::JsValue) -> Option<CellStyle> {
         border,
         number_format,
         text_wrap,
+        indent,
+        locked,
     };
 
     if style.is_empty() { None } else { Some(style) }
@@ -557,6 +723,30 @@ mod tests {
         assert_eq!(normalize_hex_color(""), "");
     }
 
+    #[test]
+    fn test_parse_css_color() {
+        assert_eq!(parse_css_color("#f00").as_deref(), Some("#FF0000"));
+        assert_eq!(parse_css_color("#1a2B3c").as_deref(), Some("#1A2B3C"));
+        assert_eq!(
+            parse_css_color("rgb(255, 0, 0)").as_deref(),
+            Some("#FF0000")
+        );
+        assert_eq!(
+            parse_css_color("rgba(0, 128, 255, 0.5)").as_deref(),
+            Some("#0080FF")
+        );
+        assert_eq!(
+            parse_css_color("rgb(100% 0% 50%)").as_deref(),
+            Some("#FF0080")
+        );
+        assert_eq!(parse_css_color(" Red ").as_deref(), Some("#FF0000"));
+        // 透明与无法识别的颜色
+        assert_eq!(parse_css_color("rgba(0, 0, 0, 0)"), None);
+        assert_eq!(parse_css_color("transparent"), None);
+        assert_eq!(parse_css_color("hsl(0, 100%, 50%)"), None);
+        assert_eq!(parse_css_color("#12"), None);
+    }
+
     #[test]
     fn test_cell_style_is_empty() {
         let empty = CellStyle::default();
@@ -607,6 +797,8 @@ mod tests {
             border: Some(BorderConfig::All),
             number_format: Some("#,##0.00".to_string()),
             text_wrap: Some(true),
+            indent: Some(2),
+            locked: Some(false),
         };
 
         let _format = style.to_format();
@@ -745,4 +937,45 @@ mod tests {
         let format = sheet.resolve(0, 1, 1);
         assert!(format.is_some());
     }
+
+    #[test]
+    fn test_stylesheet_resolve_row_tier() {
+        let mut sheet = StyleSheet {
+            data_style: Some(CellStyle {
+                bold: Some(true),
+                ..Default::default()
+            }),
+            column_styles: vec![Some(CellStyle {
+                background_color: Some("#FFFFFF".to_string()),
+                number_format: Some("0.00".to_string()),
+                ..Default::default()
+            })],
+            row_styles: vec![CellStyle {
+                background_color: Some("#F5F5F5".to_string()),
+                font_color: Some("#333333".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        sheet.row_style_index.insert(2, 0);
+        sheet.cell_overrides.insert(
+            (2, 0),
+            CellStyle {
+                font_color: Some("#FF0000".to_string()),
+                ..Default::default()
+            },
+        );
+
+        // 行级覆盖列级背景色，单元格级覆盖行级字体颜色
+        let style = sheet.resolve_style(2, 0, 1).unwrap();
+        assert_eq!(style.bold, Some(true));
+        assert_eq!(style.background_color.as_deref(), Some("#F5F5F5"));
+        assert_eq!(style.font_color.as_deref(), Some("#FF0000"));
+        assert_eq!(style.number_format.as_deref(), Some("0.00"));
+
+        // 无行级样式的行保持列级样式
+        let style = sheet.resolve_style(1, 0, 1).unwrap();
+        assert_eq!(style.background_color.as_deref(), Some("#FFFFFF"));
+        assert!(!sheet.is_empty());
+    }
 }
@@ -1,4 +1,5 @@
 use crate::utils::{ensure_external_tbody, is_element_hidden};
+use crate::validation::sanitize_hyperlink;
 use std::collections::HashMap;
 use wasm_bindgen::JsCast;
 /// 表格数据提取模块
@@ -6,8 +7,8 @@ use wasm_bindgen::JsCast;
 /// 提供从 DOM 中提取表格数据的功能，支持合并单元格（colspan/rowspan）
 use wasm_bindgen::prelude::*;
 use web_sys::{
-    Element, HtmlCollection, HtmlTableCellElement, HtmlTableElement, HtmlTableRowElement,
-    HtmlTableSectionElement,
+    Element, HtmlAnchorElement, HtmlCollection, HtmlTableCellElement, HtmlTableElement,
+    HtmlTableRowElement, HtmlTableSectionElement,
 };
 
 /// 根据 ID 查找 table 元素
@@ -76,17 +77,364 @@ impl MergeRange {
     }
 }
 
+/// 单元格值
+///
+/// XLSX 按类型写入原生单元格（数字可参与求和/筛选），CSV 统一转为文本输出
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CellValue {
+    /// 空单元格（null / undefined）
+    #[default]
+    Empty,
+    /// 文本
+    Text(String),
+    /// 数字（仅有限值，NaN/Infinity 在解析时归为 Empty）
+    Number(f64),
+    /// 布尔值
+    Bool(bool),
+    /// 日期/日期时间（XLSX 写为带日期格式的原生日期单元格）
+    DateTime(CellDateTime),
+    /// 公式（XLSX 写为公式单元格，CSV 输出缓存值）
+    Formula(CellFormula),
+    /// 超链接（XLSX 写为可点击链接，CSV 输出显示文本）
+    Link(CellLink),
+    /// 富文本（XLSX 写为带片段格式的字符串，CSV 输出拼接后的纯文本）
+    RichText(Vec<super::rich_text::RichTextRun>),
+}
+
+impl CellValue {
+    /// 获取文本表示（与 CSV 输出一致）
+    pub fn as_text(&self) -> std::borrow::Cow<'_, str> {
+        match self {
+            CellValue::Empty => std::borrow::Cow::Borrowed(""),
+            CellValue::Text(s) => std::borrow::Cow::Borrowed(s.as_str()),
+            CellValue::Number(n) => super::data_export::format_js_number(*n).into(),
+            CellValue::Bool(b) => std::borrow::Cow::Borrowed(if *b { "true" } else { "false" }),
+            CellValue::DateTime(dt) => dt.format(dt.default_pattern()).into(),
+            CellValue::Formula(f) => f.cached.as_text(),
+            CellValue::Link(l) if l.text.is_empty() => std::borrow::Cow::Borrowed(l.url.as_str()),
+            CellValue::Link(l) => std::borrow::Cow::Borrowed(l.text.as_str()),
+            CellValue::RichText(runs) => super::rich_text::rich_text_plain(runs).into(),
+        }
+    }
+
+    /// 转为文本（文本值零拷贝移出）
+    pub fn into_text(self) -> String {
+        match self {
+            CellValue::Text(s) => s,
+            other => other.as_text().into_owned(),
+        }
+    }
+
+    /// 转为文本，日期按指定模式渲染（`None` 时与 `into_text` 一致）
+    pub fn into_text_with_date_format(self, date_format: Option<&str>) -> String {
+        match self {
+            CellValue::DateTime(dt) => dt.format(date_format.unwrap_or(dt.default_pattern())),
+            CellValue::Formula(f) => f.cached.into_text_with_date_format(date_format),
+            other => other.into_text(),
+        }
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(s: String) -> Self {
+        CellValue::Text(s)
+    }
+}
+
+impl From<&str> for CellValue {
+    fn from(s: &str) -> Self {
+        CellValue::Text(s.to_string())
+    }
+}
+
+/// 公式单元格
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellFormula {
+    /// 公式文本（如 "=SUM(B2:B10)"，占位符已展开）
+    pub formula: String,
+    /// 缓存结果（CSV 输出该值；XLSX 中作为重新计算前的显示值）
+    pub cached: Box<CellValue>,
+}
+
+/// 超链接单元格
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellLink {
+    /// 已通过安全校验的链接地址（`#Sheet2!A1` 形式表示工作簿内部链接）
+    pub url: String,
+    /// 显示文本（为空时显示链接地址）
+    pub text: String,
+}
+
+/// 日期时间值（本地时间，不含时区）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CellDateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub millisecond: u16,
+    /// 是否包含时间部分（决定默认显示格式）
+    pub has_time: bool,
+}
+
+/// ISO-8601 字符串解析结果
+pub struct IsoDateTime {
+    /// 字符串中的日期时间字面值
+    pub value: CellDateTime,
+    /// 是否带有时区标识（`Z` 或 `±HH:mm`），带时区时需换算为本地时间
+    pub has_zone: bool,
+}
+
+impl CellDateTime {
+    /// 仅日期的默认渲染模式
+    pub const DATE_PATTERN: &'static str = "YYYY-MM-DD";
+    /// 日期时间的默认渲染模式
+    pub const DATETIME_PATTERN: &'static str = "YYYY-MM-DD HH:mm:ss";
+
+    /// 创建仅日期值（非法日期返回 None）
+    pub fn from_ymd(year: u16, month: u8, day: u8) -> Option<Self> {
+        if year > 9999 || !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month)
+        {
+            return None;
+        }
+        Some(Self {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            millisecond: 0,
+            has_time: false,
+        })
+    }
+
+    /// 附加时间部分（非法时间返回 None）
+    pub fn and_hms_milli(self, hour: u8, minute: u8, second: u8, millisecond: u16) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 || millisecond > 999 {
+            return None;
+        }
+        Some(Self {
+            hour,
+            minute,
+            second,
+            millisecond,
+            has_time: true,
+            ..self
+        })
+    }
+
+    /// 截断为仅日期
+    pub fn date_only(self) -> Self {
+        Self {
+            hour: 0,
+            minute: 0,
+            second: 0,
+            millisecond: 0,
+            has_time: false,
+            ..self
+        }
+    }
+
+    /// 标记为日期时间（保留已有时间部分）
+    pub fn with_time_flag(self) -> Self {
+        Self {
+            has_time: true,
+            ..self
+        }
+    }
+
+    /// 默认渲染模式
+    pub fn default_pattern(&self) -> &'static str {
+        if self.has_time {
+            Self::DATETIME_PATTERN
+        } else {
+            Self::DATE_PATTERN
+        }
+    }
+
+    /// 按模式渲染为文本
+    ///
+    /// 支持 dayjs 风格的占位符：`YYYY` `YY` `MM` `M` `DD` `D` `HH` `H` `mm` `m` `ss` `s` `SSS`，
+    /// `[...]` 内为原样输出的字面量
+    pub fn format(&self, pattern: &str) -> String {
+        const TOKENS: [&str; 13] = [
+            "YYYY", "SSS", "YY", "MM", "DD", "HH", "mm", "ss", "M", "D", "H", "m", "s",
+        ];
+
+        let mut out = String::with_capacity(pattern.len() + 8);
+        let mut rest = pattern;
+        'outer: while !rest.is_empty() {
+            if let Some(literal) = rest.strip_prefix('[')
+                && let Some(end) = literal.find(']')
+            {
+                out.push_str(&literal[..end]);
+                rest = &literal[end + 1..];
+                continue;
+            }
+            for token in TOKENS {
+                if let Some(tail) = rest.strip_prefix(token) {
+                    match token {
+                        "YYYY" => out.push_str(&format!("{:04}", self.year)),
+                        "YY" => out.push_str(&format!("{:02}", self.year % 100)),
+                        "MM" => out.push_str(&format!("{:02}", self.month)),
+                        "M" => out.push_str(&self.month.to_string()),
+                        "DD" => out.push_str(&format!("{:02}", self.day)),
+                        "D" => out.push_str(&self.day.to_string()),
+                        "HH" => out.push_str(&format!("{:02}", self.hour)),
+                        "H" => out.push_str(&self.hour.to_string()),
+                        "mm" => out.push_str(&format!("{:02}", self.minute)),
+                        "m" => out.push_str(&self.minute.to_string()),
+                        "ss" => out.push_str(&format!("{:02}", self.second)),
+                        "s" => out.push_str(&self.second.to_string()),
+                        _ => out.push_str(&format!("{:03}", self.millisecond)),
+                    }
+                    rest = tail;
+                    continue 'outer;
+                }
+            }
+            let ch = rest.chars().next().unwrap_or_default();
+            out.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+        out
+    }
+
+    /// 解析 ISO-8601 日期/日期时间字符串
+    ///
+    /// 支持 `YYYY-MM-DD`、`YYYY-MM-DDTHH:mm[:ss[.SSS]]`（`T` 可替换为空格），
+    /// 以及可选的 `Z` / `±HH:mm` / `±HHmm` 时区后缀；其他格式返回 None
+    pub fn parse_iso(s: &str) -> Option<IsoDateTime> {
+        let s = s.trim();
+        let b = s.as_bytes();
+        if b.len() < 10 || b[4] != b'-' || b[7] != b'-' {
+            return None;
+        }
+        let date = Self::from_ymd(
+            parse_digits(&b[0..4])? as u16,
+            parse_digits(&b[5..7])? as u8,
+            parse_digits(&b[8..10])? as u8,
+        )?;
+        if b.len() == 10 {
+            return Some(IsoDateTime {
+                value: date,
+                has_zone: false,
+            });
+        }
+
+        // 时间部分：[T ]HH:mm[:ss[.fff]]
+        if !matches!(b[10], b'T' | b't' | b' ') || b.len() < 16 || b[13] != b':' {
+            return None;
+        }
+        let hour = parse_digits(&b[11..13])? as u8;
+        let minute = parse_digits(&b[14..16])? as u8;
+        let mut pos = 16;
+        let mut second = 0;
+        let mut millisecond = 0;
+        if b.get(pos) == Some(&b':') {
+            second = parse_digits(b.get(pos + 1..pos + 3)?)? as u8;
+            pos += 3;
+            if b.get(pos) == Some(&b'.') {
+                let start = pos + 1;
+                let mut end = start;
+                while end < b.len() && b[end].is_ascii_digit() {
+                    end += 1;
+                }
+                if end == start {
+                    return None;
+                }
+                // 只保留毫秒精度，多余位截断
+                let frac = &b[start..end.min(start + 3)];
+                millisecond = parse_digits(frac)? as u16 * 10u16.pow(3 - frac.len() as u32);
+                pos = end;
+            }
+        }
+        let value = date.and_hms_milli(hour, minute, second, millisecond)?;
+
+        // 时区后缀
+        let zone = &b[pos..];
+        let has_zone = match zone {
+            [] => false,
+            [b'Z' | b'z'] => true,
+            [b'+' | b'-', h1, h2, b':', m1, m2] | [b'+' | b'-', h1, h2, m1, m2] => {
+                parse_digits(&[*h1, *h2])?;
+                parse_digits(&[*m1, *m2])?;
+                true
+            }
+            _ => return None,
+        };
+
+        Some(IsoDateTime { value, has_zone })
+    }
+}
+
+/// 解析纯 ASCII 数字片段
+fn parse_digits(bytes: &[u8]) -> Option<u32> {
+    if bytes.is_empty() || !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(
+        bytes
+            .iter()
+            .fold(0, |acc, b| acc * 10 + u32::from(b - b'0')),
+    )
+}
+
+/// 指定年月的天数（公历闰年规则）
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// 将带类型的行数据转为纯文本行（CSV 导出使用）
+///
+/// `date_format` 为日期单元格的渲染模式，`None` 时按是否含时间使用默认模式
+pub fn cells_to_text_rows(
+    rows: Vec<Vec<CellValue>>,
+    date_format: Option<&str>,
+) -> Vec<Vec<String>> {
+    rows.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|cell| cell.into_text_with_date_format(date_format))
+                .collect()
+        })
+        .collect()
+}
+
 /// 表格数据结构，包含单元格数据和合并信息
 #[derive(Debug, Clone)]
 pub struct TableData {
-    /// 二维字符串数组，表示表格数据
-    pub rows: Vec<Vec<String>>,
+    /// 二维单元格数组，表示表格数据
+    pub rows: Vec<Vec<CellValue>>,
     /// 合并单元格区域列表
     pub merge_ranges: Vec<MergeRange>,
     /// 表头行数（用于 XLSX 冻结窗格），0 表示无表头
     pub header_row_count: usize,
+    /// 表尾行数（合计行等，位于数据区之后），0 表示无表尾
+    pub footer_row_count: usize,
     /// 可选的样式表（仅 XLSX 导出时使用）
     pub style_sheet: Option<super::style::StyleSheet>,
+    /// 工作表级选项（仅 XLSX 导出时使用）
+    pub sheet_options: super::sheet_options::SheetOptions,
+    /// 列数据验证（列索引, 验证配置），仅 XLSX 导出时使用
+    pub data_validations: Vec<(u16, super::data_validation::ColumnValidation)>,
+    /// 列级条件格式，仅 XLSX 导出时使用（工作表级规则见 `sheet_options`）
+    pub conditional_formats: Vec<super::conditional_format::ConditionalFormatRule>,
+    /// 单元格批注（行, 列）→ 批注文本，XLSX 写为 Excel 批注，CSV 默认忽略
+    pub notes: std::collections::HashMap<(u32, u16), String>,
+    /// 单元格图片（行, 列）→ 图片，仅 XLSX 导出时使用
+    pub images: std::collections::HashMap<(u32, u16), super::cell_image::CellImage>,
+    /// 列配置中启用自动列宽的列索引（工作表级开关见 `sheet_options`）
+    pub auto_width_columns: Vec<u16>,
+    /// 树形数据的行大纲（`outline` 模式），仅 XLSX 导出时使用
+    pub outline: Option<super::outline::RowOutline>,
 }
 
 impl TableData {
@@ -96,7 +444,15 @@ impl TableData {
             rows: Vec::new(),
             merge_ranges: Vec::new(),
             header_row_count: 0,
+            footer_row_count: 0,
             style_sheet: None,
+            sheet_options: Default::default(),
+            data_validations: Vec::new(),
+            conditional_formats: Vec::new(),
+            notes: std::collections::HashMap::new(),
+            images: std::collections::HashMap::new(),
+            auto_width_columns: Vec::new(),
+            outline: None,
         }
     }
 
@@ -106,14 +462,43 @@ impl TableData {
             rows: Vec::with_capacity(capacity),
             merge_ranges: Vec::new(),
             header_row_count: 0,
+            footer_row_count: 0,
             style_sheet: None,
+            sheet_options: Default::default(),
+            data_validations: Vec::new(),
+            conditional_formats: Vec::new(),
+            notes: std::collections::HashMap::new(),
+            images: std::collections::HashMap::new(),
+            auto_width_columns: Vec::new(),
+            outline: None,
+        }
+    }
+
+    /// 记录单元格级样式（如 DOM 提取时采集的页面样式）
+    pub fn insert_cell_style(&mut self, row: u32, col: u16, style: super::style::CellStyle) {
+        self.style_sheet
+            .get_or_insert_with(Default::default)
+            .cell_overrides
+            .insert((row, col), style);
+    }
+
+    /// 注入全局表头 / 数据行样式，保留提取阶段已记录的单元格样式
+    pub fn apply_global_styles(
+        &mut self,
+        header_style: Option<&super::style::CellStyle>,
+        data_style: Option<&super::style::CellStyle>,
+    ) {
+        if header_style.is_none() && data_style.is_none() {
+            return;
         }
+        let style_sheet = self.style_sheet.get_or_insert_with(Default::default);
+        style_sheet.header_style = header_style.cloned();
+        style_sheet.data_style = data_style.cloned();
     }
 
     /// 获取纯文本数据（用于 CSV 导出等场景）
-    #[allow(dead_code)]
     pub fn into_rows(self) -> Vec<Vec<String>> {
-        self.rows
+        cells_to_text_rows(self.rows, None)
     }
 }
 
@@ -244,6 +629,21 @@ pub fn get_table_row(
         .map_err(|_| JsValue::from_str(&format!("第 {} 行不是有效的表格行", row_idx + 1)))
 }
 
+/// DOM 提取时的可选采集项（默认全部关闭，仅 XLSX 导出使用）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DomCaptureOptions {
+    /// 采集单元格内第一个 `<a href>` 的链接地址
+    pub links: bool,
+    /// 采集单元格的 `data-note`（优先）或 `title` 属性作为批注
+    pub notes: bool,
+    /// 采集单元格内第一个 `<img>` 的 data URL 作为图片
+    pub images: bool,
+    /// 将单元格内的粗体、颜色、删除线等内联格式提取为富文本
+    pub rich_text: bool,
+    /// 读取单元格的计算样式（字体、颜色、背景、对齐、边框），导出为单元格样式
+    pub styles: bool,
+}
+
 /// 单行处理结果
 pub struct RowProcessResult {
     /// 行数据（单元格文本列表）
@@ -263,6 +663,7 @@ pub struct RowProcessResult {
 /// * `row_idx` - 当前行在原始表格中的索引（用于 tracker 和错误信息）
 /// * `tracker` - rowspan 追踪器
 /// * `exclude_hidden` - 是否排除隐藏的列
+/// * `capture` - 额外采集项（链接、批注等，结果记录在 `cell_spans` 中）
 ///
 /// # 返回值
 /// * `Ok(RowProcessResult)` - 包含行数据和单元格跨度信息
@@ -272,6 +673,7 @@ pub fn process_row_cells(
     row_idx: u32,
     tracker: &mut RowSpanTracker,
     exclude_hidden: bool,
+    capture: &DomCaptureOptions,
 ) -> Result<RowProcessResult, JsValue> {
     let mut row_data = Vec::new();
     let mut cell_spans = Vec::new();
@@ -306,7 +708,7 @@ pub fn process_row_cells(
             continue;
         }
 
-        let span = get_cell_span(&cell);
+        let span = get_cell_span(&cell, capture);
 
         // 记录单元格位置和跨度信息（供调用方计算合并区域）
         cell_spans.push((col_idx, span.clone()));
@@ -344,28 +746,122 @@ pub(crate) struct CellSpan {
     pub colspan: u32,
     /// 行跨度（rowspan 属性值）
     pub rowspan: u32,
+    /// 单元格内第一个链接（已通过安全校验，需开启 `DomCaptureOptions::links`）
+    pub link: Option<String>,
+    /// 单元格批注（需开启 `DomCaptureOptions::notes`）
+    pub note: Option<String>,
+    /// 单元格内第一张图片（data URL, alt），需开启 `DomCaptureOptions::images`
+    pub image: Option<(String, Option<String>)>,
+    /// 单元格富文本片段（需开启 `DomCaptureOptions::rich_text`，无内联格式时为 None）
+    pub rich_text: Option<Vec<super::rich_text::RichTextRun>>,
+    /// 单元格的页面计算样式（需开启 `DomCaptureOptions::styles`）
+    pub style: Option<super::style::CellStyle>,
+}
+
+impl CellSpan {
+    /// 转为单元格值（链接优先于富文本，二者皆无时为纯文本）
+    pub fn to_cell_value(&self) -> CellValue {
+        match (&self.link, &self.rich_text) {
+            (Some(url), _) => CellValue::Link(CellLink {
+                url: url.clone(),
+                text: self.text.clone(),
+            }),
+            (None, Some(runs)) => CellValue::RichText(runs.clone()),
+            (None, None) => CellValue::Text(self.text.clone()),
+        }
+    }
+
+    /// 是否需要以非纯文本的单元格值写入
+    pub fn has_typed_value(&self) -> bool {
+        self.link.is_some() || self.rich_text.is_some()
+    }
 }
 
 /// 获取单元格的跨度信息
 ///
 /// # 参数
 /// * `cell` - HTML 表格单元格元素
+/// * `capture` - 额外采集项
 ///
 /// # 返回值
 /// 包含文本内容和跨度信息的 CellSpan 结构
-pub(crate) fn get_cell_span(cell: &HtmlTableCellElement) -> CellSpan {
+pub(crate) fn get_cell_span(cell: &HtmlTableCellElement, capture: &DomCaptureOptions) -> CellSpan {
     let text = cell.inner_text();
     // colspan/rowspan 最小为 1
     let colspan = cell.col_span().max(1);
     let rowspan = cell.row_span().max(1);
+    let link = if capture.links {
+        first_anchor_href(cell)
+    } else {
+        None
+    };
+    let note = if capture.notes {
+        cell_note_attribute(cell)
+    } else {
+        None
+    };
+    let image = if capture.images {
+        first_image_source(cell)
+    } else {
+        None
+    };
+    let rich_text = if capture.rich_text {
+        super::rich_text::extract_rich_text(cell)
+    } else {
+        None
+    };
+    let style = if capture.styles {
+        super::computed_style::computed_cell_style(cell)
+    } else {
+        None
+    };
 
     CellSpan {
         text,
         colspan,
         rowspan,
+        link,
+        note,
+        image,
+        rich_text,
+        style,
     }
 }
 
+/// 读取单元格内第一个 `<img>` 的 data URL 与 alt
+///
+/// 同步导出无法下载远程图片，只采集 PNG / JPEG / GIF 的 data URL，其余图片忽略
+fn first_image_source(cell: &HtmlTableCellElement) -> Option<(String, Option<String>)> {
+    let img = cell.query_selector("img[src]").ok()??;
+    let src = img.get_attribute("src")?;
+    super::cell_image::data_url_payload(&src).ok()?;
+    let alt = img.get_attribute("alt").filter(|s| !s.trim().is_empty());
+    Some((src, alt))
+}
+
+/// 读取单元格的批注属性：`data-note` 优先，其次 `title`，忽略空白值
+fn cell_note_attribute(cell: &HtmlTableCellElement) -> Option<String> {
+    ["data-note", "title"]
+        .into_iter()
+        .filter_map(|name| cell.get_attribute(name))
+        .find(|v| !v.trim().is_empty())
+}
+
+/// 读取单元格内第一个 `<a href>` 的链接地址
+///
+/// `#` 开头的地址保留原值（视为工作簿内部链接），其余使用浏览器解析后的绝对地址；
+/// 未通过安全校验的地址（如 `javascript:`）被丢弃
+fn first_anchor_href(cell: &HtmlTableCellElement) -> Option<String> {
+    let anchor = cell.query_selector("a[href]").ok()??;
+    let raw = anchor.get_attribute("href")?;
+    let href = if raw.trim_start().starts_with('#') {
+        raw
+    } else {
+        anchor.dyn_into::<HtmlAnchorElement>().ok()?.href()
+    };
+    sanitize_hyperlink(&href).ok()
+}
+
 /// 用于追踪被 rowspan 占用的单元格
 pub struct RowSpanTracker {
     tracker: HashMap<(u32, usize), String>,
@@ -440,7 +936,13 @@ pub fn extract_table_data(
             continue;
         }
 
-        let proc_result = process_row_cells(&row, row_idx, &mut tracker, exclude_hidden)?;
+        let proc_result = process_row_cells(
+            &row,
+            row_idx,
+            &mut tracker,
+            exclude_hidden,
+            &DomCaptureOptions::default(),
+        )?;
         result.push(proc_result.row_data);
     }
 
@@ -454,6 +956,7 @@ pub fn extract_table_data(
 /// # 参数
 /// * `table_id` - HTML 表格元素的 ID
 /// * `exclude_hidden` - 是否排除隐藏的行和列
+/// * `capture` - 额外采集项（链接、批注、图片等）
 ///
 /// # 返回值
 /// * `Ok(TableData)` - 包含表格数据和合并区域信息
@@ -461,6 +964,7 @@ pub fn extract_table_data(
 pub fn extract_table_data_with_merge(
     table_id: &str,
     exclude_hidden: bool,
+    capture: &DomCaptureOptions,
 ) -> Result<TableData, JsValue> {
     let table = resolve_table(table_id)?;
     let rows = table.rows();
@@ -480,6 +984,7 @@ pub fn extract_table_data_with_merge(
     result.header_row_count = header_row_count;
     let mut tracker = RowSpanTracker::new();
     let mut output_row_idx: u32 = 0;
+    let mut image_budget = super::cell_image::ImageBudget::default();
 
     for row_idx in 0..row_count {
         let row = get_table_row(&rows, row_idx)?;
@@ -488,7 +993,7 @@ pub fn extract_table_data_with_merge(
             continue;
         }
 
-        let proc_result = process_row_cells(&row, row_idx, &mut tracker, exclude_hidden)?;
+        let proc_result = process_row_cells(&row, row_idx, &mut tracker, exclude_hidden, capture)?;
 
         // 根据 cell_spans 计算合并区域
         compute_merge_ranges(
@@ -500,7 +1005,48 @@ pub fn extract_table_data_with_merge(
             &mut result.merge_ranges,
         );
 
-        result.rows.push(proc_result.row_data);
+        let mut row_cells: Vec<CellValue> = proc_result
+            .row_data
+            .into_iter()
+            .map(CellValue::Text)
+            .collect();
+        // 带链接 / 富文本的单元格替换为对应的值，批注、图片与页面样式按输出位置记录
+        for (col_idx, span) in &proc_result.cell_spans {
+            if span.has_typed_value()
+                && let Some(cell) = row_cells.get_mut(*col_idx)
+            {
+                *cell = span.to_cell_value();
+            }
+            let Ok(col) = u16::try_from(*col_idx) else {
+                continue;
+            };
+            if let Some(ref note) = span.note {
+                result.notes.insert((output_row_idx, col), note.clone());
+            }
+            if let Some(ref style) = span.style {
+                result.insert_cell_style(output_row_idx, col, style.clone());
+            }
+            if let Some((ref src, ref alt)) = span.image {
+                // 无效或超出上限的图片跳过，不影响整张表导出；单元格无文本时以 alt 代替
+                match super::cell_image::decode_dom_image(src, &mut image_budget) {
+                    Some(data) => {
+                        result.images.insert(
+                            (output_row_idx, col),
+                            super::cell_image::CellImage {
+                                data,
+                                alt: alt.clone(),
+                            },
+                        );
+                    }
+                    None => {
+                        if let Some(cell) = row_cells.get_mut(*col_idx) {
+                            super::cell_image::fill_image_alt(cell, alt.as_ref());
+                        }
+                    }
+                }
+            }
+        }
+        result.rows.push(row_cells);
         output_row_idx += 1;
     }
 
@@ -584,3 +1130,90 @@ pub(crate) fn count_visible_rows(
     }
     visible_rows_covered
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_value_text_matches_csv_output() {
+        assert_eq!(CellValue::Number(42.0).as_text(), "42");
+        assert_eq!(CellValue::Number(0.1 + 0.2).as_text(), "0.3");
+        assert_eq!(CellValue::Number(-5.0).as_text(), "-5");
+        assert_eq!(CellValue::Bool(true).as_text(), "true");
+        assert_eq!(CellValue::Bool(false).as_text(), "false");
+        assert_eq!(CellValue::Empty.as_text(), "");
+        assert_eq!(CellValue::from("张三").into_text(), "张三");
+    }
+
+    #[test]
+    fn test_table_data_into_rows() {
+        let table_data = TableData {
+            rows: vec![
+                vec![CellValue::from("姓名"), CellValue::from("年龄")],
+                vec![CellValue::from("张三"), CellValue::Number(28.0)],
+                vec![CellValue::Empty, CellValue::Bool(true)],
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            table_data.into_rows(),
+            vec![
+                vec!["姓名".to_string(), "年龄".to_string()],
+                vec!["张三".to_string(), "28".to_string()],
+                vec![String::new(), "true".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_iso_date_and_datetime() {
+        let date = CellDateTime::parse_iso("2024-02-29").unwrap();
+        assert_eq!(date.value, CellDateTime::from_ymd(2024, 2, 29).unwrap());
+        assert!(!date.has_zone);
+
+        let dt = CellDateTime::parse_iso("2024-03-05T08:09:10.5").unwrap();
+        assert_eq!(
+            dt.value,
+            CellDateTime::from_ymd(2024, 3, 5)
+                .and_then(|d| d.and_hms_milli(8, 9, 10, 500))
+                .unwrap()
+        );
+        assert!(!dt.has_zone);
+
+        assert!(
+            CellDateTime::parse_iso("2024-03-05 08:09Z")
+                .unwrap()
+                .has_zone
+        );
+        assert!(
+            CellDateTime::parse_iso("2024-03-05T08:09:10+08:00")
+                .unwrap()
+                .has_zone
+        );
+
+        // 非法日期 / 非 ISO 文本
+        assert!(CellDateTime::parse_iso("2023-02-29").is_none());
+        assert!(CellDateTime::parse_iso("2024-13-01").is_none());
+        assert!(CellDateTime::parse_iso("20240301").is_none());
+        assert!(CellDateTime::parse_iso("2024-03-05T25:00").is_none());
+        assert!(CellDateTime::parse_iso("2024-03-05 备注").is_none());
+    }
+
+    #[test]
+    fn test_date_cell_text_rendering() {
+        let dt = CellDateTime::from_ymd(2024, 3, 5)
+            .and_then(|d| d.and_hms_milli(8, 9, 10, 7))
+            .unwrap();
+        assert_eq!(CellValue::DateTime(dt).as_text(), "2024-03-05 08:09:10");
+        assert_eq!(CellValue::DateTime(dt.date_only()).as_text(), "2024-03-05");
+        assert_eq!(dt.format("YYYY/M/D H:m:s.SSS"), "2024/3/5 8:9:10.007");
+        assert_eq!(dt.format("YY年MM月DD日 [YYYY]"), "24年03月05日 YYYY");
+
+        let rows = cells_to_text_rows(
+            vec![vec![CellValue::DateTime(dt), CellValue::Number(1.0)]],
+            Some("DD.MM.YYYY"),
+        );
+        assert_eq!(rows, vec![vec!["05.03.2024".to_string(), "1".to_string()]]);
+    }
+}
@@ -0,0 +1,2338 @@
+/// 模板导出模块
+///
+/// 打开设计好的 XLSX 模板，替换 `{{field}}` 占位符并按数组数据展开 `{{#rows}}` 行区域。
+/// 区域下方的行、合并单元格、公式引用与条件格式范围随之下移，其他工作表与定义名称中指向展开区域的引用一并调整，
+/// Excel 表格、绘图与批注等关联部件中的位置随行移动，样式、图片、主题等其余部件按原始压缩数据逐字节复制
+use super::data_export::js_value_to_cell;
+use super::export_xlsx::{create_and_download_xlsx, to_excel_datetime};
+use super::table_extractor::CellValue;
+use super::xlsx_package::{
+    TagEdit, attr_value, attrs_text, drop_calc_chain_override, drop_calc_chain_rel,
+    enable_full_calc, local_name, parse_relationships, parse_workbook_sheets, part_names,
+    raw_attrs, read_part, rels_path, render_tag, resolve_target, rewrite_parts, rewrite_tags,
+    rewrite_texts,
+};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use rust_xlsxwriter::utility::column_number_to_name;
+use std::collections::HashMap;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+
+/// Excel 最大行数
+const MAX_ROWS: u32 = 1_048_576;
+/// Excel 最大列数
+const MAX_COLS: u32 = 16_384;
+/// 绑定数据的最大嵌套深度（防止循环引用导致栈溢出）
+const MAX_BINDING_DEPTH: usize = 32;
+
+/// 按模板导出 Excel 文件
+///
+/// # 参数
+/// * `template_bytes` - XLSX 模板文件字节（`Uint8Array`）
+/// * `bindings` - 绑定数据对象：
+///   - 标量字段替换 `{{field}}` 占位符，支持 `{{customer.name}}` 点号路径
+///   - 数组字段展开 `{{#rows}}` … `{{/rows}}` 包围的行区域，区域内先查找数组元素的字段，再查找顶层字段；
+///     数组为空时删除区域所在的行
+/// * `options` - 可选配置对象：`filename` 导出文件名
+///
+/// 单元格内容恰好为一个占位符时按值类型写入（数字、布尔、日期写为原生单元格，日期显示格式取模板单元格的格式），
+/// 与其他文字混排时按文本替换；找不到的字段替换为空
+///
+/// # 示例
+/// ```javascript
+/// import { export_from_template } from './pkg/belobog_stellar_grid.js';
+///
+/// const template = new Uint8Array(await (await fetch('/templates/月报.xlsx')).arrayBuffer());
+/// export_from_template(template, {
+///   title: '2024 年 6 月销售月报',
+///   rows: [{ name: '华东', amount: 1200 }, { name: '华南', amount: 980 }],
+/// }, { filename: '月报.xlsx' });
+/// ```
+#[wasm_bindgen]
+pub fn export_from_template(
+    template_bytes: &[u8],
+    bindings: JsValue,
+    options: Option<JsValue>,
+) -> Result<(), JsValue> {
+    let filename = options
+        .as_ref()
+        .and_then(|opts| js_sys::Reflect::get(opts, &JsValue::from_str("filename")).ok())
+        .and_then(|v| v.as_string());
+
+    let bytes = fill_template(template_bytes, &parse_bindings(&bindings)?)
+        .map_err(|e| JsValue::from_str(&e))?;
+    create_and_download_xlsx(&bytes, filename)
+}
+
+/// 按模板生成 Excel 文件字节（不触发下载）
+///
+/// 参数同 `export_from_template`，可在 Web Worker 中使用
+///
+/// # 返回值
+/// * `Ok(Uint8Array)` - 生成的 XLSX 字节
+/// * `Err(JsValue)` - 生成失败
+#[wasm_bindgen]
+pub fn generate_template_bytes(
+    template_bytes: &[u8],
+    bindings: JsValue,
+) -> Result<js_sys::Uint8Array, JsValue> {
+    let bytes = fill_template(template_bytes, &parse_bindings(&bindings)?)
+        .map_err(|e| JsValue::from_str(&e))?;
+    Ok(js_sys::Uint8Array::from(bytes.as_slice()))
+}
+
+/// 模板绑定值
+#[derive(Debug, Clone)]
+pub(crate) enum TemplateValue {
+    /// 标量（文本、数字、布尔、日期）
+    Cell(CellValue),
+    /// 数组（展开 `{{#name}}` 行区域）
+    List(Vec<TemplateValue>),
+    /// 对象（`{{a.b}}` 点号路径逐级查找）
+    Object(HashMap<String, TemplateValue>),
+}
+
+impl TemplateValue {
+    /// 按点号路径查找字段
+    fn get(&self, path: &str) -> Option<&TemplateValue> {
+        path.split('.').try_fold(self, |value, key| match value {
+            TemplateValue::Object(fields) => fields.get(key),
+            _ => None,
+        })
+    }
+}
+
+/// 解析绑定数据（顶层必须是对象）
+fn parse_bindings(bindings: &JsValue) -> Result<TemplateValue, JsValue> {
+    if !bindings.is_object() || js_sys::Array::is_array(bindings) {
+        return Err(JsValue::from_str("模板绑定数据必须是对象"));
+    }
+    Ok(js_to_template_value(bindings, 0))
+}
+
+/// 将 JS 值转换为模板绑定值（Date 按标量处理）
+fn js_to_template_value(val: &JsValue, depth: usize) -> TemplateValue {
+    if depth >= MAX_BINDING_DEPTH {
+        return TemplateValue::Cell(CellValue::Empty);
+    }
+    if js_sys::Array::is_array(val) {
+        let items = js_sys::Array::from(val)
+            .iter()
+            .map(|item| js_to_template_value(&item, depth + 1))
+            .collect();
+        TemplateValue::List(items)
+    } else if val.is_object() && !val.is_instance_of::<js_sys::Date>() && !val.is_function() {
+        let fields = js_sys::Object::entries(val.unchecked_ref())
+            .iter()
+            .filter_map(|entry| {
+                let pair = js_sys::Array::from(&entry);
+                let key = pair.get(0).as_string()?;
+                Some((key, js_to_template_value(&pair.get(1), depth + 1)))
+            })
+            .collect();
+        TemplateValue::Object(fields)
+    } else {
+        TemplateValue::Cell(js_value_to_cell(val))
+    }
+}
+
+/// 填充模板并返回新的 XLSX 字节
+///
+/// 先确定各含占位符工作表的行映射，再改写全部工作表与定义名称中指向这些工作表的公式引用
+/// （跨表合计、打印区域等随之移动）；有工作表被改写时删除计算链并设置打开时重新计算，
+/// 使移动后的公式得到正确结果
+pub(crate) fn fill_template(bytes: &[u8], bindings: &TemplateValue) -> Result<Vec<u8>, String> {
+    let names = part_names(bytes)?;
+    let shared_strings = if names.iter().any(|n| n == "xl/sharedStrings.xml") {
+        parse_shared_strings(&read_part(bytes, "xl/sharedStrings.xml")?)?
+    } else {
+        Vec::new()
+    };
+    let workbook_xml = read_part(bytes, "xl/workbook.xml")?;
+    let workbook_rels = parse_relationships(&read_part(bytes, "xl/_rels/workbook.xml.rels")?)?;
+    let sheets = parse_workbook_sheets(&workbook_xml, &workbook_rels)?;
+
+    let mut shifts = SheetShifts::default();
+    let mut parsed = Vec::with_capacity(sheets.len());
+    for sheet in &sheets {
+        let xml = read_part(bytes, &sheet.part)?;
+        let tables = table_data_rows(bytes, &names, &sheet.part)?;
+        let template = parse_template_sheet(&xml, &shared_strings, bindings, &tables)
+            .map_err(|e| format!("{} 填充失败: {}", sheet_label(&sheet.part), e))?
+            .map(|(template, shift)| {
+                shifts.insert(&sheet.name, shift);
+                template
+            });
+        parsed.push((sheet, xml, template));
+    }
+    if parsed.iter().all(|(_, _, template)| template.is_none()) {
+        return Ok(bytes.to_vec());
+    }
+
+    let mut edits = HashMap::new();
+    for (sheet, xml, template) in parsed {
+        let template = match template {
+            Some(template) => Some(template),
+            // 其余工作表只在公式引用了展开的工作表时改写
+            None if references_shifted(&xml, &shifts, &sheet.name)? => parse_sheet_rows(&xml)?,
+            None => continue,
+        };
+        let filled = render_sheet(
+            &xml,
+            template,
+            &shifts,
+            &sheet.name,
+            &shared_strings,
+            bindings,
+        )
+        .map_err(|e| format!("{} 填充失败: {}", sheet_label(&sheet.part), e))?;
+        edits.insert(sheet.part.clone(), Some(filled.into_bytes()));
+        if let Some(shift) = shifts.get(&sheet.name) {
+            for (part, xml) in rewrite_sheet_parts(bytes, &names, &sheet.part, shift)? {
+                edits.insert(part, Some(xml.into_bytes()));
+            }
+        }
+    }
+
+    if names.iter().any(|n| n == "xl/calcChain.xml") {
+        edits.insert("xl/calcChain.xml".to_string(), None);
+        for (part, rewrite) in [
+            (
+                "xl/_rels/workbook.xml.rels",
+                drop_calc_chain_rel as fn(&str) -> Result<String, String>,
+            ),
+            ("[Content_Types].xml", drop_calc_chain_override),
+        ] {
+            if names.iter().any(|n| n == part) {
+                edits.insert(
+                    part.to_string(),
+                    Some(rewrite(&read_part(bytes, part)?)?.into_bytes()),
+                );
+            }
+        }
+    }
+    // 图表数据系列引用（`<c:f>报表!$B$5:$B$5</c:f>`）与数据透视表的数据源范围
+    for name in &names {
+        let is_chart = name.starts_with("xl/charts/chart") && name.ends_with(".xml");
+        let is_pivot_cache =
+            name.starts_with("xl/pivotCache/pivotCacheDefinition") && name.ends_with(".xml");
+        if !is_chart && !is_pivot_cache {
+            continue;
+        }
+        let xml = read_part(bytes, name)?;
+        let rewritten = if is_chart {
+            rewrite_texts(&xml, |path, text| {
+                path.last()
+                    .is_some_and(|e| e == "f")
+                    .then(|| shifts.map_qualified(text))
+            })?
+        } else {
+            rewrite_tags(&xml, |e, empty| {
+                let shift = (local_name(e) == "worksheetSource")
+                    .then(|| attr_value(e, "sheet"))
+                    .flatten()
+                    .and_then(|sheet| shifts.get(&sheet));
+                Ok(match shift {
+                    Some(shift) => map_attr(e, empty, "ref", |r| shift.map_sqref(r)),
+                    None => TagEdit::Keep,
+                })
+            })?
+        };
+        if rewritten != xml {
+            edits.insert(name.clone(), Some(rewritten.into_bytes()));
+        }
+    }
+    let workbook_xml = rewrite_texts(&workbook_xml, |path, text| {
+        path.last()
+            .is_some_and(|e| e == "definedName")
+            .then(|| shifts.map_qualified(text))
+    })?;
+    // 公式缓存值已随行移动失效，打开文件时重新计算
+    edits.insert(
+        "xl/workbook.xml".to_string(),
+        Some(enable_full_calc(&workbook_xml)?.into_bytes()),
+    );
+
+    rewrite_parts(bytes, edits)
+}
+
+/// 错误信息中的工作表部件名（如 `sheet1.xml`）
+fn sheet_label(part: &str) -> &str {
+    part.rsplit('/').next().unwrap_or(part)
+}
+
+// ============================================================================
+// 共享字符串
+// ============================================================================
+
+/// 解析共享字符串表（富文本片段拼接为纯文本，忽略拼音注音）
+fn parse_shared_strings(xml: &str) -> Result<Vec<String>, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current: Option<String> = None;
+    let (mut in_text, mut in_phonetic) = (false, false);
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| format!("解析 sharedStrings.xml 失败: {}", e))?
+        {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"si" => current = Some(String::new()),
+                b"rPh" => in_phonetic = true,
+                b"t" => in_text = !in_phonetic,
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"si" => strings.extend(current.take()),
+                b"rPh" => in_phonetic = false,
+                b"t" => in_text = false,
+                _ => {}
+            },
+            Event::Text(t) if in_text => {
+                if let Some(s) = current.as_mut() {
+                    s.push_str(&t.unescape().map_err(|e| e.to_string())?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+// ============================================================================
+// 工作表模型
+// ============================================================================
+
+/// 工作表中的一行
+struct SheetRow {
+    /// 行号（0-based）
+    index: u32,
+    /// 除 `r` 外的属性
+    attrs: Vec<(String, String)>,
+    cells: Vec<SheetCell>,
+}
+
+/// 工作表中的一个单元格
+struct SheetCell {
+    /// 列号（0-based）
+    col: u32,
+    /// 除 `r` 外的属性
+    attrs: Vec<(String, String)>,
+    formula: Option<SheetFormula>,
+    /// `<v>` 文本（已反转义）
+    value: Option<String>,
+    /// `<is>` 元素原文
+    inline: Option<String>,
+}
+
+/// 单元格公式
+struct SheetFormula {
+    attrs: Vec<(String, String)>,
+    /// 公式文本（已反转义）
+    text: String,
+}
+
+impl SheetCell {
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 单元格中的字符串内容（共享字符串、内联字符串或无公式的 `str` 类型）
+    fn text(&self, shared_strings: &[String]) -> Option<String> {
+        match self.attr("t") {
+            Some("s") => self
+                .value
+                .as_deref()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .and_then(|i| shared_strings.get(i).cloned()),
+            Some("inlineStr") => self.inline.as_deref().map(inline_text),
+            Some("str") if self.formula.is_none() => self.value.clone(),
+            _ => None,
+        }
+    }
+}
+
+/// 提取 `<is>` 元素中的纯文本
+fn inline_text(raw: &str) -> String {
+    parse_shared_strings(&format!("<sst><si>{}</si></sst>", raw))
+        .ok()
+        .and_then(|mut strings| strings.pop())
+        .unwrap_or_default()
+}
+
+/// 定位 `<sheetData>` 的内容范围（不含首尾标签）
+fn sheet_data_span(xml: &str) -> Result<Option<(usize, usize)>, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut inner_start = None;
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader
+            .read_event()
+            .map_err(|e| format!("解析工作表失败: {}", e))?
+        {
+            Event::Start(e) if e.local_name().as_ref() == b"sheetData" => {
+                inner_start = Some(reader.buffer_position() as usize);
+            }
+            Event::End(e) if e.local_name().as_ref() == b"sheetData" => {
+                return Ok(inner_start.map(|s| (s, start)));
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// 解析 `<sheetData>` 内容为行列表
+fn parse_rows(xml: &str) -> Result<Vec<SheetRow>, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut rows: Vec<SheetRow> = Vec::new();
+    let mut row: Option<SheetRow> = None;
+    let mut cell: Option<SheetCell> = None;
+    let mut text: Option<String> = None;
+    let mut inline_start: Option<usize> = None;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析工作表失败: {}", e))?;
+        if let Some(is_start) = inline_start {
+            if let Event::End(e) = &event
+                && e.local_name().as_ref() == b"is"
+            {
+                let end = reader.buffer_position() as usize;
+                if let Some(c) = cell.as_mut() {
+                    c.inline = Some(xml[is_start..end].to_string());
+                }
+                inline_start = None;
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let empty = matches!(event, Event::Empty(_));
+                match e.local_name().as_ref() {
+                    b"row" => {
+                        let index = attr_value(e, "r")
+                            .and_then(|r| r.parse::<u32>().ok())
+                            .and_then(|r| r.checked_sub(1))
+                            .unwrap_or_else(|| rows.last().map_or(0, |r| r.index + 1));
+                        let new_row = SheetRow {
+                            index,
+                            attrs: without_attr(raw_attrs(e), "r"),
+                            cells: Vec::new(),
+                        };
+                        if empty {
+                            rows.push(new_row);
+                        } else {
+                            row = Some(new_row);
+                        }
+                    }
+                    b"c" => {
+                        let col = attr_value(e, "r")
+                            .and_then(|r| parse_cell_ref(&r))
+                            .map(|r| r.col)
+                            .unwrap_or_else(|| {
+                                row.as_ref()
+                                    .and_then(|r| r.cells.last())
+                                    .map_or(0, |c| c.col + 1)
+                            });
+                        let new_cell = SheetCell {
+                            col,
+                            attrs: without_attr(raw_attrs(e), "r"),
+                            formula: None,
+                            value: None,
+                            inline: None,
+                        };
+                        match (empty, row.as_mut()) {
+                            (true, Some(r)) => r.cells.push(new_cell),
+                            (false, _) => cell = Some(new_cell),
+                            _ => {}
+                        }
+                    }
+                    b"f" => {
+                        if let Some(c) = cell.as_mut() {
+                            c.formula = Some(SheetFormula {
+                                attrs: raw_attrs(e),
+                                text: String::new(),
+                            });
+                            if !empty {
+                                text = Some(String::new());
+                            }
+                        }
+                    }
+                    b"v" if !empty => text = Some(String::new()),
+                    b"is" if !empty => inline_start = Some(start),
+                    _ => {}
+                }
+            }
+            Event::Text(ref t) => {
+                if let Some(buf) = text.as_mut() {
+                    buf.push_str(&t.unescape().map_err(|e| e.to_string())?);
+                }
+            }
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"f" => {
+                    if let (Some(c), Some(t)) = (cell.as_mut(), text.take())
+                        && let Some(f) = c.formula.as_mut()
+                    {
+                        f.text = t;
+                    }
+                }
+                b"v" => {
+                    if let Some(c) = cell.as_mut() {
+                        c.value = text.take();
+                    }
+                }
+                b"c" => {
+                    if let (Some(r), Some(c)) = (row.as_mut(), cell.take()) {
+                        r.cells.push(c);
+                    }
+                }
+                b"row" => rows.extend(row.take()),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(rows)
+}
+
+/// 去掉指定属性
+fn without_attr(mut attrs: Vec<(String, String)>, key: &str) -> Vec<(String, String)> {
+    attrs.retain(|(k, _)| k != key);
+    attrs
+}
+
+// ============================================================================
+// 单元格引用与行映射
+// ============================================================================
+
+/// 单元格引用（0-based）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CellRef {
+    row: u32,
+    col: u32,
+    row_abs: bool,
+    col_abs: bool,
+}
+
+impl CellRef {
+    fn to_a1(self) -> String {
+        format!(
+            "{}{}{}{}",
+            if self.col_abs { "$" } else { "" },
+            column_number_to_name(self.col as u16),
+            if self.row_abs { "$" } else { "" },
+            self.row + 1
+        )
+    }
+
+    /// 整行引用的一端（如 `$3`）
+    fn to_row_a1(self) -> String {
+        format!("{}{}", if self.row_abs { "$" } else { "" }, self.row + 1)
+    }
+}
+
+/// 解析 A1 引用（支持 `$` 绝对引用标记）
+fn parse_cell_ref(s: &str) -> Option<CellRef> {
+    let (col_abs, rest) = match s.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let letters = rest.bytes().take_while(u8::is_ascii_alphabetic).count();
+    if letters == 0 || letters > 3 {
+        return None;
+    }
+    let (col_part, rest) = rest.split_at(letters);
+    let (row_abs, digits) = match rest.strip_prefix('$') {
+        Some(digits) => (true, digits),
+        None => (false, rest),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let col = col_part.bytes().fold(0u32, |acc, b| {
+        acc * 26 + u32::from(b.to_ascii_uppercase() - b'A' + 1)
+    });
+    let row = digits.parse::<u32>().ok()?;
+    if col > MAX_COLS || row == 0 || row > MAX_ROWS {
+        return None;
+    }
+    Some(CellRef {
+        row: row - 1,
+        col: col - 1,
+        row_abs,
+        col_abs,
+    })
+}
+
+/// 解析整行引用的一端（如 `3`、`$3`），列号固定为 0
+fn parse_row_ref(s: &str) -> Option<CellRef> {
+    let (row_abs, digits) = match s.strip_prefix('$') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let row = digits.parse::<u32>().ok()?;
+    if row == 0 || row > MAX_ROWS {
+        return None;
+    }
+    Some(CellRef {
+        row: row - 1,
+        col: 0,
+        row_abs,
+        col_abs: false,
+    })
+}
+
+/// 引用在公式中的位置：单独引用、区域起点或区域终点
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RefRole {
+    Single,
+    Start,
+    /// 区域终点，`start` 为同一区域映射前的起点
+    End {
+        start: CellRef,
+    },
+}
+
+/// 公式中标识符（函数名、名称、引用、不带引号的工作表名）的字节，非 ASCII 字符可出现在名称中
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'.' || b == b'$' || !b.is_ascii()
+}
+
+/// 遍历公式中的单元格引用（`A1`、`A1:B2`）与整行引用（`3:5`）并替换
+///
+/// 回调的第一个参数为引用限定的工作表名（本工作表为 `None`，带引号的名称已去掉引号），
+/// 返回 `None` 表示引用的行已删除，写为 `#REF!`；
+/// 跳过字符串字面量、外部工作簿与跨多个工作表的三维引用、函数名与结构化引用
+fn map_formula_refs(
+    formula: &str,
+    mut map: impl FnMut(Option<&str>, CellRef, RefRole) -> Option<CellRef>,
+) -> String {
+    let bytes = formula.as_bytes();
+    let mut out = String::with_capacity(formula.len());
+    let mut i = 0;
+    let token_end = |from: usize| {
+        let mut j = from;
+        while j < bytes.len() && is_ident(bytes[j]) {
+            j += 1;
+        }
+        j
+    };
+
+    // 跳过 `!` 之后不平移的限定引用（如 `[1]Sheet2!A1:B2`）
+    let skip_qualified = |i: usize| {
+        let mut j = i;
+        while j < bytes.len() && (is_ident(bytes[j]) || bytes[j] == b':') {
+            j += 1;
+        }
+        j
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                // 字符串字面量或带引号的工作表名（成对引号为转义）
+                let mut j = i + 1;
+                while j < bytes.len() {
+                    if bytes[j] == quote {
+                        if bytes.get(j + 1) == Some(&quote) {
+                            j += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    j += 1;
+                }
+                j = (j + 1).min(bytes.len());
+                if quote == b'\'' && bytes.get(j) == Some(&b'!') {
+                    let sheet = formula[i + 1..j - 1].replace("''", "'");
+                    out.push_str(&formula[i..=j]);
+                    i = j + 1;
+                    // 外部工作簿（`'[1]Sheet1'!A1`）与三维引用（`'Sheet1:Sheet3'!A1`）不平移
+                    let local = !sheet.starts_with('[') && !sheet.contains(':');
+                    match local
+                        .then(|| map_ref_at(formula, i, Some(&sheet), &mut map))
+                        .flatten()
+                    {
+                        Some((text, end)) => {
+                            out.push_str(&text);
+                            i = end;
+                        }
+                        None => {
+                            let end = skip_qualified(i);
+                            out.push_str(&formula[i..end]);
+                            i = end;
+                        }
+                    }
+                } else {
+                    out.push_str(&formula[i..j]);
+                    i = j;
+                }
+            }
+            b'[' => {
+                // 结构化引用或外部工作簿编号（`[1]Sheet1!A1` 整体跳过）
+                let mut depth = 0;
+                let mut j = i;
+                while j < bytes.len() {
+                    match bytes[j] {
+                        b'[' => depth += 1,
+                        b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    j += 1;
+                }
+                j = (j + 1).min(bytes.len());
+                let k = token_end(j);
+                if bytes.get(k) == Some(&b'!') {
+                    j = skip_qualified(k + 1);
+                }
+                out.push_str(&formula[i..j]);
+                i = j;
+            }
+            b if is_ident(b) && b != b'.' => {
+                if let Some((text, end)) = map_ref_at(formula, i, None, &mut map) {
+                    out.push_str(&text);
+                    i = end;
+                    continue;
+                }
+                let j = token_end(i);
+                let end = match bytes.get(j) {
+                    Some(b'!') => {
+                        match map_ref_at(formula, j + 1, Some(&formula[i..j]), &mut map) {
+                            Some((text, end)) => {
+                                out.push_str(&formula[i..=j]);
+                                out.push_str(&text);
+                                i = end;
+                                continue;
+                            }
+                            None => skip_qualified(j + 1),
+                        }
+                    }
+                    // 三维引用 `Sheet1:Sheet3!A1`
+                    Some(b':') if bytes.get(token_end(j + 1)) == Some(&b'!') => {
+                        skip_qualified(token_end(j + 1) + 1)
+                    }
+                    // 函数名、名称与数字字面量（含科学计数）
+                    _ => j,
+                };
+                out.push_str(&formula[i..end]);
+                i = end;
+            }
+            _ => {
+                let len = formula[i..].chars().next().map_or(1, char::len_utf8);
+                out.push_str(&formula[i..i + len]);
+                i += len;
+            }
+        }
+    }
+    out
+}
+
+/// 映射 `pos` 处的单个引用或区域，返回替换后的文本与结束位置；该处不是引用时返回 `None`
+///
+/// 后随 `(` 或 `!` 的标识符是函数名或工作表名，不是引用；区域终点须紧跟在冒号之后
+fn map_ref_at(
+    formula: &str,
+    pos: usize,
+    sheet: Option<&str>,
+    map: &mut impl FnMut(Option<&str>, CellRef, RefRole) -> Option<CellRef>,
+) -> Option<(String, usize)> {
+    let bytes = formula.as_bytes();
+    let token = |from: usize| {
+        let mut end = from;
+        while end < bytes.len() && is_ident(bytes[end]) {
+            end += 1;
+        }
+        (!matches!(bytes.get(end), Some(b'(' | b'!'))).then(|| (&formula[from..end], end))
+    };
+    let (first, j) = token(pos)?;
+    let second = (bytes.get(j) == Some(&b':'))
+        .then(|| token(j + 1))
+        .flatten();
+
+    let mut range = |first: CellRef, last: CellRef, to_a1: fn(CellRef) -> String| match (
+        map(sheet, first, RefRole::Start),
+        map(sheet, last, RefRole::End { start: first }),
+    ) {
+        (Some(a), Some(b)) => format!("{}:{}", to_a1(a), to_a1(b)),
+        _ => "#REF!".to_string(),
+    };
+
+    if let Some(first) = parse_cell_ref(first) {
+        return Some(
+            match second.and_then(|(t, k)| Some((parse_cell_ref(t)?, k))) {
+                Some((last, k)) => (range(first, last, CellRef::to_a1), k),
+                None => (
+                    map(sheet, first, RefRole::Single)
+                        .map_or_else(|| "#REF!".to_string(), CellRef::to_a1),
+                    j,
+                ),
+            },
+        );
+    }
+    let first = parse_row_ref(first)?;
+    let (last, k) = second.and_then(|(t, k)| Some((parse_row_ref(t)?, k)))?;
+    Some((range(first, last, CellRef::to_row_a1), k))
+}
+
+/// 按行列偏移平移公式中的相对引用（展开共享公式时使用，其他工作表的引用同样平移）
+fn offset_formula(formula: &str, row_delta: i64, col_delta: i64) -> String {
+    map_formula_refs(formula, |_, mut r, _| {
+        if !r.row_abs {
+            r.row = (i64::from(r.row) + row_delta).clamp(0, i64::from(MAX_ROWS - 1)) as u32;
+        }
+        if !r.col_abs {
+            r.col = (i64::from(r.col) + col_delta).clamp(0, i64::from(MAX_COLS - 1)) as u32;
+        }
+        Some(r)
+    })
+}
+
+/// 行区域：模板中 `{{#name}}` 与 `{{/name}}` 所在行之间的行
+#[derive(Debug)]
+struct RowRegion {
+    name: String,
+    /// 起始行（0-based）
+    first: u32,
+    /// 结束行（0-based，inclusive）
+    last: u32,
+    /// 展开份数（数组为空时为 0，区域所在的行被删除；位于 Excel 表格数据行内时保留 1 份空白行）
+    copies: u32,
+}
+
+impl RowRegion {
+    fn height(&self) -> u32 {
+        self.last - self.first + 1
+    }
+
+    fn contains(&self, row: u32) -> bool {
+        (self.first..=self.last).contains(&row)
+    }
+}
+
+/// 模板行到输出行的映射
+struct RowShift {
+    regions: Vec<RowRegion>,
+}
+
+impl RowShift {
+    fn region_of(&self, row: u32) -> Option<&RowRegion> {
+        self.regions.iter().find(|r| r.contains(row))
+    }
+
+    /// 模板行在第 `copy` 份区域副本（区域外的行忽略 `copy`）中的输出行号；
+    /// 已删除区域内的行返回区域原来的位置，即区域下方第一行上移后的行号
+    fn map(&self, row: u32, copy: u32) -> u64 {
+        let shift: i64 = self
+            .regions
+            .iter()
+            .filter(|r| r.last < row)
+            .map(|r| i64::from(r.height()) * (i64::from(r.copies) - 1))
+            .sum();
+        let offset = self.region_of(row).map_or(0, |r| match r.copies {
+            0 => -i64::from(row - r.first),
+            copies => i64::from(r.height()) * i64::from(copy.min(copies - 1)),
+        });
+        (i64::from(row) + shift + offset) as u64
+    }
+
+    /// 区域外公式 / 范围中的引用：区域起点指向第一份副本，终点指向最后一份副本，使范围覆盖全部展开行
+    ///
+    /// 区域已删除时起点移到区域下方、终点移到区域上方；单独的引用与行已全部删除的范围返回 `None`（与 Excel 删除行一致）
+    fn map_ref(&self, r: CellRef, role: RefRole) -> Option<CellRef> {
+        match (role, self.region_of(r.row)) {
+            (RefRole::Single, Some(region)) if region.copies == 0 => None,
+            (RefRole::End { start }, Some(region)) if region.copies == 0 => {
+                let row = self.map(region.first, 0).checked_sub(1)?;
+                let first = self.map_ref(start, RefRole::Start)?;
+                (u64::from(first.row) <= row).then_some(CellRef {
+                    row: row as u32,
+                    ..r
+                })
+            }
+            (RefRole::End { .. }, Some(region)) => Some(self.with_row(r, region.copies - 1)),
+            _ => Some(self.with_row(r, 0)),
+        }
+    }
+
+    /// 第 `copy` 份区域副本中公式的引用：指向同一区域的相对引用随副本移动
+    fn map_ref_in_copy(
+        &self,
+        r: CellRef,
+        role: RefRole,
+        region: &RowRegion,
+        copy: u32,
+    ) -> Option<CellRef> {
+        if region.contains(r.row) && !r.row_abs {
+            Some(self.with_row(r, copy))
+        } else {
+            self.map_ref(r, role)
+        }
+    }
+
+    /// 图片、批注等锚点所在行：区域内的锚点跟随第一份副本，区域已删除时移到区域原来的位置
+    fn map_row(&self, row: u32) -> u32 {
+        self.map(row, 0).min(u64::from(MAX_ROWS - 1)) as u32
+    }
+
+    fn with_row(&self, mut r: CellRef, copy: u32) -> CellRef {
+        r.row = self.map(r.row, copy).min(u64::from(MAX_ROWS - 1)) as u32;
+        r
+    }
+
+    /// 映射 `A1:B2 C3` 形式的范围列表，完全位于已删除区域内的范围被移除（全部移除时返回空字符串）
+    fn map_sqref(&self, sqref: &str) -> String {
+        sqref
+            .split_whitespace()
+            .filter_map(|part| self.map_range(part, |r, role| self.map_ref(r, role)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// 映射单个范围，范围的行全部被删除时返回 `None`
+    fn map_range(
+        &self,
+        range: &str,
+        map: impl Fn(CellRef, RefRole) -> Option<CellRef>,
+    ) -> Option<String> {
+        // 单个单元格按区域处理，使区域内的条件格式等覆盖全部副本
+        let (a, b) = match range.split_once(':') {
+            Some((a, b)) => (parse_cell_ref(a), parse_cell_ref(b)),
+            None => (parse_cell_ref(range), parse_cell_ref(range)),
+        };
+        let (Some(a), Some(b)) = (a, b) else {
+            return Some(range.to_string());
+        };
+        let (first, last) = (map(a, RefRole::Start)?, map(b, RefRole::End { start: a })?);
+        if first == last && !range.contains(':') {
+            Some(first.to_a1())
+        } else {
+            Some(format!("{}:{}", first.to_a1(), last.to_a1()))
+        }
+    }
+
+    /// 合并单元格：完全位于区域内的按副本复制（区域已删除时移除），其余按范围映射
+    fn map_merge(&self, range: &str) -> Vec<String> {
+        let region = range
+            .split_once(':')
+            .and_then(|(a, b)| Some((parse_cell_ref(a)?, parse_cell_ref(b)?)))
+            .and_then(|(a, b)| {
+                self.region_of(a.row)
+                    .filter(|region| region.contains(b.row))
+            });
+        match region {
+            Some(region) => (0..region.copies)
+                .filter_map(|copy| self.map_range(range, |r, _| Some(self.with_row(r, copy))))
+                .collect(),
+            None => self
+                .map_range(range, |r, role| self.map_ref(r, role))
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// 各工作表的行映射（按工作表名查找，不区分大小写）
+#[derive(Default)]
+struct SheetShifts {
+    shifts: HashMap<String, RowShift>,
+}
+
+impl SheetShifts {
+    fn insert(&mut self, sheet: &str, shift: RowShift) {
+        self.shifts.insert(sheet.to_lowercase(), shift);
+    }
+
+    fn get(&self, sheet: &str) -> Option<&RowShift> {
+        self.shifts.get(&sheet.to_lowercase())
+    }
+
+    /// 限定了其他工作表的引用按该工作表的行映射平移（未展开的工作表保持不变，内层 `None` 表示引用的行已删除）；
+    /// 引用所在工作表 `own` 本身时返回 `None`，由调用方按本表的映射处理
+    fn map_other(
+        &self,
+        own: &str,
+        sheet: Option<&str>,
+        r: CellRef,
+        role: RefRole,
+    ) -> Option<Option<CellRef>> {
+        let sheet = sheet.filter(|s| s.to_lowercase() != own.to_lowercase())?;
+        Some(
+            self.get(sheet)
+                .map_or(Some(r), |shift| shift.map_ref(r, role)),
+        )
+    }
+
+    /// 工作簿级公式（定义名称）：只平移限定了工作表的引用
+    fn map_qualified(&self, formula: &str) -> String {
+        map_formula_refs(formula, |sheet, r, role| {
+            match sheet.and_then(|s| self.get(s)) {
+                Some(shift) => shift.map_ref(r, role),
+                None => Some(r),
+            }
+        })
+    }
+}
+
+// ============================================================================
+// 占位符
+// ============================================================================
+
+/// 占位符标签
+#[derive(Debug, PartialEq)]
+enum Tag<'a> {
+    /// `{{#name}}` 区域开始
+    Open(&'a str),
+    /// `{{/name}}` 区域结束
+    Close(&'a str),
+    /// `{{path}}` 字段
+    Field(&'a str),
+}
+
+/// 按出现顺序拆分文本：`Ok` 为普通文字，`Err` 为占位符标签
+fn split_tags(text: &str) -> Vec<Result<&str, Tag<'_>>> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        if start > 0 {
+            parts.push(Ok(&rest[..start]));
+        }
+        let inner = rest[start + 2..start + 2 + len].trim();
+        parts.push(Err(if let Some(name) = inner.strip_prefix('#') {
+            Tag::Open(name.trim())
+        } else if let Some(name) = inner.strip_prefix('/') {
+            Tag::Close(name.trim())
+        } else {
+            Tag::Field(inner)
+        }));
+        rest = &rest[start + 2 + len + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(Ok(rest));
+    }
+    parts
+}
+
+/// 在作用域中按顺序查找字段（区域元素优先，顶层数据兜底）
+fn lookup<'a>(scopes: &[&'a TemplateValue], path: &str) -> Option<&'a TemplateValue> {
+    scopes.iter().find_map(|scope| scope.get(path))
+}
+
+/// 渲染单元格文本：恰好一个字段时保留值类型，否则按文本拼接（区域标签移除）
+fn render_text(text: &str, scopes: &[&TemplateValue]) -> CellValue {
+    let parts: Vec<_> = split_tags(text)
+        .into_iter()
+        .filter(|p| !matches!(p, Err(Tag::Open(_) | Tag::Close(_))))
+        .collect();
+    let scalar = |path: &str| match lookup(scopes, path) {
+        Some(TemplateValue::Cell(value)) => value.clone(),
+        _ => CellValue::Empty,
+    };
+
+    if let [Err(Tag::Field(path))] = parts.as_slice() {
+        return scalar(path);
+    }
+    let text: String = parts
+        .iter()
+        .map(|part| match part {
+            Ok(s) => s.to_string(),
+            Err(Tag::Field(path)) => scalar(path).into_text(),
+            Err(_) => String::new(),
+        })
+        .collect();
+    if text.is_empty() {
+        CellValue::Empty
+    } else {
+        CellValue::Text(text)
+    }
+}
+
+/// 扫描行区域（按行顺序匹配 `{{#name}}` / `{{/name}}`，不支持嵌套）
+///
+/// `tables` 为工作表中 Excel 表格的数据行范围：空数组的区域位于表格数据行内时保留一行空白行（表格至少需要一行数据行），
+/// 其余空数组的区域删除所在的行
+fn find_regions(
+    rows: &[SheetRow],
+    shared_strings: &[String],
+    bindings: &TemplateValue,
+    tables: &[(u32, u32)],
+) -> Result<Vec<RowRegion>, String> {
+    let mut regions = Vec::new();
+    let mut open: Option<(String, u32)> = None;
+    for row in rows {
+        for cell in &row.cells {
+            let Some(text) = cell.text(shared_strings) else {
+                continue;
+            };
+            for tag in split_tags(&text).into_iter().filter_map(Result::err) {
+                match (tag, &open) {
+                    (Tag::Open(name), None) => open = Some((name.to_string(), row.index)),
+                    (Tag::Open(name), Some((outer, _))) => {
+                        return Err(format!(
+                            "不支持嵌套的行区域：{{{{#{}}}}} 位于 {{{{#{}}}}} 内",
+                            name, outer
+                        ));
+                    }
+                    (Tag::Close(name), Some((current, first))) if name == current.as_str() => {
+                        let in_table = tables
+                            .iter()
+                            .any(|&(top, bottom)| *first <= bottom && row.index >= top);
+                        let copies = match bindings.get(name) {
+                            Some(TemplateValue::List(items)) if items.is_empty() && in_table => 1,
+                            Some(TemplateValue::List(items)) => items.len(),
+                            _ => return Err(format!("行区域 {{{{#{}}}}} 需要数组数据", name)),
+                        };
+                        regions.push(RowRegion {
+                            name: name.to_string(),
+                            first: *first,
+                            last: row.index,
+                            copies: u32::try_from(copies).unwrap_or(u32::MAX),
+                        });
+                        open = None;
+                    }
+                    (Tag::Close(name), _) => {
+                        return Err(format!("{{{{/{}}}}} 没有对应的区域开始标签", name));
+                    }
+                    (Tag::Field(_), _) => {}
+                }
+            }
+        }
+    }
+    match open {
+        Some((name, _)) => Err(format!(
+            "行区域 {{{{#{}}}}} 缺少结束标签 {{{{/{}}}}}",
+            name, name
+        )),
+        None => Ok(regions),
+    }
+}
+
+// ============================================================================
+// 工作表填充
+// ============================================================================
+
+/// 工作表中的公式元素：单元格公式与条件格式、数据验证的公式
+const FORMULA_ELEMENTS: &[&str] = &["f", "formula", "formula1", "formula2"];
+
+/// 待改写的工作表：`<sheetData>` 的内容范围与按行号排序的行（共享公式已展开）
+struct SheetTemplate {
+    span: (usize, usize),
+    rows: Vec<SheetRow>,
+}
+
+/// 解析 `<sheetData>`，没有时返回 `None`
+fn parse_sheet_rows(xml: &str) -> Result<Option<SheetTemplate>, String> {
+    let Some((start, end)) = sheet_data_span(xml)? else {
+        return Ok(None);
+    };
+    let mut rows = parse_rows(&xml[start..end])?;
+    rows.sort_by_key(|r| r.index);
+    expand_shared_formulas(&mut rows);
+    Ok(Some(SheetTemplate {
+        span: (start, end),
+        rows,
+    }))
+}
+
+/// 解析含占位符的工作表并确定行映射（`tables` 为 Excel 表格的数据行范围）；不含占位符时返回 `None` 保持原文
+fn parse_template_sheet(
+    xml: &str,
+    shared_strings: &[String],
+    bindings: &TemplateValue,
+    tables: &[(u32, u32)],
+) -> Result<Option<(SheetTemplate, RowShift)>, String> {
+    let Some(template) = parse_sheet_rows(xml)? else {
+        return Ok(None);
+    };
+    let rows = &template.rows;
+    let has_placeholder = rows.iter().flat_map(|r| &r.cells).any(|c| {
+        c.text(shared_strings)
+            .is_some_and(|t| split_tags(&t).iter().any(Result::is_err))
+    });
+    if !has_placeholder {
+        return Ok(None);
+    }
+
+    let shift = RowShift {
+        regions: find_regions(rows, shared_strings, bindings, tables)?,
+    };
+    if let Some(last) = rows.last()
+        && shift.map(last.index, u32::MAX) >= u64::from(MAX_ROWS)
+    {
+        return Err(format!("展开后超出 Excel 最大行数 {}", MAX_ROWS));
+    }
+    Ok(Some((template, shift)))
+}
+
+/// 工作表中是否有公式或超链接引用了展开的其他工作表
+fn references_shifted(xml: &str, shifts: &SheetShifts, own: &str) -> Result<bool, String> {
+    let mut found = false;
+    let mut check = |formula: &str| {
+        map_formula_refs(formula, |sheet, r, role| {
+            let mapped = shifts.map_other(own, sheet, r, role).unwrap_or(Some(r));
+            found |= mapped != Some(r);
+            Some(r)
+        });
+    };
+    rewrite_texts(xml, |path, text| {
+        if path
+            .last()
+            .is_some_and(|e| FORMULA_ELEMENTS.contains(&e.as_str()))
+        {
+            check(text);
+        }
+        None
+    })?;
+    rewrite_tags(xml, |e, _| {
+        if local_name(e) == "hyperlink"
+            && let Some(location) = attr_value(e, "location")
+        {
+            check(&location);
+        }
+        Ok(TagEdit::Keep)
+    })?;
+    Ok(found)
+}
+
+/// 写出工作表：行按本表的行映射展开并填充占位符，公式引用按被引用工作表的行映射平移
+fn render_sheet(
+    xml: &str,
+    template: Option<SheetTemplate>,
+    shifts: &SheetShifts,
+    own: &str,
+    shared_strings: &[String],
+    bindings: &TemplateValue,
+) -> Result<String, String> {
+    let no_shift = RowShift {
+        regions: Vec::new(),
+    };
+    let shift = shifts.get(own).unwrap_or(&no_shift);
+    let map_ref = |sheet: Option<&str>, r: CellRef, role: RefRole| {
+        shifts
+            .map_other(own, sheet, r, role)
+            .unwrap_or_else(|| shift.map_ref(r, role))
+    };
+
+    let xml = match template {
+        Some(SheetTemplate {
+            span: (start, end),
+            rows,
+        }) => {
+            let sheet_data = render_rows(&rows, shift, shifts, own, shared_strings, bindings);
+            format!("{}{}{}", &xml[..start], sheet_data, &xml[end..])
+        }
+        None => xml.to_string(),
+    };
+    let xml = rewrite_sheet_ranges(&xml, shift, |formula| map_formula_refs(formula, map_ref))?;
+    // `<sheetData>` 以外的公式与扩展列表（`extLst`）中的范围：条件格式、数据验证、迷你图等
+    let xml = rewrite_texts(&xml, |path, text| {
+        if path.iter().any(|e| e == "sheetData") {
+            return None;
+        }
+        match path.last().map(String::as_str) {
+            Some(e) if FORMULA_ELEMENTS.contains(&e) => Some(map_formula_refs(text, map_ref)),
+            Some("sqref") => Some(shift.map_sqref(text)),
+            _ => None,
+        }
+    })?;
+    if shift.regions.iter().any(|r| r.copies == 0) {
+        prune_removed_ranges(&xml)
+    } else {
+        Ok(xml)
+    }
+}
+
+/// 写出 `<sheetData>` 内容：区域内的行按副本重复，其余行按映射移动
+fn render_rows(
+    rows: &[SheetRow],
+    shift: &RowShift,
+    shifts: &SheetShifts,
+    own: &str,
+    shared_strings: &[String],
+    bindings: &TemplateValue,
+) -> String {
+    let mut sheet_data = String::new();
+    let mut i = 0;
+    while i < rows.len() {
+        match shift.region_of(rows[i].index) {
+            Some(region) => {
+                let count = rows[i..]
+                    .iter()
+                    .take_while(|r| r.index <= region.last)
+                    .count();
+                let items = match bindings.get(&region.name) {
+                    Some(TemplateValue::List(items)) => items.as_slice(),
+                    _ => &[],
+                };
+                for copy in 0..region.copies {
+                    // 空数组在表格中保留的空白行不查找顶层字段
+                    let scopes: Vec<&TemplateValue> = match items.get(copy as usize) {
+                        Some(item) => vec![item, bindings],
+                        None => Vec::new(),
+                    };
+                    for row in &rows[i..i + count] {
+                        write_row(
+                            &mut sheet_data,
+                            row,
+                            shift.map(row.index, copy),
+                            &scopes,
+                            shared_strings,
+                            |sheet, r, role| {
+                                shifts
+                                    .map_other(own, sheet, r, role)
+                                    .unwrap_or_else(|| shift.map_ref_in_copy(r, role, region, copy))
+                            },
+                        );
+                    }
+                }
+                i += count;
+            }
+            None => {
+                let row = &rows[i];
+                write_row(
+                    &mut sheet_data,
+                    row,
+                    shift.map(row.index, 0),
+                    &[bindings],
+                    shared_strings,
+                    |sheet, r, role| {
+                        shifts
+                            .map_other(own, sheet, r, role)
+                            .unwrap_or_else(|| shift.map_ref(r, role))
+                    },
+                );
+                i += 1;
+            }
+        }
+    }
+    sheet_data
+}
+
+/// 将共享公式展开为普通公式，使每个单元格的公式可以单独平移
+fn expand_shared_formulas(rows: &mut [SheetRow]) {
+    let is_shared = |f: &SheetFormula| f.attrs.iter().any(|(k, v)| k == "t" && v == "shared");
+    let si = |f: &SheetFormula| {
+        f.attrs
+            .iter()
+            .find(|(k, _)| k == "si")
+            .map(|(_, v)| v.clone())
+    };
+
+    let mut masters: HashMap<String, (u32, u32, String)> = HashMap::new();
+    for row in rows.iter() {
+        for cell in &row.cells {
+            if let Some(f) = &cell.formula
+                && is_shared(f)
+                && !f.text.is_empty()
+                && let Some(id) = si(f)
+            {
+                masters
+                    .entry(id)
+                    .or_insert((row.index, cell.col, f.text.clone()));
+            }
+        }
+    }
+
+    for row in rows.iter_mut() {
+        for cell in &mut row.cells {
+            let Some(f) = cell.formula.as_mut().filter(|f| is_shared(f)) else {
+                continue;
+            };
+            if f.text.is_empty()
+                && let Some((master_row, master_col, text)) = si(f).and_then(|id| masters.get(&id))
+            {
+                f.text = offset_formula(
+                    text,
+                    i64::from(row.index) - i64::from(*master_row),
+                    i64::from(cell.col) - i64::from(*master_col),
+                );
+            }
+            f.attrs.retain(|(k, _)| k != "t" && k != "si" && k != "ref");
+        }
+    }
+}
+
+/// 写出一行（行号与单元格引用改为输出位置，占位符替换为绑定值，公式引用按 `map_ref` 平移）
+fn write_row(
+    out: &mut String,
+    row: &SheetRow,
+    index: u64,
+    scopes: &[&TemplateValue],
+    shared_strings: &[String],
+    map_ref: impl Fn(Option<&str>, CellRef, RefRole) -> Option<CellRef>,
+) {
+    let cells: String = row
+        .cells
+        .iter()
+        .map(|cell| write_cell(cell, index, scopes, shared_strings, &map_ref))
+        .collect();
+    let attrs = attrs_text(&row.attrs);
+    if cells.is_empty() {
+        out.push_str(&format!("<row r=\"{}\"{}/>", index + 1, attrs));
+    } else {
+        out.push_str(&format!(
+            "<row r=\"{}\"{}>{}</row>",
+            index + 1,
+            attrs,
+            cells
+        ));
+    }
+}
+
+/// 写出一个单元格
+fn write_cell(
+    cell: &SheetCell,
+    row: u64,
+    scopes: &[&TemplateValue],
+    shared_strings: &[String],
+    map_ref: &impl Fn(Option<&str>, CellRef, RefRole) -> Option<CellRef>,
+) -> String {
+    let reference = format!("{}{}", column_number_to_name(cell.col as u16), row + 1);
+    let mut attrs = cell.attrs.clone();
+    let mut content = String::new();
+
+    let template_text = cell
+        .text(shared_strings)
+        .filter(|t| split_tags(t).iter().any(Result::is_err));
+    match (&cell.formula, template_text) {
+        (None, Some(text)) => {
+            attrs.retain(|(k, _)| k != "t");
+            let (cell_type, body) = cell_value_xml(render_text(&text, scopes));
+            if let Some(t) = cell_type {
+                attrs.push(("t".to_string(), t.to_string()));
+            }
+            content = body;
+        }
+        _ => {
+            if let Some(f) = &cell.formula {
+                // 数组公式的 ref 范围随公式一起平移
+                let mut formula_attrs = f.attrs.clone();
+                for (key, value) in formula_attrs.iter_mut() {
+                    if key == "ref" {
+                        *value = map_formula_refs(value, map_ref);
+                    }
+                }
+                let text = map_formula_refs(&f.text, map_ref);
+                if text.is_empty() {
+                    content.push_str(&format!("<f{}/>", attrs_text(&formula_attrs)));
+                } else {
+                    content.push_str(&format!(
+                        "<f{}>{}</f>",
+                        attrs_text(&formula_attrs),
+                        escape(text.as_str())
+                    ));
+                }
+            }
+            if let Some(v) = &cell.value {
+                content.push_str(&format!("<v>{}</v>", escape(v.as_str())));
+            }
+            if let Some(inline) = &cell.inline {
+                content.push_str(inline);
+            }
+        }
+    }
+
+    if content.is_empty() {
+        format!("<c r=\"{}\"{}/>", reference, attrs_text(&attrs))
+    } else {
+        format!(
+            "<c r=\"{}\"{}>{}</c>",
+            reference,
+            attrs_text(&attrs),
+            content
+        )
+    }
+}
+
+/// 单元格值对应的类型属性与内容 XML（日期写为序列值，显示格式沿用模板单元格）
+fn cell_value_xml(value: CellValue) -> (Option<&'static str>, String) {
+    match value {
+        CellValue::Empty => (None, String::new()),
+        CellValue::Number(n) => (None, format!("<v>{}</v>", n)),
+        CellValue::Bool(b) => (Some("b"), format!("<v>{}</v>", u8::from(b))),
+        CellValue::DateTime(dt) => match to_excel_datetime(&dt) {
+            Some(date) => (None, format!("<v>{}</v>", date.to_excel())),
+            None => inline_string_xml(&CellValue::DateTime(dt).into_text()),
+        },
+        other => inline_string_xml(&other.into_text()),
+    }
+}
+
+fn inline_string_xml(text: &str) -> (Option<&'static str>, String) {
+    (
+        Some("inlineStr"),
+        format!("<is><t xml:space=\"preserve\">{}</t></is>", escape(text)),
+    )
+}
+
+/// 平移工作表中 `<sheetData>` 以外的范围：尺寸、合并单元格、自动筛选与排序、条件格式、数据验证、
+/// 受保护区域、忽略的错误、水平分页符与超链接，超链接的文档内位置（`location`）按公式引用由 `map_formula` 平移
+fn rewrite_sheet_ranges(
+    xml: &str,
+    shift: &RowShift,
+    map_formula: impl Fn(&str) -> String,
+) -> Result<String, String> {
+    let mut in_row_breaks = false;
+    rewrite_tags(xml, |e, empty| {
+        let name = local_name(e);
+        if name != "brk" {
+            in_row_breaks = name == "rowBreaks";
+        }
+        Ok(match name.as_str() {
+            "mergeCells" => TagEdit::Replace(render_tag(e, &[("count", None)], empty)),
+            "mergeCell" => {
+                let Some(range) = attr_value(e, "ref") else {
+                    return Ok(TagEdit::Keep);
+                };
+                let ranges = if empty {
+                    shift.map_merge(&range)
+                } else {
+                    vec![shift.map_sqref(&range)]
+                };
+                let tags = ranges
+                    .iter()
+                    .map(|r| render_tag(e, &[("ref", Some(r))], empty))
+                    .collect();
+                TagEdit::Replace(tags)
+            }
+            "hyperlink" => {
+                let range = attr_value(e, "ref").map(|r| shift.map_sqref(&r));
+                let location = attr_value(e, "location").map(|l| map_formula(&l));
+                TagEdit::Replace(render_tag(
+                    e,
+                    &[("ref", range.as_deref()), ("location", location.as_deref())],
+                    empty,
+                ))
+            }
+            // 分页符位于 `id` 行（0-based）之上，区域内的分页符位于第一份副本之上
+            "brk" if in_row_breaks => map_attr(e, empty, "id", |id| {
+                id.parse::<u32>()
+                    .map_or_else(|_| id.to_string(), |row| shift.map_row(row).to_string())
+            }),
+            "dimension" | "autoFilter" | "sortState" | "sortCondition" => {
+                map_attr(e, empty, "ref", |r| shift.map_sqref(r))
+            }
+            "conditionalFormatting" | "dataValidation" | "protectedRange" | "ignoredError" => {
+                map_attr(e, empty, "sqref", |r| shift.map_sqref(r))
+            }
+            _ => TagEdit::Keep,
+        })
+    })
+}
+
+/// 删除范围后不再包含子元素时一并删除的容器
+const RANGE_CONTAINERS: &[&str] = &[
+    "mergeCells",
+    "hyperlinks",
+    "conditionalFormattings",
+    "dataValidations",
+    "protectedRanges",
+    "ignoredErrors",
+    "ext",
+    "extLst",
+];
+
+/// 删除行已全部删除的范围元素：`ref` / `sqref` 为空的元素与 `<xm:sqref>` 为空的扩展条件格式、数据验证，
+/// 以及因此不再包含子元素的容器
+fn prune_removed_ranges(xml: &str) -> Result<String, String> {
+    /// 已开始的元素：在输出中的起始位置、是否保留、是否有保留的子元素、是否有文本
+    struct Open {
+        start: usize,
+        keep: bool,
+        container: bool,
+        has_child: bool,
+        has_text: bool,
+    }
+    let removed = |e: &BytesStart| {
+        ["ref", "sqref"]
+            .iter()
+            .any(|key| attr_value(e, key).is_some_and(|v| v.trim().is_empty()))
+    };
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut out = String::with_capacity(xml.len());
+    let mut stack: Vec<Open> = Vec::new();
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 XML 失败: {}", e))?;
+        let end = reader.buffer_position() as usize;
+        match &event {
+            Event::Eof => break,
+            Event::Start(e) => stack.push(Open {
+                start: out.len(),
+                keep: !removed(e),
+                container: RANGE_CONTAINERS.contains(&local_name(e).as_str()),
+                has_child: false,
+                has_text: false,
+            }),
+            Event::Empty(e) => {
+                if removed(e) || RANGE_CONTAINERS.contains(&local_name(e).as_str()) {
+                    continue;
+                }
+                if let Some(parent) = stack.last_mut() {
+                    parent.has_child = true;
+                }
+            }
+            Event::Text(t) => {
+                if let Some(open) = stack.last_mut() {
+                    open.has_text |= !t.iter().all(u8::is_ascii_whitespace);
+                }
+            }
+            Event::End(e) => {
+                out.push_str(&xml[start..end]);
+                let Some(open) = stack.pop() else {
+                    continue;
+                };
+                // 扩展条件格式 / 数据验证的范围为空时删除所在元素
+                let empty_sqref = e.local_name().as_ref() == b"sqref" && !open.has_text;
+                if empty_sqref && let Some(parent) = stack.last_mut() {
+                    parent.keep = false;
+                }
+                if empty_sqref || !open.keep || (open.container && !open.has_child) {
+                    out.truncate(open.start);
+                } else if let Some(parent) = stack.last_mut() {
+                    parent.has_child = true;
+                }
+                continue;
+            }
+            _ => {}
+        }
+        out.push_str(&xml[start..end]);
+    }
+    Ok(out)
+}
+
+/// 工作表中 Excel 表格的数据行范围（0-based，不含表头行与汇总行）
+fn table_data_rows(
+    bytes: &[u8],
+    names: &[String],
+    sheet_part: &str,
+) -> Result<Vec<(u32, u32)>, String> {
+    let rels = rels_path(sheet_part);
+    if !names.contains(&rels) {
+        return Ok(Vec::new());
+    }
+    let mut rows = Vec::new();
+    for rel in parse_relationships(&read_part(bytes, &rels)?)? {
+        let part = resolve_target(sheet_part, &rel.target);
+        if rel.external || !rel.rel_type.ends_with("/table") || !names.contains(&part) {
+            continue;
+        }
+        rewrite_tags(&read_part(bytes, &part)?, |e, _| {
+            let range = (local_name(e) == "table")
+                .then(|| attr_value(e, "ref"))
+                .flatten()
+                .and_then(|r| {
+                    let (a, b) = r.split_once(':')?;
+                    Some((parse_cell_ref(a)?, parse_cell_ref(b)?))
+                });
+            if let Some((first, last)) = range {
+                let count = |key: &str, default: u32| {
+                    attr_value(e, key)
+                        .and_then(|v| v.parse::<u32>().ok())
+                        .unwrap_or(default)
+                };
+                rows.push((
+                    first.row + count("headerRowCount", 1),
+                    last.row.saturating_sub(count("totalsRowCount", 0)),
+                ));
+            }
+            Ok(TagEdit::Keep)
+        })?;
+    }
+    Ok(rows)
+}
+
+/// 按 `map` 改写标签的属性，没有该属性时保持原文
+fn map_attr(e: &BytesStart, empty: bool, key: &str, map: impl FnOnce(&str) -> String) -> TagEdit {
+    match attr_value(e, key) {
+        Some(value) => TagEdit::Replace(render_tag(e, &[(key, Some(&map(&value)))], empty)),
+        None => TagEdit::Keep,
+    }
+}
+
+/// 平移工作表关联部件中的行位置：Excel 表格范围、绘图（图片、图表、形状）锚点与批注位置
+///
+/// 位于行区域内的图片与批注跟随第一份副本，不随副本复制；跨越区域的锚点随区域拉伸
+fn rewrite_sheet_parts(
+    bytes: &[u8],
+    names: &[String],
+    sheet_part: &str,
+    shift: &RowShift,
+) -> Result<Vec<(String, String)>, String> {
+    let rels = rels_path(sheet_part);
+    if !names.contains(&rels) {
+        return Ok(Vec::new());
+    }
+    let mut rewritten = Vec::new();
+    for rel in parse_relationships(&read_part(bytes, &rels)?)? {
+        let part = resolve_target(sheet_part, &rel.target);
+        if rel.external || !names.contains(&part) {
+            continue;
+        }
+        let xml = read_part(bytes, &part)?;
+        let xml = match rel.rel_type.rsplit('/').next().unwrap_or_default() {
+            "table" => rewrite_tags(&xml, |e, empty| {
+                Ok(match local_name(e).as_str() {
+                    "table" | "autoFilter" | "sortState" | "sortCondition" => {
+                        map_attr(e, empty, "ref", |r| shift.map_sqref(r))
+                    }
+                    _ => TagEdit::Keep,
+                })
+            })?,
+            // `<xdr:from>` / `<xdr:to>` 中的 `<xdr:row>`（0-based）
+            "drawing" => rewrite_texts(&xml, |path, text| {
+                let anchor = match path {
+                    [.., parent, last] if last == "row" => parent == "from" || parent == "to",
+                    _ => false,
+                };
+                anchor
+                    .then(|| text.trim().parse::<u32>().ok())
+                    .flatten()
+                    .map(|row| shift.map_row(row).to_string())
+            })?,
+            "vmlDrawing" => rewrite_vml_rows(&xml, |row| shift.map_row(row)),
+            "comments" | "threadedComment" => rewrite_tags(&xml, |e, empty| {
+                Ok(match local_name(e).as_str() {
+                    "comment" | "threadedComment" => map_attr(e, empty, "ref", |r| {
+                        shift
+                            .map_range(r, |cell, _| Some(shift.with_row(cell, 0)))
+                            .unwrap_or_else(|| r.to_string())
+                    }),
+                    _ => TagEdit::Keep,
+                })
+            })?,
+            _ => continue,
+        };
+        rewritten.push((part, xml));
+    }
+    Ok(rewritten)
+}
+
+/// 改写 VML 批注形状的锚点行：`<x:Anchor>` 中的上、下边所在行与 `<x:Row>`（均为 0-based）
+///
+/// VML 为旧式标记（Excel 生成的文件中可能含未闭合的 `<br>`），按文本查找元素而不解析 XML
+fn rewrite_vml_rows(vml: &str, map_row: impl Fn(u32) -> u32) -> String {
+    let map_number = |text: &str| {
+        let value = text.trim();
+        value.parse::<u32>().map_or_else(
+            |_| text.to_string(),
+            |row| text.replacen(value, &map_row(row).to_string(), 1),
+        )
+    };
+    let mut out = String::with_capacity(vml.len());
+    let mut rest = vml;
+    while let Some((start, tag)) = ["<x:Anchor>", "<x:Row>"]
+        .into_iter()
+        .filter_map(|tag| rest.find(tag).map(|i| (i + tag.len(), tag)))
+        .min()
+    {
+        let Some(len) = rest[start..].find(&format!("</{}", &tag[1..])) else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let text = &rest[start..start + len];
+        if tag == "<x:Row>" {
+            out.push_str(&map_number(text));
+        } else {
+            // 左列, 左偏移, 上行, 上偏移, 右列, 右偏移, 下行, 下偏移
+            let fields: Vec<String> = text
+                .split(',')
+                .enumerate()
+                .map(|(i, field)| {
+                    if i == 2 || i == 6 {
+                        map_number(field)
+                    } else {
+                        field.to_string()
+                    }
+                })
+                .collect();
+            out.push_str(&fields.join(","));
+        }
+        rest = &rest[start + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::table_extractor::CellDateTime;
+    use super::*;
+    use crate::core::test_support::parse_xml;
+    use calamine::{Data, Reader, Xlsx};
+    use rust_xlsxwriter::{
+        Chart, ChartType, ConditionalFormatCell, ConditionalFormatCellRule, DataValidation, Format,
+        Formula, Image, Note, Table, TableColumn, Url, Workbook, Worksheet,
+    };
+    use std::io::Cursor;
+
+    fn object(fields: Vec<(&str, TemplateValue)>) -> TemplateValue {
+        TemplateValue::Object(
+            fields
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    fn text(s: &str) -> TemplateValue {
+        TemplateValue::Cell(CellValue::from(s))
+    }
+
+    fn number(n: f64) -> TemplateValue {
+        TemplateValue::Cell(CellValue::Number(n))
+    }
+
+    /// 报表模板：标题、表头、单行明细区域、合计行与备注行
+    fn report_template() -> Vec<u8> {
+        let mut workbook = Workbook::new();
+        report_sheet(workbook.add_worksheet());
+        workbook.save_to_buffer().unwrap()
+    }
+
+    /// 写入报表工作表（明细区域为第 5 行，合计在第 6 行）
+    fn report_sheet(sheet: &mut Worksheet) -> &mut Worksheet {
+        sheet.set_name("报表").unwrap();
+        sheet
+            .merge_range(0, 0, 0, 3, "{{title}}", &Format::new().set_bold())
+            .unwrap();
+        sheet.write(1, 0, "制表人：{{author}}").unwrap();
+        sheet
+            .write_with_format(
+                1,
+                2,
+                "{{date}}",
+                &Format::new().set_num_format("yyyy-mm-dd"),
+            )
+            .unwrap();
+        sheet.write(1, 3, "{{meta.version}}").unwrap();
+        for (col, title) in ["名称", "数量", "单价", "金额"].into_iter().enumerate() {
+            sheet.write(3, col as u16, title).unwrap();
+        }
+        sheet.write(4, 0, "{{#items}}{{name}}").unwrap();
+        sheet.write(4, 1, "{{qty}}").unwrap();
+        sheet.write(4, 2, "{{price}}{{/items}}").unwrap();
+        sheet.write(4, 3, Formula::new("=B5*C5")).unwrap();
+        sheet
+            .merge_range(4, 4, 4, 5, "{{tag}}", &Format::new())
+            .unwrap();
+        sheet.write(5, 0, "合计").unwrap();
+        sheet.write(5, 1, Formula::new("=SUM(B5:B5)")).unwrap();
+        sheet
+            .write(5, 3, Formula::new("=SUM(D5:D5)+'报表'!$D$5+\"A1\""))
+            .unwrap();
+        sheet
+            .merge_range(6, 0, 6, 1, "备注：{{note}}", &Format::new())
+            .unwrap();
+        sheet
+            .add_conditional_format(
+                4,
+                1,
+                4,
+                1,
+                &ConditionalFormatCell::new().set_rule(ConditionalFormatCellRule::GreaterThan(5)),
+            )
+            .unwrap();
+        sheet
+    }
+
+    fn report_bindings(items: usize) -> TemplateValue {
+        let items = (0..items)
+            .map(|i| {
+                object(vec![
+                    ("name", text(&format!("商品{}", i + 1))),
+                    ("qty", number(i as f64 + 1.0)),
+                    ("price", number(10.0)),
+                    ("tag", text("热销")),
+                ])
+            })
+            .collect();
+        object(vec![
+            ("title", text("六月销售月报")),
+            ("author", text("张三")),
+            (
+                "date",
+                TemplateValue::Cell(CellValue::DateTime(
+                    CellDateTime::from_ymd(2024, 6, 30).unwrap(),
+                )),
+            ),
+            ("meta", object(vec![("version", number(2.0))])),
+            ("note", text("含税")),
+            ("items", TemplateValue::List(items)),
+        ])
+    }
+
+    #[test]
+    fn test_fill_template_expands_rows() {
+        let template = report_template();
+        let bytes = fill_template(&template, &report_bindings(3)).unwrap();
+
+        let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes.clone())).unwrap();
+        let range = workbook.worksheet_range("报表").unwrap();
+        assert_eq!(
+            range.get_value((0, 0)),
+            Some(&Data::String("六月销售月报".into()))
+        );
+        assert_eq!(
+            range.get_value((1, 0)),
+            Some(&Data::String("制表人：张三".into()))
+        );
+        assert!(matches!(range.get_value((1, 2)), Some(Data::DateTime(_))));
+        assert_eq!(range.get_value((1, 3)), Some(&Data::Float(2.0)));
+        for i in 0..3u32 {
+            let name = format!("商品{}", i + 1);
+            assert_eq!(range.get_value((4 + i, 0)), Some(&Data::String(name)));
+            assert_eq!(
+                range.get_value((4 + i, 1)),
+                Some(&Data::Float(f64::from(i + 1)))
+            );
+            assert_eq!(range.get_value((4 + i, 2)), Some(&Data::Float(10.0)));
+            assert_eq!(
+                range.get_value((4 + i, 4)),
+                Some(&Data::String("热销".into()))
+            );
+        }
+        assert_eq!(range.get_value((7, 0)), Some(&Data::String("合计".into())));
+        assert_eq!(
+            range.get_value((8, 0)),
+            Some(&Data::String("备注：含税".into()))
+        );
+
+        // 区域内公式随副本移动，区域外的范围扩展到全部展开行
+        let formulas = workbook.worksheet_formula("报表").unwrap();
+        assert_eq!(formulas.get_value((4, 3)), Some(&"B5*C5".to_string()));
+        assert_eq!(formulas.get_value((6, 3)), Some(&"B7*C7".to_string()));
+        assert_eq!(formulas.get_value((7, 1)), Some(&"SUM(B5:B7)".to_string()));
+        assert_eq!(
+            formulas.get_value((7, 3)),
+            Some(&"SUM(D5:D7)+'报表'!$D$5+\"A1\"".to_string())
+        );
+
+        let sheet = read_part(&bytes, "xl/worksheets/sheet1.xml").unwrap();
+        for merge in ["A1:D1", "E5:F5", "E6:F6", "E7:F7", "A9:B9"] {
+            assert!(
+                sheet.contains(&format!("<mergeCell ref=\"{}\"/>", merge)),
+                "{}",
+                merge
+            );
+        }
+        assert!(sheet.contains("sqref=\"B5:B7\""));
+        assert!(sheet.contains("<dimension ref=\"A1:F9\"/>"));
+        assert!(
+            read_part(&bytes, "xl/workbook.xml")
+                .unwrap()
+                .contains("fullCalcOnLoad=\"1\"")
+        );
+
+        // 样式等其余部件保持不变
+        assert_eq!(
+            read_part(&bytes, "xl/styles.xml").unwrap(),
+            read_part(&template, "xl/styles.xml").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fill_template_empty_region_removes_rows() {
+        // 数组为空时删除区域所在的行，下方的行上移
+        let bytes = fill_template(&report_template(), &report_bindings(0)).unwrap();
+        let sheet = parse_xml(&read_part(&bytes, "xl/worksheets/sheet1.xml").unwrap());
+        let rows: Vec<_> = sheet
+            .find_all("row")
+            .iter()
+            .filter_map(|r| r.attr("r"))
+            .collect();
+        assert_eq!(rows, vec!["1", "2", "4", "5", "6"]);
+
+        // 完全指向已删除行的引用与 Excel 删除行一致变为 #REF!
+        let formula = |cell: &str| sheet.find_by("c", "r", cell).unwrap().child("f").unwrap();
+        assert_eq!(formula("B5").text, "SUM(#REF!)");
+        assert_eq!(formula("D5").text, "SUM(#REF!)+'报表'!#REF!+\"A1\"");
+
+        // 区域内的合并单元格与条件格式随行删除，其余合并单元格上移
+        let merges: Vec<_> = sheet
+            .find_all("mergeCell")
+            .iter()
+            .filter_map(|m| m.attr("ref"))
+            .collect();
+        assert_eq!(merges, vec!["A1:D1", "A6:B6"]);
+        assert!(sheet.find("conditionalFormatting").is_none());
+
+        let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes)).unwrap();
+        let range = workbook.worksheet_range("报表").unwrap();
+        assert_eq!(range.get_value((4, 0)), Some(&Data::String("合计".into())));
+        assert_eq!(
+            range.get_value((5, 0)),
+            Some(&Data::String("备注：含税".into()))
+        );
+    }
+
+    #[test]
+    fn test_fill_template_empty_region_ignores_top_level_fields() {
+        // 顶层字段与区域字段同名时，空数组的区域不会填入顶层的值
+        let mut bindings = report_bindings(0);
+        if let TemplateValue::Object(fields) = &mut bindings {
+            fields.insert("name".to_string(), text("顶层名称"));
+            fields.insert("qty".to_string(), number(99.0));
+        }
+        let has_top_level = |bytes: Vec<u8>, sheet: &str| {
+            let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes)).unwrap();
+            let range = workbook.worksheet_range(sheet).unwrap();
+            range.used_cells().any(|(_, _, value)| {
+                matches!(value, Data::String(s) if s == "顶层名称")
+                    || matches!(value, Data::Float(n) if *n == 99.0)
+            })
+        };
+        assert!(!has_top_level(
+            fill_template(&report_template(), &bindings).unwrap(),
+            "报表"
+        ));
+
+        // 区域位于 Excel 表格的数据行内时保留一行空白行，表格范围不变
+        let bytes = fill_template(&detail_template(), &bindings).unwrap();
+        let table = parse_xml(&read_part(&bytes, "xl/tables/table1.xml").unwrap());
+        assert_eq!(table.attr("ref"), Some("A1:B2"));
+        let sheet = parse_xml(&read_part(&bytes, "xl/worksheets/sheet1.xml").unwrap());
+        assert!(sheet.find_by("c", "r", "A2").unwrap().child("is").is_none());
+        let total = sheet.find_by("c", "r", "B3").unwrap();
+        assert_eq!(total.child("f").unwrap().text, "SUM(B2:B2)");
+        assert!(!has_top_level(bytes, "明细"));
+    }
+
+    #[test]
+    fn test_fill_template_empty_and_invalid_regions() {
+        let missing = object(vec![("title", text("月报"))]);
+        let err = fill_template(&report_template(), &missing).unwrap_err();
+        assert!(err.contains("{{#items}} 需要数组数据"), "{}", err);
+
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().write(0, 0, "{{#items}}").unwrap();
+        let err =
+            fill_template(&workbook.save_to_buffer().unwrap(), &report_bindings(1)).unwrap_err();
+        assert!(err.contains("缺少结束标签"), "{}", err);
+
+        // 不含占位符的模板原样返回
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().write(0, 0, "固定内容").unwrap();
+        let plain = workbook.save_to_buffer().unwrap();
+        assert_eq!(fill_template(&plain, &report_bindings(1)).unwrap(), plain);
+    }
+
+    #[test]
+    fn test_render_text() {
+        let bindings = report_bindings(0);
+        let scopes = [&bindings];
+        assert_eq!(
+            render_text("{{ meta.version }}", &scopes),
+            CellValue::Number(2.0)
+        );
+        assert_eq!(
+            render_text("v{{meta.version}} {{missing}}", &scopes),
+            CellValue::from("v2 ")
+        );
+        assert_eq!(
+            render_text("{{#items}}{{/items}}", &scopes),
+            CellValue::Empty
+        );
+        assert_eq!(
+            render_text("{{未闭合", &scopes),
+            CellValue::from("{{未闭合")
+        );
+    }
+
+    #[test]
+    fn test_offset_formula() {
+        assert_eq!(
+            offset_formula("A1*$B$1+LOG10(C1)", 2, 1),
+            "B3*$B$1+LOG10(D3)"
+        );
+        assert_eq!(
+            offset_formula("SUM(A1:A$3)&\"A1\"&Sheet2!A1&'其他 表'!A1", 1, 0),
+            "SUM(A2:A$3)&\"A1\"&Sheet2!A2&'其他 表'!A2"
+        );
+        assert_eq!(
+            offset_formula("Sales[金额]*1E5+TRUE", 1, 1),
+            "Sales[金额]*1E5+TRUE"
+        );
+    }
+
+    #[test]
+    fn test_map_formula_refs() {
+        let shift_sheet2 = |formula: &str| {
+            map_formula_refs(formula, |sheet, mut r, _| {
+                if sheet == Some("Sheet2") {
+                    r.row += 10;
+                }
+                Some(r)
+            })
+        };
+        assert_eq!(shift_sheet2("Sheet2!A1+A1"), "Sheet2!A11+A1");
+        assert_eq!(shift_sheet2("SUM(Sheet2!$B$2:B3)"), "SUM(Sheet2!$B$12:B13)");
+        // 引号内的工作表名去掉引号与转义后传给回调
+        assert_eq!(
+            map_formula_refs("'O''Brien 表'!A1", |sheet, mut r, _| {
+                assert_eq!(sheet, Some("O'Brien 表"));
+                r.row += 1;
+                Some(r)
+            }),
+            "'O''Brien 表'!A2"
+        );
+        // 整行引用
+        assert_eq!(shift_sheet2("Sheet2!$4:$5,1:1"), "Sheet2!$14:$15,1:1");
+        // 外部工作簿、三维引用、整列引用与名称保持不变
+        for formula in [
+            "[1]Sheet2!A1",
+            "'[1]Sheet2'!A1",
+            "SUM(Sheet1:Sheet2!A1)",
+            "SUM('Sheet1:Sheet2'!A1)",
+            "Sheet2!A:A",
+            "Sheet2!合计",
+            "Sheet2!#REF!",
+        ] {
+            assert_eq!(shift_sheet2(formula), formula);
+        }
+    }
+
+    /// 含跨表引用的模板：报表工作表带打印区域、打印标题与命名区域，汇总工作表引用报表的明细与合计
+    fn cross_sheet_template() -> Vec<u8> {
+        let mut workbook = Workbook::new();
+        report_sheet(workbook.add_worksheet())
+            .set_print_area(0, 0, 6, 5)
+            .unwrap()
+            .set_repeat_rows(3, 3)
+            .unwrap();
+        let summary = workbook.add_worksheet().set_name("汇总").unwrap();
+        summary
+            .write(0, 0, Formula::new("=SUM('报表'!B5:B5)+报表!D6"))
+            .unwrap();
+        summary
+            .write(1, 0, Formula::new("=报表!A7&COUNTA(报表!$A$1:$A$4)"))
+            .unwrap();
+        summary
+            .write_url(2, 0, Url::new("internal:'报表'!A6"))
+            .unwrap();
+        workbook.define_name("合计数量", "=报表!$B$6").unwrap();
+        workbook.save_to_buffer().unwrap()
+    }
+
+    #[test]
+    fn test_fill_template_shifts_cross_sheet_refs() {
+        let bytes = fill_template(&cross_sheet_template(), &report_bindings(3)).unwrap();
+
+        // 其他工作表中指向展开区域及其下方的引用随之移动，区域上方的引用不变
+        let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes.clone())).unwrap();
+        let formulas = workbook.worksheet_formula("汇总").unwrap();
+        assert_eq!(
+            formulas.get_value((0, 0)),
+            Some(&"SUM('报表'!B5:B7)+报表!D8".to_string())
+        );
+        assert_eq!(
+            formulas.get_value((1, 0)),
+            Some(&"报表!A9&COUNTA(报表!$A$1:$A$4)".to_string())
+        );
+        let summary = parse_xml(&read_part(&bytes, "xl/worksheets/sheet2.xml").unwrap());
+        assert_eq!(
+            summary.find("hyperlink").unwrap().attr("location"),
+            Some("'报表'!A8")
+        );
+
+        // 打印区域扩展到全部展开行，表头的打印标题不变，命名区域指向移动后的合计行
+        let workbook = parse_xml(&read_part(&bytes, "xl/workbook.xml").unwrap());
+        let defined_name = |name: &str| workbook.find_by("definedName", "name", name).unwrap();
+        assert!(
+            defined_name("_xlnm.Print_Area")
+                .text
+                .ends_with("!$A$1:$F$9"),
+            "{}",
+            defined_name("_xlnm.Print_Area").text
+        );
+        assert!(defined_name("_xlnm.Print_Titles").text.ends_with("!$4:$4"));
+        assert_eq!(defined_name("合计数量").text, "报表!$B$8");
+        assert_eq!(
+            workbook.find("calcPr").unwrap().attr("fullCalcOnLoad"),
+            Some("1")
+        );
+    }
+
+    #[test]
+    fn test_fill_template_keeps_unrelated_sheets() {
+        // 没有引用展开工作表的其他工作表逐字节保留
+        let mut workbook = Workbook::new();
+        report_sheet(workbook.add_worksheet());
+        let other = workbook.add_worksheet().set_name("说明").unwrap();
+        other.write(0, 0, Formula::new("=SUM(A2:A9)")).unwrap();
+        other.write(1, 0, Formula::new("=报表!A1")).unwrap();
+        let template = workbook.save_to_buffer().unwrap();
+
+        let bytes = fill_template(&template, &report_bindings(3)).unwrap();
+        assert_eq!(
+            read_part(&bytes, "xl/worksheets/sheet2.xml").unwrap(),
+            read_part(&template, "xl/worksheets/sheet2.xml").unwrap()
+        );
+    }
+    #[test]
+    fn test_fill_template_shifts_sheet_ranges() {
+        let mut workbook = Workbook::new();
+        let sheet = report_sheet(workbook.add_worksheet());
+        sheet.autofilter(3, 0, 4, 3).unwrap();
+        sheet.set_page_breaks(&[6]).unwrap();
+        sheet
+            .add_data_validation(
+                0,
+                5,
+                0,
+                5,
+                &DataValidation::new().allow_list_formula(Formula::new("=$A$5:$A$5")),
+            )
+            .unwrap();
+        let bytes =
+            fill_template(&workbook.save_to_buffer().unwrap(), &report_bindings(3)).unwrap();
+
+        let sheet = parse_xml(&read_part(&bytes, "xl/worksheets/sheet1.xml").unwrap());
+        assert_eq!(
+            sheet.child("autoFilter").unwrap().attr("ref"),
+            Some("A4:D7")
+        );
+        // 备注行之上的分页符随备注行下移
+        let breaks = sheet.child("rowBreaks").unwrap();
+        assert_eq!(breaks.child("brk").unwrap().attr("id"), Some("8"));
+        let validation = sheet.find_by("dataValidation", "sqref", "F1").unwrap();
+        assert_eq!(validation.child("formula1").unwrap().text, "$A$5:$A$7");
+    }
+
+    /// 明细模板：表格（表头 + 单行区域）、合计行，区域内与区域下方各有一张图片与一条批注，图表引用区域数据
+    fn detail_template() -> Vec<u8> {
+        let png = crate::core::cell_image::decode_data_url(&format!(
+            "data:image/png;base64,{}",
+            "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII="
+        ))
+        .unwrap();
+        let image = Image::new_from_buffer(&png).unwrap();
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet().set_name("明细").unwrap();
+        sheet.write(1, 0, "{{#items}}{{name}}").unwrap();
+        sheet.write(1, 1, "{{qty}}{{/items}}").unwrap();
+        sheet.write(2, 0, "合计").unwrap();
+        sheet.write(2, 1, Formula::new("=SUM(B2:B2)")).unwrap();
+        sheet
+            .add_table(
+                0,
+                0,
+                1,
+                1,
+                &Table::new().set_columns(&[
+                    TableColumn::new().set_header("名称"),
+                    TableColumn::new().set_header("数量"),
+                ]),
+            )
+            .unwrap();
+        sheet.insert_image(1, 2, &image).unwrap();
+        sheet.insert_image(3, 0, &image).unwrap();
+        sheet.insert_note(1, 0, &Note::new("区域内")).unwrap();
+        sheet.insert_note(2, 0, &Note::new("合计说明")).unwrap();
+        let mut chart = Chart::new(ChartType::Column);
+        chart.add_series().set_values("明细!$B$1:$B$2");
+        sheet.insert_chart(4, 3, &chart).unwrap();
+        workbook.save_to_buffer().unwrap()
+    }
+
+    #[test]
+    fn test_fill_template_shifts_sheet_parts() {
+        let bytes = fill_template(&detail_template(), &report_bindings(3)).unwrap();
+
+        // 表格范围覆盖全部展开行
+        let table = parse_xml(&read_part(&bytes, "xl/tables/table1.xml").unwrap());
+        assert_eq!(table.attr("ref"), Some("A1:B4"));
+        assert_eq!(
+            table.child("autoFilter").unwrap().attr("ref"),
+            Some("A1:B4")
+        );
+
+        // 区域内的图片留在第一份副本，区域下方的图片与图表下移
+        let drawing = parse_xml(&read_part(&bytes, "xl/drawings/drawing1.xml").unwrap());
+        let mut rows: Vec<_> = drawing
+            .find_all("from")
+            .iter()
+            .map(|from| from.child("row").unwrap().text.parse::<u32>().unwrap())
+            .collect();
+        rows.sort_unstable();
+        assert_eq!(rows, vec![1, 5, 6]);
+        let chart = parse_xml(&read_part(&bytes, "xl/charts/chart1.xml").unwrap());
+        let series = chart.find("val").unwrap().find("f").unwrap();
+        assert!(series.text.ends_with("!$B$1:$B$4"), "{}", series.text);
+
+        // 批注与其 VML 形状
+        let comments = parse_xml(&read_part(&bytes, "xl/comments1.xml").unwrap());
+        let refs: Vec<_> = comments
+            .find_all("comment")
+            .iter()
+            .filter_map(|c| c.attr("ref"))
+            .collect();
+        assert_eq!(refs, vec!["A2", "A5"]);
+        let vml = read_part(&bytes, "xl/drawings/vmlDrawing1.vml").unwrap();
+        assert!(vml.contains("<x:Row>1</x:Row>"));
+        assert!(vml.contains("<x:Row>4</x:Row>"));
+        assert!(!vml.contains("<x:Row>2</x:Row>"));
+    }
+
+    #[test]
+    fn test_fill_template_shifts_pivot_source() {
+        let template = rewrite_parts(
+            &detail_template(),
+            HashMap::from([(
+                "xl/pivotCache/pivotCacheDefinition1.xml".to_string(),
+                Some(
+                    b"<pivotCacheDefinition><cacheSource type=\"worksheet\">\
+                      <worksheetSource ref=\"A1:B2\" sheet=\"\xe6\x98\x8e\xe7\xbb\x86\"/></cacheSource>\
+                      </pivotCacheDefinition>"
+                        .to_vec(),
+                ),
+            )]),
+        )
+        .unwrap();
+        let bytes = fill_template(&template, &report_bindings(2)).unwrap();
+        let cache =
+            parse_xml(&read_part(&bytes, "xl/pivotCache/pivotCacheDefinition1.xml").unwrap());
+        assert_eq!(
+            cache.find("worksheetSource").unwrap().attr("ref"),
+            Some("A1:B3")
+        );
+    }
+
+    #[test]
+    fn test_rewrite_vml_rows() {
+        let vml = "<v:shape><x:ClientData ObjectType=\"Note\"><br>\
+                   <x:Anchor>\n    1, 15, 2, 10, 3, 15, 6, 4</x:Anchor>\
+                   <x:Row>3</x:Row><x:RowHidden/><x:Column>0</x:Column></x:ClientData></v:shape>";
+        assert_eq!(
+            rewrite_vml_rows(vml, |row| row + 10),
+            "<v:shape><x:ClientData ObjectType=\"Note\"><br>\
+             <x:Anchor>\n    1, 15, 12, 10, 3, 15, 16, 4</x:Anchor>\
+             <x:Row>13</x:Row><x:RowHidden/><x:Column>0</x:Column></x:ClientData></v:shape>"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+/// 单元测试辅助
+///
+/// 由 `TableData` 生成 XLSX，并将包内部件解析为元素树，测试按元素与属性断言，
+/// 不依赖 XML 原文的属性顺序与转义写法
+use super::export_xlsx::generate_xlsx_bytes;
+use super::table_extractor::TableData;
+use super::xlsx_package::read_part;
+use quick_xml::events::{BytesStart, Event};
+
+/// XML 元素（名称为去掉命名空间前缀的本地名，属性名保留原文，如 `r:id`）
+#[derive(Debug, Default)]
+pub(crate) struct XmlElement {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    /// 直接包含的文本（已反转义）
+    pub text: String,
+    pub children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    /// 读取属性值（已反转义）
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 第一个名为 `name` 的直接子元素
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// 第一个名为 `name` 的后代元素（深度优先）
+    pub fn find(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find_map(|c| {
+            if c.name == name {
+                Some(c)
+            } else {
+                c.find(name)
+            }
+        })
+    }
+
+    /// 全部名为 `name` 的后代元素（文档顺序）
+    pub fn find_all(&self, name: &str) -> Vec<&XmlElement> {
+        let mut found = Vec::new();
+        self.collect(name, &mut found);
+        found
+    }
+
+    fn collect<'a>(&'a self, name: &str, found: &mut Vec<&'a XmlElement>) {
+        for child in &self.children {
+            if child.name == name {
+                found.push(child);
+            }
+            child.collect(name, found);
+        }
+    }
+
+    /// 按属性值查找后代元素，如 `find_by("c", "r", "B2")`
+    pub fn find_by(&self, name: &str, key: &str, value: &str) -> Option<&XmlElement> {
+        self.find_all(name)
+            .into_iter()
+            .find(|e| e.attr(key) == Some(value))
+    }
+}
+
+/// 由开始标签构造元素
+fn element(e: &BytesStart) -> XmlElement {
+    XmlElement {
+        name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+        attrs: e
+            .attributes()
+            .flatten()
+            .map(|a| {
+                (
+                    String::from_utf8_lossy(a.key.as_ref()).into_owned(),
+                    a.unescape_value().unwrap().into_owned(),
+                )
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// 解析 XML 文本，返回根元素
+pub(crate) fn parse_xml(xml: &str) -> XmlElement {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut stack = vec![XmlElement::default()];
+    loop {
+        match reader.read_event().unwrap() {
+            Event::Start(e) => stack.push(element(&e)),
+            Event::Empty(e) => {
+                let el = element(&e);
+                stack.last_mut().unwrap().children.push(el);
+            }
+            Event::End(_) => {
+                let el = stack.pop().unwrap();
+                stack.last_mut().unwrap().children.push(el);
+            }
+            Event::Text(t) => stack
+                .last_mut()
+                .unwrap()
+                .text
+                .push_str(&t.unescape().unwrap()),
+            Event::CData(t) => stack
+                .last_mut()
+                .unwrap()
+                .text
+                .push_str(&String::from_utf8_lossy(&t)),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let mut document = stack.pop().unwrap();
+    assert!(stack.is_empty(), "XML 标签未闭合");
+    document.children.pop().expect("XML 缺少根元素")
+}
+
+/// 读取并解析包内部件
+pub(crate) fn part_xml(bytes: &[u8], name: &str) -> XmlElement {
+    parse_xml(&read_part(bytes, name).unwrap())
+}
+
+/// 以默认工作簿选项生成单工作表 XLSX
+pub(crate) fn generate(table_data: &TableData) -> Vec<u8> {
+    generate_xlsx_bytes(table_data, None, false, None, &Default::default()).unwrap()
+}
+
+/// 生成 XLSX 并返回解析后的工作表 XML（`xl/worksheets/sheet1.xml`）
+pub(crate) fn sheet_xml(table_data: &TableData) -> XmlElement {
+    part_xml(&generate(table_data), "xl/worksheets/sheet1.xml")
+}
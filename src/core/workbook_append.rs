@@ -0,0 +1,1778 @@
+/// 追加工作表模块
+///
+/// 将新生成的工作表合并进已有的 XLSX 文件：已有工作表及其部件逐字节保留，
+/// 只改写工作簿、关系、内容类型、样式与共享字符串等工作簿级部件
+use super::export_xlsx::generate_xlsx_multi_bytes;
+use super::parse_export_data_options;
+use super::workbook_export::build_workbook_sheets;
+use super::workbook_options::WorkbookOptions;
+use super::xlsx_package::{
+    Relationship, TagEdit, attr_value, drop_calc_chain_override, drop_calc_chain_rel,
+    enable_full_calc, local_name, parse_relationships, parse_workbook_sheets, part_names,
+    raw_attrs, read_part, read_part_bytes, rels_path, render_tag, resolve_target, rewrite_parts,
+    rewrite_tags, rewrite_texts,
+};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use std::collections::{HashMap, HashSet};
+use wasm_bindgen::prelude::*;
+
+/// styles.xml 中各集合元素的顺序（缺失的集合按此顺序插入）
+const STYLE_SECTIONS: &[&str] = &[
+    "numFmts",
+    "fonts",
+    "fills",
+    "borders",
+    "cellStyleXfs",
+    "cellXfs",
+    "cellStyles",
+    "dxfs",
+    "tableStyles",
+    "colors",
+    "extLst",
+];
+
+/// 自定义数字格式的起始编号（更小的编号为 Excel 内置格式）
+const FIRST_CUSTOM_NUM_FMT: u32 = 164;
+
+/// 扩展文档属性部件（含工作表名称列表）
+const APP_PROPERTIES: &str = "docProps/app.xml";
+
+/// VML 绘图中每个形状编号块的大小
+const VML_SHAPE_BLOCK: u64 = 1024;
+
+/// 向已有的 XLSX 文件追加工作表
+///
+/// # 参数
+/// * `existing_bytes` - 已有 XLSX 文件字节（`Uint8Array`）
+/// * `sheets` - 工作表配置数组，格式同 `export_workbook`（DOM 表格 `tableId` 或 JS 数据 `data`），另支持：
+///   - `replace`: 为 `true` 时替换同名的已有工作表（保持原位置），否则同名时报错
+/// * `options` - 可选配置：`progressCallback` / `strictProgressCallback`、`headerStyle` / `cellStyle`
+///
+/// 已有工作表的内容、样式、合并单元格与公式保持不变，新工作表追加在末尾
+///
+/// # 返回值
+/// * `Ok(Uint8Array)` - 合并后的 XLSX 字节
+/// * `Err(JsValue)` - 文件无效、工作表重名或生成失败
+///
+/// # 示例
+/// ```javascript
+/// import { append_sheets_to_workbook } from './pkg/belobog_stellar_grid.js';
+///
+/// const existing = new Uint8Array(await file.arrayBuffer());
+/// const bytes = append_sheets_to_workbook(existing, [
+///   { data: juneOrders, columns, sheetName: '6月' },
+///   { tableId: 'summary-table', sheetName: '汇总', replace: true },
+/// ]);
+/// ```
+#[wasm_bindgen]
+pub fn append_sheets_to_workbook(
+    existing_bytes: &[u8],
+    sheets: JsValue,
+    options: Option<JsValue>,
+) -> Result<js_sys::Uint8Array, JsValue> {
+    let opts = parse_export_data_options(options)?;
+    let sheets_data = build_workbook_sheets(&sheets, &opts)?;
+    let replace: Vec<bool> = js_sys::Array::from(&sheets)
+        .iter()
+        .map(|item| {
+            js_sys::Reflect::get(&item, &JsValue::from_str("replace"))
+                .ok()
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        })
+        .collect();
+
+    let generated = generate_xlsx_multi_bytes(
+        &sheets_data,
+        opts.progress_callback.as_ref(),
+        opts.strict_progress,
+        None,
+        &WorkbookOptions::default(),
+    )?;
+    let bytes = append_sheets(existing_bytes, &generated, &replace)
+        .map_err(|e| JsValue::from_str(&format!("追加工作表失败: {}", e)))?;
+
+    Ok(js_sys::Uint8Array::from(bytes.as_slice()))
+}
+
+/// 内容类型表
+struct ContentTypes {
+    /// 扩展名（小写）→ 内容类型
+    defaults: HashMap<String, String>,
+    /// 部件名（小写，不含前导 `/`）→ 内容类型
+    overrides: HashMap<String, String>,
+}
+
+/// 合并样式后生成的工作表中样式引用的偏移量
+struct StyleRemap {
+    xf_offset: usize,
+    dxf_offset: usize,
+}
+
+/// 将 `generated` 中的全部工作表合并进 `existing`
+///
+/// `replace[i]` 为 `true` 时第 i 个新工作表替换同名的已有工作表，否则同名时报错
+pub(crate) fn append_sheets(
+    existing: &[u8],
+    generated: &[u8],
+    replace: &[bool],
+) -> Result<Vec<u8>, String> {
+    let existing_names = part_names(existing)?;
+    let mut taken: HashSet<String> = existing_names.iter().map(|n| n.to_lowercase()).collect();
+    let mut edits: HashMap<String, Option<Vec<u8>>> = HashMap::new();
+
+    let mut workbook_xml = read_part(existing, "xl/workbook.xml")?;
+    let mut workbook_rels_xml = read_part(existing, "xl/_rels/workbook.xml.rels")?;
+    let mut content_types_xml = read_part(existing, "[Content_Types].xml")?;
+    let workbook_rels = parse_relationships(&workbook_rels_xml)?;
+    let sheets = parse_workbook_sheets(&workbook_xml, &workbook_rels)?;
+    let content_types = parse_content_types(&content_types_xml)?;
+
+    let generated_workbook = read_part(generated, "xl/workbook.xml")?;
+    let generated_rels = parse_relationships(&read_part(generated, "xl/_rels/workbook.xml.rels")?)?;
+    let generated_sheets = parse_workbook_sheets(&generated_workbook, &generated_rels)?;
+    let generated_types = parse_content_types(&read_part(generated, "[Content_Types].xml")?)?;
+
+    // 样式
+    let styles_part = workbook_part(&workbook_rels, "/styles")
+        .ok_or_else(|| "XLSX 文件缺少样式部件".to_string())?;
+    let (styles_xml, remap) = merge_styles(
+        &read_part(existing, &styles_part)?,
+        &read_part(generated, "xl/styles.xml")?,
+    )?;
+    edits.insert(styles_part, Some(styles_xml.into_bytes()));
+
+    let mut new_rels = Vec::new();
+    let mut new_types = Vec::new();
+    let mut next_rel_id = 1;
+    let mut alloc_rel_id = || loop {
+        let id = format!("rId{}", next_rel_id);
+        next_rel_id += 1;
+        if workbook_rels.iter().all(|r| r.id != id) {
+            break id;
+        }
+    };
+
+    // 共享字符串：追加到已有的共享字符串表，没有时直接使用生成的表
+    let mut sst_offset = 0;
+    if let Some(generated_sst) = workbook_part(&generated_rels, "/sharedStrings") {
+        let generated_sst_xml = read_part(generated, &generated_sst)?;
+        match workbook_part(&workbook_rels, "/sharedStrings") {
+            Some(sst_part) => {
+                let (xml, offset) =
+                    merge_shared_strings(&read_part(existing, &sst_part)?, &generated_sst_xml)?;
+                sst_offset = offset;
+                edits.insert(sst_part, Some(xml.into_bytes()));
+            }
+            None => {
+                let part = unique_part_name(&mut taken, "xl/sharedStrings.xml");
+                new_rels.push(relationship_xml(
+                    &alloc_rel_id(),
+                    &rel_type_of(&generated_rels, &generated_sst),
+                    &relative_target("xl/workbook.xml", &part),
+                ));
+                new_types.extend(content_type_entry(
+                    &content_types,
+                    &generated_types,
+                    &generated_sst,
+                    &part,
+                ));
+                edits.insert(part, Some(generated_sst_xml.into_bytes()));
+            }
+        }
+    }
+
+    let mut ids = GlobalIds::collect(existing, &existing_names)?;
+    let mut new_sheet_tags = Vec::new();
+    let mut added_names: Vec<String> = Vec::new();
+    let mut sheet_states: HashMap<String, Option<String>> = HashMap::new();
+    let mut replaced_parts: Vec<String> = Vec::new();
+    let mut removed_locals: HashSet<u32> = HashSet::new();
+    let mut local_index: HashMap<u32, u32> = HashMap::new();
+    let mut next_sheet_id = sheets.iter().map(|s| s.sheet_id).max().unwrap_or(0) + 1;
+    let mut appended = 0;
+
+    for (i, sheet) in generated_sheets.iter().enumerate() {
+        let state = sheet_state(&generated_workbook, &sheet.name)?;
+        let target = sheets
+            .iter()
+            .position(|s| s.name.to_lowercase() == sheet.name.to_lowercase());
+
+        let part = match target {
+            Some(index) if replace.get(i).copied().unwrap_or(false) => {
+                let old = &sheets[index];
+                replaced_parts.push(old.part.clone());
+                let old_rels = rels_path(&old.part);
+                if existing_names.contains(&old_rels) {
+                    edits.insert(old_rels, None);
+                }
+                sheet_states.insert(old.name.clone(), state);
+                removed_locals.insert(index as u32);
+                local_index.insert(i as u32, index as u32);
+                old.part.clone()
+            }
+            Some(_) => {
+                return Err(format!(
+                    "工作表 '{}' 已存在，如需覆盖请设置 replace: true",
+                    sheet.name
+                ));
+            }
+            None => {
+                let part = unique_part_name(&mut taken, "xl/worksheets/sheet1.xml");
+                let rel_id = alloc_rel_id();
+                new_rels.push(relationship_xml(
+                    &rel_id,
+                    &rel_type_of(&generated_rels, &sheet.part),
+                    &relative_target("xl/workbook.xml", &part),
+                ));
+                new_types.extend(content_type_entry(
+                    &content_types,
+                    &generated_types,
+                    &sheet.part,
+                    &part,
+                ));
+                let state_attr = state
+                    .map(|s| format!(" state=\"{}\"", escape(s.as_str())))
+                    .unwrap_or_default();
+                new_sheet_tags.push(format!(
+                    "<sheet name=\"{}\" sheetId=\"{}\"{} {}=\"{}\"/>",
+                    escape(sheet.name.as_str()),
+                    next_sheet_id,
+                    state_attr,
+                    sheets.first().map_or("r:id", |s| s.rel_attr.0.as_str()),
+                    rel_id
+                ));
+                next_sheet_id += 1;
+                added_names.push(sheet.name.clone());
+                local_index.insert(i as u32, (sheets.len() + appended) as u32);
+                appended += 1;
+                part
+            }
+        };
+
+        let sheet_xml = rewrite_sheet(&read_part(generated, &sheet.part)?, &remap, sst_offset)?;
+        edits.insert(part.clone(), Some(sheet_xml.into_bytes()));
+        copy_related_parts(
+            generated,
+            &sheet.part,
+            &part,
+            &mut CopyContext {
+                taken: &mut taken,
+                edits: &mut edits,
+                ids: &mut ids,
+                remap: &remap,
+                existing_types: &content_types,
+                generated_types: &generated_types,
+                new_types: &mut new_types,
+            },
+        )?;
+    }
+
+    // 被替换工作表独占的部件（绘图、图表、批注、数据透视表等）一并删除，
+    // 其他工作表或工作簿仍引用的部件（共用的图片、数据透视缓存等）保留
+    let names: HashSet<String> = existing_names.iter().cloned().collect();
+    let skipped: HashSet<String> = replaced_parts.iter().cloned().collect();
+    let referenced = related_parts(existing, &names, &["xl/workbook.xml".to_string()], &skipped)?;
+    let mut removed_parts: Vec<String> = Vec::new();
+    for part in related_parts(existing, &names, &replaced_parts, &HashSet::new())? {
+        if !referenced.contains(&part) {
+            let rels = rels_path(&part);
+            if names.contains(&rels) {
+                edits.insert(rels, None);
+            }
+            edits.insert(part.clone(), None);
+            removed_parts.push(part);
+        }
+    }
+
+    // 工作簿：追加工作表条目、更新被替换工作表的隐藏状态与工作表级定义名称
+    workbook_xml = rewrite_tags(&workbook_xml, |e, empty| {
+        if local_name(e) != "sheet" {
+            return Ok(TagEdit::Keep);
+        }
+        Ok(
+            match attr_value(e, "name").and_then(|n| sheet_states.get(&n)) {
+                Some(state) => {
+                    TagEdit::Replace(render_tag(e, &[("state", state.as_deref())], empty))
+                }
+                None => TagEdit::Keep,
+            },
+        )
+    })?;
+    workbook_xml = insert_before_end(&workbook_xml, "sheets", &new_sheet_tags.concat())?;
+    let defined_names = generated_defined_names(&generated_workbook, &local_index)?;
+    workbook_xml = merge_defined_names(&workbook_xml, &removed_locals, &defined_names)?;
+
+    workbook_rels_xml = insert_before_end(&workbook_rels_xml, "Relationships", &new_rels.concat())?;
+
+    let removed_set: HashSet<String> = removed_parts.iter().map(|p| p.to_lowercase()).collect();
+    content_types_xml = rewrite_tags(&content_types_xml, |e, empty| {
+        let removed = local_name(e) == "Override"
+            && attr_value(e, "PartName")
+                .is_some_and(|p| removed_set.contains(&p.trim_start_matches('/').to_lowercase()));
+        Ok(if removed && empty {
+            TagEdit::Remove
+        } else {
+            TagEdit::Keep
+        })
+    })?;
+    let mut seen_defaults = HashSet::new();
+    new_types.retain(|t| !t.starts_with("<Default") || seen_defaults.insert(t.clone()));
+    content_types_xml = insert_before_end(&content_types_xml, "Types", &new_types.concat())?;
+
+    // 被替换的工作表可能出现在计算链中，删除计算链由 Excel 重新生成；
+    // 引用被替换工作表的公式缓存值已失效，打开文件时重新计算
+    if !removed_locals.is_empty() {
+        if existing_names.iter().any(|n| n == "xl/calcChain.xml") {
+            edits.insert("xl/calcChain.xml".to_string(), None);
+            workbook_rels_xml = drop_calc_chain_rel(&workbook_rels_xml)?;
+            content_types_xml = drop_calc_chain_override(&content_types_xml)?;
+        }
+        workbook_xml = enable_full_calc(&workbook_xml)?;
+    }
+
+    // 文档属性中的工作表名称列表
+    if !added_names.is_empty() && existing_names.iter().any(|n| n == APP_PROPERTIES) {
+        let sheet_names: Vec<&str> = sheets.iter().map(|s| s.name.as_str()).collect();
+        let app_xml = add_sheet_titles(
+            &read_part(existing, APP_PROPERTIES)?,
+            &sheet_names,
+            &added_names,
+        )?;
+        edits.insert(APP_PROPERTIES.to_string(), Some(app_xml.into_bytes()));
+    }
+
+    edits.insert(
+        "xl/workbook.xml".to_string(),
+        Some(workbook_xml.into_bytes()),
+    );
+    edits.insert(
+        "xl/_rels/workbook.xml.rels".to_string(),
+        Some(workbook_rels_xml.into_bytes()),
+    );
+    edits.insert(
+        "[Content_Types].xml".to_string(),
+        Some(content_types_xml.into_bytes()),
+    );
+
+    rewrite_parts(existing, edits)
+}
+
+// ============================================================================
+// 工作簿、关系与内容类型
+// ============================================================================
+
+/// 读取工作表的 `state` 属性（hidden / veryHidden）
+fn sheet_state(workbook_xml: &str, name: &str) -> Result<Option<String>, String> {
+    let mut state = None;
+    rewrite_tags(workbook_xml, |e, _| {
+        if local_name(e) == "sheet" && attr_value(e, "name").as_deref() == Some(name) {
+            state = attr_value(e, "state");
+        }
+        Ok(TagEdit::Keep)
+    })?;
+    Ok(state)
+}
+
+/// 解析内容类型表
+fn parse_content_types(xml: &str) -> Result<ContentTypes, String> {
+    let mut types = ContentTypes {
+        defaults: HashMap::new(),
+        overrides: HashMap::new(),
+    };
+    rewrite_tags(xml, |e, _| {
+        let content_type = attr_value(e, "ContentType").unwrap_or_default();
+        match local_name(e).as_str() {
+            "Default" => {
+                if let Some(ext) = attr_value(e, "Extension") {
+                    types.defaults.insert(ext.to_lowercase(), content_type);
+                }
+            }
+            "Override" => {
+                if let Some(part) = attr_value(e, "PartName") {
+                    types
+                        .overrides
+                        .insert(part.trim_start_matches('/').to_lowercase(), content_type);
+                }
+            }
+            _ => {}
+        }
+        Ok(TagEdit::Keep)
+    })?;
+    Ok(types)
+}
+
+/// 工作簿关系中指定类型（按后缀匹配）的部件路径
+fn workbook_part(rels: &[Relationship], type_suffix: &str) -> Option<String> {
+    rels.iter()
+        .find(|r| !r.external && r.rel_type.ends_with(type_suffix))
+        .map(|r| resolve_target("xl/workbook.xml", &r.target))
+}
+
+/// 指向指定部件的关系类型
+fn rel_type_of(rels: &[Relationship], part: &str) -> String {
+    rels.iter()
+        .find(|r| resolve_target("xl/workbook.xml", &r.target) == part)
+        .map(|r| r.rel_type.clone())
+        .unwrap_or_default()
+}
+
+fn relationship_xml(id: &str, rel_type: &str, target: &str) -> String {
+    format!(
+        "<Relationship Id=\"{}\" Type=\"{}\" Target=\"{}\"/>",
+        escape(id),
+        escape(rel_type),
+        escape(target)
+    )
+}
+
+/// 新部件的内容类型声明：生成文件中为 Override 的照搬，按扩展名声明且已有文件缺少该扩展名时补充 Default
+fn content_type_entry(
+    existing: &ContentTypes,
+    generated: &ContentTypes,
+    generated_part: &str,
+    part: &str,
+) -> Option<String> {
+    if let Some(content_type) = generated.overrides.get(&generated_part.to_lowercase()) {
+        return Some(format!(
+            "<Override PartName=\"/{}\" ContentType=\"{}\"/>",
+            escape(part),
+            escape(content_type.as_str())
+        ));
+    }
+    let ext = part.rsplit_once('.')?.1.to_lowercase();
+    if existing.defaults.contains_key(&ext) {
+        return None;
+    }
+    generated.defaults.get(&ext).map(|content_type| {
+        format!(
+            "<Default Extension=\"{}\" ContentType=\"{}\"/>",
+            escape(ext.as_str()),
+            escape(content_type.as_str())
+        )
+    })
+}
+
+/// 由来源部件指向目标部件的相对路径
+fn relative_target(source: &str, part: &str) -> String {
+    let source_dir: Vec<&str> = source
+        .rsplit_once('/')
+        .map_or(Vec::new(), |(dir, _)| dir.split('/').collect());
+    let target: Vec<&str> = part.split('/').collect();
+    let common = source_dir
+        .iter()
+        .zip(&target)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut path = vec![".."; source_dir.len() - common];
+    path.extend(&target[common..]);
+    path.join("/")
+}
+
+/// 在包内不冲突的前提下尽量沿用部件名，冲突时改为下一个可用的序号（如 `sheet3.xml`）
+fn unique_part_name(taken: &mut HashSet<String>, name: &str) -> String {
+    let candidate = if taken.contains(&name.to_lowercase()) {
+        let (stem, ext) = name.rsplit_once('.').unwrap_or((name, ""));
+        let prefix = stem.trim_end_matches(|c: char| c.is_ascii_digit());
+        (1..)
+            .map(|n| format!("{}{}.{}", prefix, n, ext))
+            .find(|n| !taken.contains(&n.to_lowercase()))
+            .unwrap_or_default()
+    } else {
+        name.to_string()
+    };
+    taken.insert(candidate.to_lowercase());
+    candidate
+}
+
+/// 由 `roots` 出发（递归）引用的包内部件，不含关系部件本身；`skip` 中的部件不再向下查找
+fn related_parts(
+    bytes: &[u8],
+    names: &HashSet<String>,
+    roots: &[String],
+    skip: &HashSet<String>,
+) -> Result<Vec<String>, String> {
+    let mut result = Vec::new();
+    let mut queue = roots.to_vec();
+    while let Some(source) = queue.pop() {
+        if skip.contains(&source) {
+            continue;
+        }
+        let rels = rels_path(&source);
+        if !names.contains(&rels) {
+            continue;
+        }
+        for rel in parse_relationships(&read_part(bytes, &rels)?)? {
+            let target = resolve_target(&source, &rel.target);
+            if !rel.external && names.contains(&target) && !result.contains(&target) {
+                result.push(target.clone());
+                queue.push(target);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// 在扩展文档属性的工作表名称列表（`TitlesOfParts` 中工作表分组的末尾）追加新工作表，并更新分组计数
+///
+/// 按已有工作表名称识别工作表分组（分组标题随 Excel 语言而不同），识别不到时原样返回
+fn add_sheet_titles(xml: &str, sheet_names: &[&str], added: &[String]) -> Result<String, String> {
+    let ends_with = |path: &[String], tail: &[&str]| {
+        path.len() >= tail.len()
+            && path[path.len() - tail.len()..]
+                .iter()
+                .zip(tail)
+                .all(|(a, b)| a == b)
+    };
+    let mut counts: Vec<usize> = Vec::new();
+    let mut titles: Vec<String> = Vec::new();
+    rewrite_texts(xml, |path, text| {
+        if ends_with(path, &["HeadingPairs", "vector", "variant", "i4"]) {
+            counts.push(text.trim().parse().unwrap_or(0));
+        } else if ends_with(path, &["TitlesOfParts", "vector", "lpstr"]) {
+            titles.push(text.to_string());
+        }
+        None
+    })?;
+
+    let mut offset = 0;
+    let mut group = None;
+    for (i, count) in counts.iter().enumerate() {
+        let end = (offset + count).min(titles.len());
+        if titles[offset.min(end)..end]
+            .iter()
+            .any(|t| sheet_names.contains(&t.as_str()))
+        {
+            group = Some((i, end));
+            break;
+        }
+        offset = end;
+    }
+    let Some((group, insert_at)) = group else {
+        return Ok(xml.to_string());
+    };
+
+    let mut out = String::with_capacity(xml.len());
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut path: Vec<String> = Vec::new();
+    let (mut count_index, mut title_index) = (0, 0);
+    let mut items = String::new();
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 app.xml 失败: {}", e))?;
+        let end = reader.buffer_position() as usize;
+        match &event {
+            Event::Eof => break,
+            Event::Start(e) => {
+                path.push(local_name(e));
+                if ends_with(&path, &["TitlesOfParts", "vector"]) {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                    let prefix = name.rsplit_once(':').map_or("", |(p, _)| p);
+                    let tag = if prefix.is_empty() {
+                        "lpstr".to_string()
+                    } else {
+                        format!("{}:lpstr", prefix)
+                    };
+                    items = added
+                        .iter()
+                        .map(|n| format!("<{}>{}</{}>", tag, escape(n.as_str()), tag))
+                        .collect();
+                    let size = (titles.len() + added.len()).to_string();
+                    out.push_str(&render_tag(e, &[("size", Some(&size))], false));
+                    if insert_at == 0 {
+                        out.push_str(&items);
+                    }
+                    continue;
+                }
+            }
+            Event::End(_) => {
+                let closed_title = ends_with(&path, &["TitlesOfParts", "vector", "lpstr"]);
+                path.pop();
+                out.push_str(&xml[start..end]);
+                if closed_title {
+                    title_index += 1;
+                    if title_index == insert_at {
+                        out.push_str(&items);
+                    }
+                }
+                continue;
+            }
+            Event::Text(_) if ends_with(&path, &["HeadingPairs", "vector", "variant", "i4"]) => {
+                count_index += 1;
+                if count_index - 1 == group {
+                    out.push_str(&(counts[group] + added.len()).to_string());
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        out.push_str(&xml[start..end]);
+    }
+    Ok(out)
+}
+
+/// 在指定元素的结束标签前插入内容
+fn insert_before_end(xml: &str, element: &str, content: &str) -> Result<String, String> {
+    if content.is_empty() {
+        return Ok(xml.to_string());
+    }
+    let mut reader = quick_xml::Reader::from_str(xml);
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader
+            .read_event()
+            .map_err(|e| format!("解析 XML 失败: {}", e))?
+        {
+            Event::End(e) if e.local_name().as_ref() == element.as_bytes() => {
+                return Ok(format!("{}{}{}", &xml[..start], content, &xml[start..]));
+            }
+            Event::Empty(e) if e.local_name().as_ref() == element.as_bytes() => {
+                let end = reader.buffer_position() as usize;
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                let open = render_tag(&e, &[], false);
+                return Ok(format!(
+                    "{}{}{}</{}>{}",
+                    &xml[..start],
+                    open,
+                    content,
+                    name,
+                    &xml[end..]
+                ));
+            }
+            Event::Eof => return Err(format!("XML 中缺少 <{}> 元素", element)),
+            _ => {}
+        }
+    }
+}
+
+/// 生成文件中的工作表级定义名称（打印标题、筛选区域等），`localSheetId` 改为合并后的位置
+fn generated_defined_names(
+    workbook_xml: &str,
+    local_index: &HashMap<u32, u32>,
+) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+    for (start_tag, inner) in defined_name_elements(workbook_xml)? {
+        let Some(local) = attr_value(&start_tag, "localSheetId").and_then(|v| v.parse().ok())
+        else {
+            continue;
+        };
+        if let Some(index) = local_index.get(&local) {
+            let index = index.to_string();
+            names.push(format!(
+                "{}{}</{}>",
+                render_tag(&start_tag, &[("localSheetId", Some(&index))], false),
+                inner,
+                String::from_utf8_lossy(start_tag.name().as_ref())
+            ));
+        }
+    }
+    Ok(names)
+}
+
+/// 工作簿中的全部 `<definedName>`：开始标签与内容原文
+fn defined_name_elements(xml: &str) -> Result<Vec<(BytesStart<'static>, String)>, String> {
+    let mut elements = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut current: Option<(BytesStart<'static>, usize)> = None;
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader
+            .read_event()
+            .map_err(|e| format!("解析 workbook.xml 失败: {}", e))?
+        {
+            Event::Start(e) if e.local_name().as_ref() == b"definedName" => {
+                current = Some((e.into_owned(), reader.buffer_position() as usize));
+            }
+            Event::End(e) if e.local_name().as_ref() == b"definedName" => {
+                if let Some((tag, inner_start)) = current.take() {
+                    elements.push((tag, xml[inner_start..start].to_string()));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(elements)
+}
+
+/// 删除被替换工作表的定义名称并加入新的定义名称（没有 `<definedNames>` 时在 `</sheets>` 后创建）
+fn merge_defined_names(
+    xml: &str,
+    removed_locals: &HashSet<u32>,
+    added: &[String],
+) -> Result<String, String> {
+    let mut out = String::with_capacity(xml.len());
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut skipping = false;
+    let needs_container = !added.is_empty() && !xml.contains("<definedNames");
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 workbook.xml 失败: {}", e))?;
+        let end = reader.buffer_position() as usize;
+        match &event {
+            Event::Eof => break,
+            Event::Start(e) if e.local_name().as_ref() == b"definedName" => {
+                skipping = attr_value(e, "localSheetId")
+                    .and_then(|v| v.parse::<u32>().ok())
+                    .is_some_and(|i| removed_locals.contains(&i));
+            }
+            Event::End(e) if e.local_name().as_ref() == b"definedName" && skipping => {
+                skipping = false;
+                continue;
+            }
+            Event::End(e) if e.local_name().as_ref() == b"definedNames" => {
+                out.push_str(&added.concat());
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"definedNames" => {
+                out.push_str(&format!("<definedNames>{}</definedNames>", added.concat()));
+                continue;
+            }
+            Event::End(e) if e.local_name().as_ref() == b"sheets" && needs_container => {
+                out.push_str(&xml[start..end]);
+                out.push_str(&format!("<definedNames>{}</definedNames>", added.concat()));
+                continue;
+            }
+            _ => {}
+        }
+        if !skipping {
+            out.push_str(&xml[start..end]);
+        }
+    }
+    Ok(out)
+}
+
+// ============================================================================
+// 样式与共享字符串
+// ============================================================================
+
+/// 指定路径元素（按本地名称，从根元素起）的直接子元素原文
+fn child_elements(xml: &str, path: &[&str]) -> Result<Vec<String>, String> {
+    let mut children = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut stack: Vec<String> = Vec::new();
+    let mut child_start = None;
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 XML 失败: {}", e))?;
+        let end = reader.buffer_position() as usize;
+        let in_parent = stack.len() == path.len() && stack.iter().zip(path).all(|(a, b)| a == b);
+        match &event {
+            Event::Start(e) => {
+                if in_parent {
+                    child_start = Some(start);
+                }
+                stack.push(local_name(e));
+            }
+            Event::Empty(_) if in_parent => children.push(xml[start..end].to_string()),
+            Event::End(_) => {
+                stack.pop();
+                let back_in_parent =
+                    stack.len() == path.len() && stack.iter().zip(path).all(|(a, b)| a == b);
+                if back_in_parent && let Some(s) = child_start.take() {
+                    children.push(xml[s..end].to_string());
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(children)
+}
+
+/// 在 styles.xml 的集合元素末尾追加子元素并更新 `count`，返回追加前的子元素数量
+///
+/// 集合元素不存在时按规范顺序插入新元素
+fn append_style_section(
+    xml: &str,
+    section: &str,
+    children: &[String],
+) -> Result<(String, usize), String> {
+    let existing = child_elements(xml, &["styleSheet", section])?.len();
+    if children.is_empty() {
+        return Ok((xml.to_string(), existing));
+    }
+    let count = (existing + children.len()).to_string();
+    let content = children.concat();
+
+    let created = |at: usize| {
+        format!(
+            "{}<{} count=\"{}\">{}</{}>{}",
+            &xml[..at],
+            section,
+            count,
+            content,
+            section,
+            &xml[at..]
+        )
+    };
+    let following = &STYLE_SECTIONS[STYLE_SECTIONS
+        .iter()
+        .position(|s| *s == section)
+        .map_or(STYLE_SECTIONS.len(), |i| i + 1)..];
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut depth = 0;
+    // 已找到的集合开始标签：(起止位置, 更新 count 后的标签)
+    let mut open: Option<(usize, usize, String)> = None;
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 styles.xml 失败: {}", e))?;
+        let end = reader.buffer_position() as usize;
+        match &event {
+            Event::Start(e) if depth == 1 && local_name(e) == section => {
+                open = Some((start, end, render_tag(e, &[("count", Some(&count))], false)));
+            }
+            Event::End(_) if depth == 2 && open.is_some() => {
+                let (tag_start, tag_end, tag) = open.take().unwrap_or_default();
+                let xml = format!(
+                    "{}{}{}{}{}",
+                    &xml[..tag_start],
+                    tag,
+                    &xml[tag_end..start],
+                    content,
+                    &xml[start..]
+                );
+                return Ok((xml, existing));
+            }
+            Event::Empty(e) if depth == 1 && local_name(e) == section => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                let tag = render_tag(e, &[("count", Some(&count))], false);
+                let xml = format!(
+                    "{}{}{}</{}>{}",
+                    &xml[..start],
+                    tag,
+                    content,
+                    name,
+                    &xml[end..]
+                );
+                return Ok((xml, existing));
+            }
+            Event::Start(e) | Event::Empty(e)
+                if depth == 1 && following.contains(&local_name(e).as_str()) =>
+            {
+                return Ok((created(start), existing));
+            }
+            Event::End(_) if depth == 1 => return Ok((created(start), existing)),
+            Event::Eof => return Err("styles.xml 缺少根元素".to_string()),
+            _ => {}
+        }
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+/// 改写 XML 片段根元素的属性（`map` 返回新值，`None` 保持原值）
+fn rewrite_root_attrs(
+    fragment: &str,
+    map: impl Fn(&str, &str) -> Option<String>,
+) -> Result<String, String> {
+    let mut first = true;
+    rewrite_tags(fragment, |e, empty| {
+        if !std::mem::take(&mut first) {
+            return Ok(TagEdit::Keep);
+        }
+        let changes: Vec<(String, String)> = raw_attrs(e)
+            .into_iter()
+            .filter_map(|(k, v)| map(&k, &v).map(|nv| (k, nv)))
+            .collect();
+        let set: Vec<(&str, Option<&str>)> = changes
+            .iter()
+            .map(|(k, v)| (k.as_str(), Some(v.as_str())))
+            .collect();
+        Ok(TagEdit::Replace(render_tag(e, &set, empty)))
+    })
+}
+
+/// 将生成文件的样式追加到已有样式表，返回新的样式表与样式引用偏移
+///
+/// 字体、填充、边框、单元格格式与差异格式整体追加；自定义数字格式按格式代码去重后重新编号
+fn merge_styles(existing: &str, generated: &str) -> Result<(String, StyleRemap), String> {
+    let generated_section = |section: &str| child_elements(generated, &["styleSheet", section]);
+    let num_fmt = |fragment: &str| -> Option<(u32, String)> {
+        let mut result = None;
+        rewrite_tags(fragment, |e, _| {
+            if result.is_none() {
+                result = attr_value(e, "numFmtId")
+                    .and_then(|id| id.parse().ok())
+                    .zip(attr_value(e, "formatCode"));
+            }
+            Ok(TagEdit::Keep)
+        })
+        .ok()?;
+        result
+    };
+
+    let existing_fmts: Vec<(u32, String)> = child_elements(existing, &["styleSheet", "numFmts"])?
+        .iter()
+        .filter_map(|f| num_fmt(f))
+        .collect();
+    let mut next_fmt = existing_fmts
+        .iter()
+        .map(|(id, _)| id + 1)
+        .max()
+        .unwrap_or(FIRST_CUSTOM_NUM_FMT)
+        .max(FIRST_CUSTOM_NUM_FMT);
+    let mut fmt_map = HashMap::new();
+    let mut new_fmts = Vec::new();
+    for fragment in generated_section("numFmts")? {
+        let Some((id, code)) = num_fmt(&fragment) else {
+            continue;
+        };
+        match existing_fmts.iter().find(|(_, c)| *c == code) {
+            Some((existing_id, _)) => {
+                fmt_map.insert(id, *existing_id);
+            }
+            None => {
+                fmt_map.insert(id, next_fmt);
+                let new_id = next_fmt.to_string();
+                new_fmts.push(rewrite_root_attrs(&fragment, |k, _| {
+                    (k == "numFmtId").then(|| new_id.clone())
+                })?);
+                next_fmt += 1;
+            }
+        }
+    }
+
+    let (xml, _) = append_style_section(existing, "numFmts", &new_fmts)?;
+    let (xml, font_offset) = append_style_section(&xml, "fonts", &generated_section("fonts")?)?;
+    let (xml, fill_offset) = append_style_section(&xml, "fills", &generated_section("fills")?)?;
+    let (xml, border_offset) =
+        append_style_section(&xml, "borders", &generated_section("borders")?)?;
+
+    let offset = |value: &str, by: usize| value.parse::<usize>().ok().map(|v| (v + by).to_string());
+    let xfs = generated_section("cellXfs")?
+        .iter()
+        .map(|xf| {
+            rewrite_root_attrs(xf, |k, v| match k {
+                "numFmtId" => v
+                    .parse::<u32>()
+                    .ok()
+                    .and_then(|id| fmt_map.get(&id))
+                    .map(u32::to_string),
+                "fontId" => offset(v, font_offset),
+                "fillId" => offset(v, fill_offset),
+                "borderId" => offset(v, border_offset),
+                _ => None,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let (xml, xf_offset) = append_style_section(&xml, "cellXfs", &xfs)?;
+    let (xml, dxf_offset) = append_style_section(&xml, "dxfs", &generated_section("dxfs")?)?;
+
+    Ok((
+        xml,
+        StyleRemap {
+            xf_offset,
+            dxf_offset,
+        },
+    ))
+}
+
+/// 将生成文件的共享字符串追加到已有共享字符串表，返回新表与索引偏移
+fn merge_shared_strings(existing: &str, generated: &str) -> Result<(String, usize), String> {
+    let offset = child_elements(existing, &["sst"])?.len();
+    let added = child_elements(generated, &["sst"])?;
+    let generated_refs = root_count(generated, "count")?.unwrap_or(added.len());
+    let existing_refs = root_count(existing, "count")?.unwrap_or(offset);
+    let unique = (offset + added.len()).to_string();
+    let refs = (existing_refs + generated_refs).to_string();
+
+    let xml = rewrite_root_attrs(existing, |k, _| match k {
+        "uniqueCount" => Some(unique.clone()),
+        "count" => Some(refs.clone()),
+        _ => None,
+    })?;
+    let xml = insert_before_end(&xml, "sst", &added.concat())?;
+    Ok((xml, offset))
+}
+
+/// 根元素上的计数属性
+fn root_count(xml: &str, key: &str) -> Result<Option<usize>, String> {
+    let mut value = None;
+    let mut first = true;
+    rewrite_tags(xml, |e, _| {
+        if std::mem::take(&mut first) {
+            value = attr_value(e, key).and_then(|v| v.parse().ok());
+        }
+        Ok(TagEdit::Keep)
+    })?;
+    Ok(value)
+}
+
+// ============================================================================
+// 工作表及其部件
+// ============================================================================
+
+/// 改写生成的工作表：样式索引加上合并后的偏移，共享字符串索引加上追加偏移，取消选中状态
+fn rewrite_sheet(xml: &str, remap: &StyleRemap, sst_offset: usize) -> Result<String, String> {
+    let mut out = String::with_capacity(xml.len());
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let (mut shared_cell, mut in_value) = (false, false);
+    let shift = |e: &BytesStart, key: &str, by: usize| {
+        attr_value(e, key)
+            .and_then(|v| v.parse::<usize>().ok())
+            .map(|v| (v + by).to_string())
+    };
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析工作表失败: {}", e))?;
+        let end = reader.buffer_position() as usize;
+        match &event {
+            Event::Eof => break,
+            Event::Start(e) | Event::Empty(e) => {
+                let empty = matches!(event, Event::Empty(_));
+                let name = local_name(e);
+                let (key, value) = match name.as_str() {
+                    "c" => {
+                        shared_cell = !empty && attr_value(e, "t").as_deref() == Some("s");
+                        ("s", shift(e, "s", remap.xf_offset))
+                    }
+                    "row" => ("s", shift(e, "s", remap.xf_offset)),
+                    "col" => ("style", shift(e, "style", remap.xf_offset)),
+                    "cfRule" => ("dxfId", shift(e, "dxfId", remap.dxf_offset)),
+                    "sheetView" => {
+                        out.push_str(&render_tag(e, &[("tabSelected", None)], empty));
+                        continue;
+                    }
+                    "v" => {
+                        in_value = shared_cell && !empty;
+                        ("", None)
+                    }
+                    _ => ("", None),
+                };
+                match value {
+                    Some(v) => out.push_str(&render_tag(e, &[(key, Some(&v))], empty)),
+                    None => out.push_str(&xml[start..end]),
+                }
+            }
+            Event::Text(t) if in_value => {
+                let text = t.unescape().map_err(|e| e.to_string())?;
+                match text.trim().parse::<usize>() {
+                    Ok(index) => out.push_str(&(index + sst_offset).to_string()),
+                    Err(_) => out.push_str(&xml[start..end]),
+                }
+            }
+            Event::End(e) => {
+                match e.local_name().as_ref() {
+                    b"v" => in_value = false,
+                    b"c" => shared_cell = false,
+                    _ => {}
+                }
+                out.push_str(&xml[start..end]);
+            }
+            _ => out.push_str(&xml[start..end]),
+        }
+    }
+    Ok(out)
+}
+
+/// 工作簿范围内须唯一的编号：Excel 表格的 id 与名称、VML 绘图的形状编号块
+struct GlobalIds {
+    next_table_id: u32,
+    table_names: HashSet<String>,
+    next_vml_block: u64,
+}
+
+impl GlobalIds {
+    fn collect(bytes: &[u8], names: &[String]) -> Result<Self, String> {
+        let mut ids = GlobalIds {
+            next_table_id: 1,
+            table_names: HashSet::new(),
+            next_vml_block: 1,
+        };
+        for name in names {
+            if name.starts_with("xl/tables/") && name.ends_with(".xml") {
+                let xml = read_part(bytes, name)?;
+                rewrite_tags(&xml, |e, _| {
+                    if local_name(e) == "table" {
+                        if let Some(id) = attr_value(e, "id").and_then(|v| v.parse::<u32>().ok()) {
+                            ids.next_table_id = ids.next_table_id.max(id + 1);
+                        }
+                        for key in ["name", "displayName"] {
+                            if let Some(n) = attr_value(e, key) {
+                                ids.table_names.insert(n.to_lowercase());
+                            }
+                        }
+                    }
+                    Ok(TagEdit::Keep)
+                })?;
+            } else if name.ends_with(".vml") {
+                let vml = String::from_utf8_lossy(&read_part_bytes(bytes, name)?).into_owned();
+                for block in vml_blocks(&vml) {
+                    ids.next_vml_block = ids.next_vml_block.max(block + 1);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// 分配不与已有表格冲突的名称（冲突时追加 `_2` 等序号）
+    fn table_name(&mut self, name: &str) -> String {
+        let candidate = (1..)
+            .map(|n| {
+                if n == 1 {
+                    name.to_string()
+                } else {
+                    format!("{}_{}", name, n)
+                }
+            })
+            .find(|n| !self.table_names.contains(&n.to_lowercase()))
+            .unwrap_or_default();
+        self.table_names.insert(candidate.to_lowercase());
+        candidate
+    }
+}
+
+/// VML 绘图 `<o:idmap data="1,2">` 中的形状编号块
+fn vml_blocks(vml: &str) -> Vec<u64> {
+    vml.split("<o:idmap")
+        .skip(1)
+        .filter_map(|rest| {
+            let tag = &rest[..rest.find('>')?];
+            let data = tag.split("data=\"").nth(1)?.split('"').next()?;
+            Some(
+                data.split(',')
+                    .filter_map(|v| v.trim().parse().ok())
+                    .collect::<Vec<u64>>(),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+/// 将 VML 绘图的形状编号块改为 `block`（`_x0000_s{块 × 1024 + 序号}` 形式的形状 ID 一并改写）
+fn renumber_vml(vml: &str, block: u64) -> String {
+    let Some(old) = vml_blocks(vml).first().copied() else {
+        return vml.to_string();
+    };
+    let vml = vml.replace(&format!("data=\"{}\"", old), &format!("data=\"{}\"", block));
+    let mut out = String::with_capacity(vml.len());
+    let mut rest = vml.as_str();
+    while let Some(pos) = rest.find("_x0000_s") {
+        let (head, tail) = rest.split_at(pos + "_x0000_s".len());
+        out.push_str(head);
+        let digits = tail.bytes().take_while(u8::is_ascii_digit).count();
+        match tail[..digits].parse::<u64>() {
+            Ok(id) if id >= old * VML_SHAPE_BLOCK => {
+                out.push_str(&(id - old * VML_SHAPE_BLOCK + block * VML_SHAPE_BLOCK).to_string());
+            }
+            _ => out.push_str(&tail[..digits]),
+        }
+        rest = &tail[digits..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 复制相关部件时共用的状态
+struct CopyContext<'a> {
+    taken: &'a mut HashSet<String>,
+    edits: &'a mut HashMap<String, Option<Vec<u8>>>,
+    ids: &'a mut GlobalIds,
+    remap: &'a StyleRemap,
+    existing_types: &'a ContentTypes,
+    generated_types: &'a ContentTypes,
+    new_types: &'a mut Vec<String>,
+}
+
+/// 复制生成文件中工作表引用的部件（批注、绘图、图片、表格等），重命名冲突的部件并改写关系目标
+fn copy_related_parts(
+    generated: &[u8],
+    source: &str,
+    target: &str,
+    ctx: &mut CopyContext,
+) -> Result<(), String> {
+    let source_rels = rels_path(source);
+    let Ok(rels_xml) = read_part(generated, &source_rels) else {
+        return Ok(());
+    };
+
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    for rel in parse_relationships(&rels_xml)? {
+        if rel.external {
+            continue;
+        }
+        let part = resolve_target(source, &rel.target);
+        let new_part = unique_part_name(ctx.taken, &part);
+        ctx.new_types.extend(content_type_entry(
+            ctx.existing_types,
+            ctx.generated_types,
+            &part,
+            &new_part,
+        ));
+
+        let content = read_part_bytes(generated, &part)?;
+        let content = if part.starts_with("xl/tables/") {
+            let xml = String::from_utf8_lossy(&content);
+            rewrite_table(&xml, ctx)?.into_bytes()
+        } else if part.ends_with(".vml") {
+            let block = ctx.ids.next_vml_block;
+            ctx.ids.next_vml_block += 1;
+            renumber_vml(&String::from_utf8_lossy(&content), block).into_bytes()
+        } else {
+            content
+        };
+        ctx.edits.insert(new_part.clone(), Some(content));
+        copy_related_parts(generated, &part, &new_part, ctx)?;
+        renamed.insert(rel.id, relative_target(target, &new_part));
+    }
+
+    let rels_xml = rewrite_tags(&rels_xml, |e, empty| {
+        Ok(match attr_value(e, "Id").and_then(|id| renamed.get(&id)) {
+            Some(new_target) if local_name(e) == "Relationship" => {
+                TagEdit::Replace(render_tag(e, &[("Target", Some(new_target))], empty))
+            }
+            _ => TagEdit::Keep,
+        })
+    })?;
+    ctx.edits
+        .insert(rels_path(target), Some(rels_xml.into_bytes()));
+    Ok(())
+}
+
+/// 改写 Excel 表格部件：分配唯一的 id 与名称，差异格式引用加上合并后的偏移
+fn rewrite_table(xml: &str, ctx: &mut CopyContext) -> Result<String, String> {
+    let dxf_offset = ctx.remap.dxf_offset;
+    let ids = &mut *ctx.ids;
+    rewrite_tags(xml, |e, empty| {
+        let mut changes: Vec<(String, String)> = raw_attrs(e)
+            .into_iter()
+            .filter(|(k, _)| k.ends_with("DxfId"))
+            .filter_map(|(k, v)| Some((k, (v.parse::<usize>().ok()? + dxf_offset).to_string())))
+            .collect();
+        if local_name(e) == "table" {
+            changes.push(("id".to_string(), ids.next_table_id.to_string()));
+            ids.next_table_id += 1;
+            let name = ids.table_name(&attr_value(e, "name").unwrap_or_else(|| "Table".into()));
+            changes.push(("name".to_string(), name.clone()));
+            changes.push(("displayName".to_string(), name));
+        }
+        if changes.is_empty() {
+            return Ok(TagEdit::Keep);
+        }
+        let set: Vec<(&str, Option<&str>)> = changes
+            .iter()
+            .map(|(k, v)| (k.as_str(), Some(v.as_str())))
+            .collect();
+        Ok(TagEdit::Replace(render_tag(e, &set, empty)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::parse_xml;
+    use crate::core::xlsx_package::rewrite_part;
+    use calamine::{Data, Reader, Xlsx};
+    use rust_xlsxwriter::{Chart, ChartType, Format, Formula, Image, Note, Table, Workbook};
+    use std::io::Cursor;
+
+    /// 已有文件：汇总（含批注与公式）与旧数据
+    fn existing_workbook() -> Vec<u8> {
+        let mut workbook = Workbook::new();
+        let summary = workbook.add_worksheet().set_name("汇总").unwrap();
+        summary
+            .write_with_format(0, 0, "合计", &Format::new().set_italic())
+            .unwrap();
+        summary.write(0, 1, Formula::new("=SUM(1,2)")).unwrap();
+        summary.insert_note(0, 0, &Note::new("旧批注")).unwrap();
+        summary.write(1, 0, "共享").unwrap();
+        let old = workbook.add_worksheet().set_name("旧数据").unwrap();
+        old.write(0, 0, "过期").unwrap();
+        workbook.save_to_buffer().unwrap()
+    }
+
+    /// 生成文件：本月（Excel 表格、批注与粗体备注）与旧数据
+    fn generated_workbook() -> Vec<u8> {
+        let mut workbook = Workbook::new();
+        let month = workbook.add_worksheet().set_name("本月").unwrap();
+        month.write(0, 0, "名称").unwrap();
+        month.write(0, 1, "数量").unwrap();
+        month.write(1, 0, "共享").unwrap();
+        month.write(1, 1, 3).unwrap();
+        month.insert_note(1, 1, &Note::new("新批注")).unwrap();
+        month.add_table(0, 0, 1, 1, &Table::new()).unwrap();
+        month
+            .write_with_format(3, 0, "备注", &Format::new().set_bold())
+            .unwrap();
+        let old = workbook.add_worksheet().set_name("旧数据").unwrap();
+        old.write(0, 0, "最新").unwrap();
+        workbook.save_to_buffer().unwrap()
+    }
+
+    fn cell(bytes: &[u8], sheet: &str, row: u32, col: u32) -> Data {
+        let mut xlsx = Xlsx::new(Cursor::new(bytes.to_vec())).unwrap();
+        let range = xlsx.worksheet_range(sheet).unwrap();
+        range.get_value((row, col)).cloned().unwrap_or(Data::Empty)
+    }
+
+    #[test]
+    fn test_append_sheets_keeps_existing_and_replaces() {
+        let existing = existing_workbook();
+        let bytes = append_sheets(&existing, &generated_workbook(), &[false, true]).unwrap();
+
+        let xlsx = Xlsx::new(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(xlsx.sheet_names(), vec!["汇总", "旧数据", "本月"]);
+        assert_eq!(cell(&bytes, "汇总", 0, 0), Data::String("合计".into()));
+        assert_eq!(cell(&bytes, "汇总", 1, 0), Data::String("共享".into()));
+        assert_eq!(cell(&bytes, "旧数据", 0, 0), Data::String("最新".into()));
+        assert_eq!(cell(&bytes, "本月", 0, 0), Data::String("名称".into()));
+        assert_eq!(cell(&bytes, "本月", 1, 1), Data::Float(3.0));
+
+        // 已有工作表及其批注部件逐字节保留
+        for part in ["xl/worksheets/sheet1.xml", "xl/comments1.xml"] {
+            assert_eq!(
+                read_part_bytes(&bytes, part).unwrap(),
+                read_part_bytes(&existing, part).unwrap()
+            );
+        }
+
+        // 新工作表的单元格样式指向合并后的粗体字体
+        let sheet_part = rels_path("xl/worksheets/sheet3.xml");
+        let sheet = read_part(&bytes, "xl/worksheets/sheet3.xml").unwrap();
+        let xf: usize = sheet.split("<c r=\"A4\" s=\"").nth(1).unwrap()[..]
+            .split('"')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let styles = read_part(&bytes, "xl/styles.xml").unwrap();
+        let xfs = child_elements(&styles, &["styleSheet", "cellXfs"]).unwrap();
+        let font: usize = attr_value_of(&xfs[xf], "fontId").parse().unwrap();
+        let fonts = child_elements(&styles, &["styleSheet", "fonts"]).unwrap();
+        assert!(fonts[font].contains("<b/>"));
+        assert!(!sheet.contains("tabSelected"));
+
+        // 新工作表的批注与表格重命名为不冲突的部件
+        let rels = read_part(&bytes, &sheet_part).unwrap();
+        assert!(rels.contains("../comments2.xml"));
+        assert!(rels.contains("../drawings/vmlDrawing2.vml"));
+        assert!(rels.contains("../tables/table1.xml"));
+        let vml_parts: Vec<String> = part_names(&bytes)
+            .unwrap()
+            .into_iter()
+            .filter(|n| n.ends_with(".vml"))
+            .collect();
+        let mut blocks: Vec<u64> = vml_parts
+            .iter()
+            .flat_map(|n| vml_blocks(&read_part(&bytes, n).unwrap()))
+            .collect();
+        blocks.sort_unstable();
+        blocks.dedup();
+        assert_eq!(blocks.len(), vml_parts.len());
+
+        let content_types = read_part(&bytes, "[Content_Types].xml").unwrap();
+        assert!(content_types.contains("/xl/worksheets/sheet3.xml"));
+        assert!(content_types.contains("/xl/tables/table1.xml"));
+    }
+
+    /// 已有文件：汇总与透视两个工作表共用一张图片，透视含图表与数据透视表（数据透视缓存由工作簿引用）
+    fn pivot_workbook() -> Vec<u8> {
+        let png = crate::core::cell_image::decode_data_url(&format!(
+            "data:image/png;base64,{}",
+            "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII="
+        ))
+        .unwrap();
+        let image = Image::new_from_buffer(&png).unwrap();
+        let mut workbook = Workbook::new();
+        let summary = workbook.add_worksheet().set_name("汇总").unwrap();
+        summary.write(0, 0, "数量").unwrap();
+        summary.write(1, 0, 3).unwrap();
+        summary.insert_image(3, 0, &image).unwrap();
+        let pivot = workbook.add_worksheet().set_name("透视").unwrap();
+        pivot.insert_image(0, 0, &image).unwrap();
+        let mut chart = Chart::new(ChartType::Column);
+        chart.add_series().set_values("汇总!$A$2:$A$2");
+        pivot.insert_chart(0, 3, &chart).unwrap();
+        let bytes = workbook.save_to_buffer().unwrap();
+
+        let add = |xml: String, end: &str, content: &str| {
+            xml.replace(end, &format!("{}{}", content, end))
+        };
+        let sheet_rels = add(
+            read_part(&bytes, "xl/worksheets/_rels/sheet2.xml.rels").unwrap(),
+            "</Relationships>",
+            "<Relationship Id=\"rIdP\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotTable\" Target=\"../pivotTables/pivotTable1.xml\"/>",
+        );
+        let workbook_rels = add(
+            read_part(&bytes, "xl/_rels/workbook.xml.rels").unwrap(),
+            "</Relationships>",
+            "<Relationship Id=\"rIdP\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition\" Target=\"pivotCache/pivotCacheDefinition1.xml\"/>",
+        );
+        let workbook_xml = add(
+            read_part(&bytes, "xl/workbook.xml").unwrap(),
+            "</workbook>",
+            "<pivotCaches><pivotCache cacheId=\"1\" r:id=\"rIdP\"/></pivotCaches>",
+        );
+        let part = |xml: &str| Some(xml.as_bytes().to_vec());
+        rewrite_parts(
+            &bytes,
+            HashMap::from([
+                ("xl/worksheets/_rels/sheet2.xml.rels".to_string(), part(&sheet_rels)),
+                ("xl/_rels/workbook.xml.rels".to_string(), part(&workbook_rels)),
+                ("xl/workbook.xml".to_string(), part(&workbook_xml)),
+                (
+                    "xl/pivotTables/pivotTable1.xml".to_string(),
+                    part("<pivotTableDefinition name=\"透视表1\" cacheId=\"1\"/>"),
+                ),
+                (
+                    "xl/pivotTables/_rels/pivotTable1.xml.rels".to_string(),
+                    part("<Relationships><Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition\" Target=\"../pivotCache/pivotCacheDefinition1.xml\"/></Relationships>"),
+                ),
+                (
+                    "xl/pivotCache/pivotCacheDefinition1.xml".to_string(),
+                    part("<pivotCacheDefinition r:id=\"rId1\"><cacheSource type=\"worksheet\"><worksheetSource ref=\"A1:A2\" sheet=\"汇总\"/></cacheSource></pivotCacheDefinition>"),
+                ),
+                (
+                    "xl/pivotCache/_rels/pivotCacheDefinition1.xml.rels".to_string(),
+                    part("<Relationships><Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheRecords\" Target=\"pivotCacheRecords1.xml\"/></Relationships>"),
+                ),
+                (
+                    "xl/pivotCache/pivotCacheRecords1.xml".to_string(),
+                    part("<pivotCacheRecords count=\"0\"/>"),
+                ),
+            ]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_append_sheets_replace_keeps_shared_parts() {
+        let existing = pivot_workbook();
+        let mut workbook = Workbook::new();
+        workbook
+            .add_worksheet()
+            .set_name("透视")
+            .unwrap()
+            .write(0, 0, "新数据")
+            .unwrap();
+        let bytes = append_sheets(&existing, &workbook.save_to_buffer().unwrap(), &[true]).unwrap();
+        let names = part_names(&bytes).unwrap();
+        assert_eq!(cell(&bytes, "透视", 0, 0), Data::String("新数据".into()));
+
+        // 被替换工作表独占的部件删除，工作簿引用的数据透视缓存与汇总的图片保留
+        for removed in [
+            "xl/pivotTables/pivotTable1.xml",
+            "xl/pivotTables/_rels/pivotTable1.xml.rels",
+            "xl/drawings/drawing2.xml",
+            "xl/charts/chart1.xml",
+        ] {
+            assert!(!names.iter().any(|n| n == removed), "{}", removed);
+        }
+        for kept in [
+            "xl/pivotCache/pivotCacheDefinition1.xml",
+            "xl/pivotCache/pivotCacheRecords1.xml",
+            "xl/drawings/drawing1.xml",
+            "xl/media/image1.png",
+        ] {
+            assert!(names.iter().any(|n| n == kept), "{}", kept);
+        }
+
+        // 剩余关系的目标全部存在，内容类型中不再声明已删除的部件
+        for rels in names.iter().filter(|n| n.ends_with(".rels")) {
+            let source = rels
+                .replace("_rels/", "")
+                .trim_end_matches(".rels")
+                .to_string();
+            for rel in parse_relationships(&read_part(&bytes, rels).unwrap()).unwrap() {
+                let target = resolve_target(&source, &rel.target);
+                assert!(
+                    rel.external || names.contains(&target),
+                    "{} -> {}",
+                    rels,
+                    target
+                );
+            }
+        }
+        let content_types = parse_xml(&read_part(&bytes, "[Content_Types].xml").unwrap());
+        for entry in content_types.find_all("Override") {
+            let part = entry.attr("PartName").unwrap().trim_start_matches('/');
+            assert!(names.iter().any(|n| n == part), "{}", part);
+        }
+    }
+
+    /// 去掉 rust_xlsxwriter 默认写入的 `fullCalcOnLoad`，模拟 Excel 保存的文件
+    fn without_full_calc(bytes: &[u8]) -> Vec<u8> {
+        rewrite_part(bytes, "xl/workbook.xml", |xml| {
+            Ok(xml.replace(" fullCalcOnLoad=\"1\"", ""))
+        })
+        .unwrap()
+    }
+
+    /// 扩展文档属性中的分组计数与工作表名称列表
+    fn app_titles(bytes: &[u8]) -> (Vec<String>, Vec<String>) {
+        let app = parse_xml(&read_part(bytes, APP_PROPERTIES).unwrap());
+        let texts = |parent: &str, name: &str| -> Vec<String> {
+            let vector = app.find(parent).unwrap().child("vector").unwrap();
+            assert_eq!(
+                vector.attr("size").unwrap().parse::<usize>().unwrap(),
+                vector.children.len()
+            );
+            vector
+                .find_all(name)
+                .into_iter()
+                .map(|e| e.text.clone())
+                .collect()
+        };
+        (texts("HeadingPairs", "i4"), texts("TitlesOfParts", "lpstr"))
+    }
+
+    #[test]
+    fn test_append_sheets_remaps_defined_names() {
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().set_name("汇总").unwrap();
+        let old = workbook.add_worksheet().set_name("旧数据").unwrap();
+        old.set_print_area(0, 0, 9, 3).unwrap();
+        let other = workbook.add_worksheet().set_name("其他").unwrap();
+        other.set_print_area(0, 0, 4, 1).unwrap();
+        let existing = without_full_calc(&workbook.save_to_buffer().unwrap());
+
+        let mut workbook = Workbook::new();
+        let month = workbook.add_worksheet().set_name("本月").unwrap();
+        month.write(0, 0, "名称").unwrap();
+        month.set_repeat_rows(0, 0).unwrap();
+        let old = workbook.add_worksheet().set_name("旧数据").unwrap();
+        old.write(0, 0, "名称").unwrap();
+        old.autofilter(0, 0, 3, 0).unwrap();
+        let generated = workbook.save_to_buffer().unwrap();
+
+        let bytes = append_sheets(&existing, &generated, &[false, true]).unwrap();
+        let workbook_xml = parse_xml(&read_part(&bytes, "xl/workbook.xml").unwrap());
+        let mut names: Vec<(String, String, String)> = workbook_xml
+            .find_all("definedName")
+            .into_iter()
+            .map(|e| {
+                (
+                    e.attr("name").unwrap().to_string(),
+                    e.attr("localSheetId").unwrap().to_string(),
+                    e.text.clone(),
+                )
+            })
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                (
+                    "_xlnm.Print_Area".into(),
+                    "2".into(),
+                    "其他!$A$1:$B$5".into()
+                ),
+                ("_xlnm.Print_Titles".into(), "3".into(), "本月!$1:$1".into()),
+                (
+                    "_xlnm._FilterDatabase".into(),
+                    "1".into(),
+                    "旧数据!$A$1:$A$4".into()
+                ),
+            ]
+        );
+
+        // 替换工作表后重新计算公式，新工作表追加到文档属性的工作表分组末尾
+        assert_eq!(
+            workbook_xml.find("calcPr").unwrap().attr("fullCalcOnLoad"),
+            Some("1")
+        );
+        let (counts, titles) = app_titles(&bytes);
+        assert_eq!(counts, vec!["4", "2"]);
+        assert_eq!(
+            titles,
+            vec![
+                "汇总",
+                "旧数据",
+                "其他",
+                "本月",
+                "其他!Print_Area",
+                "旧数据!Print_Area"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_append_sheets_replaces_hidden_sheet() {
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().set_name("汇总").unwrap();
+        let hidden = workbook.add_worksheet().set_name("隐藏").unwrap();
+        hidden.write(0, 0, "旧值").unwrap();
+        hidden.set_hidden(true);
+        let existing = workbook.save_to_buffer().unwrap();
+
+        let generated = |hidden: bool| {
+            let mut workbook = Workbook::new();
+            workbook.add_worksheet().set_name("新表").unwrap();
+            let sheet = workbook.add_worksheet().set_name("隐藏").unwrap();
+            sheet.write(0, 0, "新值").unwrap();
+            sheet.set_hidden(hidden);
+            workbook.save_to_buffer().unwrap()
+        };
+        let state = |bytes: &[u8], name: &str| {
+            parse_xml(&read_part(bytes, "xl/workbook.xml").unwrap())
+                .find_by("sheet", "name", name)
+                .unwrap()
+                .attr("state")
+                .map(String::from)
+        };
+
+        let bytes = append_sheets(&existing, &generated(true), &[false, true]).unwrap();
+        assert_eq!(cell(&bytes, "隐藏", 0, 0), Data::String("新值".into()));
+        assert_eq!(state(&bytes, "隐藏").as_deref(), Some("hidden"));
+        assert_eq!(state(&bytes, "新表"), None);
+        let (counts, titles) = app_titles(&bytes);
+        assert_eq!(counts, vec!["3"]);
+        assert_eq!(titles, vec!["汇总", "隐藏", "新表"]);
+
+        // 新工作表不隐藏时替换后的工作表随之显示
+        let bytes = append_sheets(&existing, &generated(false), &[false, true]).unwrap();
+        assert_eq!(state(&bytes, "隐藏"), None);
+    }
+
+    #[test]
+    fn test_append_sheets_without_shared_strings() {
+        let mut workbook = Workbook::new();
+        workbook
+            .add_worksheet()
+            .set_name("数值")
+            .unwrap()
+            .write(0, 0, 1)
+            .unwrap();
+        let existing = workbook.save_to_buffer().unwrap();
+        assert!(
+            !part_names(&existing)
+                .unwrap()
+                .iter()
+                .any(|n| n == "xl/sharedStrings.xml")
+        );
+
+        let bytes = append_sheets(&existing, &generated_workbook(), &[false, false]).unwrap();
+        assert_eq!(cell(&bytes, "数值", 0, 0), Data::Float(1.0));
+        assert_eq!(cell(&bytes, "本月", 0, 0), Data::String("名称".into()));
+        assert_eq!(cell(&bytes, "旧数据", 0, 0), Data::String("最新".into()));
+
+        let rels =
+            parse_relationships(&read_part(&bytes, "xl/_rels/workbook.xml.rels").unwrap()).unwrap();
+        assert_eq!(
+            workbook_part(&rels, "/sharedStrings").as_deref(),
+            Some("xl/sharedStrings.xml")
+        );
+        let content_types = parse_xml(&read_part(&bytes, "[Content_Types].xml").unwrap());
+        assert!(
+            content_types
+                .find_by("Override", "PartName", "/xl/sharedStrings.xml")
+                .is_some()
+        );
+        // 未替换工作表时不改动计算设置
+        assert!(
+            !read_part(&without_full_calc(&bytes), "xl/workbook.xml")
+                .unwrap()
+                .contains("fullCalcOnLoad")
+        );
+    }
+
+    #[test]
+    fn test_merge_styles_without_num_fmts_and_dxfs() {
+        let existing = "<styleSheet><fonts count=\"1\"><font/></fonts>\
+                        <fills count=\"1\"><fill/></fills><borders count=\"1\"><border/></borders>\
+                        <cellXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\"/></cellXfs>\
+                        <cellStyles count=\"1\"><cellStyle name=\"Normal\" xfId=\"0\"/></cellStyles>\
+                        <tableStyles count=\"0\"/></styleSheet>";
+        let generated = "<styleSheet><numFmts count=\"1\"><numFmt numFmtId=\"164\" formatCode=\"0.0%\"/></numFmts>\
+                         <fonts count=\"1\"><font><b/></font></fonts>\
+                         <cellXfs count=\"1\"><xf numFmtId=\"164\" fontId=\"0\"/></cellXfs>\
+                         <dxfs count=\"1\"><dxf><font><i/></font></dxf></dxfs></styleSheet>";
+        let (xml, remap) = merge_styles(existing, generated).unwrap();
+        assert_eq!((remap.xf_offset, remap.dxf_offset), (1, 0));
+
+        // 缺少的 numFmts 与 dxfs 按规范顺序插入
+        let styles = parse_xml(&xml);
+        let sections: Vec<&str> = styles.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            sections,
+            vec![
+                "numFmts",
+                "fonts",
+                "fills",
+                "borders",
+                "cellXfs",
+                "cellStyles",
+                "dxfs",
+                "tableStyles"
+            ]
+        );
+        let section = |name: &str| styles.child(name).unwrap();
+        assert_eq!(section("numFmts").attr("count"), Some("1"));
+        assert_eq!(
+            section("numFmts")
+                .child("numFmt")
+                .unwrap()
+                .attr("formatCode"),
+            Some("0.0%")
+        );
+        assert_eq!(section("fonts").attr("count"), Some("2"));
+        assert_eq!(section("dxfs").attr("count"), Some("1"));
+        let xf = &section("cellXfs").children[1];
+        assert_eq!(
+            (xf.attr("numFmtId"), xf.attr("fontId")),
+            (Some("164"), Some("1"))
+        );
+    }
+
+    #[test]
+    fn test_append_sheets_rejects_duplicate_name() {
+        let err = append_sheets(&existing_workbook(), &generated_workbook(), &[false, false])
+            .unwrap_err();
+        assert!(err.contains("旧数据"));
+        assert!(err.contains("replace"));
+    }
+
+    #[test]
+    fn test_relative_target_and_unique_part_name() {
+        assert_eq!(
+            relative_target("xl/workbook.xml", "xl/worksheets/sheet3.xml"),
+            "worksheets/sheet3.xml"
+        );
+        assert_eq!(
+            relative_target("xl/worksheets/sheet3.xml", "xl/comments2.xml"),
+            "../comments2.xml"
+        );
+        assert_eq!(
+            resolve_target("xl/worksheets/sheet1.xml", "../drawings/vmlDrawing1.vml"),
+            "xl/drawings/vmlDrawing1.vml"
+        );
+
+        let mut taken: HashSet<String> = ["xl/worksheets/sheet1.xml", "xl/worksheets/sheet2.xml"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            unique_part_name(&mut taken, "xl/worksheets/sheet1.xml"),
+            "xl/worksheets/sheet3.xml"
+        );
+        assert_eq!(
+            unique_part_name(&mut taken, "xl/tables/table1.xml"),
+            "xl/tables/table1.xml"
+        );
+    }
+
+    fn attr_value_of(fragment: &str, key: &str) -> String {
+        fragment
+            .split(&format!("{}=\"", key))
+            .nth(1)
+            .and_then(|s| s.split('"').next())
+            .unwrap_or_default()
+            .to_string()
+    }
+}
@@ -0,0 +1,158 @@
+/// 混合工作簿导出模块
+///
+/// 在同一个工作簿中组合 DOM 表格工作表与 JS 数据工作表，统一交给多工作表写入流程
+use super::export_xlsx::{export_as_xlsx_multi, generate_xlsx_multi_bytes};
+use super::table_extractor::TableData;
+use super::{
+    ExportDataOptions, ExportFormat, build_data_table, build_dom_sheet, merge_sheet_style,
+    parse_export_data_options, parse_sheet_config,
+};
+use wasm_bindgen::prelude::*;
+
+/// 导出混合工作簿：每个工作表来自 DOM 表格或 JS 数据
+///
+/// # 参数
+/// * `sheets` - JS 数组，每个元素为以下两种之一：
+///   - DOM 表格：`{ tableId, sheetName?, ... }`，其余字段同 `export_tables_xlsx` 的工作表配置
+///   - JS 数据：`{ data, columns?, childrenKey?, sheetName?, ... }`，其余字段同 `export_data` 的 options
+///     （`format`、`filename`、`progressCallback` 等文件级字段忽略）
+/// * `options` - 可选的工作簿级配置对象：
+///   - `filename`: 导出文件名
+///   - `progressCallback` / `strictProgressCallback`: 进度回调与严格模式
+///   - `headerStyle` / `cellStyle`: 全局表头 / 数据行样式，与各工作表的样式合并（工作表设置优先）
+///   - `properties`: 文档属性；`workbookProtection`: 工作簿结构保护
+///
+/// 工作表名称按 Excel 规则自动修正：非法字符替换为 `_`，超过 31 个字符截断，重名时追加 `(2)` 等序号
+///
+/// # 示例
+/// ```javascript
+/// import { export_workbook } from './pkg/belobog_stellar_grid.js';
+///
+/// export_workbook(
+///   [
+///     { tableId: 'summary-table', sheetName: '汇总' },
+///     { data: orders, columns, sheetName: '订单明细', freezeCols: 1 },
+///   ],
+///   { filename: '月报.xlsx', headerStyle: { bold: true } }
+/// );
+/// ```
+#[wasm_bindgen]
+pub fn export_workbook(sheets: JsValue, options: Option<JsValue>) -> Result<(), JsValue> {
+    let opts = parse_export_data_options(options)?;
+    let sheets_data = build_workbook_sheets(&sheets, &opts)?;
+
+    export_as_xlsx_multi(
+        sheets_data,
+        opts.filename,
+        opts.progress_callback,
+        opts.strict_progress,
+        None,
+        &opts.workbook_options,
+    )
+}
+
+/// 生成混合工作簿的 XLSX 字节（不触发下载）
+///
+/// 参数同 `export_workbook`（`filename` 忽略）。Web Worker 中没有 DOM，只能使用 `data` 工作表
+///
+/// # 返回值
+/// * `Ok(Uint8Array)` - 生成的 XLSX 字节
+/// * `Err(JsValue)` - 生成失败
+#[wasm_bindgen]
+pub fn generate_workbook_bytes(
+    sheets: JsValue,
+    options: Option<JsValue>,
+) -> Result<js_sys::Uint8Array, JsValue> {
+    let opts = parse_export_data_options(options)?;
+    let sheets_data = build_workbook_sheets(&sheets, &opts)?;
+
+    let bytes = generate_xlsx_multi_bytes(
+        &sheets_data,
+        opts.progress_callback.as_ref(),
+        opts.strict_progress,
+        None,
+        &opts.workbook_options,
+    )?;
+
+    Ok(js_sys::Uint8Array::from(bytes.as_slice()))
+}
+
+/// 按工作表配置逐个构建工作表数据（DOM 表格或 JS 数据）
+pub(super) fn build_workbook_sheets(
+    sheets: &JsValue,
+    workbook: &ExportDataOptions,
+) -> Result<Vec<(String, TableData)>, JsValue> {
+    if !js_sys::Array::is_array(sheets) {
+        return Err(JsValue::from_str("工作表配置必须是数组"));
+    }
+
+    let array = js_sys::Array::from(sheets);
+    if array.length() == 0 {
+        return Err(JsValue::from_str("工作表配置数组不能为空"));
+    }
+
+    let mut sheets_data = Vec::with_capacity(array.length() as usize);
+
+    for (idx, item) in array.iter().enumerate() {
+        let sheet_name = js_sys::Reflect::get(&item, &JsValue::from_str("sheetName"))
+            .ok()
+            .and_then(|v| v.as_string())
+            .unwrap_or_else(|| format!("Sheet{}", idx + 1));
+
+        let is_present = |key: &str| {
+            js_sys::Reflect::get(&item, &JsValue::from_str(key))
+                .ok()
+                .filter(|v| !v.is_undefined() && !v.is_null())
+        };
+
+        let table_data = match (is_present("tableId"), is_present("data")) {
+            (Some(_), Some(_)) => {
+                return Err(JsValue::from_str(&format!(
+                    "第 {} 个工作表配置不能同时提供 tableId 与 data",
+                    idx + 1
+                )));
+            }
+            (Some(_), None) => {
+                let config = parse_sheet_config(&item, idx)?;
+                build_dom_sheet(
+                    &config,
+                    workbook.header_style.as_ref(),
+                    workbook.cell_style.as_ref(),
+                )?
+            }
+            (None, Some(data)) => build_data_sheet(&data, &item, workbook).map_err(|e| {
+                JsValue::from_str(&format!(
+                    "第 {} 个工作表 '{}' 导出失败: {}",
+                    idx + 1,
+                    sheet_name,
+                    e.as_string().unwrap_or_default()
+                ))
+            })?,
+            (None, None) => {
+                return Err(JsValue::from_str(&format!(
+                    "第 {} 个工作表配置需要提供 tableId 或 data",
+                    idx + 1
+                )));
+            }
+        };
+
+        sheets_data.push((sheet_name, table_data));
+    }
+
+    Ok(sheets_data)
+}
+
+/// 按 export_data 的配置构建数据工作表，全局样式与工作表样式合并（工作表设置优先）
+fn build_data_sheet(
+    data: &JsValue,
+    item: &JsValue,
+    workbook: &ExportDataOptions,
+) -> Result<TableData, JsValue> {
+    let mut opts = parse_export_data_options(Some(item.clone()))?;
+    opts.format = ExportFormat::Xlsx;
+    opts.header_style =
+        merge_sheet_style(workbook.header_style.as_ref(), opts.header_style.as_ref());
+    opts.cell_style = merge_sheet_style(workbook.cell_style.as_ref(), opts.cell_style.as_ref());
+
+    build_data_table(data, &opts)
+}
@@ -0,0 +1,288 @@
+/// 工作簿级选项模块
+///
+/// 解析与工作表无关的工作簿级 XLSX 设置（如 `properties` 文档属性：标题、作者、公司等摘要信息与自定义属性，
+/// `workbookProtection` 工作簿结构保护），CSV 导出时忽略
+use super::data_export::get_object_property;
+use super::protection::{parse_workbook_protection, protect_workbook_structure};
+use rust_xlsxwriter::{DocProperties, ExcelDateTime, Workbook};
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+
+/// 工作簿级选项（仅 XLSX 导出时使用）
+#[derive(Clone, Default)]
+pub struct WorkbookOptions {
+    /// 文档属性（为 None 时只写入默认的创建时间）
+    pub properties: Option<DocProperties>,
+    /// 工作簿结构保护密码（为 None 时不保护，空字符串表示无密码保护）
+    pub structure_password: Option<String>,
+}
+
+impl WorkbookOptions {
+    /// 按工作簿级选项创建 Workbook
+    pub(crate) fn new_workbook(&self) -> Workbook {
+        let mut workbook = Workbook::new();
+        if let Some(ref properties) = self.properties {
+            workbook.set_properties(properties);
+        }
+        workbook
+    }
+
+    /// 保存工作簿为字节，并写入 rust_xlsxwriter 不直接支持的工作簿级设置
+    pub(crate) fn save(&self, workbook: &mut Workbook) -> Result<Vec<u8>, JsValue> {
+        let bytes = workbook
+            .save_to_buffer()
+            .map_err(|e| JsValue::from_str(&format!("生成 Excel 文件失败: {}", e)))?;
+
+        match self.structure_password {
+            Some(ref password) if !bytes.is_empty() => protect_workbook_structure(&bytes, password),
+            _ => Ok(bytes),
+        }
+    }
+}
+
+/// 从导出配置对象中解析工作簿级选项（export_data / export_table / 多工作表导出共用）
+///
+/// 读取字段：`properties`、`workbookProtection`
+pub fn parse_workbook_options(obj: &JsValue) -> Result<WorkbookOptions, JsValue> {
+    if obj.is_null() || obj.is_undefined() {
+        return Ok(WorkbookOptions::default());
+    }
+
+    let properties = js_sys::Reflect::get(obj, &JsValue::from_str("properties"))
+        .map_err(|e| JsValue::from_str(&format!("读取 properties 配置失败: {:?}", e)))?;
+    let protection = js_sys::Reflect::get(obj, &JsValue::from_str("workbookProtection"))
+        .map_err(|e| JsValue::from_str(&format!("读取 workbookProtection 配置失败: {:?}", e)))?;
+
+    Ok(WorkbookOptions {
+        properties: parse_doc_properties(&properties)?,
+        structure_password: parse_workbook_protection(&protection)?,
+    })
+}
+
+/// 文档属性设置方法
+type PropertySetter = fn(DocProperties, String) -> DocProperties;
+
+/// 字符串类文档属性：(配置字段, 设置方法)
+const TEXT_PROPERTIES: [(&str, PropertySetter); 10] = [
+    ("title", |p, v| p.set_title(v)),
+    ("subject", |p, v| p.set_subject(v)),
+    ("author", |p, v| p.set_author(v)),
+    ("manager", |p, v| p.set_manager(v)),
+    ("company", |p, v| p.set_company(v)),
+    ("category", |p, v| p.set_category(v)),
+    ("keywords", |p, v| p.set_keywords(v)),
+    ("comment", |p, v| p.set_comment(v)),
+    ("status", |p, v| p.set_status(v)),
+    ("hyperlinkBase", |p, v| p.set_hyperlink_base(v)),
+];
+
+/// 解析 `properties` 配置
+///
+/// 对象：`{ title?, subject?, author?, manager?, company?, category?, keywords?, comment?,
+/// status?, hyperlinkBase?, created?, custom? }`，`created` 为 Date 或 ISO-8601 字符串
+/// （默认导出时刻），`custom` 为 `{ 名称: string | number | boolean | Date }`
+///
+/// # 示例
+/// ```javascript
+/// {
+///   properties: {
+///     author: '张三',
+///     company: '某某科技',
+///     custom: { 部门: '财务部', 密级: '内部', 导出时间: new Date() },
+///   },
+/// }
+/// ```
+fn parse_doc_properties(val: &JsValue) -> Result<Option<DocProperties>, JsValue> {
+    if val.is_null() || val.is_undefined() {
+        return Ok(None);
+    }
+    if !val.is_object() || js_sys::Array::is_array(val) {
+        return Err(JsValue::from_str("properties 配置必须是对象"));
+    }
+
+    let mut properties = DocProperties::new();
+    for (key, set) in TEXT_PROPERTIES {
+        let v = get_object_property(val, key)?;
+        if v.is_null() || v.is_undefined() {
+            continue;
+        }
+        let text = v
+            .as_string()
+            .ok_or_else(|| JsValue::from_str(&format!("properties.{} 必须是字符串", key)))?;
+        properties = set(properties, text);
+    }
+
+    let created = get_object_property(val, "created")?;
+    if !created.is_null() && !created.is_undefined() {
+        let dt = js_value_to_utc_datetime(&created).ok_or_else(|| {
+            JsValue::from_str("properties.created 无效，应为 Date 或 ISO-8601 日期字符串")
+        })?;
+        properties = properties.set_creation_datetime(&dt);
+    }
+
+    let custom = get_object_property(val, "custom")?;
+    if !custom.is_null() && !custom.is_undefined() {
+        if !custom.is_object() || js_sys::Array::is_array(&custom) {
+            return Err(JsValue::from_str("properties.custom 必须是对象"));
+        }
+        let entries = js_sys::Object::entries(custom.unchecked_ref());
+        for entry in entries.iter() {
+            let entry = js_sys::Array::from(&entry);
+            let name = entry.get(0).as_string().unwrap_or_default();
+            properties = set_custom_property(properties, name, &entry.get(1))?;
+        }
+    }
+
+    Ok(Some(properties))
+}
+
+/// 按值类型写入一条自定义属性（整数写为整型，其余数字写为实数）
+fn set_custom_property(
+    properties: DocProperties,
+    name: String,
+    value: &JsValue,
+) -> Result<DocProperties, JsValue> {
+    check_custom_property_name(&name).map_err(|e| JsValue::from_str(&e))?;
+
+    if let Some(text) = value.as_string() {
+        return Ok(properties.set_custom_property(name, text));
+    }
+    if let Some(b) = value.as_bool() {
+        return Ok(properties.set_custom_property(name, b));
+    }
+    if let Some(n) = value.as_f64() {
+        if !n.is_finite() {
+            return Err(JsValue::from_str(&format!(
+                "自定义属性 '{}' 的值无效: {}",
+                name, n
+            )));
+        }
+        return Ok(match as_i32(n) {
+            Some(i) => properties.set_custom_property(name, i),
+            None => properties.set_custom_property(name, n),
+        });
+    }
+    if value.is_instance_of::<js_sys::Date>()
+        && let Some(dt) = js_value_to_utc_datetime(value)
+    {
+        return Ok(properties.set_custom_property(name, &dt));
+    }
+
+    Err(JsValue::from_str(&format!(
+        "自定义属性 '{}' 的值必须是字符串、数字、布尔值或有效的 Date",
+        name
+    )))
+}
+
+/// 校验自定义属性名称（1–255 个字符）
+fn check_custom_property_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.chars().count() > 255 {
+        return Err(format!(
+            "自定义属性名称无效: '{}'，长度应为 1–255 个字符",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// 可无损表示为 i32 的整数
+fn as_i32(n: f64) -> Option<i32> {
+    (n.fract() == 0.0 && (f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(&n))
+        .then_some(n as i32)
+}
+
+/// 将 JS Date / ISO-8601 字符串转换为 UTC 日期时间（文档属性中的时间按 UTC 存储）
+fn js_value_to_utc_datetime(val: &JsValue) -> Option<ExcelDateTime> {
+    let date = match val.dyn_ref::<js_sys::Date>() {
+        Some(date) => date.clone(),
+        None if val.is_string() => js_sys::Date::new(val),
+        None => return None,
+    };
+    if !date.get_time().is_finite() {
+        return None;
+    }
+
+    ExcelDateTime::from_ymd(
+        u16::try_from(date.get_utc_full_year()).ok()?,
+        (date.get_utc_month() + 1) as u8,
+        date.get_utc_date() as u8,
+    )
+    .ok()?
+    .and_hms_milli(
+        date.get_utc_hours() as u16,
+        date.get_utc_minutes() as u8,
+        date.get_utc_seconds() as u8,
+        date.get_utc_milliseconds() as u16,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::table_extractor::{CellValue, TableData};
+    use crate::core::test_support::part_xml;
+
+    #[test]
+    fn test_as_i32() {
+        assert_eq!(as_i32(3.0), Some(3));
+        assert_eq!(as_i32(-2_147_483_648.0), Some(i32::MIN));
+        assert_eq!(as_i32(2_147_483_648.0), None);
+        assert_eq!(as_i32(1.5), None);
+    }
+
+    #[test]
+    fn test_check_custom_property_name() {
+        assert!(check_custom_property_name("部门").is_ok());
+        assert!(check_custom_property_name(&"名".repeat(255)).is_ok());
+        assert!(check_custom_property_name("").is_err());
+        assert!(check_custom_property_name(&"名".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn test_write_doc_properties() {
+        let created = ExcelDateTime::from_ymd(2024, 3, 5)
+            .unwrap()
+            .and_hms(8, 9, 10)
+            .unwrap();
+        let properties = DocProperties::new()
+            .set_author("张三")
+            .set_company("某某科技")
+            .set_creation_datetime(&created)
+            .set_custom_property("部门", "财务部")
+            .set_custom_property("密级", 2)
+            .set_custom_property("已审核", true);
+        let workbook_options = WorkbookOptions {
+            properties: Some(properties),
+            ..Default::default()
+        };
+
+        let table_data = TableData {
+            rows: vec![vec![CellValue::from("名称")]],
+            ..Default::default()
+        };
+        let bytes = super::super::export_xlsx::generate_xlsx_bytes(
+            &table_data,
+            None,
+            false,
+            None,
+            &workbook_options,
+        )
+        .unwrap();
+
+        let core = part_xml(&bytes, "docProps/core.xml");
+        assert_eq!(core.child("creator").unwrap().text, "张三");
+        assert_eq!(core.child("created").unwrap().text, "2024-03-05T08:09:10Z");
+        let app = part_xml(&bytes, "docProps/app.xml");
+        assert_eq!(app.child("Company").unwrap().text, "某某科技");
+
+        let custom = part_xml(&bytes, "docProps/custom.xml");
+        let property = |name: &str| {
+            let value = &custom.find_by("property", "name", name).unwrap().children[0];
+            (value.name.clone(), value.text.clone())
+        };
+        assert_eq!(property("部门"), ("lpwstr".into(), "财务部".into()));
+        assert_eq!(property("密级"), ("i4".into(), "2".into()));
+        assert_eq!(property("已审核"), ("bool".into(), "true".into()));
+    }
+}
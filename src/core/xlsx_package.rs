@@ -0,0 +1,495 @@
+/// XLSX 包改写模块
+///
+/// 在 XLSX 文件上改写、增删个别部件（如 `xl/workbook.xml`），
+/// 其余部件按原始压缩数据逐字节复制，用于写入库本身不支持的工作簿级设置与模板、追加工作表等包级操作
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// 读取包内指定部件的文本内容
+pub(crate) fn read_part(bytes: &[u8], name: &str) -> Result<String, String> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("读取 XLSX 文件失败: {}", e))?;
+    let mut file = archive
+        .by_name(name)
+        .map_err(|e| format!("XLSX 文件缺少 {}: {}", name, e))?;
+    let mut xml = String::new();
+    file.read_to_string(&mut xml)
+        .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+    Ok(xml)
+}
+
+/// 读取包内指定部件的原始字节（用于图片等二进制部件）
+pub(crate) fn read_part_bytes(bytes: &[u8], name: &str) -> Result<Vec<u8>, String> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("读取 XLSX 文件失败: {}", e))?;
+    let mut file = archive
+        .by_name(name)
+        .map_err(|e| format!("XLSX 文件缺少 {}: {}", name, e))?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)
+        .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+    Ok(content)
+}
+
+/// 列出包内全部部件名称（保持原顺序）
+pub(crate) fn part_names(bytes: &[u8]) -> Result<Vec<String>, String> {
+    let archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("读取 XLSX 文件失败: {}", e))?;
+    Ok(archive.file_names().map(String::from).collect())
+}
+
+/// 改写包内指定部件，保持部件顺序不变
+///
+/// `rewrite` 接收部件原文本并返回新文本；目标部件不存在时返回错误
+pub(crate) fn rewrite_part(
+    bytes: &[u8],
+    name: &str,
+    rewrite: impl FnOnce(String) -> Result<String, String>,
+) -> Result<Vec<u8>, String> {
+    let content = rewrite(read_part(bytes, name)?)?;
+    rewrite_parts(
+        bytes,
+        HashMap::from([(name.to_string(), Some(content.into_bytes()))]),
+    )
+}
+
+/// 批量改写包内部件，保持部件顺序不变
+///
+/// `edits` 中 `Some(content)` 替换部件内容，`None` 删除部件；包内不存在的部件按名称顺序追加在末尾，
+/// 未列出的部件逐字节复制
+pub(crate) fn rewrite_parts(
+    bytes: &[u8],
+    mut edits: HashMap<String, Option<Vec<u8>>>,
+) -> Result<Vec<u8>, String> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("读取 XLSX 文件失败: {}", e))?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::with_capacity(bytes.len())));
+
+    for i in 0..archive.len() {
+        let file = archive
+            .by_index_raw(i)
+            .map_err(|e| format!("读取 XLSX 部件失败: {}", e))?;
+        let name = file.name().to_string();
+        match edits.remove(&name) {
+            Some(Some(content)) => write_file(&mut writer, &name, &content)?,
+            Some(None) => {}
+            None => writer
+                .raw_copy_file(file)
+                .map_err(|e| format!("复制 XLSX 部件失败: {}", e))?,
+        }
+    }
+
+    let mut added: Vec<_> = edits
+        .into_iter()
+        .filter_map(|(name, content)| Some((name, content?)))
+        .collect();
+    added.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, content) in added {
+        write_file(&mut writer, &name, &content)?;
+    }
+
+    writer
+        .finish()
+        .map(Cursor::into_inner)
+        .map_err(|e| format!("生成 XLSX 文件失败: {}", e))
+}
+
+/// 以 Deflate 压缩写入一个部件
+fn write_file(
+    writer: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    content: &[u8],
+) -> Result<(), String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    writer
+        .start_file(name, options)
+        .and_then(|_| writer.write_all(content).map_err(Into::into))
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))
+}
+
+/// 原样复制 XML 的同时改写部分标签
+pub(crate) enum TagEdit {
+    /// 保持原文
+    Keep,
+    /// 删除（仅用于自闭合标签）
+    Remove,
+    /// 替换为新的标签文本
+    Replace(String),
+}
+
+/// 逐个事件复制 XML 原文，`edit` 对开始 / 自闭合标签返回改写方式（第二个参数表示是否自闭合）
+pub(crate) fn rewrite_tags(
+    xml: &str,
+    mut edit: impl FnMut(&BytesStart, bool) -> Result<TagEdit, String>,
+) -> Result<String, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut out = String::with_capacity(xml.len());
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 XML 失败: {}", e))?;
+        let end = reader.buffer_position() as usize;
+        let result = match &event {
+            Event::Eof => break,
+            Event::Start(e) => edit(e, false)?,
+            Event::Empty(e) => edit(e, true)?,
+            _ => TagEdit::Keep,
+        };
+        match result {
+            TagEdit::Keep => out.push_str(&xml[start..end]),
+            TagEdit::Remove => {}
+            TagEdit::Replace(tag) => out.push_str(&tag),
+        }
+    }
+    Ok(out)
+}
+
+/// 逐个事件复制 XML 原文，改写元素内的文本
+///
+/// `edit` 接收从根元素起的元素路径（本地名称，最后一项为文本所在元素）与反转义后的文本，
+/// 返回 `Some` 且与原文不同时替换为新文本（重新转义）
+pub(crate) fn rewrite_texts(
+    xml: &str,
+    mut edit: impl FnMut(&[String], &str) -> Option<String>,
+) -> Result<String, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut out = String::with_capacity(xml.len());
+    let mut elements: Vec<String> = Vec::new();
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 XML 失败: {}", e))?;
+        let end = reader.buffer_position() as usize;
+        match &event {
+            Event::Eof => break,
+            Event::Start(e) => elements.push(local_name(e)),
+            Event::End(_) => {
+                elements.pop();
+            }
+            Event::Text(t) if !elements.is_empty() => {
+                let text = t.unescape().map_err(|e| format!("解析 XML 失败: {}", e))?;
+                if let Some(new) = edit(&elements, &text).filter(|new| *new != text) {
+                    out.push_str(&escape(new.as_str()));
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        out.push_str(&xml[start..end]);
+    }
+    Ok(out)
+}
+
+/// 获取 XML 元素的本地名称
+pub(crate) fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// 读取属性（已反转义）
+pub(crate) fn attr_value(e: &BytesStart, key: &str) -> Option<String> {
+    e.try_get_attribute(key)
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+/// 读取全部属性（保留转义后的原文）
+pub(crate) fn raw_attrs(e: &BytesStart) -> Vec<(String, String)> {
+    e.attributes()
+        .with_checks(false)
+        .flatten()
+        .map(|a| {
+            (
+                String::from_utf8_lossy(a.key.as_ref()).into_owned(),
+                String::from_utf8_lossy(&a.value).into_owned(),
+            )
+        })
+        .collect()
+}
+
+/// 重新生成标签文本：`set` 中的属性替换为新值（`None` 表示删除，原先没有的属性追加在末尾），其余属性保持原文
+pub(crate) fn render_tag(e: &BytesStart, set: &[(&str, Option<&str>)], empty: bool) -> String {
+    let mut tag = format!("<{}", String::from_utf8_lossy(e.name().as_ref()));
+    for (key, value) in raw_attrs(e) {
+        let value = match set.iter().find(|(k, _)| *k == key) {
+            Some((_, None)) => continue,
+            Some((_, Some(v))) => escape(*v).into_owned(),
+            None => value,
+        };
+        tag.push_str(&format!(" {}=\"{}\"", key, value));
+    }
+    for (key, value) in set {
+        if let Some(value) = value
+            && e.try_get_attribute(*key).ok().flatten().is_none()
+        {
+            tag.push_str(&format!(" {}=\"{}\"", key, escape(*value)));
+        }
+    }
+    tag.push_str(if empty { "/>" } else { ">" });
+    tag
+}
+
+/// 拼接属性列表为 XML 属性文本（值为转义后的原文）
+pub(crate) fn attrs_text(attrs: &[(String, String)]) -> String {
+    attrs
+        .iter()
+        .map(|(k, v)| format!(" {}=\"{}\"", k, v))
+        .collect()
+}
+
+/// 工作簿中的工作表条目
+pub(crate) struct WorkbookSheet {
+    pub name: String,
+    pub sheet_id: u32,
+    /// 关系 ID 属性名（如 `r:id`）与值
+    pub rel_attr: (String, String),
+    /// 工作表部件路径
+    pub part: String,
+}
+
+/// 关系条目
+pub(crate) struct Relationship {
+    pub id: String,
+    pub rel_type: String,
+    pub target: String,
+    pub external: bool,
+}
+
+/// 解析工作簿中的工作表列表（按显示顺序）
+pub(crate) fn parse_workbook_sheets(
+    xml: &str,
+    rels: &[Relationship],
+) -> Result<Vec<WorkbookSheet>, String> {
+    let mut sheets = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| format!("解析 workbook.xml 失败: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+                let rel_attr = raw_attrs(&e)
+                    .into_iter()
+                    .find(|(k, _)| k.ends_with(":id"))
+                    .ok_or_else(|| "workbook.xml 中的工作表缺少关系 ID".to_string())?;
+                let part = rels
+                    .iter()
+                    .find(|r| r.id == rel_attr.1)
+                    .map(|r| resolve_target("xl/workbook.xml", &r.target))
+                    .ok_or_else(|| format!("找不到工作表关系 {}", rel_attr.1))?;
+                sheets.push(WorkbookSheet {
+                    name: attr_value(&e, "name").unwrap_or_default(),
+                    sheet_id: attr_value(&e, "sheetId")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(0),
+                    rel_attr,
+                    part,
+                });
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sheets)
+}
+
+/// 解析关系部件
+pub(crate) fn parse_relationships(xml: &str) -> Result<Vec<Relationship>, String> {
+    let mut rels = Vec::new();
+    rewrite_tags(xml, |e, _| {
+        if local_name(e) == "Relationship" {
+            rels.push(Relationship {
+                id: attr_value(e, "Id").unwrap_or_default(),
+                rel_type: attr_value(e, "Type").unwrap_or_default(),
+                target: attr_value(e, "Target").unwrap_or_default(),
+                external: attr_value(e, "TargetMode").as_deref() == Some("External"),
+            });
+        }
+        Ok(TagEdit::Keep)
+    })?;
+    Ok(rels)
+}
+
+/// 部件对应的关系部件路径（如 `xl/worksheets/_rels/sheet1.xml.rels`）
+pub(crate) fn rels_path(part: &str) -> String {
+    match part.rsplit_once('/') {
+        Some((dir, file)) => format!("{}/_rels/{}.rels", dir, file),
+        None => format!("_rels/{}.rels", part),
+    }
+}
+
+/// 将关系目标解析为包内部件路径
+pub(crate) fn resolve_target(source: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut segments: Vec<&str> = source.split('/').collect();
+    segments.pop();
+    for segment in target.split('/') {
+        match segment {
+            ".." => {
+                segments.pop();
+            }
+            "." | "" => {}
+            s => segments.push(s),
+        }
+    }
+    segments.join("/")
+}
+
+/// 删除工作簿关系中的计算链
+pub(crate) fn drop_calc_chain_rel(xml: &str) -> Result<String, String> {
+    rewrite_tags(xml, |e, empty| {
+        let is_calc_chain = local_name(e) == "Relationship"
+            && attr_value(e, "Type").is_some_and(|t| t.ends_with("/calcChain"));
+        Ok(if is_calc_chain && empty {
+            TagEdit::Remove
+        } else {
+            TagEdit::Keep
+        })
+    })
+}
+
+/// 删除内容类型中的计算链声明
+pub(crate) fn drop_calc_chain_override(xml: &str) -> Result<String, String> {
+    rewrite_tags(xml, |e, empty| {
+        let is_calc_chain = local_name(e) == "Override"
+            && attr_value(e, "PartName").as_deref() == Some("/xl/calcChain.xml");
+        Ok(if is_calc_chain && empty {
+            TagEdit::Remove
+        } else {
+            TagEdit::Keep
+        })
+    })
+}
+
+/// `<workbook>` 中位于 `<calcPr>` 之后的子元素
+const AFTER_CALC_PR: &[&str] = &[
+    "oleSize",
+    "customWorkbookViews",
+    "pivotCaches",
+    "smartTagPr",
+    "smartTagTypes",
+    "webPublishing",
+    "fileRecoveryPr",
+    "webPublishObjects",
+    "extLst",
+];
+
+/// 设置打开文件时重新计算全部公式（缺少 `<calcPr>` 时按规范顺序插入）
+pub(crate) fn enable_full_calc(xml: &str) -> Result<String, String> {
+    let mut found = false;
+    let xml = rewrite_tags(xml, |e, empty| {
+        Ok(if local_name(e) == "calcPr" {
+            found = true;
+            TagEdit::Replace(render_tag(e, &[("fullCalcOnLoad", Some("1"))], empty))
+        } else {
+            TagEdit::Keep
+        })
+    })?;
+    if found {
+        return Ok(xml);
+    }
+
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    let mut depth = 0;
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 workbook.xml 失败: {}", e))?;
+        let insert = match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                depth == 1 && AFTER_CALC_PR.contains(&local_name(e).as_str())
+            }
+            Event::End(_) => depth == 1,
+            Event::Eof => return Err("workbook.xml 缺少根元素".to_string()),
+            _ => false,
+        };
+        if insert {
+            return Ok(format!(
+                "{}<calcPr fullCalcOnLoad=\"1\"/>{}",
+                &xml[..start],
+                &xml[start..]
+            ));
+        }
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_xlsxwriter::Workbook;
+
+    #[test]
+    fn test_rewrite_part() {
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().write(0, 0, "名称").unwrap();
+        let bytes = workbook.save_to_buffer().unwrap();
+
+        let rewritten = rewrite_part(&bytes, "xl/workbook.xml", |xml| {
+            Ok(xml.replace("<bookViews>", "<!-- x --><bookViews>"))
+        })
+        .unwrap();
+        assert!(
+            read_part(&rewritten, "xl/workbook.xml")
+                .unwrap()
+                .contains("<!-- x -->")
+        );
+
+        // 其余部件与部件顺序保持不变
+        let names = |bytes: &[u8]| {
+            let archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+            archive.file_names().map(String::from).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&bytes), names(&rewritten));
+        assert_eq!(
+            read_part(&bytes, "xl/sharedStrings.xml").unwrap(),
+            read_part(&rewritten, "xl/sharedStrings.xml").unwrap()
+        );
+
+        assert!(rewrite_part(&bytes, "xl/missing.xml", Ok).is_err());
+
+        // 批量改写：删除部件，其余部件保持顺序
+        let removed = rewrite_parts(
+            &bytes,
+            HashMap::from([
+                ("xl/sharedStrings.xml".to_string(), None),
+                ("xl/extra.xml".to_string(), Some(b"<extra/>".to_vec())),
+            ]),
+        )
+        .unwrap();
+        let mut expected = names(&bytes);
+        expected.retain(|name| name != "xl/sharedStrings.xml");
+        expected.push("xl/extra.xml".to_string());
+        assert_eq!(part_names(&removed).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_enable_full_calc() {
+        assert_eq!(
+            enable_full_calc("<workbook><sheets/><calcPr calcId=\"191029\"/></workbook>").unwrap(),
+            "<workbook><sheets/><calcPr calcId=\"191029\" fullCalcOnLoad=\"1\"/></workbook>"
+        );
+        // 缺少 calcPr 时插入在 pivotCaches 等后续元素之前
+        assert_eq!(
+            enable_full_calc("<workbook><sheets/><pivotCaches/></workbook>").unwrap(),
+            "<workbook><sheets/><calcPr fullCalcOnLoad=\"1\"/><pivotCaches/></workbook>"
+        );
+        assert_eq!(
+            enable_full_calc("<workbook><sheets/></workbook>").unwrap(),
+            "<workbook><sheets/><calcPr fullCalcOnLoad=\"1\"/></workbook>"
+        );
+    }
+}
@@ -11,7 +11,11 @@ pub use resource::UrlGuard;
 pub use validation::{ensure_extension, validate_filename};
 
 // 导出新的统一接口
-pub use core::{ExportFormat, export_data, export_table, export_tables_xlsx, generate_data_bytes};
+pub use core::{
+    ExportFormat, append_sheets_to_workbook, export_data, export_from_template, export_table,
+    export_tables_xlsx, export_workbook, generate_data_bytes, generate_template_bytes,
+    generate_workbook_bytes,
+};
 
 // 导出 Excel 预览接口
 pub use core::{get_excel_sheet_list, parse_excel_to_html, parse_excel_to_json};
@@ -29,7 +33,9 @@ pub use utils::{escape_csv_injection, set_panic_hook};
 // 导出 benchmark 所需的内部类型和函数（不在文档中显示）
 #[doc(hidden)]
 pub mod bench_exports {
+    pub use crate::core::csv_dialect::CsvDialect;
     pub use crate::core::export_csv::generate_csv_bytes;
     pub use crate::core::export_xlsx::{generate_xlsx_bytes, generate_xlsx_multi_bytes};
-    pub use crate::core::table_extractor::{MergeRange, TableData};
+    pub use crate::core::table_extractor::{CellValue, MergeRange, TableData};
+    pub use crate::core::workbook_options::WorkbookOptions;
 }
@@ -11,12 +11,12 @@ use crate::core::export_csv::create_and_download_csv_parts;
 ///
 /// **注意**：XLSX 格式受 `rust_xlsxwriter` 库限制无法真正流式化，
 /// 当 `format=Xlsx` 时会自动回退到 `export_data` 的同步逻辑。
+use crate::core::row_style::RowStyleOptions;
 use crate::core::{
     ExportDataOptions, ExportFormat, build_table_data_from_array, build_table_data_from_tree,
-    export_data_impl, parse_export_data_options, parse_js_array_data,
+    cells_to_text_rows, export_data_impl, parse_export_data_options, parse_js_array_data,
 };
 use crate::utils::{report_progress, yield_to_browser};
-use csv::Writer;
 use std::io::Cursor;
 use wasm_bindgen::prelude::*;
 
@@ -38,7 +38,7 @@ const DEFAULT_CHUNK_SIZE: usize = 5000;
 /// * `data` - JS 数组（二维数组或对象数组）
 /// * `options` - 配置对象（同 `export_data`，额外支持 `chunkSize` 字段）
 ///   - `chunkSize`: 每个分块包含的行数（默认 5000）
-///   - 其他字段同 `export_data` 的 options
+///   - 其他字段同 `export_data` 的 options，`format: ExportFormat.Tsv` 与 `csv` 方言同样适用
 ///
 /// # 返回值
 /// * `Promise<void>` - 异步导出完成
@@ -115,7 +115,7 @@ pub async fn export_data_streaming(
         let chunk_end = std::cmp::min(processed_rows + chunk_size, total_rows);
 
         // 创建当前分块的 CSV Writer
-        let mut wtr = Writer::from_writer(Cursor::new(Vec::new()));
+        let mut wtr = opts.csv_dialect.writer(Cursor::new(Vec::new()));
 
         // 写入当前分块的行数据
         for row_data in &rows[processed_rows..chunk_end] {
@@ -160,7 +160,7 @@ pub async fn export_data_streaming(
     }
 
     // 用所有分块片段创建 CSV Blob 并触发下载
-    create_and_download_csv_parts(&blob_parts, opts.filename, "streaming_export.csv")?;
+    create_and_download_csv_parts(&blob_parts, opts.filename, "streaming_export", opts.format)?;
 
     Ok(JsValue::UNDEFINED)
 }
@@ -184,19 +184,26 @@ fn build_rows_from_data(
     data: JsValue,
     opts: &ExportDataOptions,
 ) -> Result<Vec<Vec<String>>, JsValue> {
+    let date_format = opts.csv_date_format.as_deref();
     if let Some(ref cols) = opts.columns {
         if let Some(ref ck) = opts.children_key {
             // 树形数据模式
-            let table_data =
-                build_table_data_from_tree(cols, &data, opts.indent_column.as_deref(), ck)?;
-            Ok(table_data.rows)
+            let table_data = build_table_data_from_tree(
+                cols,
+                &data,
+                opts.indent_column.as_deref(),
+                ck,
+                &RowStyleOptions::default(),
+                None,
+            )?;
+            Ok(cells_to_text_rows(table_data.rows, date_format))
         } else {
             // 对象数组 + columns 配置
-            let table_data = build_table_data_from_array(cols, &data)?;
-            Ok(table_data.rows)
+            let table_data = build_table_data_from_array(cols, &data, &RowStyleOptions::default())?;
+            Ok(cells_to_text_rows(table_data.rows, date_format))
         }
     } else {
         // 二维数组模式
-        parse_js_array_data(&data)
+        parse_js_array_data(&data).map(|rows| cells_to_text_rows(rows, date_format))
     }
 }
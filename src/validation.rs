@@ -100,6 +100,166 @@ pub fn prepare_download_filename(
     Ok(ensure_extension(&name, extension))
 }
 
+/// 超链接地址最大长度（Excel 限制约 2080 个字符）
+const MAX_HYPERLINK_LEN: usize = 2079;
+
+/// 校验并规范化超链接地址
+///
+/// 仅允许 http / https / ftp / ftps / mailto 协议，以及 `#Sheet2!A1` 形式的工作簿内部链接；
+/// `javascript:`、`vbscript:`、`data:`、`file:` 等协议和相对地址一律拒绝
+///
+/// # 返回值
+/// * `Ok(String)` - 规范化后的地址（协议名转为小写）
+/// * `Err(String)` - 地址不安全或不合法，包含错误信息
+pub(crate) fn sanitize_hyperlink(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("链接地址不能为空".to_string());
+    }
+
+    // 浏览器会忽略协议名中的制表符/换行（如 "java\nscript:"），直接拒绝所有控制字符
+    if url.chars().any(|c| c.is_control()) {
+        return Err("链接地址不能包含控制字符".to_string());
+    }
+
+    if url.chars().count() > MAX_HYPERLINK_LEN {
+        return Err(format!("链接地址过长（最大 {} 个字符）", MAX_HYPERLINK_LEN));
+    }
+
+    // 工作簿内部链接
+    if let Some(anchor) = url.strip_prefix('#') {
+        if anchor.trim().is_empty() {
+            return Err("内部链接缺少目标位置".to_string());
+        }
+        return Ok(url.to_string());
+    }
+
+    let scheme_end = url
+        .find(':')
+        .ok_or_else(|| "链接地址缺少协议（仅支持绝对地址）".to_string())?;
+    let scheme = url[..scheme_end].to_ascii_lowercase();
+    let rest = &url[scheme_end..];
+
+    match scheme.as_str() {
+        "http" | "https" | "ftp" | "ftps" if rest.starts_with("://") && rest.len() > 3 => {}
+        "mailto" if rest.len() > 1 => {}
+        "http" | "https" | "ftp" | "ftps" | "mailto" => {
+            return Err(format!("链接地址格式无效: {}", url));
+        }
+        _ => return Err(format!("不允许的链接协议: {}", scheme)),
+    }
+
+    Ok(format!("{}{}", scheme, rest))
+}
+
+/// 校验 Excel 表格（ListObject）名称
+///
+/// 规则与 Excel 一致：以字母、下划线或反斜杠开头，仅包含字母、数字、下划线、句点和反斜杠，
+/// 不超过 255 个字符，且不能是 `C`/`R` 或形如 `A1`、`R1C1` 的单元格引用
+///
+/// # 返回值
+/// * `Ok(())` - 名称合法
+/// * `Err(String)` - 名称不合法，包含错误信息
+pub(crate) fn validate_table_name(name: &str) -> Result<(), String> {
+    let Some(first) = name.chars().next() else {
+        return Err("表格名称不能为空".to_string());
+    };
+
+    if name.chars().count() > 255 {
+        return Err("表格名称过长（最大 255 个字符）".to_string());
+    }
+
+    if !(first.is_alphabetic() || first == '_' || first == '\\') {
+        return Err(format!(
+            "表格名称 '{}' 必须以字母、下划线或反斜杠开头",
+            name
+        ));
+    }
+
+    if let Some(ch) = name
+        .chars()
+        .find(|c| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '\\')))
+    {
+        return Err(format!("表格名称 '{}' 包含非法字符: '{}'", name, ch));
+    }
+
+    if looks_like_cell_reference(name) {
+        return Err(format!("表格名称 '{}' 不能是单元格引用", name));
+    }
+
+    Ok(())
+}
+
+/// Excel 工作表名称最大长度（字符数）
+const MAX_SHEET_NAME_CHARS: usize = 31;
+
+/// 按 Excel 规则修正工作表名称，而不是在写入时报错
+///
+/// - `[ ] : * ? / \` 替换为 `_`，去掉首尾的单引号
+/// - 超过 31 个字符时截断；修正后为空的名称改为 `Sheet{序号}`
+/// - 名称不区分大小写去重，重复的名称追加 `(2)`、`(3)`…（必要时截断原名称以满足长度限制）
+pub(crate) fn sanitize_sheet_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut used = std::collections::HashSet::new();
+    let mut result = Vec::new();
+
+    for (idx, name) in names.into_iter().enumerate() {
+        let replaced: String = name
+            .chars()
+            .map(|c| match c {
+                '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+                c => c,
+            })
+            .collect();
+        let mut base = truncate_sheet_name(replaced.trim_matches('\''), MAX_SHEET_NAME_CHARS);
+        if base.is_empty() {
+            base = format!("Sheet{}", idx + 1);
+        }
+
+        let mut candidate = base.clone();
+        let mut n = 2;
+        while !used.insert(candidate.to_lowercase()) {
+            let suffix = format!("({})", n);
+            let prefix = truncate_sheet_name(&base, MAX_SHEET_NAME_CHARS - suffix.chars().count());
+            candidate = format!("{}{}", prefix, suffix);
+            n += 1;
+        }
+        result.push(candidate);
+    }
+
+    result
+}
+
+/// 截断为最多 `max` 个字符，并去掉截断后末尾的单引号
+fn truncate_sheet_name(name: &str, max: usize) -> String {
+    let truncated: String = name.chars().take(max).collect();
+    truncated.trim_end_matches('\'').to_string()
+}
+
+/// 是否形如 `C`、`R`、`A1`、`XFD1048576`、`R1C1` 的单元格引用
+fn looks_like_cell_reference(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    if upper == "C" || upper == "R" {
+        return true;
+    }
+
+    // A1 风格：1–3 个字母 + 数字
+    let letters = upper.chars().take_while(|c| c.is_ascii_uppercase()).count();
+    let digits = &upper[letters..];
+    if (1..=3).contains(&letters)
+        && !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+    {
+        return true;
+    }
+
+    // R1C1 风格：R[数字]C[数字]
+    upper
+        .strip_prefix('R')
+        .map(|rest| rest.trim_start_matches(|c: char| c.is_ascii_digit()))
+        .and_then(|rest| rest.strip_prefix('C'))
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,4 +293,77 @@ mod tests {
         let bad = prepare_download_filename(Some("../x".into()), "default.csv", "csv");
         assert!(bad.is_err());
     }
+
+    #[test]
+    fn test_sanitize_sheet_names() {
+        let long = "年度销售数据汇总表".repeat(4);
+        let names = sanitize_sheet_names([
+            "汇总",
+            "2024/01 [草稿]",
+            "'引号'",
+            "",
+            "汇总",
+            "SHEET4",
+            &long,
+            &long,
+        ]);
+        assert_eq!(names[0], "汇总");
+        assert_eq!(names[1], "2024_01 _草稿_");
+        assert_eq!(names[2], "引号");
+        assert_eq!(names[3], "Sheet4");
+        assert_eq!(names[4], "汇总(2)");
+        // 不区分大小写去重
+        assert_eq!(names[5], "SHEET4(2)");
+        assert_eq!(names[6].chars().count(), 31);
+        assert_eq!(names[7].chars().count(), 31);
+        assert!(names[7].ends_with("(2)"));
+        for name in &names {
+            assert!(rust_xlsxwriter::utility::check_sheet_name(name).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_sanitize_hyperlink() {
+        assert_eq!(
+            sanitize_hyperlink(" https://example.com/a?b=1#c ").unwrap(),
+            "https://example.com/a?b=1#c"
+        );
+        assert_eq!(
+            sanitize_hyperlink("HTTP://example.com").unwrap(),
+            "http://example.com"
+        );
+        assert_eq!(
+            sanitize_hyperlink("mailto:someone@example.com").unwrap(),
+            "mailto:someone@example.com"
+        );
+        assert_eq!(sanitize_hyperlink("#Sheet2!A1").unwrap(), "#Sheet2!A1");
+
+        // 危险协议与非法地址
+        assert!(sanitize_hyperlink("javascript:alert(1)").is_err());
+        assert!(sanitize_hyperlink("JaVaScRiPt:alert(1)").is_err());
+        assert!(sanitize_hyperlink("java\nscript:alert(1)").is_err());
+        assert!(sanitize_hyperlink("vbscript:msgbox").is_err());
+        assert!(sanitize_hyperlink("data:text/html,<script>").is_err());
+        assert!(sanitize_hyperlink("file:///etc/passwd").is_err());
+        assert!(sanitize_hyperlink("/relative/path").is_err());
+        assert!(sanitize_hyperlink("https:").is_err());
+        assert!(sanitize_hyperlink("#").is_err());
+        assert!(sanitize_hyperlink(&format!("https://{}", "a".repeat(2100))).is_err());
+    }
+
+    #[test]
+    fn test_validate_table_name() {
+        assert!(validate_table_name("Sales_2024").is_ok());
+        assert!(validate_table_name("_销售明细").is_ok());
+        assert!(validate_table_name("").is_err());
+        assert!(validate_table_name("1Sales").is_err());
+        assert!(validate_table_name("Sales Data").is_err());
+        assert!(validate_table_name("A1").is_err());
+        assert!(validate_table_name("xfd100").is_err());
+        assert!(validate_table_name("R1C1").is_err());
+        assert!(validate_table_name("rc").is_err());
+        assert!(validate_table_name("c").is_err());
+        // 超过 3 个字母的不是单元格引用
+        assert!(validate_table_name("ABCD1").is_ok());
+    }
 }
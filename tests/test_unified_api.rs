@@ -23,6 +23,13 @@ fn test_export_format_xlsx() {
     assert_eq!(format, ExportFormat::Xlsx);
 }
 
+#[test]
+fn test_export_format_tsv() {
+    let format = ExportFormat::Tsv;
+    assert_eq!(format, ExportFormat::Tsv);
+    assert_ne!(format, ExportFormat::Csv);
+}
+
 #[test]
 fn test_export_format_equality() {
     assert_eq!(ExportFormat::Csv, ExportFormat::default());